@@ -0,0 +1,35 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! ```console
+//! $ cargo bench --features benchmark-private --bench car-read-ahead
+//! ```
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use forest::benchmark_private::plain::{DEFAULT_READ_AHEAD_WINDOW, PlainCar};
+use std::{hint::black_box, io::Write as _};
+
+// Benchmark a full scan of a CAR file, reading each block separately vs.
+// reading ahead in windows of several blocks.
+fn bench_full_scan(c: &mut Criterion) {
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(
+        &zstd::decode_all(include_bytes!("../test-snapshots/chain4.car.zst").as_slice()).unwrap(),
+    )
+    .unwrap();
+    let car = PlainCar::new(file).unwrap();
+
+    let mut group = c.benchmark_group("full scan");
+    for window in [0, 64 * 1024, DEFAULT_READ_AHEAD_WINDOW] {
+        group.bench_function(BenchmarkId::new("window", window), |b| {
+            b.iter(|| {
+                car.blocks_with_read_ahead(black_box(window))
+                    .map(Result::unwrap)
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_full_scan);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+//! ```console
+//! $ cargo bench --features benchmark-private --bench token-amount
+//! ```
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use forest::benchmark_private::econ::{ONE_FIL, TokenAmount, ZERO};
+use num_traits::Zero as _;
+use std::hint::black_box;
+
+// Benchmark comparing balances against constants that are constructed in the
+// loop, which allocates for non-zero amounts, vs. borrowed from statics.
+fn bench_balance_comparisons(c: &mut Criterion) {
+    let balances = (0..1_000_u64)
+        .map(|i| TokenAmount::from_nano(i * 10_000_000))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("balance comparisons");
+    group.bench_function("== TokenAmount::zero()", |b| {
+        b.iter(|| {
+            black_box(&balances)
+                .iter()
+                .filter(|it| **it == TokenAmount::zero())
+                .count()
+        })
+    });
+    group.bench_function("== ZERO", |b| {
+        b.iter(|| {
+            black_box(&balances)
+                .iter()
+                .filter(|it| **it == *ZERO)
+                .count()
+        })
+    });
+    group.bench_function("is_zero", |b| {
+        b.iter(|| {
+            black_box(&balances)
+                .iter()
+                .filter(|it| it.is_zero())
+                .count()
+        })
+    });
+    group.bench_function(">= TokenAmount::from_whole(1)", |b| {
+        b.iter(|| {
+            black_box(&balances)
+                .iter()
+                .filter(|it| **it >= TokenAmount::from_whole(1))
+                .count()
+        })
+    });
+    group.bench_function(">= ONE_FIL", |b| {
+        b.iter(|| {
+            black_box(&balances)
+                .iter()
+                .filter(|it| **it >= *ONE_FIL)
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_balance_comparisons);
+criterion_main!(benches);
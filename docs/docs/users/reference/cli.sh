@@ -129,6 +129,13 @@ generate_markdown_section "forest-tool" "db destroy"
 
 generate_markdown_section "forest-tool" "car"
 generate_markdown_section "forest-tool" "car concat"
+generate_markdown_section "forest-tool" "car diff"
+generate_markdown_section "forest-tool" "car filter"
+generate_markdown_section "forest-tool" "car histogram"
+generate_markdown_section "forest-tool" "car index"
+generate_markdown_section "forest-tool" "car recompress"
+generate_markdown_section "forest-tool" "car shard"
+generate_markdown_section "forest-tool" "car verify"
 generate_markdown_section "forest-tool" "car validate"
 
 generate_markdown_section "forest-tool" "api"
@@ -0,0 +1,108 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+
+	"github.com/filecoin-project/go-f3/certs"
+	"github.com/filecoin-project/go-f3/certstore"
+	"github.com/filecoin-project/go-f3/gpbft"
+	"github.com/filecoin-project/go-f3/manifest"
+	"github.com/ipfs/go-datastore/namespace"
+	leveldb "github.com/ipfs/go-ds-leveldb"
+	"github.com/syndtr/goleveldb/leveldb/opt"
+)
+
+// Opens the certificate store that `run` uses for the given network under f3Root.
+// The store must have been created by running the sidecar at least once.
+func openCertStore(ctx context.Context, f3Root string, rawNetwork string, readOnly bool) (*certstore.Store, *manifest.Manifest, func() error, error) {
+	networkName := gpbft.NetworkName(rawNetwork)
+	if networkName == "testnetnet" {
+		networkName = "filecoin"
+	}
+	m := Network2PredefinedManifestMappings[networkName]
+	if m == nil {
+		m2 := manifest.LocalDevnetManifest()
+		m = &m2
+		m.NetworkName = networkName
+	}
+	ds, err := leveldb.NewDatastore(filepath.Join(f3Root, "db"), &opt.Options{ReadOnly: readOnly, ErrorIfMissing: true})
+	if err != nil {
+		return nil, nil, nil, err
+	}
+	cs, err := certstore.OpenStore(ctx, namespace.Wrap(ds, m.DatastorePrefix()))
+	if err != nil {
+		_ = ds.Close()
+		return nil, nil, nil, err
+	}
+	return cs, m, ds.Close, nil
+}
+
+// Writes the stored certificates to path, one JSON object per line, in instance order.
+func exportCertificates(ctx context.Context, f3Root string, rawNetwork string, path string) error {
+	cs, m, closer, err := openCertStore(ctx, f3Root, rawNetwork, true)
+	if err != nil {
+		return err
+	}
+	defer closer()
+
+	file, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+	defer file.Close()
+	writer := bufio.NewWriter(file)
+	encoder := json.NewEncoder(writer)
+	latest := cs.Latest()
+	if latest == nil {
+		return writer.Flush()
+	}
+	for i := m.InitialInstance; i <= latest.GPBFTInstance; i++ {
+		cert, err := cs.Get(ctx, i)
+		if errors.Is(err, certstore.ErrCertNotFound) {
+			// The store may start after the initial instance
+			continue
+		} else if err != nil {
+			return err
+		}
+		if err := encoder.Encode(cert); err != nil {
+			return err
+		}
+	}
+	return writer.Flush()
+}
+
+// Appends the certificates in path, as written by exportCertificates, to the store.
+// Certificates the store already has are skipped.
+func importCertificates(ctx context.Context, f3Root string, rawNetwork string, path string) error {
+	cs, _, closer, err := openCertStore(ctx, f3Root, rawNetwork, false)
+	if err != nil {
+		return err
+	}
+	defer closer()
+
+	file, err := os.Open(path)
+	if err != nil {
+		return err
+	}
+	defer file.Close()
+	decoder := json.NewDecoder(bufio.NewReader(file))
+	for decoder.More() {
+		var cert certs.FinalityCertificate
+		if err := decoder.Decode(&cert); err != nil {
+			return err
+		}
+		if latest := cs.Latest(); latest != nil && cert.GPBFTInstance <= latest.GPBFTInstance {
+			continue
+		}
+		if err := cs.Put(ctx, &cert); err != nil {
+			return fmt.Errorf("failed to import certificate for instance %d: %w", cert.GPBFTInstance, err)
+		}
+	}
+	return nil
+}
@@ -29,11 +29,14 @@ import (
 var GoF3NodeImpl GoF3Node
 
 type GoF3Node interface {
-	run(rpc_endpoint *string, jwt *string, f3_rpc_endpoint *string, initial_power_table *string, bootstrap_epoch *int64, finality *int64, f3_root *string) bool
+	run(rpc_endpoint *string, jwt *string, f3_rpc_endpoint *string, initial_power_table *string, bootstrap_epoch *int64, finality *int64, f3_root *string, log_level *string, log_format *string, log_file *string) bool
+	stop() bool
+	export_certificates(f3_root *string, network *string, path *string) string
+	import_certificates(f3_root *string, network *string, path *string) string
 }
 
 //export CGoF3Node_run
-func CGoF3Node_run(rpc_endpoint C.StringRef, jwt C.StringRef, f3_rpc_endpoint C.StringRef, initial_power_table C.StringRef, bootstrap_epoch C.int64_t, finality C.int64_t, f3_root C.StringRef, slot *C.void, cb *C.void) {
+func CGoF3Node_run(rpc_endpoint C.StringRef, jwt C.StringRef, f3_rpc_endpoint C.StringRef, initial_power_table C.StringRef, bootstrap_epoch C.int64_t, finality C.int64_t, f3_root C.StringRef, log_level C.StringRef, log_format C.StringRef, log_file C.StringRef, slot *C.void, cb *C.void) {
 	_new_rpc_endpoint := newString(rpc_endpoint)
 	_new_jwt := newString(jwt)
 	_new_f3_rpc_endpoint := newString(f3_rpc_endpoint)
@@ -41,7 +44,10 @@ func CGoF3Node_run(rpc_endpoint C.StringRef, jwt C.StringRef, f3_rpc_endpoint C.
 	_new_bootstrap_epoch := newC_int64_t(bootstrap_epoch)
 	_new_finality := newC_int64_t(finality)
 	_new_f3_root := newString(f3_root)
-	resp := GoF3NodeImpl.run(&_new_rpc_endpoint, &_new_jwt, &_new_f3_rpc_endpoint, &_new_initial_power_table, &_new_bootstrap_epoch, &_new_finality, &_new_f3_root)
+	_new_log_level := newString(log_level)
+	_new_log_format := newString(log_format)
+	_new_log_file := newString(log_file)
+	resp := GoF3NodeImpl.run(&_new_rpc_endpoint, &_new_jwt, &_new_f3_rpc_endpoint, &_new_initial_power_table, &_new_bootstrap_epoch, &_new_finality, &_new_f3_root, &_new_log_level, &_new_log_format, &_new_log_file)
 	resp_ref, buffer := cvt_ref(cntC_bool, refC_bool)(&resp)
 	asmcall.CallFuncG0P2(unsafe.Pointer(cb), unsafe.Pointer(&resp_ref), unsafe.Pointer(slot))
 	runtime.KeepAlive(resp_ref)
@@ -49,6 +55,42 @@ func CGoF3Node_run(rpc_endpoint C.StringRef, jwt C.StringRef, f3_rpc_endpoint C.
 	runtime.KeepAlive(buffer)
 }
 
+//export CGoF3Node_stop
+func CGoF3Node_stop(slot *C.void, cb *C.void) {
+	resp := GoF3NodeImpl.stop()
+	resp_ref, buffer := cvt_ref(cntC_bool, refC_bool)(&resp)
+	asmcall.CallFuncG0P2(unsafe.Pointer(cb), unsafe.Pointer(&resp_ref), unsafe.Pointer(slot))
+	runtime.KeepAlive(resp_ref)
+	runtime.KeepAlive(resp)
+	runtime.KeepAlive(buffer)
+}
+
+//export CGoF3Node_export_certificates
+func CGoF3Node_export_certificates(f3_root C.StringRef, network C.StringRef, path C.StringRef, slot *C.void, cb *C.void) {
+	_new_f3_root := newString(f3_root)
+	_new_network := newString(network)
+	_new_path := newString(path)
+	resp := GoF3NodeImpl.export_certificates(&_new_f3_root, &_new_network, &_new_path)
+	resp_ref, buffer := cvt_ref(cntString, refString)(&resp)
+	asmcall.CallFuncG0P2(unsafe.Pointer(cb), unsafe.Pointer(&resp_ref), unsafe.Pointer(slot))
+	runtime.KeepAlive(resp_ref)
+	runtime.KeepAlive(resp)
+	runtime.KeepAlive(buffer)
+}
+
+//export CGoF3Node_import_certificates
+func CGoF3Node_import_certificates(f3_root C.StringRef, network C.StringRef, path C.StringRef, slot *C.void, cb *C.void) {
+	_new_f3_root := newString(f3_root)
+	_new_network := newString(network)
+	_new_path := newString(path)
+	resp := GoF3NodeImpl.import_certificates(&_new_f3_root, &_new_network, &_new_path)
+	resp_ref, buffer := cvt_ref(cntString, refString)(&resp)
+	asmcall.CallFuncG0P2(unsafe.Pointer(cb), unsafe.Pointer(&resp_ref), unsafe.Pointer(slot))
+	runtime.KeepAlive(resp_ref)
+	runtime.KeepAlive(resp)
+	runtime.KeepAlive(buffer)
+}
+
 func newString(s_ref C.StringRef) string {
 	return unsafe.String((*byte)(unsafe.Pointer(s_ref.ptr)), s_ref.len)
 }
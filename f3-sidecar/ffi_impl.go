@@ -2,8 +2,9 @@ package main
 
 import (
 	"context"
+	"fmt"
 	"os"
-	"time"
+	"sync"
 
 	logging "github.com/ipfs/go-log/v2"
 )
@@ -11,41 +12,111 @@ import (
 func init() {
 	setGoDebugEnv()
 	logging.SetAllLoggers(logging.LevelInfo)
-	err := logging.SetLogLevel("dht", "error")
+	checkError(setNoisyLogLevels())
+	err := logging.SetLogLevel("f3/sidecar", "debug")
 	checkError(err)
+	GoF3NodeImpl = &f3Impl{}
+}
+
+func setNoisyLogLevels() error {
+	if err := logging.SetLogLevel("dht", "error"); err != nil {
+		return err
+	}
 	// Always mute RtRefreshManager because it breaks terminals
-	err = logging.SetLogLevel("dht/RtRefreshManager", "fatal")
-	checkError(err)
-	err = logging.SetLogLevel("net/identify", "error")
-	checkError(err)
-	err = logging.SetLogLevel("pubsub", "error")
-	checkError(err)
-	err = logging.SetLogLevel("swarm2", "error")
-	checkError(err)
-	err = logging.SetLogLevel("f3/sidecar", "debug")
-	checkError(err)
-	GoF3NodeImpl = &f3Impl{ctx: context.Background()}
+	if err := logging.SetLogLevel("dht/RtRefreshManager", "fatal"); err != nil {
+		return err
+	}
+	if err := logging.SetLogLevel("net/identify", "error"); err != nil {
+		return err
+	}
+	if err := logging.SetLogLevel("pubsub", "error"); err != nil {
+		return err
+	}
+	return logging.SetLogLevel("swarm2", "error")
+}
+
+// Sets the level of all loggers, and where and how they write. The noisy
+// subsystems are kept at their own levels. An empty file means stderr.
+func setupLogging(level string, format string, file string) error {
+	lvl, err := logging.LevelFromString(level)
+	if err != nil {
+		return err
+	}
+	cfg := logging.Config{
+		Level:  lvl,
+		Stderr: file == "",
+		File:   file,
+	}
+	switch format {
+	case "json":
+		cfg.Format = logging.JSONOutput
+	case "plaintext":
+		cfg.Format = logging.PlaintextOutput
+	default:
+		return fmt.Errorf("unknown log format %q", format)
+	}
+	logging.SetupLogging(cfg)
+	return setNoisyLogLevels()
 }
 
 type f3Impl struct {
-	ctx context.Context
+	mu     sync.Mutex
+	cancel context.CancelFunc
 }
 
 // The nil checks of the parameters are ommitted because they are passed from Rust code which are not nil.
 // The signature pointer types that are generated by rust2go to avoid lifetime issues
-func (f3 *f3Impl) run(rpc_endpoint *string, jwt *string, f3_rpc_endpoint *string, initial_power_table *string, bootstrap_epoch *int64, finality *int64, db *string) bool {
-	var err error = nil
-	const MAX_RETRY int = 5
-	nRetry := 0
-	for nRetry <= MAX_RETRY {
-		err = run(f3.ctx, *rpc_endpoint, *jwt, *f3_rpc_endpoint, *initial_power_table, *bootstrap_epoch, *finality, *db)
-		if err != nil {
-			nRetry += 1
-			logger.Errorf("Unexpected F3 failure, retrying(%d) in 10s... error=%s", nRetry, err)
-			time.Sleep(10 * time.Second)
-		}
-	}
-	return err == nil
+func (f3 *f3Impl) run(rpc_endpoint *string, jwt *string, f3_rpc_endpoint *string, initial_power_table *string, bootstrap_epoch *int64, finality *int64, db *string, log_level *string, log_format *string, log_file *string) bool {
+	if err := setupLogging(*log_level, *log_format, *log_file); err != nil {
+		logger.Errorf("Failed to set up F3 sidecar logging: %s", err)
+	}
+	ctx, cancel := context.WithCancel(context.Background())
+	defer cancel()
+	f3.mu.Lock()
+	f3.cancel = cancel
+	f3.mu.Unlock()
+
+	// Restarts are up to the supervisor on the Rust side
+	err := run(ctx, *rpc_endpoint, *jwt, *f3_rpc_endpoint, *initial_power_table, *bootstrap_epoch, *finality, *db)
+	if ctx.Err() != nil {
+		logger.Infoln("F3 sidecar stopped")
+		return true
+	}
+	if err != nil {
+		logger.Errorf("F3 sidecar failed: %s", err)
+		return false
+	}
+	// `run` isn't expected to return on its own
+	logger.Warnln("F3 sidecar exited")
+	return true
+}
+
+// Cancels the context of the running sidecar, which makes `run` return.
+// Returns false if the sidecar has never been started.
+func (f3 *f3Impl) stop() bool {
+	f3.mu.Lock()
+	defer f3.mu.Unlock()
+	if f3.cancel == nil {
+		return false
+	}
+	f3.cancel()
+	return true
+}
+
+// Returns the error message, or an empty string on success.
+func (f3 *f3Impl) export_certificates(f3_root *string, network *string, path *string) string {
+	if err := exportCertificates(context.Background(), *f3_root, *network, *path); err != nil {
+		return err.Error()
+	}
+	return ""
+}
+
+// Returns the error message, or an empty string on success.
+func (f3 *f3Impl) import_certificates(f3_root *string, network *string, path *string) string {
+	if err := importCertificates(context.Background(), *f3_root, *network, *path); err != nil {
+		return err.Error()
+	}
+	return ""
 }
 
 func checkError(err error) {
@@ -63,3 +63,13 @@ echo "Regression testing mempool select"
 gem install http --user-install
 $FOREST_CLI_PATH chain head --format json -n 1000 | scripts/mpool_select_killer.rb
 
+
+echo "Test subcommands: f3 export-certificates and f3 import-certificates"
+# The certificate store is locked while the node runs
+$FOREST_CLI_PATH shutdown --force
+timeout 10s sh -c "while pkill -0 forest 2>/dev/null; do sleep 1; done"
+$FOREST_TOOL_PATH f3 export-certificates --chain calibnet "$TMP_DIR/f3-certificates"
+# Every certificate is already in the store, so re-importing them must leave it unchanged
+$FOREST_TOOL_PATH f3 import-certificates --chain calibnet "$TMP_DIR/f3-certificates"
+$FOREST_TOOL_PATH f3 export-certificates --chain calibnet "$TMP_DIR/f3-certificates-reexported"
+cmp "$TMP_DIR/f3-certificates" "$TMP_DIR/f3-certificates-reexported"
@@ -64,7 +64,13 @@ pub async fn export<D: Digest>(
     let frames = forest::Encoder::compress_stream_default(blocks);
 
     // Write zstd frames and include a skippable index
-    forest::Encoder::write(&mut writer, roots, frames).await?;
+    forest::Encoder::write_buffered(
+        &mut writer,
+        roots,
+        frames,
+        forest::DEFAULT_FOREST_CAR_MAX_BUFFERED_FRAMES,
+    )
+    .await?;
 
     // Flush to ensure everything has been successfully written
     writer.flush().await.context("failed to flush")?;
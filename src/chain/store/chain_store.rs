@@ -3,6 +3,7 @@
 
 use super::{
     Error,
+    events_index::{self, EventsIndexFilter},
     index::{ChainIndex, ResolveNullTipset},
     tipset_tracker::TipsetTracker,
 };
@@ -14,8 +15,12 @@ use crate::networks::{ChainConfig, Height};
 use crate::rpc::eth::{eth_tx_from_signed_eth_message, types::EthHash};
 use crate::shim::clock::ChainEpoch;
 use crate::shim::{
-    address::Address, econ::TokenAmount, executor::Receipt, message::Message,
-    state_tree::StateTree, version::NetworkVersion,
+    address::Address,
+    econ::TokenAmount,
+    executor::{Receipt, StampedEvent},
+    message::Message,
+    state_tree::StateTree,
+    version::NetworkVersion,
 };
 use crate::state_manager::StateOutput;
 use crate::utils::db::{BlockstoreExt, CborStoreExt};
@@ -25,7 +30,10 @@ use crate::{
 };
 use crate::{
     chain_sync::metrics,
-    db::{EthMappingsStore, EthMappingsStoreExt, IndicesStore, IndicesStoreExt},
+    db::{
+        ColumnStats, DbStats, EthMappingsStore, EthMappingsStoreExt, IndicesStore, IndicesStoreExt,
+        read_tipset_eth_hash, write_tipset_eth_hash,
+    },
 };
 use ahash::{HashMap, HashMapExt, HashSet};
 use anyhow::Context as _;
@@ -39,7 +47,7 @@ use parking_lot::{Mutex, RwLock};
 use serde::{Serialize, de::DeserializeOwned};
 use std::{num::NonZeroUsize, sync::Arc};
 use tokio::sync::broadcast::{self, Sender as Publisher};
-use tracing::{debug, trace, warn};
+use tracing::{debug, error, trace, warn};
 
 // A cap on the size of the future_sink
 const SINK_CAP: usize = 200;
@@ -87,6 +95,10 @@ pub struct ChainStore<DB> {
 
     /// Needed by the Ethereum mapping.
     pub chain_config: Arc<ChainConfig>,
+
+    /// Whether conflicting writes to the Ethereum mappings fail, see
+    /// [`ChainStore::with_strict_eth_mappings`].
+    strict_eth_mappings: bool,
 }
 
 impl<DB> BitswapStoreRead for ChainStore<DB>
@@ -140,11 +152,21 @@ where
             eth_mappings,
             indices,
             chain_config,
+            strict_eth_mappings: false,
         };
 
         Ok(cs)
     }
 
+    /// Makes [`Self::put_tipset_key`] and [`Self::put_mapping`] fail, rather than overwrite
+    /// an existing Ethereum mapping with a different value. Such conflicts indicate a bug
+    /// in the hash computation, or a corrupted database, and are logged either way, though
+    /// [`Self::put_mapping`] only looks for them in debug builds otherwise.
+    pub fn with_strict_eth_mappings(mut self, strict: bool) -> Self {
+        self.strict_eth_mappings = strict;
+        self
+    }
+
     /// Sets heaviest tipset
     pub fn set_heaviest_tipset(&self, ts: Arc<Tipset>) -> Result<(), Error> {
         metrics::HEAD_EPOCH.set(ts.epoch());
@@ -172,13 +194,29 @@ where
         Ok(())
     }
 
-    /// Writes the `TipsetKey` to the blockstore for `EthAPI` queries.
+    /// Writes the `TipsetKey` to the blockstore for `EthAPI` queries, along with the
+    /// reverse mapping read by [`Self::get_eth_hash_by_tipset_key`].
     pub fn put_tipset_key(&self, tsk: &TipsetKey) -> Result<(), Error> {
         let hash = tsk.cid()?.into();
+        if let Some(existing) = self.eth_mappings.read_bin(&hash)?
+            && fvm_ipld_encoding::from_slice::<TipsetKey>(&existing)
+                .ok()
+                .as_ref()
+                != Some(tsk)
+        {
+            self.eth_mapping_conflict(&hash, &existing, &tsk.to_string())?;
+        }
         self.eth_mappings.write_obj(&hash, tsk)?;
+        write_tipset_eth_hash(self.eth_mappings.as_ref(), tsk, &hash)?;
         Ok(())
     }
 
+    /// Returns the Ethereum block hash of a tipset, if it's indexed. It isn't, e.g., when
+    /// the indexer is disabled, and is then the hash of the tipset key.
+    pub fn get_eth_hash_by_tipset_key(&self, tsk: &TipsetKey) -> Result<Option<EthHash>, Error> {
+        Ok(read_tipset_eth_hash(self.eth_mappings.as_ref(), tsk)?)
+    }
+
     /// Reads the `TipsetKey` from the blockstore for `EthAPI` queries.
     pub fn get_required_tipset_key(&self, hash: &EthHash) -> Result<TipsetKey, Error> {
         let tsk = self
@@ -190,11 +228,48 @@ where
     }
 
     /// Writes with timestamp the `Hash` to `Cid` mapping to the blockstore for `EthAPI` queries.
+    /// Only the `Cid` of an existing mapping has to match, the timestamp is updated. As this
+    /// is called for every message, the existing mapping is only checked in
+    /// [strict mode](Self::with_strict_eth_mappings) or in debug builds.
     pub fn put_mapping(&self, k: EthHash, v: Cid, timestamp: u64) -> Result<(), Error> {
+        if (self.strict_eth_mappings || cfg!(debug_assertions))
+            && let Some(existing) = self.eth_mappings.read_bin(&k)?
+            && fvm_ipld_encoding::from_slice::<(Cid, u64)>(&existing)
+                .map(|(cid, _)| cid)
+                .ok()
+                != Some(v)
+        {
+            self.eth_mapping_conflict(&k, &existing, &v.to_string())?;
+        }
         self.eth_mappings.write_obj(&k, &(v, timestamp))?;
         Ok(())
     }
 
+    /// Reports that the Ethereum mapping of `hash` is about to be overwritten with a
+    /// different value. Fails in [strict mode](Self::with_strict_eth_mappings).
+    fn eth_mapping_conflict(
+        &self,
+        hash: &EthHash,
+        existing: &[u8],
+        new: &str,
+    ) -> Result<(), Error> {
+        let existing = if let Ok(tsk) = fvm_ipld_encoding::from_slice::<TipsetKey>(existing) {
+            tsk.to_string()
+        } else if let Ok((cid, _)) = fvm_ipld_encoding::from_slice::<(Cid, u64)>(existing) {
+            cid.to_string()
+        } else {
+            format!("0x{}", hex::encode(existing))
+        };
+        metrics::ETH_MAPPING_CONFLICT_TOTAL.inc();
+        error!("Conflicting Ethereum mapping for {hash}: existing {existing}, new {new}");
+        if self.strict_eth_mappings {
+            return Err(Error::Other(format!(
+                "conflicting Ethereum mapping for {hash}: existing {existing}, new {new}"
+            )));
+        }
+        Ok(())
+    }
+
     /// Reads the `Cid` from the blockstore for `EthAPI` queries.
     pub fn get_mapping(&self, hash: &EthHash) -> Result<Option<Cid>, Error> {
         Ok(self
@@ -212,6 +287,44 @@ where
         Ok(self.indices.read_obj(key)?)
     }
 
+    /// Writes the secondary index of the events of `ts`, see [`events_index`].
+    pub fn put_events_index(&self, ts: &Tipset, events: &[Vec<StampedEvent>]) -> Result<(), Error> {
+        events_index::write_tipset_events(self.indices.as_ref(), ts, events)?;
+        Ok(())
+    }
+
+    /// Returns `false` if `ts` has no events matching `filter` according to the
+    /// secondary index of events, see [`events_index::may_have_matching_events`].
+    pub fn may_have_matching_events(
+        &self,
+        ts: &Tipset,
+        filter: &EventsIndexFilter,
+    ) -> Result<bool, Error> {
+        Ok(events_index::may_have_matching_events(
+            self.indices.as_ref(),
+            ts,
+            filter,
+        )?)
+    }
+
+    /// Returns the number of entries and the approximate size of the Ethereum mappings store.
+    pub fn eth_mappings_stats(&self) -> Result<ColumnStats, Error> {
+        Ok(self.eth_mappings.stats()?)
+    }
+
+    /// Returns the number of entries and the approximate size of the indices store.
+    pub fn indices_stats(&self) -> Result<ColumnStats, Error> {
+        Ok(self.indices.stats()?)
+    }
+
+    /// Returns the statistics of all stores backing the Ethereum RPC.
+    pub fn db_stats(&self) -> Result<DbStats, Error> {
+        Ok(DbStats {
+            eth_mappings: self.eth_mappings_stats()?,
+            indices: self.indices_stats()?,
+        })
+    }
+
     /// Expands tipset to tipset with all other headers in the same epoch using
     /// the tipset tracker.
     fn expand_tipset(&self, header: CachingBlockHeader) -> Result<Tipset, Error> {
@@ -656,10 +769,10 @@ where
                     continue;
                 }
                 if let Some(bal) = balances.get_mut(from_address) {
-                    if *bal < message.required_funds() {
+                    let Some(remaining) = bal.checked_sub(&message.required_funds()) else {
                         continue;
-                    }
-                    *bal -= message.required_funds();
+                    };
+                    *bal = remaining;
                 } else {
                     continue;
                 }
@@ -736,6 +849,7 @@ pub mod headchange_json {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::memory_chain_store;
     use crate::utils::multihash::prelude::*;
     use crate::{blocks::RawBlockHeader, shim::address::Address};
     use cid::Cid;
@@ -794,6 +908,68 @@ mod tests {
         assert!(cs.is_block_validated(&cid));
     }
 
+    #[test]
+    fn db_stats_counts_entries() {
+        let cs = memory_chain_store(&Arc::new(crate::db::MemoryDB::default()));
+        assert_eq!(cs.db_stats().unwrap(), DbStats::default());
+
+        let cids = (0..5u8)
+            .map(|i| Cid::new_v1(DAG_CBOR, MultihashCode::Blake2b256.digest(&[i])))
+            .collect_vec();
+        for (i, cid) in cids.iter().enumerate() {
+            cs.put_mapping(EthHash::from(*cid), *cid, i as u64).unwrap();
+        }
+        for cid in &cids[..3] {
+            cs.put_index(cid, &TipsetKey::from(nunny::vec![*cid]))
+                .unwrap();
+        }
+
+        let stats = cs.db_stats().unwrap();
+        assert_eq!(stats.eth_mappings.entries, 5);
+        assert_eq!(stats.indices.entries, 3);
+        assert!(stats.eth_mappings.approximate_size_bytes > 5 * 32);
+        assert!(stats.indices.approximate_size_bytes > 0);
+    }
+
+    #[test]
+    fn eth_mapping_conflicts() {
+        let cid = |i: u8| Cid::new_v1(DAG_CBOR, MultihashCode::Blake2b256.digest(&[i]));
+        // The counter is global, and incremented by concurrent tests too
+        let conflicts = || metrics::ETH_MAPPING_CONFLICT_TOTAL.get();
+        for strict in [false, true] {
+            let db = Arc::new(crate::db::MemoryDB::default());
+            let cs = memory_chain_store(&db).with_strict_eth_mappings(strict);
+
+            // Another tipset key under the hash of `tsk`
+            let tsk = TipsetKey::from(nunny::vec![cid(0)]);
+            let other = TipsetKey::from(nunny::vec![cid(1)]);
+            let hash = tsk.cid().unwrap().into();
+            EthMappingsStoreExt::write_obj(db.as_ref(), &hash, &other).unwrap();
+            let before = conflicts();
+            assert_eq!(cs.put_tipset_key(&tsk).is_err(), strict);
+            assert!(conflicts() > before);
+            let expected = if strict { &other } else { &tsk };
+            assert_eq!(&cs.get_required_tipset_key(&hash).unwrap(), expected);
+            // Rewriting the same value is fine, even in strict mode
+            let tsk = TipsetKey::from(nunny::vec![cid(4)]);
+            cs.put_tipset_key(&tsk).unwrap();
+            cs.put_tipset_key(&tsk).unwrap();
+
+            // Another message under the same hash
+            let hash = EthHash::from(cid(2));
+            cs.put_mapping(hash.clone(), cid(2), 0).unwrap();
+            let before = conflicts();
+            assert_eq!(cs.put_mapping(hash.clone(), cid(3), 1).is_err(), strict);
+            if strict || cfg!(debug_assertions) {
+                assert!(conflicts() > before);
+            }
+            let expected = if strict { cid(2) } else { cid(3) };
+            assert_eq!(cs.get_mapping(&hash).unwrap(), Some(expected));
+            // A new timestamp is fine, even in strict mode
+            cs.put_mapping(hash.clone(), expected, 2).unwrap();
+        }
+    }
+
     #[test]
     fn test_messages_in_tipset_cache() {
         let cache = MsgsInTipsetCache::new(2).unwrap();
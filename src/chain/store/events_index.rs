@@ -0,0 +1,180 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! A secondary index of events in the indices store, so that event queries filtered by
+//! emitter or by first topic, e.g., `eth_getLogs` with an address, skip the tipsets without
+//! matching events instead of loading and decoding all of their events.
+//!
+//! For each indexed tipset, it records:
+//! - that the tipset is indexed, keyed by its epoch;
+//! - that it has events emitted by an actor, keyed by `(emitter_actor_id, epoch)`;
+//! - that it has events whose first topic (the `t1` entry) has a value, keyed by
+//!   `(topic0, epoch)`.
+//!
+//! Several tipsets can share an epoch, so each value is the list of the tipsets it applies
+//! to. To keep the column small, tipsets are listed by an 8-byte summary of their keys
+//! rather than by the keys themselves. A summary collision only means that a tipset is
+//! scanned needlessly.
+
+use crate::blocks::{Tipset, TipsetKey};
+use crate::db::{IndicesStore, IndicesStoreExt};
+use crate::shim::clock::ChainEpoch;
+use crate::shim::executor::StampedEvent;
+use crate::shim::fvm_shared_latest::ActorID;
+use crate::utils::multihash::prelude::*;
+use ahash::HashSet;
+use cid::Cid;
+use fvm_ipld_encoding::IPLD_RAW;
+use parking_lot::Mutex;
+
+/// The key of the event entry holding the first topic of an FEVM log.
+pub const TOPIC0_KEY: &str = "t1";
+
+/// Serializes the read-modify-write updates of the index, which can happen concurrently,
+/// e.g., during a backfill while the node is syncing.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Clone, Copy)]
+#[repr(u8)]
+enum KeyKind {
+    Indexed,
+    Emitter,
+    Topic0,
+}
+
+/// The index keys are hashed, and their codec differs from that of the events roots, which
+/// share the indices store.
+fn index_key(kind: KeyKind, epoch: ChainEpoch, discriminator: &[u8]) -> Cid {
+    let mut bytes = Vec::with_capacity(1 + size_of::<ChainEpoch>() + discriminator.len());
+    bytes.push(kind as u8);
+    bytes.extend_from_slice(&epoch.to_be_bytes());
+    bytes.extend_from_slice(discriminator);
+    Cid::new_v1(IPLD_RAW, MultihashCode::Blake2b256.digest(&bytes))
+}
+
+fn indexed_key(epoch: ChainEpoch) -> Cid {
+    index_key(KeyKind::Indexed, epoch, &[])
+}
+
+fn emitter_key(emitter: ActorID, epoch: ChainEpoch) -> Cid {
+    index_key(KeyKind::Emitter, epoch, &emitter.to_be_bytes())
+}
+
+fn topic0_key(topic0: &[u8], epoch: ChainEpoch) -> Cid {
+    index_key(KeyKind::Topic0, epoch, topic0)
+}
+
+fn tipset_summary(tsk: &TipsetKey) -> anyhow::Result<u64> {
+    let cid = tsk.cid()?;
+    let digest = cid.hash().digest();
+    anyhow::ensure!(digest.len() >= 8, "tipset key hash is too short");
+    Ok(u64::from_be_bytes(digest[..8].try_into()?))
+}
+
+fn contains_summary(
+    db: &(impl IndicesStore + ?Sized),
+    key: &Cid,
+    summary: u64,
+) -> anyhow::Result<bool> {
+    Ok(db
+        .read_obj::<Vec<u64>>(key)?
+        .is_some_and(|summaries| summaries.contains(&summary)))
+}
+
+fn append_summary(
+    db: &(impl IndicesStore + ?Sized),
+    key: &Cid,
+    summary: u64,
+) -> anyhow::Result<()> {
+    let mut summaries = db.read_obj::<Vec<u64>>(key)?.unwrap_or_default();
+    if !summaries.contains(&summary) {
+        summaries.push(summary);
+        db.write_obj(key, &summaries)?;
+    }
+    Ok(())
+}
+
+/// Indexes the events of `ts`, grouped by message like [`crate::state_manager::StateOutput::events`].
+/// This is idempotent.
+pub fn write_tipset_events(
+    db: &(impl IndicesStore + ?Sized),
+    ts: &Tipset,
+    events: &[Vec<StampedEvent>],
+) -> anyhow::Result<()> {
+    let epoch = ts.epoch();
+    let summary = tipset_summary(ts.key())?;
+    let mut keys = HashSet::default();
+    for event in events.iter().flatten() {
+        keys.insert(emitter_key(event.emitter(), epoch));
+        for entry in event.event().entries() {
+            if entry.key() == TOPIC0_KEY {
+                keys.insert(topic0_key(entry.value(), epoch));
+            }
+        }
+    }
+
+    let _guard = WRITE_LOCK.lock();
+    for key in &keys {
+        append_summary(db, key, summary)?;
+    }
+    // Marked last, so that a partially indexed tipset is still scanned
+    append_summary(db, &indexed_key(epoch), summary)
+}
+
+/// The emitters and first topics of the events that a query asks for, see
+/// [`may_have_matching_events`]. [`None`] matches any.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventsIndexFilter {
+    pub emitters: Option<Vec<ActorID>>,
+    pub topic0s: Option<Vec<Vec<u8>>>,
+}
+
+impl EventsIndexFilter {
+    /// Whether the filter can't rule out any tipset.
+    pub fn is_wildcard(&self) -> bool {
+        self.emitters.is_none() && self.topic0s.is_none()
+    }
+}
+
+/// Returns `false` only if the events of `ts` are indexed, see [`write_tipset_events`], and
+/// either none of them has an emitter in `filter`, or none of them has a first topic in
+/// `filter`. Otherwise, the events of `ts` have to be scanned.
+pub fn may_have_matching_events(
+    db: &(impl IndicesStore + ?Sized),
+    ts: &Tipset,
+    filter: &EventsIndexFilter,
+) -> anyhow::Result<bool> {
+    if filter.is_wildcard() {
+        return Ok(true);
+    }
+    let epoch = ts.epoch();
+    let summary = tipset_summary(ts.key())?;
+    if !contains_summary(db, &indexed_key(epoch), summary)? {
+        return Ok(true);
+    }
+    if let Some(emitters) = &filter.emitters {
+        let mut found = false;
+        for emitter in emitters {
+            if contains_summary(db, &emitter_key(*emitter, epoch), summary)? {
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            return Ok(false);
+        }
+    }
+    if let Some(topic0s) = &filter.topic0s {
+        let mut found = false;
+        for topic0 in topic0s {
+            if contains_summary(db, &topic0_key(topic0, epoch), summary)? {
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
@@ -44,6 +44,15 @@ pub static INVALID_TIPSET_TOTAL: LazyLock<Counter> = LazyLock::new(|| {
     );
     metric
 });
+pub static ETH_MAPPING_CONFLICT_TOTAL: LazyLock<Counter> = LazyLock::new(|| {
+    let metric = Counter::default();
+    crate::metrics::default_registry().register(
+        "eth_mapping_conflict_total",
+        "Total number of Ethereum mappings written over a different existing value",
+        metric.clone(),
+    );
+    metric
+});
 pub static HEAD_EPOCH: LazyLock<Gauge> = LazyLock::new(|| {
     let metric = Gauge::default();
     crate::metrics::default_registry().register(
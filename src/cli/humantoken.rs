@@ -421,24 +421,25 @@ mod print {
         /// - `{:.#4}`: both
         ///
         /// ```
-        /// # use forest::doctest_private::{TokenAmountPretty as _, TokenAmount};
+        /// # use forest::doctest_private::{TokenAmountPretty, TokenAmount};
         ///
-        /// let amount = TokenAmount::from_nano(1500);
+        /// // Call through the trait, `TokenAmount::pretty` formats differently
+        /// let amount = TokenAmountPretty::pretty(&TokenAmount::from_nano(1500));
         ///
         /// // Defaults to precise, with SI prefix
-        /// assert_eq!("1.5 microFIL", format!("{}", amount.pretty()));
+        /// assert_eq!("1.5 microFIL", format!("{}", amount));
         ///
         /// // Rounded to 1 s.f
-        /// assert_eq!("~2 microFIL", format!("{:.1}", amount.pretty()));
+        /// assert_eq!("~2 microFIL", format!("{:.1}", amount));
         ///
         /// // Show absolute FIL
-        /// assert_eq!("0.0000015 FIL", format!("{:#}", amount.pretty()));
+        /// assert_eq!("0.0000015 FIL", format!("{:#}", amount));
         ///
         /// // Rounded absolute FIL
-        /// assert_eq!("~0.000002 FIL", format!("{:#.1}", amount.pretty()));
+        /// assert_eq!("~0.000002 FIL", format!("{:#.1}", amount));
         ///
         /// // We only indicate lost precision when relevant
-        /// assert_eq!("1.5 microFIL", format!("{:.2}", amount.pretty()));
+        /// assert_eq!("1.5 microFIL", format!("{:.2}", amount));
         /// ```
         ///
         /// # Formatting
@@ -565,76 +566,172 @@ mod print {
 
         #[test]
         fn test_display() {
-            assert_eq!("0 FIL", format!("{}", attos("0").pretty()));
+            assert_eq!(
+                "0 FIL",
+                format!("{}", TokenAmountPretty::pretty(&attos("0")))
+            );
 
             // Absolute works
-            assert_eq!("1 attoFIL", format!("{}", attos("1").pretty()));
+            assert_eq!(
+                "1 attoFIL",
+                format!("{}", TokenAmountPretty::pretty(&attos("1")))
+            );
             assert_eq!(
                 "0.000000000000000001 FIL",
-                format!("{:#}", attos("1").pretty())
+                format!("{:#}", TokenAmountPretty::pretty(&attos("1")))
             );
 
             // We select the right suffix
-            assert_eq!("1 femtoFIL", format!("{}", attos("1000").pretty()));
-            assert_eq!("1.001 femtoFIL", format!("{}", attos("1001").pretty()));
+            assert_eq!(
+                "1 femtoFIL",
+                format!("{}", TokenAmountPretty::pretty(&attos("1000")))
+            );
+            assert_eq!(
+                "1.001 femtoFIL",
+                format!("{}", TokenAmountPretty::pretty(&attos("1001")))
+            );
 
             // If you ask for 0 precision, you get it
-            assert_eq!("~0 FIL", format!("{:.0}", attos("1001").pretty()));
+            assert_eq!(
+                "~0 FIL",
+                format!("{:.0}", TokenAmountPretty::pretty(&attos("1001")))
+            );
 
             // Rounding without a prefix
-            assert_eq!("~10 FIL", format!("{:.1}", fils("11").pretty()));
+            assert_eq!(
+                "~10 FIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&fils("11")))
+            );
 
             // Rounding with absolute
             assert_eq!(
                 "~0.000000000000002 FIL",
-                format!("{:#.1}", attos("1940").pretty())
+                format!("{:#.1}", TokenAmountPretty::pretty(&attos("1940")))
             );
             assert_eq!(
                 "~0.0000000000000019 FIL",
-                format!("{:#.2}", attos("1940").pretty())
+                format!("{:#.2}", TokenAmountPretty::pretty(&attos("1940")))
             );
             assert_eq!(
                 "0.00000000000000194 FIL",
-                format!("{:#.3}", attos("1940").pretty())
+                format!("{:#.3}", TokenAmountPretty::pretty(&attos("1940")))
             );
 
             // Small numbers with a gap then a trailing one are rounded down
-            assert_eq!("~1 femtoFIL", format!("{:.1}", attos("1001").pretty()));
-            assert_eq!("~1 femtoFIL", format!("{:.2}", attos("1001").pretty()));
-            assert_eq!("~1 femtoFIL", format!("{:.3}", attos("1001").pretty()));
-            assert_eq!("1.001 femtoFIL", format!("{:.4}", attos("1001").pretty()));
-            assert_eq!("1.001 femtoFIL", format!("{:.5}", attos("1001").pretty()));
+            assert_eq!(
+                "~1 femtoFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&attos("1001")))
+            );
+            assert_eq!(
+                "~1 femtoFIL",
+                format!("{:.2}", TokenAmountPretty::pretty(&attos("1001")))
+            );
+            assert_eq!(
+                "~1 femtoFIL",
+                format!("{:.3}", TokenAmountPretty::pretty(&attos("1001")))
+            );
+            assert_eq!(
+                "1.001 femtoFIL",
+                format!("{:.4}", TokenAmountPretty::pretty(&attos("1001")))
+            );
+            assert_eq!(
+                "1.001 femtoFIL",
+                format!("{:.5}", TokenAmountPretty::pretty(&attos("1001")))
+            );
 
             // Small numbers with trailing numbers are rounded down
-            assert_eq!("~1 femtoFIL", format!("{:.1}", attos("1234").pretty()));
-            assert_eq!("~1.2 femtoFIL", format!("{:.2}", attos("1234").pretty()));
-            assert_eq!("~1.23 femtoFIL", format!("{:.3}", attos("1234").pretty()));
-            assert_eq!("1.234 femtoFIL", format!("{:.4}", attos("1234").pretty()));
-            assert_eq!("1.234 femtoFIL", format!("{:.5}", attos("1234").pretty()));
+            assert_eq!(
+                "~1 femtoFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&attos("1234")))
+            );
+            assert_eq!(
+                "~1.2 femtoFIL",
+                format!("{:.2}", TokenAmountPretty::pretty(&attos("1234")))
+            );
+            assert_eq!(
+                "~1.23 femtoFIL",
+                format!("{:.3}", TokenAmountPretty::pretty(&attos("1234")))
+            );
+            assert_eq!(
+                "1.234 femtoFIL",
+                format!("{:.4}", TokenAmountPretty::pretty(&attos("1234")))
+            );
+            assert_eq!(
+                "1.234 femtoFIL",
+                format!("{:.5}", TokenAmountPretty::pretty(&attos("1234")))
+            );
 
             // Small numbers are rounded appropriately
-            assert_eq!("~2 femtoFIL", format!("{:.1}", attos("1900").pretty()));
-            assert_eq!("~2 femtoFIL", format!("{:.1}", attos("1500").pretty()));
-            assert_eq!("~1 femtoFIL", format!("{:.1}", attos("1400").pretty()));
+            assert_eq!(
+                "~2 femtoFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&attos("1900")))
+            );
+            assert_eq!(
+                "~2 femtoFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&attos("1500")))
+            );
+            assert_eq!(
+                "~1 femtoFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&attos("1400")))
+            );
 
             // Big numbers with a gap then a trailing one are rounded down
-            assert_eq!("~1 kiloFIL", format!("{:.1}", fils("1001").pretty()));
-            assert_eq!("~1 kiloFIL", format!("{:.2}", fils("1001").pretty()));
-            assert_eq!("~1 kiloFIL", format!("{:.3}", fils("1001").pretty()));
-            assert_eq!("1.001 kiloFIL", format!("{:.4}", fils("1001").pretty()));
-            assert_eq!("1.001 kiloFIL", format!("{:.5}", fils("1001").pretty()));
+            assert_eq!(
+                "~1 kiloFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&fils("1001")))
+            );
+            assert_eq!(
+                "~1 kiloFIL",
+                format!("{:.2}", TokenAmountPretty::pretty(&fils("1001")))
+            );
+            assert_eq!(
+                "~1 kiloFIL",
+                format!("{:.3}", TokenAmountPretty::pretty(&fils("1001")))
+            );
+            assert_eq!(
+                "1.001 kiloFIL",
+                format!("{:.4}", TokenAmountPretty::pretty(&fils("1001")))
+            );
+            assert_eq!(
+                "1.001 kiloFIL",
+                format!("{:.5}", TokenAmountPretty::pretty(&fils("1001")))
+            );
 
             // Big numbers with trailing numbers are rounded down
-            assert_eq!("~1 kiloFIL", format!("{:.1}", fils("1234").pretty()));
-            assert_eq!("~1.2 kiloFIL", format!("{:.2}", fils("1234").pretty()));
-            assert_eq!("~1.23 kiloFIL", format!("{:.3}", fils("1234").pretty()));
-            assert_eq!("1.234 kiloFIL", format!("{:.4}", fils("1234").pretty()));
-            assert_eq!("1.234 kiloFIL", format!("{:.5}", fils("1234").pretty()));
+            assert_eq!(
+                "~1 kiloFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&fils("1234")))
+            );
+            assert_eq!(
+                "~1.2 kiloFIL",
+                format!("{:.2}", TokenAmountPretty::pretty(&fils("1234")))
+            );
+            assert_eq!(
+                "~1.23 kiloFIL",
+                format!("{:.3}", TokenAmountPretty::pretty(&fils("1234")))
+            );
+            assert_eq!(
+                "1.234 kiloFIL",
+                format!("{:.4}", TokenAmountPretty::pretty(&fils("1234")))
+            );
+            assert_eq!(
+                "1.234 kiloFIL",
+                format!("{:.5}", TokenAmountPretty::pretty(&fils("1234")))
+            );
 
             // Big numbers are rounded appropriately
-            assert_eq!("~2 kiloFIL", format!("{:.1}", fils("1900").pretty()));
-            assert_eq!("~2 kiloFIL", format!("{:.1}", fils("1500").pretty()));
-            assert_eq!("~1 kiloFIL", format!("{:.1}", fils("1400").pretty()));
+            assert_eq!(
+                "~2 kiloFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&fils("1900")))
+            );
+            assert_eq!(
+                "~2 kiloFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&fils("1500")))
+            );
+            assert_eq!(
+                "~1 kiloFIL",
+                format!("{:.1}", TokenAmountPretty::pretty(&fils("1400")))
+            );
         }
     }
 }
@@ -648,11 +745,11 @@ mod fuzz {
     quickcheck! {
         fn roundtrip(expected: crate::shim::econ::TokenAmount) -> () {
             // Default formatting
-            let actual = parse(&format!("{}", expected.pretty())).unwrap();
+            let actual = parse(&format!("{}", TokenAmountPretty::pretty(&expected))).unwrap();
             assert_eq!(expected, actual);
 
             // Absolute formatting
-            let actual = parse(&format!("{:#}", expected.pretty())).unwrap();
+            let actual = parse(&format!("{:#}", TokenAmountPretty::pretty(&expected))).unwrap();
             assert_eq!(expected, actual);
 
             // Don't test rounded formatting...
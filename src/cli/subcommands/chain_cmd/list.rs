@@ -62,17 +62,13 @@ impl ChainListCommand {
                 for b in ts.block_headers() {
                     let msgs = ChainGetBlockMessages::call(&client, (*b.cid(),)).await?;
                     let len = msgs.bls_msg.len() + msgs.secp_msg.len();
-                    let mut limit_sum = 0;
-                    let mut premium_sum = TokenAmount::zero();
+                    let messages = msgs
+                        .bls_msg
+                        .iter()
+                        .chain(msgs.secp_msg.iter().map(|m| m.message()));
+                    let limit_sum = messages.clone().map(|m| m.gas_limit).sum::<u64>();
+                    let premium_sum = messages.map(|m| &m.gas_premium).sum::<TokenAmount>();
                     let mut premium_avg = BigInt::zero();
-                    for m in &msgs.bls_msg {
-                        limit_sum += m.gas_limit;
-                        premium_sum += m.gas_premium.clone();
-                    }
-                    for m in &msgs.secp_msg {
-                        limit_sum += m.message().gas_limit;
-                        premium_sum += m.message().gas_premium.clone();
-                    }
 
                     if len > 0 {
                         premium_avg = premium_sum.atto() / BigInt::from(len);
@@ -12,6 +12,8 @@ use crate::cli::subcommands::Config;
 pub enum ConfigCommands {
     /// Dump default configuration to standard output
     Dump,
+    /// Check the F3 environment variables of the current shell for malformed values
+    Check,
 }
 
 impl ConfigCommands {
@@ -24,6 +26,18 @@ impl ConfigCommands {
                     .context("Could not convert configuration to TOML format")?
             )
             .context("Failed to write the configuration"),
+            Self::Check => {
+                let warnings = crate::f3::validate_env();
+                for warning in &warnings {
+                    writeln!(sink, "{warning}")?;
+                }
+                anyhow::ensure!(
+                    warnings.is_empty(),
+                    "found {} malformed environment variable(s)",
+                    warnings.len()
+                );
+                writeln!(sink, "No problems found").context("Failed to write the report")
+            }
         }
     }
 }
@@ -20,7 +20,6 @@ use crate::{
     shim::fvm_shared_latest::ActorID,
 };
 use ahash::HashSet;
-use anyhow::Context as _;
 use cid::Cid;
 use clap::{Subcommand, ValueEnum};
 use itertools::Itertools as _;
@@ -368,12 +367,7 @@ impl F3PowerTableCommands {
             (instance.saturating_sub(manifest.committee_lookback),),
         )
         .await?;
-        let tsk = lookback
-            .ec_chain
-            .last()
-            .context("lookback EC chain is empty")?
-            .key
-            .clone();
+        let tsk = lookback.chain_head().key.clone();
         Ok((tsk, previous.supplemental_data.power_table))
     }
 }
@@ -5,6 +5,7 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::blocks::Tipset;
 use crate::cli::humantoken::TokenAmountPretty;
+use crate::daemon::db_util::EthIndexStatus;
 use crate::rpc::{self, prelude::*};
 use crate::shim::address::Address;
 use crate::shim::clock::{BLOCKS_PER_EPOCH, ChainEpoch, EPOCH_DURATION_SECONDS};
@@ -37,6 +38,8 @@ pub struct NodeStatusInfo {
     pub network: String,
     pub default_wallet_address: Option<Address>,
     pub default_wallet_address_balance: Option<TokenAmount>,
+    /// Absent if the node doesn't report it, e.g. Lotus
+    pub eth_index: Option<EthIndexStatus>,
 }
 
 #[derive(Debug, strum::Display, PartialEq)]
@@ -88,6 +91,7 @@ impl NodeStatusInfo {
             network,
             default_wallet_address,
             default_wallet_address_balance,
+            eth_index: None,
         }
     }
 
@@ -107,7 +111,7 @@ impl NodeStatusInfo {
         };
 
         let chain = {
-            let base_fee_fmt = self.base_fee.pretty();
+            let base_fee_fmt = TokenAmountPretty::pretty(&self.base_fee);
             let lag_time = humantime::format_duration(Duration::from_secs(self.lag.unsigned_abs()));
             let behind = if self.lag < 0 {
                 format!("{lag_time} ahead")
@@ -121,7 +125,9 @@ impl NodeStatusInfo {
             )
         };
 
-        let chain_health = format!("Chain health: {:.2}%\n\n", self.health);
+        let chain_health = format!("Chain health: {:.2}%", self.health);
+
+        let eth_index = self.eth_index.map(|status| format!("eth index: {status}"));
 
         let wallet_info = {
             let wallet_address = self
@@ -133,13 +139,15 @@ impl NodeStatusInfo {
             let wallet_balance = self
                 .default_wallet_address_balance
                 .as_ref()
-                .map(|balance| format!("{:.4}", balance.pretty()))
+                .map(|balance| format!("{:.4}", TokenAmountPretty::pretty(balance)))
                 .unwrap_or("could not find balance".to_string());
 
             format!("Default wallet address: {wallet_address} [{wallet_balance}]")
         };
 
-        [network, uptime, chain, chain_health, wallet_info].join("\n")
+        let mut lines = vec![network, uptime, chain, chain_health];
+        lines.extend(eth_index);
+        [lines.join("\n"), wallet_info].join("\n\n\n")
     }
 }
 
@@ -159,20 +167,23 @@ impl InfoCommand {
 
         let default_wallet_address_balance = if let Some(def_addr) = default_wallet_address {
             let balance = WalletBalance::call(&client, (def_addr,)).await?;
-            Some(balance)
+            Some(balance.into_inner())
         } else {
             None
         };
 
-        let node_status_info = NodeStatusInfo::new(
-            cur_duration,
-            blocks_per_tipset_last_finality,
-            &head,
-            start_time,
-            network,
-            default_wallet_address,
-            default_wallet_address_balance,
-        );
+        let node_status_info = NodeStatusInfo {
+            eth_index: node_status.eth_index,
+            ..NodeStatusInfo::new(
+                cur_duration,
+                blocks_per_tipset_last_finality,
+                &head,
+                start_time,
+                network,
+                default_wallet_address,
+                default_wallet_address_balance,
+            )
+        };
 
         println!("{}", node_status_info.format(Utc::now()));
 
@@ -190,7 +201,7 @@ mod tests {
     use quickcheck_macros::quickcheck;
     use std::{str::FromStr, sync::Arc, time::Duration};
 
-    use super::{NodeStatusInfo, SyncStatus};
+    use super::{EthIndexStatus, NodeStatusInfo, SyncStatus};
 
     fn mock_tipset_at(seconds_since_unix_epoch: u64) -> Arc<Tipset> {
         let mock_header = CachingBlockHeader::new(RawBlockHeader {
@@ -214,6 +225,7 @@ mod tests {
             network: "calibnet".to_string(),
             default_wallet_address: None,
             default_wallet_address_balance: None,
+            eth_index: None,
         }
     }
 
@@ -288,6 +300,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn eth_index_status() {
+        let mut status = mock_node_status();
+        assert!(
+            !status
+                .format(DateTime::<chrono::Utc>::MIN_UTC)
+                .contains("eth index")
+        );
+        status.eth_index = Some(EthIndexStatus::Backfilling {
+            remaining_epochs: 42,
+        });
+        assert!(
+            status
+                .format(DateTime::<chrono::Utc>::MIN_UTC)
+                .contains("eth index: backfilling (42 epochs remaining)")
+        );
+    }
+
     #[test]
     fn chain_status_test() {
         let duration = Duration::from_secs(100_000);
@@ -35,6 +35,9 @@ pub enum SnapshotCommands {
         /// How many state-roots to include. Lower limit is 900 for `calibnet` and `mainnet`.
         #[arg(short, long)]
         depth: Option<crate::chain::ChainEpochDelta>,
+        /// Upload the snapshot to this URL once exported, resuming interrupted uploads.
+        #[arg(long, conflicts_with = "dry_run")]
+        upload_url: Option<url::Url>,
     },
 }
 
@@ -47,6 +50,7 @@ impl SnapshotCommands {
                 dry_run,
                 tipset,
                 depth,
+                upload_url,
             } => {
                 let chain_head = ChainHead::call(&client, ()).await?;
 
@@ -129,9 +133,14 @@ impl SnapshotCommands {
                 if let Some(hash) = hash_result {
                     save_checksum(&output_path, hash).await?;
                 }
-                temp_path.persist(output_path)?;
+                temp_path.persist(&output_path)?;
 
                 println!("Export completed.");
+
+                if let Some(upload_url) = upload_url {
+                    crate::utils::net::upload_forest_car(&output_path, &upload_url).await?;
+                    println!("Upload completed.");
+                }
                 Ok(())
             }
         }
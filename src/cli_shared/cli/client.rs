@@ -12,6 +12,8 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use crate::daemon::db_util::ImportMode;
+use crate::db::car::ProbeOrder;
+use crate::rpc::UnknownFields;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(transparent)]
@@ -34,7 +36,7 @@ impl Default for BufferSize {
 }
 
 #[serde_as]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 #[cfg_attr(test, derive(derive_quickcheck_arbitrary::Arbitrary))]
 pub struct Client {
@@ -47,6 +49,11 @@ pub struct Client {
     pub snapshot_head: Option<i64>,
     pub snapshot_path: Option<PathBuf>,
     pub import_mode: ImportMode,
+    /// Directory for the intermediate files of snapshot imports, e.g., downloads and
+    /// trans-coding output. Defaults to the car DB directory.
+    pub import_scratch_dir: Option<PathBuf>,
+    /// Rejects snapshots whose blocks are not in depth-first order when importing them.
+    pub import_strict_order: bool,
     /// Skips loading import CAR file and assumes it's already been loaded.
     /// Will use the CIDs in the header of the file to index the chain.
     pub skip_load: bool,
@@ -63,10 +70,32 @@ pub struct Client {
     pub rpc_address: SocketAddr,
     /// Path to a list of RPC methods to allow/disallow.
     pub rpc_filter_list: Option<PathBuf>,
+    /// Whether unknown fields in RPC request parameters are ignored or rejected.
+    /// Methods may override this.
+    pub rpc_unknown_fields: UnknownFields,
     /// Healthcheck bind, e.g. 127.0.0.1:2346
     pub healthcheck_address: SocketAddr,
     /// Load actors from the bundle file (possibly generating it if it doesn't exist)
     pub load_actors: bool,
+    /// Whether the database or the snapshot `CAR`s are probed first on block reads.
+    pub blockstore_probe_order: ProbeOrder,
+    /// Number of recently missed CIDs remembered per store, so that repeated
+    /// misses skip the lookup. Set to `0` to disable.
+    #[cfg_attr(test, arbitrary(gen(|g| u16::arbitrary(g) as _)))]
+    pub blockstore_negative_cache_size: usize,
+    /// Build a bloom filter with this false positive rate over the blocks of each
+    /// snapshot `CAR`, so that lookups skip the `CAR`s that don't hold a block.
+    #[cfg_attr(
+        test,
+        arbitrary(gen(|g| Option::<u8>::arbitrary(g).map(|it| (f64::from(it) + 1.0) / 1000.0)))
+    )]
+    pub blockstore_bloom_filter_false_positive_rate: Option<f64>,
+    /// Once the snapshot `CAR`s are loaded, build a single index over them, so that
+    /// lookups probe at most one `CAR`. This uses memory for every block of the `CAR`s.
+    pub blockstore_compact_index: bool,
+    /// Check each snapshot `CAR` in the car DB directory for corruption on startup,
+    /// skipping corrupt ones instead of failing to start.
+    pub verify_car_db_on_load: bool,
 }
 
 impl Default for Client {
@@ -80,6 +109,8 @@ impl Default for Client {
             enable_health_check: true,
             snapshot_path: None,
             import_mode: ImportMode::default(),
+            import_scratch_dir: None,
+            import_strict_order: false,
             snapshot_height: None,
             snapshot_head: None,
             skip_load: false,
@@ -89,11 +120,17 @@ impl Default for Client {
             metrics_address: FromStr::from_str("0.0.0.0:6116").unwrap(),
             rpc_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), crate::rpc::DEFAULT_PORT),
             rpc_filter_list: None,
+            rpc_unknown_fields: UnknownFields::default(),
             healthcheck_address: SocketAddr::new(
                 IpAddr::V4(Ipv4Addr::LOCALHOST),
                 crate::health::DEFAULT_HEALTHCHECK_PORT,
             ),
             load_actors: true,
+            blockstore_probe_order: ProbeOrder::default(),
+            blockstore_negative_cache_size: 0,
+            blockstore_bloom_filter_false_positive_rate: None,
+            blockstore_compact_index: false,
+            verify_car_db_on_load: false,
         }
     }
 }
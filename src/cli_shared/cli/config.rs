@@ -76,11 +76,22 @@ impl Default for FevmConfig {
 
 #[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone)]
 #[cfg_attr(test, derive(derive_quickcheck_arbitrary::Arbitrary))]
+#[serde(default)]
 pub struct ChainIndexerConfig {
     /// Enable indexing Ethereum mappings
     pub enable_indexer: bool,
     /// Number of retention epochs for indexed entries. Set to `None` to disable garbage collection.
     pub gc_retention_epochs: Option<u32>,
+    /// Number of epochs of tipset mappings to keep, older ones are pruned periodically.
+    /// Set to `None` to keep them all.
+    pub eth_mappings_retention_epochs: Option<u32>,
+    /// Backfill the Ethereum mappings on startup, within the retention epochs, if they
+    /// are missing or behind the chain head.
+    pub auto_backfill: bool,
+    /// Fail, rather than overwrite, when an Ethereum mapping conflicts with an existing one,
+    /// which indicates a bug or a corrupted database. Conflicts are logged either way, though
+    /// those of the message mappings are only looked for in debug builds otherwise.
+    pub strict_eth_mappings: bool,
 }
 
 impl Default for ChainIndexerConfig {
@@ -88,6 +99,9 @@ impl Default for ChainIndexerConfig {
         Self {
             enable_indexer: is_env_set_and_truthy(FOREST_CHAIN_INDEXER_ENABLED).unwrap_or(false),
             gc_retention_epochs: None,
+            eth_mappings_retention_epochs: None,
+            auto_backfill: true,
+            strict_eth_mappings: false,
         }
     }
 }
@@ -105,6 +119,7 @@ pub struct Config {
     pub events: EventsConfig,
     pub fevm: FevmConfig,
     pub chain_indexer: ChainIndexerConfig,
+    pub f3: crate::f3::F3Config,
 }
 
 impl Config {
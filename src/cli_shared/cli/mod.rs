@@ -88,9 +88,15 @@ pub struct CliOpts {
     /// Import a snapshot from a local CAR file or URL
     #[arg(long)]
     pub import_snapshot: Option<String>,
-    /// Snapshot import mode. Available modes are `auto`, `copy`, `move`, `symlink` and `hardlink`.
+    /// Snapshot import mode. Available modes are `auto`, `copy`, `move`, `move-with-symlink-back`, `symlink`, `hardlink` and `validate-only`.
     #[arg(long, default_value = "auto")]
     pub import_mode: ImportMode,
+    /// Directory for the intermediate files of snapshot imports, defaults to the car DB directory
+    #[arg(long)]
+    pub import_scratch_dir: Option<PathBuf>,
+    /// Reject a snapshot whose blocks are not in depth-first order. Requires a plain or zstd compressed CAR, and the `auto` or `copy` import mode
+    #[arg(long)]
+    pub import_strict_order: bool,
     /// Halt with exit code 0 after successfully importing a snapshot
     #[arg(long)]
     pub halt_after_import: bool,
@@ -154,6 +160,9 @@ pub struct CliOpts {
     /// Skip loading actors from the actors bundle.
     #[arg(long)]
     pub skip_load_actors: bool,
+    /// Start the F3 sidecar even if its data directory was created for another chain
+    #[arg(long)]
+    pub f3_force_root: bool,
 }
 
 impl CliOpts {
@@ -198,6 +207,10 @@ impl CliOpts {
         if let Some(snapshot_path) = &self.import_snapshot {
             cfg.client.snapshot_path = Some(snapshot_path.into());
             cfg.client.import_mode = self.import_mode;
+            cfg.client.import_strict_order = self.import_strict_order;
+        }
+        if let Some(import_scratch_dir) = &self.import_scratch_dir {
+            cfg.client.import_scratch_dir = Some(import_scratch_dir.clone());
         }
 
         cfg.client.snapshot_height = self.height;
@@ -68,11 +68,13 @@ fn get_chain_config_and_set_network(config: &Config) -> Arc<ChainConfig> {
     if chain_config.is_testnet() {
         CurrentNetwork::set_global(Network::Testnet);
     }
-    Arc::new(ChainConfig {
+    let mut chain_config = ChainConfig {
         enable_indexer: config.chain_indexer.enable_indexer,
         enable_receipt_event_caching: config.client.enable_rpc,
         ..chain_config
-    })
+    };
+    crate::f3::apply_f3_config(&mut chain_config, &config.f3);
+    Arc::new(chain_config)
 }
 
 fn get_or_create_p2p_keypair_and_peer_id(config: &Config) -> anyhow::Result<(Keypair, PeerId)> {
@@ -197,9 +199,23 @@ async fn setup_db(opts: &CliOpts, config: &Config) -> anyhow::Result<(Arc<DbType
     let chain_data_path = chain_path(config);
     let db_root_dir = db_root(&chain_data_path)?;
     let db_writer = Arc::new(open_db(db_root_dir.clone(), config.db_config())?);
-    let db = Arc::new(ManyCar::new(db_writer.clone()));
+    let mut db = ManyCar::new(db_writer.clone())
+        .with_probe_order(config.client.blockstore_probe_order)
+        .with_negative_cache(config.client.blockstore_negative_cache_size);
+    if let Some(false_positive_rate) = config.client.blockstore_bloom_filter_false_positive_rate {
+        anyhow::ensure!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "blockstore_bloom_filter_false_positive_rate must be between 0 and 1, got {false_positive_rate}"
+        );
+        db = db.with_bloom_filters(false_positive_rate);
+    }
+    let db = Arc::new(db);
     let forest_car_db_dir = db_root_dir.join(CAR_DB_DIR_NAME);
-    load_all_forest_cars_with_cleanup(&db, &forest_car_db_dir)?;
+    load_all_forest_cars_with_cleanup(
+        &db,
+        &forest_car_db_dir,
+        config.client.verify_car_db_on_load,
+    )?;
     if config.client.load_actors && !opts.stateless {
         load_actor_bundles(&db, config.chain()).await?;
     }
@@ -233,14 +249,17 @@ async fn create_state_manager(
         } else {
             Arc::new(DummyStore {})
         };
-    let chain_store = Arc::new(ChainStore::new(
-        Arc::clone(db),
-        Arc::new(db.clone()),
-        eth_mappings,
-        db.writer().clone(),
-        chain_config.clone(),
-        genesis_header.clone(),
-    )?);
+    let chain_store = Arc::new(
+        ChainStore::new(
+            Arc::clone(db),
+            Arc::new(db.clone()),
+            eth_mappings,
+            db.writer().clone(),
+            chain_config.clone(),
+            genesis_header.clone(),
+        )?
+        .with_strict_eth_mappings(config.chain_indexer.strict_eth_mappings),
+    );
 
     // Initialize StateManager
     let state_manager = Arc::new(StateManager::new(
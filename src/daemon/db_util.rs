@@ -1,23 +1,38 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use crate::blocks::Tipset;
+use crate::blocks::{Tipset, TipsetKey};
+use crate::chain::ChainStore;
 use crate::db::car::forest::{
     FOREST_CAR_FILE_EXTENSION, TEMP_FOREST_CAR_FILE_EXTENSION, new_forest_car_temp_path_in,
 };
 use crate::db::car::{ForestCar, ManyCar};
+use crate::db::{
+    EthMappingsStore, SettingsStore, SettingsStoreExt, setting_keys, write_tipset_eth_hashes,
+};
 use crate::interpreter::VMTrace;
+use crate::lotus_json::lotus_json_with_self;
+use crate::message::SignedMessage;
 use crate::networks::Height;
 use crate::rpc::sync::SnapshotProgressTracker;
 use crate::shim::clock::ChainEpoch;
-use crate::state_manager::{NO_CALLBACK, StateManager};
+use crate::state_manager::{NO_CALLBACK, StateManager, StateOutput};
 use crate::utils::db::car_stream::CarStream;
 use crate::utils::io::EitherMmapOrRandomAccessFile;
+use crate::utils::misc::env::{env_opt_if, env_or};
 use crate::utils::net::{DownloadFileOption, download_to};
 use anyhow::{Context, bail};
-use futures::TryStreamExt;
+use async_compression::tokio::bufread::ZstdDecoder;
+use futures::{StreamExt as _, TryStreamExt};
+use fvm_ipld_blockstore::{Blockstore as _, MemoryBlockstore};
+use parking_lot::RwLock;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
+use std::fmt;
+use std::future::Future;
+use std::io::Read as _;
+use std::num::NonZeroUsize;
 use std::{
     fs,
     path::{Path, PathBuf},
@@ -32,29 +47,35 @@ use walkdir::WalkDir;
 #[cfg(doc)]
 use crate::rpc::eth::types::EthHash;
 
-#[cfg(doc)]
-use crate::blocks::TipsetKey;
-
 #[cfg(doc)]
 use cid::Cid;
 
 /// Loads all `.forest.car.zst` snapshots and cleanup stale `.forest.car.zst.tmp` files.
+/// See [`load_all_forest_cars`] for `verify`.
 pub fn load_all_forest_cars_with_cleanup<T>(
     store: &ManyCar<T>,
     forest_car_db_dir: &Path,
+    verify: bool,
 ) -> anyhow::Result<()> {
-    load_all_forest_cars_internal(store, forest_car_db_dir, true)
+    load_all_forest_cars_internal(store, forest_car_db_dir, true, verify)
 }
 
-/// Loads all `.forest.car.zst` snapshots
-pub fn load_all_forest_cars<T>(store: &ManyCar<T>, forest_car_db_dir: &Path) -> anyhow::Result<()> {
-    load_all_forest_cars_internal(store, forest_car_db_dir, false)
+/// Loads all `.forest.car.zst` snapshots. With `verify`, each snapshot is
+/// checked with [`ForestCar::verify`] first, and corrupt ones are skipped with a
+/// warning instead of failing the whole load.
+pub fn load_all_forest_cars<T>(
+    store: &ManyCar<T>,
+    forest_car_db_dir: &Path,
+    verify: bool,
+) -> anyhow::Result<()> {
+    load_all_forest_cars_internal(store, forest_car_db_dir, false, verify)
 }
 
 fn load_all_forest_cars_internal<T>(
     store: &ManyCar<T>,
     forest_car_db_dir: &Path,
     cleanup: bool,
+    verify: bool,
 ) -> anyhow::Result<()> {
     if !forest_car_db_dir.is_dir() {
         fs::create_dir_all(forest_car_db_dir)?;
@@ -75,8 +96,23 @@ fn load_all_forest_cars_internal<T>(
         if let Some(filename) = file.file_name().and_then(OsStr::to_str) {
             if filename.ends_with(FOREST_CAR_FILE_EXTENSION) {
                 let car = ForestCar::try_from(file.as_path())
-                    .with_context(|| format!("Error loading car DB at {}", file.display()))?;
-                store.read_only(car.into())?;
+                    .map_err(anyhow::Error::from)
+                    .and_then(|car| {
+                        if verify {
+                            car.verify()?;
+                        }
+                        Ok(car)
+                    })
+                    .with_context(|| format!("Error loading car DB at {}", file.display()));
+                let car = match car {
+                    Ok(car) => car,
+                    Err(e) if verify => {
+                        warn!("Skipping corrupt car DB: {e:#}");
+                        continue;
+                    }
+                    Err(e) => return Err(e),
+                };
+                store.read_only_with_path(car.into(), file.clone())?;
                 debug!("Loaded car DB at {}", file.display());
             } else if cleanup && filename.ends_with(TEMP_FOREST_CAR_FILE_EXTENSION) {
                 // Only delete files that appear to be incomplete car DB files
@@ -119,21 +155,42 @@ pub enum ImportMode {
     Copy,
     /// Moves the snapshot to the database directory (or copies and deletes the original).
     Move,
+    /// Moves the snapshot to the database directory like `Move`, then creates a symbolic
+    /// link to it at the original path.
+    #[strum(to_string = "move-with-symlink-back")]
+    MoveWithSymlinkBack,
     /// Creates a symbolic link to the snapshot in the database directory.
     Symlink,
     /// Creates a symbolic link to the snapshot in the database directory.
     Hardlink,
+    /// Validates the snapshot in place, without writing anything to the database
+    /// directory.
+    #[strum(to_string = "validate-only")]
+    ValidateOnly,
 }
 
 /// This function validates and stores the CAR binary from `from_path`(either local path or URL) into the `{DB_ROOT}/car_db/`
 /// (automatically trans-code into `.forest.car.zst` format when needed), and returns its final file path and the heaviest tipset.
+///
+/// Intermediate files (downloads and trans-coding output) are written to `scratch_dir`, if set,
+/// instead of `forest_car_db_dir`. Only the final file is moved into `forest_car_db_dir`.
+///
+/// With `strict_order`, the snapshot is rejected unless its blocks are in depth-first order,
+/// see [`CarStream::with_strict_order`]. The order is checked while trans-coding, so the
+/// snapshot must be a plain or zstd compressed CAR, and `import_mode` must copy it, so that a
+/// rejected snapshot is left in place.
 pub async fn import_chain_as_forest_car(
     from_path: &Path,
     forest_car_db_dir: &Path,
+    scratch_dir: Option<&Path>,
     import_mode: ImportMode,
+    strict_order: bool,
     snapshot_progress_tracker: &SnapshotProgressTracker,
 ) -> anyhow::Result<(PathBuf, Tipset)> {
     info!("Importing chain from snapshot at: {}", from_path.display());
+    if strict_order && !matches!(import_mode, ImportMode::Auto | ImportMode::Copy) {
+        bail!("Strict order checks require the auto or copy import mode");
+    }
 
     let stopwatch = time::Instant::now();
 
@@ -142,10 +199,20 @@ pub async fn import_chain_as_forest_car(
         chrono::Utc::now().timestamp_millis()
     ));
 
+    let scratch_dir = scratch_dir.unwrap_or(forest_car_db_dir);
     let move_or_copy = |mode: ImportMode| {
         let forest_car_db_path = forest_car_db_path.clone();
         async move {
-            let downloaded_car_temp_path = new_forest_car_temp_path_in(forest_car_db_dir)?;
+            if Url::parse(&from_path.display().to_string()).is_err() {
+                ensure_free_space_for_import(
+                    from_path,
+                    mode,
+                    scratch_dir,
+                    forest_car_db_dir,
+                    fs2::available_space,
+                )?;
+            }
+            let downloaded_car_temp_path = new_forest_car_temp_path_in(scratch_dir)?;
             if let Ok(url) = Url::parse(&from_path.display().to_string()) {
                 download_to(
                     &url,
@@ -161,16 +228,32 @@ pub async fn import_chain_as_forest_car(
                 move_or_copy_file(from_path, &downloaded_car_temp_path, mode)?;
             }
 
-            if ForestCar::is_valid(&EitherMmapOrRandomAccessFile::open(
-                &downloaded_car_temp_path,
-            )?) {
-                downloaded_car_temp_path.persist(&forest_car_db_path)?;
+            let encoding = SnapshotEncoding::detect(&downloaded_car_temp_path)?;
+            if encoding == SnapshotEncoding::ForestCar {
+                if strict_order {
+                    bail!("Strict order checks require a plain or zstd compressed CAR snapshot");
+                }
+                persist_temp_path(downloaded_car_temp_path, &forest_car_db_path)?;
             } else {
                 // Use another temp file to make sure all final `.forest.car.zst` files are complete and valid.
-                let forest_car_db_temp_path = new_forest_car_temp_path_in(forest_car_db_dir)?;
-                transcode_into_forest_car(&downloaded_car_temp_path, &forest_car_db_temp_path)
+                let forest_car_db_temp_path = new_forest_car_temp_path_in(scratch_dir)?;
+                if encoding == SnapshotEncoding::WholeFileZstd {
+                    info!("Snapshot is a zstd compressed CAR, decompressing and transcoding");
+                    transcode_zstd_into_forest_car(
+                        &downloaded_car_temp_path,
+                        &forest_car_db_temp_path,
+                        strict_order,
+                    )
                     .await?;
-                forest_car_db_temp_path.persist(&forest_car_db_path)?;
+                } else {
+                    transcode_into_forest_car(
+                        &downloaded_car_temp_path,
+                        &forest_car_db_temp_path,
+                        strict_order,
+                    )
+                    .await?;
+                }
+                persist_temp_path(forest_car_db_temp_path, &forest_car_db_path)?;
             }
             anyhow::Ok(())
         }
@@ -181,7 +264,9 @@ pub async fn import_chain_as_forest_car(
             if Url::parse(&from_path.display().to_string()).is_ok() {
                 // Fallback to move if from_path is url
                 move_or_copy(ImportMode::Move).await?;
-            } else if ForestCar::is_valid(&EitherMmapOrRandomAccessFile::open(from_path)?) {
+            } else if !strict_order
+                && ForestCar::is_valid(&EitherMmapOrRandomAccessFile::open(from_path)?)
+            {
                 tracing::info!(
                     "Hardlinking {} to {}",
                     from_path.display(),
@@ -215,6 +300,22 @@ pub async fn import_chain_as_forest_car(
                 bail!("Snapshot file must be a valid forest.car.zst file");
             }
         }
+        ImportMode::MoveWithSymlinkBack => {
+            let from_path = std::path::absolute(from_path)?;
+            if ForestCar::is_valid(&EitherMmapOrRandomAccessFile::open(&from_path)?) {
+                move_or_copy(ImportMode::Move).await?;
+                let forest_car_db_path = std::path::absolute(&forest_car_db_path)?;
+                tracing::info!(
+                    "Symlinking {} back to {}",
+                    from_path.display(),
+                    forest_car_db_path.display()
+                );
+                std::os::unix::fs::symlink(forest_car_db_path, &from_path)
+                    .context("Error creating symlink back")?;
+            } else {
+                bail!("Snapshot file must be a valid forest.car.zst file");
+            }
+        }
         ImportMode::Hardlink => {
             if ForestCar::is_valid(&EitherMmapOrRandomAccessFile::open(from_path)?) {
                 tracing::info!(
@@ -228,6 +329,20 @@ pub async fn import_chain_as_forest_car(
                 bail!("Snapshot file must be a valid forest.car.zst file");
             }
         }
+        ImportMode::ValidateOnly => {
+            if Url::parse(&from_path.display().to_string()).is_ok() {
+                bail!("Only local snapshot files can be validated in place");
+            }
+            snapshot_progress_tracker.not_required();
+            let ts = validate_snapshot(from_path).await?;
+            info!(
+                "Validated snapshot in: {}s, heaviest tipset epoch: {}, key: {}",
+                stopwatch.elapsed().as_secs(),
+                ts.epoch(),
+                ts.key()
+            );
+            return Ok((from_path.to_owned(), ts));
+        }
     };
 
     let ts = ForestCar::try_from(forest_car_db_path.as_path())?.heaviest_tipset()?;
@@ -241,6 +356,104 @@ pub async fn import_chain_as_forest_car(
     Ok((forest_car_db_path, ts))
 }
 
+/// Environment variable for the safety margin of [`ensure_free_space_for_import`], as a
+/// percentage of the estimated space.
+const FREE_SPACE_MARGIN_ENV: &str = "FOREST_IMPORT_FREE_SPACE_MARGIN";
+const DEFAULT_FREE_SPACE_MARGIN_PERCENT: u64 = 10;
+
+/// Estimated disk usage of importing a local snapshot with [`ImportMode::Copy`] or
+/// [`ImportMode::Move`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ImportSpace {
+    /// In the scratch directory, for the copy and the trans-coding output.
+    scratch: u64,
+    /// Of the final `.forest.car.zst`, in the car DB directory.
+    output: u64,
+}
+
+impl ImportSpace {
+    /// Moves are assumed to be renames. A `.forest.car.zst` is assumed to be no larger
+    /// than the snapshot it is trans-coded from, which holds for uncompressed CARs, and
+    /// roughly for whole-file zstd ones.
+    fn estimate(from_path: &Path, mode: ImportMode) -> anyhow::Result<Self> {
+        let len = fs::metadata(from_path)?.len();
+        let copied = if mode == ImportMode::Copy { len } else { 0 };
+        let transcoded = match SnapshotEncoding::detect(from_path)? {
+            SnapshotEncoding::ForestCar => 0,
+            SnapshotEncoding::WholeFileZstd | SnapshotEncoding::Plain => len,
+        };
+        Ok(Self {
+            scratch: copied + transcoded,
+            output: len,
+        })
+    }
+}
+
+/// Fails early, rather than mid-way through trans-coding, if the filesystem of
+/// `scratch_dir` (or `forest_car_db_dir`, if different) doesn't have the space to import
+/// `from_path`, plus a safety margin, see [`FREE_SPACE_MARGIN_ENV`].
+fn ensure_free_space_for_import(
+    from_path: &Path,
+    mode: ImportMode,
+    scratch_dir: &Path,
+    forest_car_db_dir: &Path,
+    available_space: impl Fn(&Path) -> std::io::Result<u64>,
+) -> anyhow::Result<()> {
+    let margin_percent = env_or(FREE_SPACE_MARGIN_ENV, DEFAULT_FREE_SPACE_MARGIN_PERCENT);
+    let ImportSpace { scratch, output } = ImportSpace::estimate(from_path, mode)?;
+    let mut required = vec![(scratch_dir, scratch)];
+    if scratch_dir != forest_car_db_dir {
+        required.push((forest_car_db_dir, output));
+    }
+    for (dir, bytes) in required {
+        ensure_free_space(dir, bytes, margin_percent, &available_space)
+            .with_context(|| format!("Cannot import {}", from_path.display()))?;
+    }
+    Ok(())
+}
+
+/// Fails if the filesystem of `dir` has less than `bytes` free, plus `margin_percent`%.
+fn ensure_free_space(
+    dir: &Path,
+    bytes: u64,
+    margin_percent: u64,
+    available_space: impl Fn(&Path) -> std::io::Result<u64>,
+) -> anyhow::Result<()> {
+    if bytes == 0 {
+        return Ok(());
+    }
+    let required = u64::try_from(u128::from(bytes) * (100 + u128::from(margin_percent)) / 100)
+        .unwrap_or(u64::MAX);
+    let available = available_space(dir)
+        .with_context(|| format!("Error checking free space in {}", dir.display()))?;
+    anyhow::ensure!(
+        available >= required,
+        "not enough free space in {}: {} is required ({} plus a {margin_percent}% margin, set with {FREE_SPACE_MARGIN_ENV}), but only {} is available",
+        dir.display(),
+        human_bytes::human_bytes(required as f64),
+        human_bytes::human_bytes(bytes as f64),
+        human_bytes::human_bytes(available as f64),
+    );
+    Ok(())
+}
+
+/// Renames `temp_path` to `to`. If that fails, e.g., because they are on different devices,
+/// copies it to a temp file next to `to` first, so that `to` is only ever complete.
+fn persist_temp_path(temp_path: tempfile::TempPath, to: &Path) -> anyhow::Result<()> {
+    if let Err(tempfile::PathPersistError { error, path }) = temp_path.persist(to) {
+        debug!(
+            "Error renaming {} to {}, fallback to copy: {error}",
+            path.display(),
+            to.display()
+        );
+        let dir = to.parent().context("destination has no parent directory")?;
+        let copy_temp_path = new_forest_car_temp_path_in(dir)?;
+        fs::copy(&path, &copy_temp_path).context("Error copying file")?;
+        copy_temp_path.persist(to)?;
+    }
+    Ok(())
+}
+
 fn move_or_copy_file(from: &Path, to: &Path, import_mode: ImportMode) -> anyhow::Result<()> {
     match import_mode {
         ImportMode::Move => {
@@ -263,11 +476,109 @@ fn move_or_copy_file(from: &Path, to: &Path, import_mode: ImportMode) -> anyhow:
     }
 }
 
-async fn transcode_into_forest_car(from: &Path, to: &Path) -> anyhow::Result<()> {
+/// How a snapshot file is laid out on disk, as far as importing is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SnapshotEncoding {
+    /// A `.forest.car.zst`, which is used as-is.
+    ForestCar,
+    /// A CAR compressed as a whole, e.g. by the `zstd` CLI, rather than frame by
+    /// frame with an index like a `.forest.car.zst`.
+    WholeFileZstd,
+    /// Anything else, expected to be an uncompressed CAR.
+    Plain,
+}
+
+impl SnapshotEncoding {
+    /// See <https://github.com/facebook/zstd/blob/dev/doc/zstd_compression_format.md#zstandard-frames>.
+    const ZSTD_MAGIC: [u8; 4] = 0xFD2FB528_u32.to_le_bytes();
+
+    fn detect(path: &Path) -> anyhow::Result<Self> {
+        if ForestCar::is_valid(&EitherMmapOrRandomAccessFile::open(path)?) {
+            return Ok(Self::ForestCar);
+        }
+        let mut magic = [0; 4];
+        let is_zstd = match fs::File::open(path)?.read_exact(&mut magic) {
+            Ok(()) => magic == Self::ZSTD_MAGIC,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => false,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(if is_zstd {
+            Self::WholeFileZstd
+        } else {
+            Self::Plain
+        })
+    }
+}
+
+/// Checks that the snapshot at `path` is well-formed and returns its heaviest tipset,
+/// without copying it. A `.forest.car.zst` is checked by opening it, any other CAR
+/// (compressed or not) is scanned, checking the hash of every block.
+async fn validate_snapshot(path: &Path) -> anyhow::Result<Tipset> {
+    if SnapshotEncoding::detect(path)? == SnapshotEncoding::ForestCar {
+        return ForestCar::try_from(path)?.heaviest_tipset();
+    }
+    let mut car_stream = CarStream::new(tokio::io::BufReader::new(
+        tokio::fs::File::open(path).await?,
+    ))
+    .await
+    .context("Snapshot file is not a valid CAR")?;
+    let roots = car_stream.header_v1.roots.clone();
+    // Only the blocks of the heaviest tipset are kept, to load it
+    let root_blocks = MemoryBlockstore::new();
+    while let Some(block) = car_stream.try_next().await? {
+        block.validate()?;
+        if roots.contains(&block.cid) {
+            root_blocks.put_keyed(&block.cid, &block.data)?;
+        }
+    }
+    Tipset::load_required(&root_blocks, &TipsetKey::from(roots))
+}
+
+async fn transcode_into_forest_car(
+    from: &Path,
+    to: &Path,
+    strict_order: bool,
+) -> anyhow::Result<()> {
     let car_stream = CarStream::new(tokio::io::BufReader::new(
         tokio::fs::File::open(from).await?,
     ))
     .await?;
+    write_forest_car(car_stream, to, strict_order).await
+}
+
+/// Transcodes a [`SnapshotEncoding::WholeFileZstd`] snapshot, decompressing it
+/// as a single stream (of one or more zstd frames) and indexing the decompressed
+/// CAR as it goes.
+async fn transcode_zstd_into_forest_car(
+    from: &Path,
+    to: &Path,
+    strict_order: bool,
+) -> anyhow::Result<()> {
+    // The CARv2 header, if any, is extracted upfront as the decompressed stream
+    // cannot seek back.
+    let (file, header_v2) = CarStream::extract_header_v2_and_reset_reader_position(
+        tokio::io::BufReader::new(tokio::fs::File::open(from).await?),
+    )
+    .await
+    .context("failed to decompress zstd snapshot")?;
+    let mut decoder = ZstdDecoder::new(file);
+    decoder.multiple_members(true);
+    let car_stream = CarStream::new_with_header_v2(tokio::io::BufReader::new(decoder), header_v2)
+        .await
+        .context("zstd snapshot does not contain a valid CAR")?;
+    write_forest_car(car_stream, to, strict_order).await
+}
+
+async fn write_forest_car(
+    car_stream: CarStream<impl tokio::io::AsyncBufRead + Unpin>,
+    to: &Path,
+    strict_order: bool,
+) -> anyhow::Result<()> {
+    let car_stream = if strict_order {
+        car_stream.with_strict_order()
+    } else {
+        car_stream
+    };
     let roots = car_stream.header_v1.roots.clone();
 
     let mut writer = tokio::io::BufWriter::new(tokio::fs::File::create(to).await?);
@@ -293,18 +604,18 @@ pub fn populate_eth_mappings<DB>(
 where
     DB: fvm_ipld_blockstore::Blockstore,
 {
-    let mut delegated_messages = vec![];
-
     // Hygge is the start of Ethereum support in the FVM (through the FEVM actor).
     // Before this height, no notion of an Ethereum-like API existed.
     let hygge = state_manager.chain_config().epoch(Height::Hygge);
 
     // TODO(elmattic): https://github.com/ChainSafe/forest/issues/5567
-    let from_epoch = std::env::var("FOREST_ETH_MAPPINGS_RANGE")
-        .ok()
-        .and_then(|v| v.parse::<i64>().ok())
-        .map(|num_epochs| (head_ts.epoch().saturating_sub(num_epochs)).max(hygge))
-        .unwrap_or(hygge);
+    let from_epoch = env_opt_if::<i64>(
+        "FOREST_ETH_MAPPINGS_RANGE",
+        |&num_epochs| num_epochs >= 0,
+        "a non-negative number of epochs",
+    )
+    .map(|num_epochs| (head_ts.epoch().saturating_sub(num_epochs)).max(hygge))
+    .unwrap_or(hygge);
 
     tracing::info!(
         "Populating column EthMappings from range: [{}, {}]",
@@ -312,74 +623,311 @@ where
         head_ts.epoch()
     );
 
+    let chain_store = state_manager.chain_store();
+    let delegated_messages = collect_delegated_messages(chain_store, head_ts, from_epoch, |ts| {
+        Ok(chain_store.put_tipset_key(ts.key())?)
+    })?;
+    chain_store.process_signed_messages(&delegated_messages)?;
+
+    Ok(())
+}
+
+/// How far the `EthMappings` column is populated, see [`backfill_eth_mappings_if_behind`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum EthIndexStatus {
+    /// The indexer is disabled, or the column hasn't been checked yet.
+    #[default]
+    Unknown,
+    /// The column is being backfilled, `remaining_epochs` to go.
+    Backfilling { remaining_epochs: u64 },
+    /// The column is populated up to the chain head.
+    Ready,
+}
+lotus_json_with_self!(EthIndexStatus);
+
+impl fmt::Display for EthIndexStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => f.write_str("unknown"),
+            Self::Backfilling { remaining_epochs } => {
+                write!(f, "backfilling ({remaining_epochs} epochs remaining)")
+            }
+            Self::Ready => f.write_str("ready"),
+        }
+    }
+}
+
+static ETH_INDEX_STATUS: RwLock<EthIndexStatus> = RwLock::new(EthIndexStatus::Unknown);
+
+/// The state of the `EthMappings` column, see [`EthIndexStatus`].
+pub fn eth_index_status() -> EthIndexStatus {
+    *ETH_INDEX_STATUS.read()
+}
+
+fn set_eth_index_status(status: EthIndexStatus) {
+    *ETH_INDEX_STATUS.write() = status;
+}
+
+/// Records that the `EthMappings` column is populated up to `epoch`, so that
+/// [`backfill_eth_mappings_if_behind`] doesn't process it again. With `auto_backfill`,
+/// only recorded once the column is [ready](EthIndexStatus::Ready), as a backfill
+/// leaves a gap until it completes. Without it, nothing fills the gaps, so the
+/// checkpoint follows the indexed tipsets.
+pub fn advance_eth_mappings_checkpoint(
+    settings: &impl SettingsStore,
+    epoch: ChainEpoch,
+    auto_backfill: bool,
+) -> anyhow::Result<()> {
+    if !auto_backfill || eth_index_status() == EthIndexStatus::Ready {
+        SettingsStoreExt::write_obj(settings, setting_keys::ETH_MAPPINGS_CHECKPOINT_KEY, &epoch)?;
+    }
+    Ok(())
+}
+
+/// The epoch from which the `EthMappings` column has to be backfilled to cover
+/// `retention_epochs` (every epoch since Hygge if [`None`]) up to `head_epoch`,
+/// given that it is populated up to `checkpoint`. [`None`] if it's up to date.
+fn eth_mappings_backfill_from(
+    checkpoint: Option<ChainEpoch>,
+    head_epoch: ChainEpoch,
+    retention_epochs: Option<ChainEpoch>,
+    hygge: ChainEpoch,
+) -> Option<ChainEpoch> {
+    let mut from_epoch = hygge;
+    if let Some(retention_epochs) = retention_epochs {
+        from_epoch = from_epoch.max(head_epoch.saturating_sub(retention_epochs));
+    }
+    if let Some(checkpoint) = checkpoint {
+        from_epoch = from_epoch.max(checkpoint.saturating_add(1));
+    }
+    (from_epoch <= head_epoch).then_some(from_epoch)
+}
+
+/// Populates the `EthMappings` column for the tipsets it is missing, i.e. after the
+/// checkpoint persisted in `settings` and within `retention_epochs` of `head_ts`,
+/// reporting progress in [`eth_index_status`]. Returns the number of tipsets that
+/// were processed.
+///
+/// Unlike [`populate_eth_mappings`], this is meant to run in the background of a
+/// node that upgraded from an older version, or imported a fresh snapshot.
+pub fn backfill_eth_mappings_if_behind<DB>(
+    chain_store: &ChainStore<DB>,
+    settings: &impl SettingsStore,
+    head_ts: &Tipset,
+    retention_epochs: Option<ChainEpoch>,
+) -> anyhow::Result<usize>
+where
+    DB: fvm_ipld_blockstore::Blockstore,
+{
+    let checkpoint = SettingsStoreExt::read_obj::<ChainEpoch>(
+        settings,
+        setting_keys::ETH_MAPPINGS_CHECKPOINT_KEY,
+    )?;
+    let Some(from_epoch) = eth_mappings_backfill_from(
+        checkpoint,
+        head_ts.epoch(),
+        retention_epochs,
+        chain_store.chain_config.epoch(Height::Hygge),
+    ) else {
+        set_eth_index_status(EthIndexStatus::Ready);
+        return Ok(0);
+    };
+
+    info!(
+        "Backfilling column EthMappings from range: [{from_epoch}, {}]",
+        head_ts.epoch()
+    );
+    set_eth_index_status(EthIndexStatus::Backfilling {
+        remaining_epochs: head_ts.epoch().abs_diff(from_epoch) + 1,
+    });
+    let mut processed = 0;
     for ts in head_ts
         .clone()
-        .chain(&state_manager.chain_store().blockstore())
+        .chain(chain_store.blockstore())
+        .take_while(|ts| ts.epoch() >= from_epoch)
     {
-        if ts.epoch() < from_epoch {
-            break;
-        }
-        delegated_messages.append(
-            &mut state_manager
-                .chain_store()
-                .headers_delegated_messages(ts.block_headers().iter())?,
-        );
-        state_manager.chain_store().put_tipset_key(ts.key())?;
+        index_tipset_eth_mappings(chain_store, &ts)?;
+        processed += 1;
+        set_eth_index_status(EthIndexStatus::Backfilling {
+            remaining_epochs: ts.epoch().abs_diff(from_epoch),
+        });
     }
-    state_manager
-        .chain_store()
-        .process_signed_messages(&delegated_messages)?;
+    SettingsStoreExt::write_obj(
+        settings,
+        setting_keys::ETH_MAPPINGS_CHECKPOINT_KEY,
+        &head_ts.epoch(),
+    )?;
+    set_eth_index_status(EthIndexStatus::Ready);
+    info!("Backfilled column EthMappings with {processed} tipsets");
+    Ok(processed)
+}
 
+/// Writes the `EthMappings` entries of `ts`: its tipset key, and its delegated messages.
+pub fn index_tipset_eth_mappings<DB>(
+    chain_store: &ChainStore<DB>,
+    ts: &Tipset,
+) -> anyhow::Result<()>
+where
+    DB: fvm_ipld_blockstore::Blockstore,
+{
+    chain_store.put_tipset_key(ts.key())?;
+    chain_store.process_signed_messages(
+        &chain_store.headers_delegated_messages(ts.block_headers().iter())?,
+    )
+}
+
+/// Writes the events roots of `ts`, and the secondary index of its events, from its
+/// computed `state_output`, to the indices column, see [`backfill_db`].
+pub fn index_tipset_events<DB>(
+    chain_store: &ChainStore<DB>,
+    ts: &Tipset,
+    state_output: &StateOutput,
+) -> anyhow::Result<()>
+where
+    DB: fvm_ipld_blockstore::Blockstore,
+{
+    for events_root in state_output.events_roots.iter().flatten() {
+        chain_store.put_index(events_root, ts.key())?;
+    }
+    chain_store.put_events_index(ts, &state_output.events)?;
     Ok(())
 }
 
+/// The number of tipsets whose reverse mappings are written at once by
+/// [`backfill_tipset_eth_hashes`].
+const TIPSET_ETH_HASHES_BACKFILL_BATCH_SIZE: usize = 10_000;
+
+/// Writes the reverse tipset mappings, see [`ChainStore::get_eth_hash_by_tipset_key`],
+/// of a database populated before they were introduced, from its forward mappings. This
+/// is recorded in `db`, so that it's done once. Returns the number of tipsets that were
+/// processed.
+pub fn backfill_tipset_eth_hashes(
+    db: &(impl EthMappingsStore + SettingsStore),
+) -> anyhow::Result<usize> {
+    if SettingsStoreExt::read_obj::<bool>(db, setting_keys::ETH_MAPPINGS_REVERSE_INDEX_KEY)?
+        .unwrap_or_default()
+    {
+        return Ok(0);
+    }
+
+    let tipset_keys = db.get_tipset_keys()?;
+    info!(
+        "Backfilling reverse tipset mappings of column EthMappings for {} tipsets",
+        tipset_keys.len()
+    );
+    let mut processed = 0;
+    for batch in tipset_keys.chunks(TIPSET_ETH_HASHES_BACKFILL_BATCH_SIZE) {
+        write_tipset_eth_hashes(db, batch)?;
+        processed += batch.len();
+        debug!(
+            "Backfilled reverse tipset mappings for {processed}/{} tipsets",
+            tipset_keys.len()
+        );
+    }
+    SettingsStoreExt::write_obj(db, setting_keys::ETH_MAPPINGS_REVERSE_INDEX_KEY, &true)?;
+    Ok(tipset_keys.len())
+}
+
+/// The delegated messages, with the timestamps of their blocks, of the tipsets from
+/// `from_ts` back to `to_epoch` (inclusive), see [`ChainStore::headers_delegated_messages`].
+/// `on_tipset` is called with each of these tipsets, so that they're walked only once.
+pub fn collect_delegated_messages<DB>(
+    chain_store: &ChainStore<DB>,
+    from_ts: &Tipset,
+    to_epoch: ChainEpoch,
+    mut on_tipset: impl FnMut(&Tipset) -> anyhow::Result<()>,
+) -> anyhow::Result<Vec<(SignedMessage, u64)>>
+where
+    DB: fvm_ipld_blockstore::Blockstore,
+{
+    let mut delegated_messages = vec![];
+    for ts in from_ts
+        .clone()
+        .chain(chain_store.blockstore())
+        .take_while(|ts| ts.epoch() >= to_epoch)
+    {
+        on_tipset(&ts)?;
+        delegated_messages
+            .append(&mut chain_store.headers_delegated_messages(ts.block_headers().iter())?);
+    }
+    Ok(delegated_messages)
+}
+
 /// To support the Event RPC API, a new column has been added to parity-db for handling the mapping of:
 /// - [`Cid`] to [`TipsetKey`].
 ///
+/// It also holds the secondary index of events, see [`crate::chain::store::events_index`].
+///
 /// This function traverses the chain store and populates the new column accordingly.
 pub async fn backfill_db<DB>(
     state_manager: &Arc<StateManager<DB>>,
     head_ts: &Tipset,
     to_epoch: ChainEpoch,
+    max_concurrency: NonZeroUsize,
 ) -> anyhow::Result<()>
 where
     DB: fvm_ipld_blockstore::Blockstore + Send + Sync + 'static,
 {
-    let mut delegated_messages = vec![];
+    backfill_db_with(
+        state_manager.chain_store(),
+        head_ts,
+        to_epoch,
+        max_concurrency,
+        |ts| async move {
+            Ok(state_manager
+                .compute_tipset_state(ts, NO_CALLBACK, VMTrace::NotTraced)
+                .await?)
+        },
+    )
+    .await
+}
 
-    for ts in head_ts
-        .clone()
-        .chain(&state_manager.chain_store().blockstore())
-    {
-        let epoch = ts.epoch();
-        if epoch < to_epoch {
-            break;
-        }
-        let tsk = ts.key().clone();
+/// Default number of tipset states computed concurrently by [`backfill_db`].
+pub fn default_backfill_concurrency() -> NonZeroUsize {
+    std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
 
-        let ts = Arc::new(ts);
+/// Computes the states of up to `max_concurrency` tipsets ahead, while the
+/// indices are still written in chain order.
+async fn backfill_db_with<DB, F, Fut>(
+    chain_store: &ChainStore<DB>,
+    head_ts: &Tipset,
+    to_epoch: ChainEpoch,
+    max_concurrency: NonZeroUsize,
+    compute_tipset_state: F,
+) -> anyhow::Result<()>
+where
+    DB: fvm_ipld_blockstore::Blockstore,
+    F: Fn(Arc<Tipset>) -> Fut,
+    Fut: Future<Output = anyhow::Result<StateOutput>>,
+{
+    let tipsets = head_ts
+        .clone()
+        .chain(chain_store.blockstore())
+        .take_while(|ts| ts.epoch() >= to_epoch)
+        .map(Arc::new);
+    let mut states = futures::stream::iter(tipsets)
+        .map(|ts| {
+            let state_output = compute_tipset_state(ts.clone());
+            async move { anyhow::Ok((ts, state_output.await?)) }
+        })
+        .buffered(max_concurrency.get());
 
-        let state_output = state_manager
-            .compute_tipset_state(ts.clone(), NO_CALLBACK, VMTrace::NotTraced)
-            .await?;
+    let mut delegated_messages = vec![];
+    while let Some((ts, state_output)) = states.try_next().await? {
+        let epoch = ts.epoch();
+        let tsk = ts.key();
         for events_root in state_output.events_roots.iter().flatten() {
             println!("Indexing events root @{epoch}: {events_root}");
-
-            state_manager.chain_store().put_index(events_root, &tsk)?;
         }
+        index_tipset_events(chain_store, &ts, &state_output)?;
 
-        delegated_messages.append(
-            &mut state_manager
-                .chain_store()
-                .headers_delegated_messages(ts.block_headers().iter())?,
-        );
-        println!("Indexing tipset @{}: {}", epoch, &tsk);
-        state_manager.chain_store().put_tipset_key(&tsk)?;
+        println!("Indexing tipset @{epoch}: {tsk}");
+        chain_store.put_tipset_key(tsk)?;
+        delegated_messages
+            .append(&mut chain_store.headers_delegated_messages(ts.block_headers().iter())?);
     }
-
-    state_manager
-        .chain_store()
-        .process_signed_messages(&delegated_messages)?;
+    chain_store.process_signed_messages(&delegated_messages)?;
 
     Ok(())
 }
@@ -387,17 +935,264 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::blocks::TipsetKey;
+    use crate::db::MemoryDB;
+    use crate::networks::ChainConfig;
+    use crate::test_utils::chain4_store;
+    use fvm_ipld_blockstore::Blockstore as _;
+
+    #[tokio::test]
+    async fn backfill_db_concurrent_matches_sequential() {
+        async fn backfill(max_concurrency: usize) -> MemoryDB {
+            let (db, chain_store, head_ts) = chain4_store(ChainConfig::default()).await;
+            backfill_db_with(
+                &chain_store,
+                &head_ts,
+                0,
+                NonZeroUsize::new(max_concurrency).unwrap(),
+                |ts| async move {
+                    // Make the computations finish out of order
+                    tokio::time::sleep(time::Duration::from_millis(ts.epoch() as u64 % 3)).await;
+                    Ok(StateOutput {
+                        state_root: *ts.parent_state(),
+                        receipt_root: *ts.parent_state(),
+                        events: vec![],
+                        events_roots: vec![Some(ts.key().cid()?)],
+                    })
+                },
+            )
+            .await
+            .unwrap();
+            drop(chain_store);
+            Arc::into_inner(db).unwrap()
+        }
+
+        let sequential = backfill(1).await;
+        assert!(!sequential.indices_db.read().is_empty());
+        for max_concurrency in [2, 8] {
+            let concurrent = backfill(max_concurrency).await;
+            assert_eq!(*concurrent.indices_db.read(), *sequential.indices_db.read());
+            assert_eq!(
+                *concurrent.eth_mappings_db.read(),
+                *sequential.eth_mappings_db.read()
+            );
+        }
+    }
+
+    #[test]
+    fn eth_mappings_backfill_range() {
+        let hygge = 10;
+        // Nothing indexed yet
+        assert_eq!(eth_mappings_backfill_from(None, 100, None, hygge), Some(10));
+        assert_eq!(
+            eth_mappings_backfill_from(None, 100, Some(30), hygge),
+            Some(70)
+        );
+        assert_eq!(eth_mappings_backfill_from(None, 5, None, hygge), None);
+        // Partially indexed
+        assert_eq!(
+            eth_mappings_backfill_from(Some(80), 100, None, hygge),
+            Some(81)
+        );
+        assert_eq!(
+            eth_mappings_backfill_from(Some(50), 100, Some(30), hygge),
+            Some(70)
+        );
+        // Up to date
+        assert_eq!(
+            eth_mappings_backfill_from(Some(100), 100, None, hygge),
+            None
+        );
+        assert_eq!(
+            eth_mappings_backfill_from(Some(120), 100, None, hygge),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn backfill_eth_mappings_only_processes_the_gap() {
+        fn indexed_epochs(chain_store: &ChainStore<MemoryDB>, head_ts: &Tipset) -> Vec<ChainEpoch> {
+            head_ts
+                .clone()
+                .chain(chain_store.blockstore())
+                .filter(|ts| {
+                    chain_store
+                        .get_required_tipset_key(&ts.key().cid().unwrap().into())
+                        .is_ok()
+                })
+                .map(|ts| ts.epoch())
+                .collect()
+        }
+
+        // Indexed up to a few epochs before the head
+        let (db, chain_store, head_ts) = chain4_store(ChainConfig::devnet()).await;
+        let epochs = head_ts
+            .clone()
+            .chain(&db)
+            .map(|ts| ts.epoch())
+            .collect::<Vec<_>>();
+        let checkpoint = epochs[3];
+        SettingsStoreExt::write_obj(&db, setting_keys::ETH_MAPPINGS_CHECKPOINT_KEY, &checkpoint)
+            .unwrap();
+        let processed = backfill_eth_mappings_if_behind(&chain_store, &db, &head_ts, None).unwrap();
+        assert_eq!(processed, 3);
+        assert_eq!(indexed_epochs(&chain_store, &head_ts), epochs[..3]);
+        assert_eq!(
+            SettingsStoreExt::read_obj::<ChainEpoch>(
+                &db,
+                setting_keys::ETH_MAPPINGS_CHECKPOINT_KEY
+            )
+            .unwrap(),
+            Some(head_ts.epoch())
+        );
+        // Up to date
+        assert_eq!(
+            backfill_eth_mappings_if_behind(&chain_store, &db, &head_ts, None).unwrap(),
+            0
+        );
+
+        // Not indexed at all, within the retention epochs
+        let (db, chain_store, head_ts) = chain4_store(ChainConfig::devnet()).await;
+        let processed =
+            backfill_eth_mappings_if_behind(&chain_store, &db, &head_ts, Some(2)).unwrap();
+        let expected = epochs
+            .iter()
+            .copied()
+            .take_while(|&epoch| epoch >= head_ts.epoch() - 2)
+            .collect::<Vec<_>>();
+        assert_eq!(processed, expected.len());
+        assert_eq!(indexed_epochs(&chain_store, &head_ts), expected);
+    }
+
+    #[tokio::test]
+    async fn backfill_tipset_eth_hashes_from_forward_mappings() {
+        let (db, chain_store, head_ts) = chain4_store(ChainConfig::default()).await;
+        let tipsets = head_ts.clone().chain(&db).collect::<Vec<_>>();
+
+        // Populated by a version without the reverse mappings
+        for ts in &tipsets {
+            let hash = ts.key().cid().unwrap().into();
+            crate::db::EthMappingsStoreExt::write_obj(db.as_ref(), &hash, ts.key()).unwrap();
+        }
+        for ts in &tipsets {
+            assert_eq!(
+                chain_store.get_eth_hash_by_tipset_key(ts.key()).unwrap(),
+                None
+            );
+        }
+
+        assert_eq!(backfill_tipset_eth_hashes(&db).unwrap(), tipsets.len());
+        for ts in &tipsets {
+            let hash = chain_store
+                .get_eth_hash_by_tipset_key(ts.key())
+                .unwrap()
+                .unwrap();
+            assert_eq!(hash, ts.key().cid().unwrap().into());
+            assert_eq!(
+                chain_store.get_required_tipset_key(&hash).unwrap(),
+                *ts.key()
+            );
+        }
+        // Done once
+        assert_eq!(backfill_tipset_eth_hashes(&db).unwrap(), 0);
+
+        // Populated by this version, both directions at once
+        let tsk = TipsetKey::from(nunny::vec![*head_ts.min_ticket_block().cid()]);
+        chain_store.put_tipset_key(&tsk).unwrap();
+        let hash = tsk.cid().unwrap().into();
+        assert_eq!(
+            crate::db::read_tipset_eth_hash(db.as_ref(), &tsk).unwrap(),
+            Some(hash.clone())
+        );
+        assert_eq!(chain_store.get_required_tipset_key(&hash).unwrap(), tsk);
+    }
+
+    #[test]
+    fn collect_delegated_messages_in_range() {
+        use crate::blocks::{CachingBlockHeader, RawBlockHeader};
+        use crate::chain_sync::TipsetValidator;
+        use crate::test_utils::{
+            construct_eth_messages, construct_messages, memory_chain_store_with_genesis,
+        };
+
+        let db = Arc::new(MemoryDB::default());
+        // Each tipset has a delegated message, and one signed with secp256k1
+        let (_, secp) = construct_messages();
+        let mut chain: Vec<Tipset> = vec![];
+        let mut delegated = vec![];
+        for epoch in 0..4 {
+            let (_, eth) = construct_eth_messages(epoch as u64);
+            let secp_msgs = [eth.clone(), secp.clone()];
+            crate::chain::persist_objects(&db, secp_msgs.iter()).unwrap();
+            let header = CachingBlockHeader::new(RawBlockHeader {
+                epoch,
+                timestamp: 100 + epoch as u64,
+                messages: TipsetValidator::compute_msg_root(&db, &[], &secp_msgs).unwrap(),
+                parents: match chain.last() {
+                    Some(parent) => parent.key().clone(),
+                    None => RawBlockHeader::default().parents,
+                },
+                ..Default::default()
+            });
+            crate::chain::persist_objects(&db, std::iter::once(&header)).unwrap();
+            delegated.push((eth, header.timestamp));
+            chain.push(Tipset::from(header));
+        }
+        let chain_store = memory_chain_store_with_genesis(
+            &db,
+            ChainConfig::devnet(),
+            chain[0].min_ticket_block().clone(),
+        );
+        let head_ts = chain.last().unwrap();
+
+        let collect = |from_ts: &Tipset, to_epoch| {
+            let mut walked = vec![];
+            let collected = collect_delegated_messages(&chain_store, from_ts, to_epoch, |ts| {
+                walked.push(ts.epoch());
+                Ok(())
+            })
+            .unwrap();
+            (collected, walked)
+        };
+
+        // From the head back, inclusive
+        assert_eq!(
+            collect(head_ts, 1),
+            (
+                delegated[1..].iter().rev().cloned().collect::<Vec<_>>(),
+                vec![3, 2, 1]
+            )
+        );
+        assert_eq!(
+            collect(head_ts, 0),
+            (
+                delegated.iter().rev().cloned().collect::<Vec<_>>(),
+                vec![3, 2, 1, 0]
+            )
+        );
+        assert_eq!(collect(&chain[1], 1), (vec![delegated[1].clone()], vec![1]));
+        assert_eq!(collect(head_ts, 4), (vec![], vec![]));
+    }
 
     #[tokio::test]
     async fn import_snapshot_from_file_valid() {
-        for import_mode in [ImportMode::Auto, ImportMode::Copy, ImportMode::Move] {
+        for import_mode in [
+            ImportMode::Auto,
+            ImportMode::Copy,
+            ImportMode::Move,
+            ImportMode::ValidateOnly,
+        ] {
             import_snapshot_from_file("test-snapshots/chain4.car", import_mode)
                 .await
                 .unwrap();
         }
 
         // Linking is not supported for raw CAR files.
-        for import_mode in [ImportMode::Symlink, ImportMode::Hardlink] {
+        for import_mode in [
+            ImportMode::Symlink,
+            ImportMode::Hardlink,
+            ImportMode::MoveWithSymlinkBack,
+        ] {
             import_snapshot_from_file("test-snapshots/chain4.car", import_mode)
                 .await
                 .unwrap_err();
@@ -406,28 +1201,253 @@ mod test {
 
     #[tokio::test]
     async fn import_snapshot_from_compressed_file_valid() {
-        for import_mode in [ImportMode::Auto, ImportMode::Copy, ImportMode::Move] {
+        for import_mode in [
+            ImportMode::Auto,
+            ImportMode::Copy,
+            ImportMode::Move,
+            ImportMode::ValidateOnly,
+        ] {
             import_snapshot_from_file("test-snapshots/chain4.car.zst", import_mode)
                 .await
                 .unwrap();
         }
 
         // Linking is not supported for raw CAR files.
-        for import_mode in [ImportMode::Symlink, ImportMode::Hardlink] {
+        for import_mode in [
+            ImportMode::Symlink,
+            ImportMode::Hardlink,
+            ImportMode::MoveWithSymlinkBack,
+        ] {
             import_snapshot_from_file("test-snapshots/chain4.car", import_mode)
                 .await
                 .unwrap_err();
         }
     }
 
+    #[test]
+    fn import_space_estimate() {
+        let len = |path: &str| fs::metadata(path).unwrap().len();
+        for (path, mode, scratch) in [
+            ("test-snapshots/chain4.car", ImportMode::Copy, 2),
+            ("test-snapshots/chain4.car", ImportMode::Move, 1),
+            ("test-snapshots/chain4.car.zst", ImportMode::Copy, 2),
+            ("test-snapshots/chain4.forest.car.zst", ImportMode::Copy, 1),
+            ("test-snapshots/chain4.forest.car.zst", ImportMode::Move, 0),
+        ] {
+            assert_eq!(
+                ImportSpace::estimate(Path::new(path), mode).unwrap(),
+                ImportSpace {
+                    scratch: scratch * len(path),
+                    output: len(path),
+                },
+                "{path} {mode}"
+            );
+        }
+    }
+
+    #[test]
+    fn ensure_free_space_with_margin() {
+        let dir = Path::new("db");
+        let available = |bytes: u64| move |_: &Path| Ok(bytes);
+        ensure_free_space(dir, 0, 10, |_| Err(std::io::Error::other("unused"))).unwrap();
+        ensure_free_space(dir, 1000, 10, available(1100)).unwrap();
+        ensure_free_space(dir, 1000, 0, available(1000)).unwrap();
+        let err = ensure_free_space(dir, 1000, 10, available(1099)).unwrap_err();
+        assert!(
+            err.to_string().starts_with("not enough free space in db"),
+            "{err}"
+        );
+        ensure_free_space(dir, u64::MAX, 10, available(u64::MAX)).unwrap();
+        ensure_free_space(dir, 1, 10, |_| Err(std::io::Error::other("statvfs failed")))
+            .unwrap_err();
+    }
+
+    #[test]
+    fn ensure_free_space_for_import_low_disk() {
+        let from_path = Path::new("test-snapshots/chain4.car");
+        let len = fs::metadata(from_path).unwrap().len();
+        let (db, scratch) = (Path::new("db"), Path::new("scratch"));
+        // Simulates a disk with `free` bytes available in `dir`, and plenty elsewhere
+        let low_disk = |dir: &'static Path, free: u64| {
+            move |path: &Path| Ok(if path == dir { free } else { u64::MAX })
+        };
+
+        // Copy and trans-code
+        ensure_free_space_for_import(from_path, ImportMode::Copy, db, db, low_disk(db, 3 * len))
+            .unwrap();
+        let err = ensure_free_space_for_import(
+            from_path,
+            ImportMode::Copy,
+            db,
+            db,
+            low_disk(db, 2 * len),
+        )
+        .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("not enough free space in db"),
+            "{err:#}"
+        );
+
+        // Only the output goes to the car DB directory
+        ensure_free_space_for_import(
+            from_path,
+            ImportMode::Copy,
+            scratch,
+            db,
+            low_disk(db, 2 * len),
+        )
+        .unwrap();
+        ensure_free_space_for_import(from_path, ImportMode::Copy, scratch, db, low_disk(db, len))
+            .unwrap_err();
+        ensure_free_space_for_import(
+            from_path,
+            ImportMode::Copy,
+            scratch,
+            db,
+            low_disk(scratch, 2 * len),
+        )
+        .unwrap_err();
+
+        // Nothing is copied or trans-coded
+        ensure_free_space_for_import(
+            Path::new("test-snapshots/chain4.forest.car.zst"),
+            ImportMode::Move,
+            db,
+            db,
+            low_disk(db, 0),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn snapshot_encoding_detect() {
+        for (path, expected) in [
+            ("test-snapshots/chain4.car", SnapshotEncoding::Plain),
+            (
+                "test-snapshots/chain4.car.zst",
+                SnapshotEncoding::WholeFileZstd,
+            ),
+            (
+                "test-snapshots/carv2.car.zst",
+                SnapshotEncoding::WholeFileZstd,
+            ),
+            (
+                "test-snapshots/chain4.forest.car.zst",
+                SnapshotEncoding::ForestCar,
+            ),
+            ("Cargo.toml", SnapshotEncoding::Plain),
+        ] {
+            assert_eq!(
+                SnapshotEncoding::detect(Path::new(path)).unwrap(),
+                expected,
+                "{path}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn import_snapshot_strict_order() {
+        let temp_db_dir = tempfile::Builder::new().tempdir().unwrap();
+        let import = |import_mode| {
+            import_chain_as_forest_car(
+                Path::new("test-snapshots/chain4.car"),
+                temp_db_dir.path(),
+                None,
+                import_mode,
+                true,
+                &SnapshotProgressTracker::default(),
+            )
+        };
+        // Forest exports emit the messages and state of each tipset before its parents
+        let error = import(ImportMode::Copy).await.unwrap_err();
+        assert!(
+            format!("{error:#}").contains("out of first-seen depth-first order"),
+            "{error:#}"
+        );
+        import(ImportMode::Symlink).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn import_snapshot_from_whole_file_zstd() {
+        // Compressed with the `zstd` CLI, as a single stream without an index
+        let file_path = Path::new("test-snapshots/chain4.car.zst");
+        let temp_db_dir = tempfile::Builder::new().tempdir().unwrap();
+        let (path, ts) = import_chain_as_forest_car(
+            file_path,
+            temp_db_dir.path(),
+            None,
+            ImportMode::Copy,
+            false,
+            &SnapshotProgressTracker::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            SnapshotEncoding::detect(&path).unwrap(),
+            SnapshotEncoding::ForestCar
+        );
+
+        let plain = CarStream::new(tokio::io::BufReader::new(
+            tokio::fs::File::open(file_path).await.unwrap(),
+        ))
+        .await
+        .unwrap();
+        assert_eq!(ts.key(), &TipsetKey::from(plain.header_v1.roots.clone()));
+        let forest_car = ForestCar::try_from(path.as_path()).unwrap();
+        for block in plain.try_collect::<Vec<_>>().await.unwrap() {
+            assert!(forest_car.has(&block.cid).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn import_snapshot_with_scratch_dir() {
+        for file_path in [
+            "test-snapshots/chain4.car",
+            "test-snapshots/chain4.car.zst",
+            "test-snapshots/chain4.forest.car.zst",
+        ] {
+            let temp_db_dir = tempfile::Builder::new().tempdir().unwrap();
+            let scratch_dir = tempfile::Builder::new().tempdir().unwrap();
+            let (path, ts) = import_chain_as_forest_car(
+                Path::new(file_path),
+                temp_db_dir.path(),
+                Some(scratch_dir.path()),
+                ImportMode::Copy,
+                false,
+                &SnapshotProgressTracker::default(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(path.parent(), Some(temp_db_dir.path()), "{file_path}");
+            assert_eq!(
+                SnapshotEncoding::detect(&path).unwrap(),
+                SnapshotEncoding::ForestCar,
+                "{file_path}"
+            );
+            assert!(ts.epoch() > 0, "{file_path}");
+            assert_eq!(
+                fs::read_dir(temp_db_dir.path()).unwrap().count(),
+                1,
+                "{file_path}"
+            );
+            assert_eq!(
+                fs::read_dir(scratch_dir.path()).unwrap().count(),
+                0,
+                "{file_path}"
+            );
+        }
+    }
+
     #[tokio::test]
     async fn import_snapshot_from_forest_car_valid() {
         for import_mode in [
             ImportMode::Auto,
             ImportMode::Copy,
             ImportMode::Move,
+            ImportMode::MoveWithSymlinkBack,
             ImportMode::Symlink,
             ImportMode::Hardlink,
+            ImportMode::ValidateOnly,
         ] {
             import_snapshot_from_file("test-snapshots/chain4.forest.car.zst", import_mode)
                 .await
@@ -441,8 +1461,10 @@ mod test {
             ImportMode::Auto,
             ImportMode::Copy,
             ImportMode::Move,
+            ImportMode::MoveWithSymlinkBack,
             ImportMode::Symlink,
             ImportMode::Hardlink,
+            ImportMode::ValidateOnly,
         ] {
             import_snapshot_from_file("Cargo.toml", *import_mode)
                 .await
@@ -456,8 +1478,10 @@ mod test {
             ImportMode::Auto,
             ImportMode::Copy,
             ImportMode::Move,
+            ImportMode::MoveWithSymlinkBack,
             ImportMode::Symlink,
             ImportMode::Hardlink,
+            ImportMode::ValidateOnly,
         ] {
             import_snapshot_from_file("dummy.car", *import_mode)
                 .await
@@ -471,8 +1495,10 @@ mod test {
             ImportMode::Auto,
             ImportMode::Copy,
             ImportMode::Move,
+            ImportMode::MoveWithSymlinkBack,
             ImportMode::Symlink,
             ImportMode::Hardlink,
+            ImportMode::ValidateOnly,
         ] {
             import_snapshot_from_file("https://forest.chainsafe.io/dummy.car", *import_mode)
                 .await
@@ -480,6 +1506,77 @@ mod test {
         }
     }
 
+    #[test]
+    fn load_all_forest_cars_skips_corrupt_cars() {
+        let car_db_dir = tempfile::Builder::new().tempdir().unwrap();
+        let good = fs::read("test-snapshots/chain4.forest.car.zst").unwrap();
+        // The header z-frame is intact, but the first block z-frame isn't
+        let mut corrupt = good.clone();
+        corrupt[512..1024].fill(0);
+        fs::write(car_db_dir.path().join("good.forest.car.zst"), &good).unwrap();
+        fs::write(car_db_dir.path().join("corrupt.forest.car.zst"), &corrupt).unwrap();
+
+        let store = ManyCar::new(MemoryDB::default());
+        load_all_forest_cars(&store, car_db_dir.path(), true).unwrap();
+        assert_eq!(store.len(), 1);
+        let expected = ForestCar::try_from(Path::new("test-snapshots/chain4.forest.car.zst"))
+            .unwrap()
+            .heaviest_tipset()
+            .unwrap();
+        assert_eq!(store.heaviest_tipset().unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn import_snapshot_validate_only() {
+        for file_path in [
+            "test-snapshots/chain4.car",
+            "test-snapshots/chain4.car.zst",
+            "test-snapshots/chain4.forest.car.zst",
+        ] {
+            let temp_db_dir = tempfile::Builder::new().tempdir().unwrap();
+            let (path, ts) = import_chain_as_forest_car(
+                Path::new(file_path),
+                temp_db_dir.path(),
+                None,
+                ImportMode::ValidateOnly,
+                false,
+                &SnapshotProgressTracker::default(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(path, Path::new(file_path));
+            assert_eq!(
+                fs::read_dir(temp_db_dir.path()).unwrap().count(),
+                0,
+                "{file_path}"
+            );
+            let expected = ForestCar::try_from(Path::new("test-snapshots/chain4.forest.car.zst"))
+                .unwrap()
+                .heaviest_tipset()
+                .unwrap();
+            assert_eq!(ts, expected, "{file_path}");
+        }
+
+        // A tampered block
+        let mut car = fs::read("test-snapshots/chain4.car").unwrap();
+        let last = car.len() - 1;
+        car[last] ^= 0xFF;
+        let temp_file = tempfile::Builder::new().tempfile().unwrap();
+        fs::write(temp_file.path(), car).unwrap();
+        let temp_db_dir = tempfile::Builder::new().tempdir().unwrap();
+        import_chain_as_forest_car(
+            temp_file.path(),
+            temp_db_dir.path(),
+            None,
+            ImportMode::ValidateOnly,
+            false,
+            &SnapshotProgressTracker::default(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(fs::read_dir(temp_db_dir.path()).unwrap().count(), 0);
+    }
+
     async fn import_snapshot_from_file(
         file_path: &str,
         import_mode: ImportMode,
@@ -494,7 +1591,9 @@ mod test {
         let (path, ts) = import_chain_as_forest_car(
             file_path,
             temp_db_dir.path(),
+            None,
             import_mode,
+            false,
             &SnapshotProgressTracker::default(),
         )
         .await?;
@@ -509,6 +1608,15 @@ mod test {
                 assert!(!file_path.exists());
                 assert!(path.is_file());
             }
+            ImportMode::MoveWithSymlinkBack => {
+                assert!(file_path.is_symlink());
+                assert_eq!(
+                    std::path::absolute(file_path.read_link()?)?,
+                    std::path::absolute(&path)?
+                );
+                assert!(!path.is_symlink());
+                assert!(path.is_file());
+            }
             _ => {
                 assert!(file_path.is_file());
                 assert!(path.is_file());
@@ -7,7 +7,7 @@ pub mod db_util;
 pub mod main;
 
 use crate::blocks::Tipset;
-use crate::chain::HeadChange;
+use crate::chain::{ChainStore, HeadChange};
 use crate::chain_sync::ChainFollower;
 use crate::chain_sync::network_context::SyncNetworkContext;
 use crate::cli_shared::snapshot;
@@ -16,10 +16,10 @@ use crate::cli_shared::{
     cli::{CliOpts, Config},
 };
 use crate::daemon::context::{AppContext, DbType};
-use crate::daemon::db_util::import_chain_as_forest_car;
+use crate::daemon::db_util::{ImportMode, import_chain_as_forest_car};
 use crate::db::gc::SnapshotGarbageCollector;
-use crate::db::ttl::EthMappingCollector;
-use crate::libp2p::{Libp2pService, PeerManager};
+use crate::db::ttl::{EthMappingCollector, run_tipset_mappings_pruner};
+use crate::libp2p::{Libp2pService, PeerId, PeerManager};
 use crate::message_pool::{MessagePool, MpoolConfig, MpoolRpcProvider};
 use crate::networks::{self, ChainConfig};
 use crate::rpc::RPCState;
@@ -27,6 +27,7 @@ use crate::rpc::eth::filter::EthEventHandler;
 use crate::rpc::start_rpc;
 use crate::shim::clock::ChainEpoch;
 use crate::shim::version::NetworkVersion;
+use crate::state_manager::StateManager;
 use crate::utils;
 use crate::utils::{
     monitoring::MemStatsTracker, proofs_api::ensure_proof_params_downloaded,
@@ -44,7 +45,7 @@ use tokio::{
         ctrl_c,
         unix::{SignalKind, signal},
     },
-    sync::mpsc,
+    sync::{broadcast::error::RecvError, mpsc, watch},
     task::JoinSet,
 };
 use tracing::{debug, info, warn};
@@ -93,16 +94,22 @@ pub async fn start_interruptable(opts: CliOpts, config: Config) -> anyhow::Resul
             Ok(())
         },
     };
+    if let Some(sidecar) = crate::f3::supervisor::F3_SIDECAR.get() {
+        sidecar.stop(crate::f3::supervisor::STOP_TIMEOUT).await;
+    }
     crate::utils::io::terminal_cleanup();
     result
 }
 
 /// This function initialize Forest with below steps
 /// - increase file descriptor limit (for parity-db)
+/// - validate the F3 configuration
 /// - setup proofs parameter cache directory
 /// - prints Forest version
 fn startup_init(config: &Config) -> anyhow::Result<()> {
     maybe_increase_fd_limit()?;
+    config.f3.validate()?;
+    crate::f3::set_configured_f3_rpc_endpoint(&config.f3);
     // Sets proof parameter file download path early, the files will be checked and
     // downloaded later right after snapshot import step
     crate::utils::proofs_api::maybe_set_proofs_parameter_cache_dir_env(&config.client.data_dir);
@@ -138,22 +145,27 @@ async fn maybe_import_snapshot(
             let (car_db_path, ts) = import_chain_as_forest_car(
                 path,
                 &ctx.db_meta_data.get_forest_car_db_dir(),
+                config.client.import_scratch_dir.as_deref(),
                 config.client.import_mode,
+                config.client.import_strict_order,
                 &snapshot_tracker,
             )
             .await?;
-            ctx.db
-                .read_only_files(std::iter::once(car_db_path.clone()))?;
-            let ts_epoch = ts.epoch();
-            // Explicitly set heaviest tipset here in case HEAD_KEY has already been set
-            // in the current setting store
-            ctx.state_manager
-                .chain_store()
-                .set_heaviest_tipset(ts.into())?;
-            debug!(
-                "Loaded car DB at {} and set current head to epoch {ts_epoch}",
-                car_db_path.display(),
-            );
+            // The snapshot is neither copied into, nor loaded from the car DB
+            if config.client.import_mode != ImportMode::ValidateOnly {
+                ctx.db
+                    .read_only_files(std::iter::once(car_db_path.clone()))?;
+                let ts_epoch = ts.epoch();
+                // Explicitly set heaviest tipset here in case HEAD_KEY has already been set
+                // in the current setting store
+                ctx.state_manager
+                    .chain_store()
+                    .set_heaviest_tipset(ts.into())?;
+                debug!(
+                    "Loaded car DB at {} and set current head to epoch {ts_epoch}",
+                    car_db_path.display(),
+                );
+            }
         }
     }
 
@@ -186,6 +198,18 @@ async fn maybe_import_snapshot(
     Ok(())
 }
 
+/// Flattens the indexes of the snapshot `CAR`s, once all of them are loaded.
+fn maybe_compact_car_db_index(config: &Config, ctx: &AppContext) -> anyhow::Result<()> {
+    if config.client.blockstore_compact_index {
+        ctx.db.compact()?;
+        info!(
+            "Compacted the car DB index to {} entries",
+            ctx.db.compact_index_len().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
 fn maybe_start_track_peak_rss_service(services: &mut JoinSet<anyhow::Result<()>>, opts: &CliOpts) {
     if opts.track_peak_rss {
         let mem_stats_tracker = MemStatsTracker::default();
@@ -227,6 +251,9 @@ async fn maybe_start_metrics_service(
                     .timestamp,
             ),
         ));
+        crate::metrics::default_registry().register_collector(Box::new(
+            crate::metrics::db::CarLayerReadsCollector::new(ctx.db.clone()),
+        ));
     }
     Ok(())
 }
@@ -353,6 +380,7 @@ fn maybe_start_rpc_service(
             .as_ref()
             .map(|path| crate::rpc::FilterList::new_from_file(path))
             .transpose()?;
+        let rpc_unknown_fields = config.client.rpc_unknown_fields;
         info!("JSON-RPC endpoint will listen at {rpc_address}");
         let eth_event_handler = Arc::new(EthEventHandler::from_config(&config.events));
         services.spawn({
@@ -382,6 +410,7 @@ fn maybe_start_rpc_service(
                     },
                     rpc_address,
                     filter_list,
+                    rpc_unknown_fields,
                 )
                 .await
             }
@@ -405,47 +434,172 @@ fn maybe_start_f3_service(opts: &CliOpts, config: &Config, ctx: &AppContext) {
         return;
     }
 
+    for warning in crate::f3::validate_env() {
+        tracing::warn!("{warning}");
+    }
+
     if !opts.halt_after_import && !opts.stateless {
-        let rpc_address = config.client.rpc_address;
-        let state_manager = &ctx.state_manager;
-        let p2p_peer_id = ctx.p2p_peer_id;
-        let admin_jwt = ctx.admin_jwt.clone();
-        tokio::task::spawn_blocking({
-            crate::rpc::f3::F3_LEASE_MANAGER
-                .set(crate::rpc::f3::F3LeaseManager::new(
-                    state_manager.chain_config().network.clone(),
+        let (f3_options, f3_provenance) = crate::f3::get_f3_sidecar_params_with_provenance(
+            ctx.state_manager.chain_config(),
+            &config.f3,
+        );
+        if f3_options.initial_power_table.is_none()
+            && crate::f3::is_sidecar_ffi_enabled(ctx.state_manager.chain_config())
+        {
+            // Computing the power table can take a while, so the rest of the startup
+            // doesn't wait for it
+            let (config, f3_force_root) = (config.clone(), opts.f3_force_root);
+            let state_manager = ctx.state_manager.clone();
+            let (p2p_peer_id, admin_jwt) = (ctx.p2p_peer_id, ctx.admin_jwt.clone());
+            tokio::spawn(async move {
+                let (f3_options, f3_provenance) =
+                    derive_initial_power_table(&state_manager, f3_options, f3_provenance).await;
+                start_f3_service(
+                    &config,
+                    f3_force_root,
+                    &state_manager,
                     p2p_peer_id,
-                ))
-                .expect("F3 lease manager should not have been initialized before");
-            let chain_config = state_manager.chain_config().clone();
-            let default_f3_root = config
-                .client
-                .data_dir
-                .join(format!("f3/{}", config.chain()));
-            let crate::f3::F3Options {
-                chain_finality,
-                bootstrap_epoch,
-                initial_power_table,
-            } = crate::f3::get_f3_sidecar_params(&chain_config);
-            move || {
-                crate::f3::run_f3_sidecar_if_enabled(
-                    &chain_config,
-                    format!("http://{rpc_address}/rpc/v1"),
                     admin_jwt,
-                    crate::rpc::f3::get_f3_rpc_endpoint().to_string(),
-                    initial_power_table
-                        .map(|i| i.to_string())
-                        .unwrap_or_default(),
-                    bootstrap_epoch,
-                    chain_finality,
-                    std::env::var("FOREST_F3_ROOT")
-                        .unwrap_or(default_f3_root.display().to_string()),
+                    f3_options,
+                    f3_provenance,
                 );
-            }
+            });
+        } else {
+            start_f3_service(
+                config,
+                opts.f3_force_root,
+                &ctx.state_manager,
+                ctx.p2p_peer_id,
+                ctx.admin_jwt.clone(),
+                f3_options,
+                f3_provenance,
+            );
+        }
+    }
+}
+
+/// Uses the initial power table derived from the chain state, if it can be derived.
+async fn derive_initial_power_table(
+    state_manager: &Arc<StateManager<DbType>>,
+    mut f3_options: crate::f3::F3Options,
+    mut f3_provenance: crate::f3::F3OptionsProvenance,
+) -> (crate::f3::F3Options, crate::f3::F3OptionsProvenance) {
+    match crate::f3::derive_initial_power_table(state_manager, &f3_options).await {
+        Ok(Some(derived)) => {
+            crate::f3::reconcile_initial_power_table(&mut f3_options, derived);
+            f3_provenance.initial_power_table = crate::utils::misc::env::Provenance::Derived;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::warn!("Failed to derive the F3 initial power table: {e:#}")
+        }
+    }
+    (f3_options, f3_provenance)
+}
+
+fn start_f3_service(
+    config: &Config,
+    f3_force_root: bool,
+    state_manager: &Arc<StateManager<DbType>>,
+    p2p_peer_id: PeerId,
+    admin_jwt: String,
+    f3_options: crate::f3::F3Options,
+    f3_provenance: crate::f3::F3OptionsProvenance,
+) {
+    let rpc_address = config.client.rpc_address;
+    let f3_root = crate::f3::get_f3_root(&config.f3, &config.client.data_dir, config.chain());
+    let f3_params = crate::f3::F3EffectiveParams {
+        options: f3_options.clone(),
+        provenance: f3_provenance,
+        root: f3_root.clone(),
+    };
+    f3_params.log();
+    crate::f3::set_effective_f3_params(f3_params);
+    if crate::f3::is_sidecar_ffi_enabled(state_manager.chain_config()) {
+        let head_epoch = state_manager.chain_store().heaviest_tipset().epoch();
+        if let Err(e) = f3_options.validate(state_manager.chain_config(), Some(head_epoch)) {
+            tracing::error!("Refusing to start the F3 sidecar: {e}");
+            return;
+        }
+        let marker = crate::f3::F3RootMarker {
+            network: state_manager.chain_config().network.to_string(),
+            genesis: *state_manager.chain_store().genesis_block_header().cid(),
+        };
+        if let Err(e) = crate::f3::check_f3_root(&f3_root, &marker, f3_force_root) {
+            tracing::error!("Refusing to start the F3 sidecar: {e}");
+            return;
+        }
+    }
+    crate::rpc::f3::F3_LEASE_MANAGER
+        .set(crate::rpc::f3::F3LeaseManager::new(
+            state_manager.chain_config().network.clone(),
+            p2p_peer_id,
+        ))
+        .expect("F3 lease manager should not have been initialized before");
+    let chain_config = state_manager.chain_config().clone();
+    let crate::f3::F3Options {
+        chain_finality,
+        bootstrap_epoch,
+        initial_power_table,
+    } = f3_options;
+    let head = watch_head_epoch(state_manager.chain_store());
+    if let Some(sidecar) = crate::f3::run_f3_sidecar_if_enabled(
+        &chain_config,
+        format!("http://{rpc_address}/rpc/v1"),
+        admin_jwt,
+        crate::rpc::f3::get_f3_rpc_endpoint().to_string(),
+        initial_power_table
+            .map(|i| i.to_string())
+            .unwrap_or_default(),
+        bootstrap_epoch,
+        chain_finality,
+        f3_root.display().to_string(),
+        crate::f3::get_f3_log_level(&config.f3),
+        config.f3.log_format.unwrap_or_default(),
+        head,
+        config
+            .f3
+            .launch_margin
+            .unwrap_or(crate::f3::DEFAULT_F3_LAUNCH_MARGIN),
+    ) {
+        crate::f3::supervisor::F3_SIDECAR
+            .set(sidecar)
+            .ok()
+            .expect("F3 sidecar should not have been started before");
+        let chain_store = state_manager.chain_store().clone();
+        tokio::spawn(async move {
+            crate::f3::metrics::poll(
+                &crate::f3::metrics::F3_METRICS,
+                &crate::f3::metrics::SidecarRpc,
+                move || chain_store.heaviest_tipset().epoch(),
+                crate::f3::metrics::POLL_INTERVAL,
+                crate::f3::metrics::MAX_POLL_INTERVAL,
+            )
+            .await
         });
     }
 }
 
+/// Tracks the epoch of the chain head, until the receiver is dropped.
+fn watch_head_epoch(chain_store: &Arc<ChainStore<DbType>>) -> watch::Receiver<ChainEpoch> {
+    let (sender, receiver) = watch::channel(chain_store.heaviest_tipset().epoch());
+    let mut head_changes = chain_store.publisher().subscribe();
+    tokio::spawn(async move {
+        loop {
+            match head_changes.recv().await {
+                Ok(HeadChange::Apply(ts)) => {
+                    if sender.send(ts.epoch()).is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+    receiver
+}
+
 fn maybe_start_indexer_service(
     services: &mut JoinSet<anyhow::Result<()>>,
     opts: &CliOpts,
@@ -458,6 +612,8 @@ fn maybe_start_indexer_service(
     {
         let mut receiver = ctx.state_manager.chain_store().publisher().subscribe();
         let chain_store = ctx.state_manager.chain_store().clone();
+        let db = ctx.db.clone();
+        let auto_backfill = config.chain_indexer.auto_backfill;
         services.spawn(async move {
             tracing::info!("Starting indexer service");
 
@@ -474,9 +630,46 @@ fn maybe_start_indexer_service(
                     chain_store.headers_delegated_messages(ts.block_headers().iter())?;
 
                 chain_store.process_signed_messages(&delegated_messages)?;
+
+                db_util::advance_eth_mappings_checkpoint(&db, ts.epoch(), auto_backfill)?;
             }
         });
 
+        // Databases populated by older versions lack the reverse tipset mappings
+        let db = ctx.db.clone();
+        services.spawn(async move {
+            if let Err(e) = asyncify(move || db_util::backfill_tipset_eth_hashes(&db)).await {
+                warn!("Failed to backfill the reverse tipset mappings: {e:#}");
+            }
+            Ok(())
+        });
+
+        // Subscribed first, so that the tipsets after this head are left to the indexer
+        if config.chain_indexer.auto_backfill {
+            let chain_store = ctx.state_manager.chain_store().clone();
+            let db = ctx.db.clone();
+            let retention_epochs = config
+                .chain_indexer
+                .eth_mappings_retention_epochs
+                .map(ChainEpoch::from);
+            services.spawn(async move {
+                let head_ts = chain_store.heaviest_tipset();
+                let backfilled = asyncify(move || {
+                    db_util::backfill_eth_mappings_if_behind(
+                        &chain_store,
+                        &db,
+                        &head_ts,
+                        retention_epochs,
+                    )
+                })
+                .await;
+                if let Err(e) = backfilled {
+                    warn!("Failed to backfill the Ethereum mappings: {e:#}");
+                }
+                Ok(())
+            });
+        }
+
         // Run the collector only if chain indexer is enabled
         if let Some(retention_epochs) = config.chain_indexer.gc_retention_epochs {
             let chain_store = ctx.state_manager.chain_store().clone();
@@ -491,6 +684,14 @@ fn maybe_start_indexer_service(
                 collector.run().await
             });
         }
+
+        if let Some(retention_epochs) = config.chain_indexer.eth_mappings_retention_epochs {
+            let chain_store = ctx.state_manager.chain_store().clone();
+            services.spawn(async move {
+                tracing::info!("Starting pruner for tipset mappings");
+                run_tipset_mappings_pruner(chain_store, retention_epochs.into()).await
+            });
+        }
     }
 }
 
@@ -569,6 +770,7 @@ pub(super) async fn start_services(
         services.shutdown().await;
         return Ok(());
     }
+    maybe_compact_car_db_index(&config, &ctx)?;
     on_app_context_and_db_initialized(&ctx);
     ctx.state_manager.populate_cache();
     maybe_start_metrics_service(&mut services, &config, &ctx).await?;
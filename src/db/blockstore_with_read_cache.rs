@@ -3,6 +3,7 @@
 
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
+use std::num::NonZeroUsize;
 use std::sync::{
     Arc,
     atomic::{self, AtomicUsize},
@@ -123,6 +124,22 @@ impl<DB: Blockstore, CACHE: BlockstoreReadCache, STATS: BlockstoreReadCacheStats
     }
 }
 
+/// A [`BlockstoreWithReadCache`] keeping up to a given number of blocks in an LRU
+/// cache, e.g. in front of a [`crate::db::car::PlainCar`], which otherwise reads
+/// every block from disk.
+pub type CachingStore<DB> =
+    BlockstoreWithReadCache<DB, LruBlockstoreReadCache, DefaultBlockstoreReadCacheStats>;
+
+impl<DB: Blockstore> CachingStore<DB> {
+    pub fn with_capacity(db: DB, capacity: NonZeroUsize) -> Self {
+        Self::new(
+            db,
+            LruBlockstoreReadCache::new_without_metrics_registry("caching_store".into(), capacity),
+            Some(DefaultBlockstoreReadCacheStats::default()),
+        )
+    }
+}
+
 impl<DB: Blockstore, CACHE: BlockstoreReadCache, STATS: BlockstoreReadCacheStats> Blockstore
     for BlockstoreWithReadCache<DB, CACHE, STATS>
 {
@@ -211,4 +228,61 @@ mod tests {
         assert_eq!(db.stats().unwrap().hit(), 5);
         assert_eq!(db.stats().unwrap().miss(), 5);
     }
+
+    #[test]
+    fn test_caching_store() {
+        /// Counts the reads that reach the inner store.
+        #[derive(Default)]
+        struct CountingStore {
+            db: MemoryDB,
+            gets: AtomicUsize,
+        }
+
+        impl Blockstore for CountingStore {
+            fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+                self.gets.fetch_add(1, atomic::Ordering::Relaxed);
+                self.db.get(k)
+            }
+
+            fn put_keyed(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
+                self.db.put_keyed(k, block)
+            }
+        }
+
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let inner = Arc::new(CountingStore::default());
+        let records = (0..3_u8)
+            .map(|i| {
+                let record = vec![i; 32];
+                let key = Cid::new_v1(DAG_CBOR, Blake2b256.digest(&record));
+                inner.put_keyed(&key, &record).unwrap();
+                (key, record)
+            })
+            .collect::<Vec<_>>();
+        let db = CachingStore::with_capacity(inner.clone(), 2.try_into().unwrap());
+        assert_send_sync(&db);
+        let gets = || inner.gets.load(atomic::Ordering::Relaxed);
+
+        let (k0, v0) = &records[0];
+        assert_eq!(&db.get(k0).unwrap().unwrap(), v0);
+        assert_eq!(gets(), 1);
+        // Served from the cache
+        assert_eq!(&db.get(k0).unwrap().unwrap(), v0);
+        assert_eq!(gets(), 1);
+
+        // Missing blocks are not cached
+        let missing = Cid::new_v1(DAG_CBOR, Blake2b256.digest(b"missing"));
+        assert_eq!(db.get(&missing).unwrap(), None);
+        assert_eq!(db.get(&missing).unwrap(), None);
+        assert_eq!(gets(), 3);
+
+        // `k0` is evicted as the least recently used
+        for (k, v) in &records[1..] {
+            assert_eq!(&db.get(k).unwrap().unwrap(), v);
+        }
+        assert_eq!(gets(), 5);
+        assert_eq!(&db.get(k0).unwrap().unwrap(), v0);
+        assert_eq!(gets(), 6);
+    }
 }
@@ -23,6 +23,8 @@ use std::sync::Arc;
 
 pub enum AnyCar<ReaderT> {
     Plain(super::PlainCar<ReaderT>),
+    /// See [`AnyCar::into_read_only`].
+    ReadOnly(super::ReadOnlyCar<ReaderT>),
     Forest(super::ForestCar<ReaderT>),
     Memory(super::PlainCar<Vec<u8>>),
 }
@@ -56,6 +58,7 @@ impl<ReaderT: RandomAccessFileReader> AnyCar<ReaderT> {
         match self {
             AnyCar::Forest(forest) => forest.heaviest_tipset_key(),
             AnyCar::Plain(plain) => plain.heaviest_tipset_key(),
+            AnyCar::ReadOnly(plain) => plain.heaviest_tipset_key(),
             AnyCar::Memory(mem) => mem.heaviest_tipset_key(),
         }
     }
@@ -66,6 +69,7 @@ impl<ReaderT: RandomAccessFileReader> AnyCar<ReaderT> {
         match self {
             AnyCar::Forest(forest) => forest.heaviest_tipset(),
             AnyCar::Plain(plain) => plain.heaviest_tipset(),
+            AnyCar::ReadOnly(plain) => plain.heaviest_tipset(),
             AnyCar::Memory(mem) => mem.heaviest_tipset(),
         }
     }
@@ -76,15 +80,28 @@ impl<ReaderT: RandomAccessFileReader> AnyCar<ReaderT> {
         match self {
             AnyCar::Forest(_) => "ForestCARv1.zst".into(),
             AnyCar::Plain(car) => format!("CARv{}", car.version()).into(),
+            AnyCar::ReadOnly(car) => format!("CARv{}", car.version()).into(),
             AnyCar::Memory(car) => format!("CARv{}.zst", car.version()).into(),
         }
     }
 
+    /// Return the CAR version. Forest CARs are `CARv1` compatible, see
+    /// [`Self::variant`] to tell them apart.
+    pub fn version(&self) -> u64 {
+        match self {
+            AnyCar::Forest(_) => 1,
+            AnyCar::Plain(car) => car.version(),
+            AnyCar::ReadOnly(car) => car.version(),
+            AnyCar::Memory(car) => car.version(),
+        }
+    }
+
     /// Discard reader type and replace with dynamic trait object.
     pub fn into_dyn(self) -> AnyCar<Box<dyn super::RandomAccessFileReader>> {
         match self {
             AnyCar::Forest(f) => AnyCar::Forest(f.into_dyn()),
             AnyCar::Plain(p) => AnyCar::Plain(p.into_dyn()),
+            AnyCar::ReadOnly(p) => AnyCar::ReadOnly(p.into_dyn()),
             AnyCar::Memory(m) => AnyCar::Memory(m),
         }
     }
@@ -94,10 +111,53 @@ impl<ReaderT: RandomAccessFileReader> AnyCar<ReaderT> {
         match self {
             AnyCar::Forest(f) => AnyCar::Forest(f.with_cache(cache, key)),
             AnyCar::Plain(p) => AnyCar::Plain(p),
+            AnyCar::ReadOnly(p) => AnyCar::ReadOnly(p),
             AnyCar::Memory(m) => AnyCar::Memory(m),
         }
     }
 
+    /// Make an uncompressed CAR read-only, so that reads skip the write cache,
+    /// see [`ReadOnlyCar`](super::ReadOnlyCar). Writes then fail.
+    pub fn into_read_only(self) -> Self {
+        match self {
+            AnyCar::Plain(p) => AnyCar::ReadOnly(p.into()),
+            other => other,
+        }
+    }
+
+    /// Build a [`CidBloomFilter`](super::CidBloomFilter) over the [`Cid`]s of
+    /// the inner CAR, see [`Self::might_contain`].
+    pub fn with_bloom_filter(self, false_positive_rate: f64) -> Result<Self> {
+        Ok(match self {
+            AnyCar::Forest(f) => AnyCar::Forest(f.with_bloom_filter(false_positive_rate)?),
+            AnyCar::Plain(p) => AnyCar::Plain(p.with_bloom_filter(false_positive_rate)),
+            AnyCar::ReadOnly(p) => AnyCar::ReadOnly(p.with_bloom_filter(false_positive_rate)),
+            AnyCar::Memory(m) => AnyCar::Memory(m.with_bloom_filter(false_positive_rate)),
+        })
+    }
+
+    /// Returns `false` if `k` is definitely not in the store. Always returns
+    /// `true` without a [bloom filter](Self::with_bloom_filter).
+    pub fn might_contain(&self, k: &Cid) -> bool {
+        match self {
+            AnyCar::Forest(forest) => forest.might_contain(k),
+            AnyCar::Plain(plain) => plain.might_contain(k),
+            AnyCar::ReadOnly(plain) => plain.might_contain(k),
+            AnyCar::Memory(mem) => mem.might_contain(k),
+        }
+    }
+
+    /// The hash [summaries](super::forest::summary) of the blocks in the
+    /// store, see [`super::ManyCar::compact`].
+    pub fn hash_summaries(&self) -> Result<Vec<u64>> {
+        match self {
+            AnyCar::Forest(forest) => forest.hash_summaries(),
+            AnyCar::Plain(plain) => Ok(plain.hash_summaries()),
+            AnyCar::ReadOnly(plain) => Ok(plain.hash_summaries()),
+            AnyCar::Memory(mem) => Ok(mem.hash_summaries()),
+        }
+    }
+
     /// Get the index size in bytes
     pub fn index_size_bytes(&self) -> Option<u32> {
         match self {
@@ -129,6 +189,7 @@ where
         match self {
             AnyCar::Forest(forest) => forest.get(k),
             AnyCar::Plain(plain) => plain.get(k),
+            AnyCar::ReadOnly(plain) => plain.get(k),
             AnyCar::Memory(mem) => mem.get(k),
         }
     }
@@ -137,6 +198,7 @@ where
         match self {
             AnyCar::Forest(forest) => forest.put_keyed(k, block),
             AnyCar::Plain(plain) => plain.put_keyed(k, block),
+            AnyCar::ReadOnly(plain) => plain.put_keyed(k, block),
             AnyCar::Memory(mem) => mem.put_keyed(k, block),
         }
     }
@@ -150,6 +212,7 @@ where
         match self {
             AnyCar::Forest(forest) => forest.put_keyed_persistent(k, block),
             AnyCar::Plain(plain) => plain.put_keyed_persistent(k, block),
+            AnyCar::ReadOnly(plain) => plain.put_keyed(k, block),
             AnyCar::Memory(mem) => mem.put_keyed_persistent(k, block),
         }
     }
@@ -185,6 +248,18 @@ mod tests {
         assert!(forest_car.has(&calibnet::GENESIS_CID).unwrap());
     }
 
+    #[test]
+    fn forest_any_into_read_only() {
+        let car = AnyCar::new(calibnet::DEFAULT_GENESIS)
+            .unwrap()
+            .into_read_only();
+        assert!(matches!(car, AnyCar::ReadOnly(_)));
+        assert!(car.has(&calibnet::GENESIS_CID).unwrap());
+        assert_eq!(car.variant(), "CARv1");
+        car.put_keyed(&calibnet::GENESIS_CID, b"any block")
+            .unwrap_err();
+    }
+
     #[test]
     fn forest_any_load_mainnet() {
         let forest_car = AnyCar::new(mainnet::DEFAULT_GENESIS).unwrap();
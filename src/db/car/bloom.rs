@@ -0,0 +1,118 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! A bloom filter over the [`Cid`]s of a CAR file, so that lookups of [`Cid`]s
+//! that are definitely not in the file can skip its index.
+//!
+//! The filter is keyed by the same `u64` hash summary as the
+//! [`ForestCar`](super::ForestCar) index, so it can be built from the index of
+//! a `.forest.car.zst` without decompressing any blocks. Hash summaries are
+//! expanded into bit positions with double hashing, see
+//! <https://www.eecs.harvard.edu/~michaelm/postscripts/rsa2008.pdf>.
+
+use super::forest::summary;
+use cid::Cid;
+use std::f64::consts::LN_2;
+
+pub struct CidBloomFilter {
+    bits: Vec<u64>,
+    num_hashes: u32,
+}
+
+impl CidBloomFilter {
+    /// Create an empty filter sized for `num_items` [`Cid`]s, with a
+    /// probability of `false_positive_rate` that [`Self::might_contain`]
+    /// returns `true` for a [`Cid`] that was never inserted.
+    ///
+    /// # Panics
+    /// - If `false_positive_rate` is not in the range `(0, 1)`.
+    pub fn new(num_items: usize, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "false positive rate must be between 0 and 1, got {false_positive_rate}"
+        );
+        let num_items = num_items.max(1) as f64;
+        // The optimal number of bits and hashes, see
+        // <https://en.wikipedia.org/wiki/Bloom_filter#Optimal_number_of_hash_functions>
+        let num_bits = (-num_items * false_positive_rate.ln() / (LN_2 * LN_2)).ceil();
+        let num_words = (num_bits / 64.0).ceil().max(1.0) as usize;
+        let num_hashes = (num_bits / num_items * LN_2).round().clamp(1.0, 32.0) as u32;
+        Self {
+            bits: vec![0; num_words],
+            num_hashes,
+        }
+    }
+
+    pub fn insert(&mut self, cid: &Cid) {
+        self.insert_summary(summary(cid))
+    }
+
+    /// Insert a hash summary as returned by [`summary`].
+    pub(super) fn insert_summary(&mut self, summary: u64) {
+        for bit in self.bit_positions(summary) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns `false` if `cid` was definitely never inserted.
+    pub fn might_contain(&self, cid: &Cid) -> bool {
+        self.bit_positions(summary(cid))
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    fn bit_positions(&self, summary: u64) -> impl Iterator<Item = usize> + use<> {
+        let num_bits = self.bits.len() as u64 * 64;
+        // The second hash must not be correlated with the first one, so mix the
+        // summary with the `SplitMix64` finalizer.
+        let mut mixed = summary;
+        mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        mixed ^= mixed >> 31;
+        let step = mixed | 1;
+        (0..u64::from(self.num_hashes)).map(move |i| {
+            usize::try_from(summary.wrapping_add(i.wrapping_mul(step)) % num_bits).unwrap()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::cid::CidCborExt as _;
+
+    #[test]
+    fn no_false_negatives() {
+        const NUM_ITEMS: u64 = 100_000;
+        const FALSE_POSITIVE_RATE: f64 = 0.01;
+        let mut filter = CidBloomFilter::new(NUM_ITEMS as usize, FALSE_POSITIVE_RATE);
+        let inserted = (0..NUM_ITEMS)
+            .map(|i| Cid::from_cbor_blake2b256(&i).unwrap())
+            .collect::<Vec<_>>();
+        for cid in &inserted {
+            filter.insert(cid);
+        }
+        assert!(inserted.iter().all(|cid| filter.might_contain(cid)));
+
+        let false_positives = (NUM_ITEMS..2 * NUM_ITEMS)
+            .map(|i| Cid::from_cbor_blake2b256(&i).unwrap())
+            .filter(|cid| filter.might_contain(cid))
+            .count();
+        let rate = false_positives as f64 / NUM_ITEMS as f64;
+        assert!(
+            rate < 2.0 * FALSE_POSITIVE_RATE,
+            "false positive rate {rate} is too high"
+        );
+    }
+
+    #[test]
+    fn empty_filter_contains_nothing() {
+        let filter = CidBloomFilter::new(0, 0.01);
+        assert!(!filter.might_contain(&Cid::from_cbor_blake2b256(&0).unwrap()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_false_positive_rate() {
+        CidBloomFilter::new(1, 1.0);
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Concatenate `.forest.car.zst` archives without decompressing or
+//! recompressing their blocks.
+
+use super::forest::Encoder;
+use super::{ForestCar, RandomAccessFileReader};
+use crate::blocks::Tipset;
+use anyhow::Context as _;
+use std::path::Path;
+use tokio::io::{AsyncWriteExt as _, BufWriter};
+
+/// Write a `.forest.car.zst` to `output` with the blocks of `base` followed by
+/// the blocks of `addition`, and the roots of `addition`, e.g. to append a diff
+/// snapshot to the snapshot it is based on.
+///
+/// The block z-frames are copied as they are, and the indexes are merged. CIDs
+/// that are in both archives are allowed: as blocks are content-addressed, the
+/// duplicate blocks are identical, and either copy may be read.
+///
+/// Unless both archives have the same roots, the roots of `addition` must be a
+/// tipset that descends from the roots of `base`, so that the output holds a
+/// single chain. Nothing is written otherwise.
+pub async fn concat_raw(
+    base: impl AsRef<Path>,
+    addition: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+) -> anyhow::Result<()> {
+    let open = |path: &Path| {
+        ForestCar::try_from(path)
+            .with_context(|| format!("{} is not a valid forest CAR", path.display()))
+    };
+    let base = open(base.as_ref())?;
+    let addition = open(addition.as_ref())?;
+    ensure_descends(&base, &addition)?;
+    let output = output.as_ref();
+    let mut writer = BufWriter::new(
+        tokio::fs::File::create(output)
+            .await
+            .with_context(|| format!("failed to create {}", output.display()))?,
+    );
+    Encoder::write_concatenated(&mut writer, addition.roots().clone(), &[&base, &addition]).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Walk the parents of the heaviest tipset of `addition`, looking them up in
+/// either archive, down to the epoch of the heaviest tipset of `base`.
+fn ensure_descends(
+    base: &ForestCar<impl RandomAccessFileReader>,
+    addition: &ForestCar<impl RandomAccessFileReader>,
+) -> anyhow::Result<()> {
+    if base.roots() == addition.roots() {
+        return Ok(());
+    }
+    let base_head = base
+        .heaviest_tipset()
+        .context("the roots of the base archive are not a tipset")?;
+    let mut tipset = addition
+        .heaviest_tipset()
+        .context("the roots of the added archive are not a tipset")?;
+    let addition_epoch = tipset.epoch();
+    while tipset.epoch() > base_head.epoch() {
+        let parents = tipset.parents();
+        tipset = match Tipset::load(addition, parents)? {
+            Some(parent) => parent,
+            None => Tipset::load_required(base, parents)
+                .with_context(|| format!("tipset {parents} is in neither archive"))?,
+        };
+    }
+    anyhow::ensure!(
+        tipset.key() == base_head.key(),
+        "conflicting roots: the added archive at epoch {addition_epoch} doesn't descend from \
+         the base archive at epoch {}",
+        base_head.epoch()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::car::PlainCar;
+    use crate::db::car::recompress::recompress;
+    use crate::networks::calibnet;
+    use futures::TryStreamExt as _;
+    use fvm_ipld_blockstore::Blockstore as _;
+    use std::io::Cursor;
+
+    /// Write the blocks of `chain4.car` to a `.forest.car.zst` with `roots`.
+    async fn write_chain4(path: &Path, roots: nunny::Vec<cid::Cid>) {
+        let blocks = crate::utils::db::car_stream::CarStream::new(Cursor::new(
+            include_bytes!("../../../test-snapshots/chain4.car").as_slice(),
+        ))
+        .await
+        .unwrap();
+        let mut writer = tokio::fs::File::create(path).await.unwrap();
+        Encoder::write(
+            &mut writer,
+            roots,
+            Encoder::compress_stream_default(blocks.map_err(anyhow::Error::from)),
+        )
+        .await
+        .unwrap();
+        writer.flush().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn concat_raw_chain4_onto_its_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain4 =
+            PlainCar::new(include_bytes!("../../../test-snapshots/chain4.car").as_slice()).unwrap();
+        let head = chain4.heaviest_tipset().unwrap();
+        let base = dir.path().join("parent.forest.car.zst");
+        write_chain4(&base, head.parents().to_cids()).await;
+        let addition = dir.path().join("chain4.forest.car.zst");
+        write_chain4(&addition, chain4.roots().clone()).await;
+        let output = dir.path().join("concatenated.forest.car.zst");
+
+        concat_raw(&base, &addition, &output).await.unwrap();
+
+        let concatenated = ForestCar::try_from(output.as_path()).unwrap();
+        assert_eq!(concatenated.roots(), chain4.roots());
+        assert_eq!(concatenated.heaviest_tipset().unwrap(), head);
+        for cid in chain4.cids() {
+            assert_eq!(concatenated.get(&cid).unwrap(), chain4.get(&cid).unwrap());
+        }
+        // The output is still readable by tools that don't know about forest CARs
+        let blocks = crate::utils::db::car_stream::CarStream::new(tokio::io::BufReader::new(
+            tokio::fs::File::open(&output).await.unwrap(),
+        ))
+        .await
+        .unwrap();
+        assert_eq!(&blocks.header_v1.roots, concatenated.roots());
+    }
+
+    #[tokio::test]
+    async fn concat_raw_conflicting_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain4 =
+            PlainCar::new(include_bytes!("../../../test-snapshots/chain4.car").as_slice()).unwrap();
+        let parent = dir.path().join("parent.forest.car.zst");
+        write_chain4(
+            &parent,
+            chain4.heaviest_tipset().unwrap().parents().to_cids(),
+        )
+        .await;
+        let head = dir.path().join("chain4.forest.car.zst");
+        write_chain4(&head, chain4.roots().clone()).await;
+        let genesis = dir.path().join("calibnet_genesis.forest.car.zst");
+        let mut encoded = vec![];
+        recompress(Cursor::new(calibnet::DEFAULT_GENESIS), &mut encoded, 3)
+            .await
+            .unwrap();
+        std::fs::write(&genesis, encoded).unwrap();
+        let output = dir.path().join("concatenated.forest.car.zst");
+
+        // Older roots
+        let error = concat_raw(&head, &parent, &output).await.unwrap_err();
+        assert!(error.to_string().contains("conflicting roots"), "{error}");
+        // Another chain
+        let error = concat_raw(&genesis, &head, &output).await.unwrap_err();
+        assert!(error.to_string().contains("conflicting roots"), "{error}");
+        assert!(!output.exists());
+    }
+
+    #[tokio::test]
+    async fn concat_raw_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let car = dir.path().join("chain4.forest.car.zst");
+        std::fs::write(
+            &car,
+            include_bytes!("../../../test-snapshots/chain4.forest.car.zst"),
+        )
+        .unwrap();
+        let output = dir.path().join("twice.forest.car.zst");
+
+        concat_raw(&car, &car, &output).await.unwrap();
+
+        let original = ForestCar::try_from(car.as_path()).unwrap();
+        let twice = ForestCar::try_from(output.as_path()).unwrap();
+        assert_eq!(twice.roots(), original.roots());
+        let plain =
+            PlainCar::new(include_bytes!("../../../test-snapshots/chain4.car").as_slice()).unwrap();
+        for cid in plain.cids() {
+            assert_eq!(twice.get(&cid).unwrap(), plain.get(&cid).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn concat_raw_rejects_plain_cars() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("chain4.car");
+        std::fs::write(&plain, include_bytes!("../../../test-snapshots/chain4.car")).unwrap();
+        let output = dir.path().join("output.forest.car.zst");
+        let error = concat_raw(&plain, &plain, &output).await.unwrap_err();
+        assert!(
+            error.to_string().contains("is not a valid forest CAR"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Compare the block sets of two [`PlainCar`]s.
+//!
+//! This is mostly useful for snapshot QA, e.g. to check that an incremental
+//! snapshot is a superset of its base snapshot.
+//!
+//! Only the indexes are walked. Block data is read from disk only for CIDs
+//! present in both archives with equal data lengths, where a byte comparison is
+//! the only way to tell the blocks apart.
+
+use super::{PlainCar, RandomAccessFileReader};
+use cid::Cid;
+use fvm_ipld_blockstore::Blockstore;
+
+/// The result of [`diff`]. All lists are sorted.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CarDiff {
+    /// CIDs indexed in `a` but not in `b`.
+    pub only_in_a: Vec<Cid>,
+    /// CIDs indexed in `b` but not in `a`.
+    pub only_in_b: Vec<Cid>,
+    /// CIDs indexed in both archives, but with differing block data.
+    pub conflicting: Vec<Cid>,
+}
+
+impl CarDiff {
+    /// `true` if both archives contain exactly the same blocks.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.conflicting.is_empty()
+    }
+
+    /// `true` if every block in `b` is also in `a`, with the same data.
+    pub fn a_is_superset(&self) -> bool {
+        self.only_in_b.is_empty() && self.conflicting.is_empty()
+    }
+}
+
+/// Report the blocks which are only in `a`, only in `b`, or in both but with
+/// different data. Blocks in the write caches are ignored.
+pub fn diff<A, B>(a: &PlainCar<A>, b: &PlainCar<B>) -> anyhow::Result<CarDiff>
+where
+    A: RandomAccessFileReader,
+    B: RandomAccessFileReader,
+{
+    let mut diff = CarDiff::default();
+    for cid in a.cids() {
+        match (a.block_data_length(&cid), b.block_data_length(&cid)) {
+            (Some(_), None) => diff.only_in_a.push(cid),
+            (Some(len_a), Some(len_b)) if len_a != len_b => diff.conflicting.push(cid),
+            (Some(_), Some(_)) => {
+                if a.get(&cid)? != b.get(&cid)? {
+                    diff.conflicting.push(cid)
+                }
+            }
+            (None, _) => {}
+        }
+    }
+    diff.only_in_b = b
+        .cids()
+        .into_iter()
+        .filter(|cid| a.block_data_length(cid).is_none())
+        .collect();
+
+    diff.only_in_a.sort();
+    diff.only_in_b.sort();
+    diff.conflicting.sort();
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::db::car_stream::{CarBlock, CarV1Header};
+    use crate::utils::multihash::prelude::*;
+    use fvm_ipld_encoding::{IPLD_RAW, to_vec};
+    use integer_encoding::VarInt as _;
+    use nunny::vec as nonempty;
+
+    fn block(data: &[u8]) -> CarBlock {
+        CarBlock {
+            cid: Cid::new_v1(IPLD_RAW, MultihashCode::Blake2b256.digest(data)),
+            data: data.to_vec(),
+        }
+    }
+
+    fn car(blocks: &[CarBlock]) -> PlainCar<Vec<u8>> {
+        let header = to_vec(&CarV1Header {
+            roots: nonempty![blocks[0].cid],
+            version: 1,
+        })
+        .unwrap();
+        let mut bytes = header.len().encode_var_vec();
+        bytes.extend(header);
+        for block in blocks {
+            block.write(&mut bytes).unwrap();
+        }
+        PlainCar::new(bytes).unwrap()
+    }
+
+    #[test]
+    fn diff_shared_subset() {
+        let (shared1, shared2) = (block(b"shared1"), block(b"shared2"));
+        let (only_a, only_b) = (block(b"only in a"), block(b"only in b"));
+        let a = car(&[shared1.clone(), only_a.clone(), shared2.clone()]);
+        let b = car(&[shared2, only_b.clone(), shared1]);
+
+        let result = diff(&a, &b).unwrap();
+        assert_eq!(result.only_in_a, vec![only_a.cid]);
+        assert_eq!(result.only_in_b, vec![only_b.cid]);
+        assert!(result.conflicting.is_empty());
+        assert!(!result.a_is_superset());
+    }
+
+    #[test]
+    fn diff_superset() {
+        let (base1, base2, extra) = (block(b"base1"), block(b"base2"), block(b"extra"));
+        let base = car(&[base1.clone(), base2.clone()]);
+        let incremental = car(&[extra.clone(), base1, base2]);
+
+        let result = diff(&incremental, &base).unwrap();
+        assert!(result.a_is_superset());
+        assert_eq!(result.only_in_a, vec![extra.cid]);
+        assert!(diff(&base, &base).unwrap().is_empty());
+    }
+
+    #[test]
+    fn diff_conflicting_data() {
+        let good = block(b"good");
+        let bad = CarBlock {
+            cid: good.cid,
+            data: b"baad".to_vec(),
+        };
+        let longer = CarBlock {
+            cid: good.cid,
+            data: b"much longer".to_vec(),
+        };
+        let diff_same_len = diff(&car(&[good.clone()]), &car(&[bad])).unwrap();
+        assert_eq!(diff_same_len.conflicting, vec![good.cid]);
+        let diff_other_len = diff(&car(&[good.clone()]), &car(&[longer])).unwrap();
+        assert_eq!(diff_other_len.conflicting, vec![good.cid]);
+    }
+}
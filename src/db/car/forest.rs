@@ -46,7 +46,7 @@
 //! CARv1 specification: <https://ipld.io/specs/transport/car/carv1/>
 //!
 
-use super::{CacheKey, ZstdFrameCache};
+use super::{CacheKey, CidBloomFilter, ZstdFrameCache};
 use crate::blocks::{Tipset, TipsetKey};
 use crate::db::PersistentStore;
 use crate::db::car::RandomAccessFileReader;
@@ -58,7 +58,7 @@ use ahash::{HashMap, HashMapExt};
 use byteorder::LittleEndian;
 use bytes::{BufMut as _, Bytes, BytesMut, buf::Writer};
 use cid::Cid;
-use futures::{Stream, TryStream, TryStreamExt as _};
+use futures::{Stream, StreamExt as _, TryStream, TryStreamExt as _};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::to_vec;
 use nunny::Vec as NonEmpty;
@@ -73,6 +73,7 @@ use std::{
     io::{Read, Write},
 };
 use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::codec::{Decoder, Encoder as _};
 use unsigned_varint::codec::UviBytes;
 
@@ -81,12 +82,16 @@ pub mod index;
 #[cfg(not(feature = "benchmark-private"))]
 mod index;
 
+pub(super) use index::summary;
+
 pub const FOREST_CAR_FILE_EXTENSION: &str = ".forest.car.zst";
 pub const TEMP_FOREST_CAR_FILE_EXTENSION: &str = ".forest.car.zst.tmp";
 /// <https://github.com/facebook/zstd/blob/dev/doc/zstd_compression_format.md#skippable-frames>
 pub const ZSTD_SKIPPABLE_FRAME_MAGIC_HEADER: [u8; 4] = [0x50, 0x2A, 0x4D, 0x18];
 pub const DEFAULT_FOREST_CAR_FRAME_SIZE: usize = 8000_usize.next_power_of_two();
 pub const DEFAULT_FOREST_CAR_COMPRESSION_LEVEL: u16 = zstd::DEFAULT_COMPRESSION_LEVEL as _;
+/// Around 512 KiB of compressed frames with [`DEFAULT_FOREST_CAR_FRAME_SIZE`].
+pub const DEFAULT_FOREST_CAR_MAX_BUFFERED_FRAMES: usize = 64;
 const ZSTD_SKIP_FRAME_LEN: u64 = 8;
 
 pub struct ForestCar<ReaderT> {
@@ -98,6 +103,7 @@ pub struct ForestCar<ReaderT> {
     frame_cache: Arc<Mutex<ZstdFrameCache>>,
     write_cache: Arc<RwLock<ahash::HashMap<Cid, Vec<u8>>>>,
     roots: NonEmpty<Cid>,
+    bloom_filter: Option<CidBloomFilter>,
 }
 
 impl<ReaderT: super::RandomAccessFileReader> ForestCar<ReaderT> {
@@ -118,9 +124,42 @@ impl<ReaderT: super::RandomAccessFileReader> ForestCar<ReaderT> {
             frame_cache: Arc::new(Mutex::new(ZstdFrameCache::default())),
             write_cache: Arc::new(RwLock::new(ahash::HashMap::default())),
             roots: header.roots,
+            bloom_filter: None,
         })
     }
 
+    /// Build a [`CidBloomFilter`] from the embedded index, so that
+    /// [`Self::might_contain`] can rule out most [`Cid`]s that aren't in the
+    /// archive. No blocks are decompressed.
+    pub fn with_bloom_filter(self, false_positive_rate: f64) -> io::Result<Self> {
+        let hashes = self.indexed.hashes()?.collect::<io::Result<Vec<_>>>()?;
+        let mut bloom_filter = CidBloomFilter::new(hashes.len(), false_positive_rate);
+        for hash in hashes {
+            bloom_filter.insert_summary(hash);
+        }
+        Ok(Self {
+            bloom_filter: Some(bloom_filter),
+            ..self
+        })
+    }
+
+    /// The hash [summaries](summary) of the blocks in the store, including
+    /// those in the write cache. No blocks are decompressed.
+    pub fn hash_summaries(&self) -> io::Result<Vec<u64>> {
+        let mut hashes = self.indexed.hashes()?.collect::<io::Result<Vec<_>>>()?;
+        hashes.extend(self.write_cache.read().keys().map(summary));
+        Ok(hashes)
+    }
+
+    /// Returns `false` if `k` is definitely not in the store. Always returns
+    /// `true` without a [bloom filter](Self::with_bloom_filter).
+    pub fn might_contain(&self, k: &Cid) -> bool {
+        self.bloom_filter
+            .as_ref()
+            .is_none_or(|bloom_filter| bloom_filter.might_contain(k))
+            || self.write_cache.read().contains_key(k)
+    }
+
     pub fn is_valid(reader: &ReaderT) -> bool {
         Self::validate_car(reader).is_ok()
     }
@@ -153,6 +192,54 @@ impl<ReaderT: super::RandomAccessFileReader> ForestCar<ReaderT> {
         &self.roots
     }
 
+    /// A quick structural check beyond [`Self::is_valid`]: the roots resolve,
+    /// and the blocks in the first and last block z-frames decompress, parse
+    /// and match their [`Cid`]s. Other z-frames are not read.
+    pub fn verify(&self) -> anyhow::Result<()> {
+        let (_, entries) = self.block_frames()?;
+        let frame_offsets = entries.iter().map(|&(_, frame_offset)| frame_offset);
+        let mut edge_frames = vec![];
+        edge_frames.extend(frame_offsets.clone().min());
+        edge_frames.extend(frame_offsets.max());
+        edge_frames.dedup();
+        let entire_file = self.indexed.reader().get_ref();
+        for frame_offset in edge_frames {
+            let mut zstd_frame =
+                decode_zstd_single_frame(Cursor::new_pos(entire_file, frame_offset))?;
+            while let Some(block_frame) =
+                UviBytes::<Bytes>::default().decode_eof(&mut zstd_frame)?
+            {
+                CarBlock::from_bytes(block_frame)?.validate()?;
+            }
+        }
+        for root in self.roots() {
+            anyhow::ensure!(self.get(root)?.is_some(), "root {root} is missing");
+        }
+        Ok(())
+    }
+
+    /// The byte range of the z-frames holding the blocks, between the header
+    /// z-frame and the index, and the entries of the index, see
+    /// [`index::Reader::entries`]. Blocks in the write cache are not included.
+    fn block_frames(&self) -> io::Result<(std::ops::Range<u64>, Vec<(u64, u64)>)> {
+        let (_, footer) = Self::validate_car(self.indexed.reader().get_ref())?;
+        let end = footer
+            .index
+            .checked_sub(ZSTD_SKIP_FRAME_LEN)
+            .ok_or_else(|| invalid_data("index offset is out of range"))?;
+        let entries = self.indexed.entries()?.collect::<io::Result<Vec<_>>>()?;
+        // The first block z-frame directly follows the header z-frame
+        let start = entries
+            .iter()
+            .map(|&(_, frame_offset)| frame_offset)
+            .min()
+            .unwrap_or(end);
+        if entries.iter().any(|&(_, frame_offset)| frame_offset >= end) {
+            return Err(invalid_data("index points past the block z-frames"));
+        }
+        Ok((start..end, entries))
+    }
+
     pub fn index_size_bytes(&self) -> u32 {
         self.index_size_bytes
     }
@@ -180,6 +267,7 @@ impl<ReaderT: super::RandomAccessFileReader> ForestCar<ReaderT> {
             frame_cache: self.frame_cache,
             write_cache: self.write_cache,
             roots: self.roots,
+            bloom_filter: self.bloom_filter,
         }
     }
 
@@ -288,14 +376,7 @@ impl Encoder {
         let mut offset = 0;
 
         // Write CARv1 header
-        let mut header_encoder = new_encoder(DEFAULT_FOREST_CAR_COMPRESSION_LEVEL)?;
-
-        let header = CarV1Header { roots, version: 1 };
-        let mut header_uvi_frame = BytesMut::new();
-        UviBytes::default().encode(Bytes::from(to_vec(&header)?), &mut header_uvi_frame)?;
-        header_encoder.write_all(&header_uvi_frame)?;
-        let header_bytes = header_encoder.finish()?.into_inner().freeze();
-
+        let header_bytes = encode_header(roots)?;
         sink.write_all(&header_bytes).await?;
         let header_len = header_bytes.len();
 
@@ -309,17 +390,79 @@ impl Encoder {
             offset += zstd_frame.len()
         }
 
-        // Create index
-        let writer = builder.into_writer();
-        write_skip_frame_header_async(&mut sink, writer.written_len().try_into().unwrap()).await?;
-        writer.write_into(&mut sink).await?;
+        write_index_and_footer(&mut sink, builder, offset as u64).await
+    }
+
+    /// Write the block z-frames of each of `cars` in turn, as they are, with a
+    /// new header and an index merged from the indexes of `cars`. Nothing is
+    /// decompressed or recompressed. Blocks that are in several of `cars` are
+    /// written several times.
+    pub async fn write_concatenated<ReaderT: super::RandomAccessFileReader>(
+        mut sink: impl AsyncWrite + Unpin,
+        roots: NonEmpty<Cid>,
+        cars: &[&ForestCar<ReaderT>],
+    ) -> anyhow::Result<()> {
+        const COPY_BUFFER_LEN: usize = 1 << 16;
+
+        let header_bytes = encode_header(roots)?;
+        sink.write_all(&header_bytes).await?;
+        let mut offset = header_bytes.len() as u64;
+
+        let mut builder = index::Builder::new();
+        let mut buffer = vec![0; COPY_BUFFER_LEN];
+        for car in cars {
+            let (frames, entries) = car.block_frames()?;
+            builder.extend_entries(
+                entries
+                    .into_iter()
+                    .map(|(hash, frame_offset)| (hash, frame_offset - frames.start + offset)),
+            )?;
+            let reader = car.indexed.reader().get_ref();
+            let mut position = frames.start;
+            while position < frames.end {
+                let len = (frames.end - position).min(COPY_BUFFER_LEN as u64) as usize;
+                reader.read_exact_at(position, &mut buffer[..len])?;
+                sink.write_all(&buffer[..len]).await?;
+                position += len as u64;
+            }
+            offset += frames.end - frames.start;
+        }
+
+        write_index_and_footer(&mut sink, builder, offset).await
+    }
 
-        // Write ForestCAR.zst footer, it's a valid ZSTD skip-frame
-        let footer = ForestCarFooter {
-            index: offset as u64 + ZSTD_SKIP_FRAME_LEN,
+    /// Like [`Encoder::write`], but `stream` is polled concurrently with writing
+    /// to `sink`, with at most `max_buffered_frames` (at least one) frames in
+    /// between. Once the buffer is full, `stream` is not polled until `sink`
+    /// catches up, so memory stays capped when e.g. the disk is slow.
+    pub async fn write_buffered(
+        sink: impl AsyncWrite + Unpin,
+        roots: NonEmpty<Cid>,
+        stream: impl TryStream<Ok = (Vec<Cid>, Bytes), Error = anyhow::Error>,
+        max_buffered_frames: usize,
+    ) -> anyhow::Result<()> {
+        let (tx, rx) = tokio::sync::mpsc::channel(max_buffered_frames.max(1));
+        let produce = async move {
+            let mut stream = std::pin::pin!(stream.into_stream());
+            while let Some(frame) = stream.next().await {
+                let is_err = frame.is_err();
+                // Sending fails if writing did
+                if tx.send(frame).await.is_err() || is_err {
+                    break;
+                }
+            }
         };
-        sink.write_all(&footer.to_le_bytes()).await?;
-        Ok(())
+        let ((), written) =
+            futures::future::join(produce, Self::write(sink, roots, ReceiverStream::new(rx))).await;
+        written
+    }
+
+    /// An upper bound of the length of the index and footer that [`Encoder::write`]
+    /// appends after `num_blocks` blocks.
+    pub fn max_index_and_footer_len(num_blocks: usize) -> u64 {
+        ZSTD_SKIP_FRAME_LEN
+            + index::Writer::max_written_len(num_blocks)
+            + ForestCarFooter::SIZE as u64
     }
 
     /// `compress_stream` with [`DEFAULT_FOREST_CAR_FRAME_SIZE`] as default frame size and [`DEFAULT_FOREST_CAR_COMPRESSION_LEVEL`] as default compression level.
@@ -391,6 +534,36 @@ impl Encoder {
     }
 }
 
+/// The header z-frame of a `.forest.car.zst`.
+fn encode_header(roots: NonEmpty<Cid>) -> anyhow::Result<Bytes> {
+    let mut header_encoder = new_encoder(DEFAULT_FOREST_CAR_COMPRESSION_LEVEL)?;
+    let header = CarV1Header { roots, version: 1 };
+    let mut header_uvi_frame = BytesMut::new();
+    UviBytes::default().encode(Bytes::from(to_vec(&header)?), &mut header_uvi_frame)?;
+    header_encoder.write_all(&header_uvi_frame)?;
+    Ok(header_encoder.finish()?.into_inner().freeze())
+}
+
+/// Write the index built by `builder` and the footer, after the block z-frames
+/// that end at `offset`.
+async fn write_index_and_footer(
+    mut sink: impl AsyncWrite + Unpin,
+    builder: index::Builder,
+    offset: u64,
+) -> anyhow::Result<()> {
+    // Create index
+    let writer = builder.into_writer();
+    write_skip_frame_header_async(&mut sink, writer.written_len().try_into().unwrap()).await?;
+    writer.write_into(&mut sink).await?;
+
+    // Write ForestCAR.zst footer, it's a valid ZSTD skip-frame
+    let footer = ForestCarFooter {
+        index: offset + ZSTD_SKIP_FRAME_LEN,
+    };
+    sink.write_all(&footer.to_le_bytes()).await?;
+    Ok(())
+}
+
 fn invalid_data(inner: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
     io::Error::new(io::ErrorKind::InvalidData, inner)
 }
@@ -480,12 +653,71 @@ mod tests {
         })
     }
 
+    #[tokio::test]
+    async fn write_buffered_is_bounded() {
+        use crate::utils::multihash::prelude::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        const FRAME_LEN: usize = 1024;
+        const FRAMES: usize = 64;
+        const MAX_BUFFERED_FRAMES: usize = 4;
+
+        let produced = Arc::new(AtomicUsize::new(0));
+        let frames = futures::stream::iter(0..FRAMES).map({
+            let produced = produced.clone();
+            move |_| {
+                produced.fetch_add(1, Ordering::SeqCst);
+                anyhow::Ok((vec![], Bytes::from(vec![0; FRAME_LEN])))
+            }
+        });
+        // A slow disk
+        let (sink, mut disk) = tokio::io::duplex(FRAME_LEN);
+        let read = tokio::spawn({
+            let produced = produced.clone();
+            async move {
+                let mut buf = vec![0; FRAME_LEN];
+                let mut read = 0;
+                loop {
+                    let n = tokio::io::AsyncReadExt::read(&mut disk, &mut buf)
+                        .await
+                        .unwrap();
+                    if n == 0 {
+                        break read;
+                    }
+                    read += n;
+                    // Besides the buffered frames, one frame may wait for room
+                    // in the buffer, one is being written, and one is in the
+                    // pipe.
+                    let written_frames = read / FRAME_LEN;
+                    let in_flight = produced
+                        .load(Ordering::SeqCst)
+                        .saturating_sub(written_frames);
+                    assert!(
+                        in_flight <= MAX_BUFFERED_FRAMES + 3,
+                        "{in_flight} frames in flight"
+                    );
+                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                }
+            }
+        });
+        let roots = nonempty![Cid::new_v1(
+            fvm_ipld_encoding::DAG_CBOR,
+            MultihashCode::Blake2b256.digest(b"root"),
+        )];
+        Encoder::write_buffered(sink, roots, frames, MAX_BUFFERED_FRAMES)
+            .await
+            .unwrap();
+        assert!(read.await.unwrap() > FRAMES * FRAME_LEN);
+        assert_eq!(produced.load(Ordering::SeqCst), FRAMES);
+    }
+
     #[quickcheck]
     fn forest_car_create_basic(blocks: nunny::Vec<CarBlock>) {
         let roots = nonempty!(blocks.first().cid);
         let forest_car =
             ForestCar::new(mk_encoded_car(1024 * 4, 3, roots.clone(), blocks.clone())).unwrap();
         assert_eq!(forest_car.roots(), &roots);
+        forest_car.verify().unwrap();
         for block in blocks {
             assert_eq!(forest_car.get(&block.cid).unwrap(), Some(block.data));
         }
@@ -86,6 +86,11 @@ mod hash;
 #[cfg(any(test, feature = "benchmark-private"))]
 pub mod hash;
 
+/// The `u64` summary of a [`Cid`] that the table is keyed by.
+pub fn summary(cid: &Cid) -> u64 {
+    hash::summary(cid).get()
+}
+
 /// Reader for the `.forest.car.zst`'s embedded index.
 ///
 /// Performs lookups and slot iteration.
@@ -190,13 +195,11 @@ where
 }
 
 #[cfg_vis(feature = "benchmark-private", pub)]
-#[allow(unused)] // required for benchmarking
 struct Iter<R> {
     inner: R,
     positions: iter::StepBy<std::ops::Range<u64>>,
 }
 
-#[allow(unused)] // required for benchmarking
 impl<R> Iterator for Iter<R>
 where
     R: ReadAt + Size,
@@ -215,7 +218,6 @@ where
     R: ReadAt + Size,
 {
     #[cfg_vis(feature = "benchmark-private", pub)]
-    #[allow(unused)] // required for benchmarking
     fn iter(&self) -> io::Result<Iter<&R>> {
         let end = self
             .inner
@@ -226,6 +228,23 @@ where
             positions: (self.table_offset..end).step_by(Slot::LEN.try_into().unwrap()),
         })
     }
+
+    /// The [hash summaries](summary) of all keys in the table.
+    pub fn hashes(&self) -> io::Result<impl Iterator<Item = io::Result<u64>> + '_> {
+        Ok(self.entries()?.map_ok(|(hash, _)| hash))
+    }
+
+    /// The [hash summaries](summary) and frame offsets of all keys in the
+    /// table, see [`Builder::extend_entries`].
+    pub fn entries(&self) -> io::Result<impl Iterator<Item = io::Result<(u64, u64)>> + '_> {
+        Ok(self.iter()?.filter_map(|slot| match slot {
+            Ok(Slot::Occupied(OccupiedSlot { hash, frame_offset })) => {
+                Some(Ok((hash.get(), frame_offset)))
+            }
+            Ok(Slot::Empty) => None,
+            Err(e) => Some(Err(e)),
+        }))
+    }
 }
 
 const DEFAULT_LOAD_FACTOR: f64 = 0.8;
@@ -258,6 +277,20 @@ impl Builder {
         }
     }
 
+    /// Add entries read with [`Reader::entries`], e.g. to merge tables.
+    pub fn extend_entries(
+        &mut self,
+        entries: impl IntoIterator<Item = (u64, u64)>,
+    ) -> io::Result<()> {
+        for (hash, frame_offset) in entries {
+            let hash = NonMaximalU64::new(hash).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid hash summary")
+            })?;
+            self.extend([(hash, frame_offset)]);
+        }
+        Ok(())
+    }
+
     pub fn into_writer(self) -> Writer {
         let Self {
             load_factor,
@@ -358,6 +391,18 @@ pub struct Writer {
 }
 
 impl Writer {
+    /// An upper bound of [`Self::written_len`] for a table of `num_entries`
+    /// entries, without hashing them.
+    pub fn max_written_len(num_entries: usize) -> u64 {
+        let initial_width =
+            initial_width(num_entries, DEFAULT_LOAD_FACTOR).map_or(0, |it| it.get());
+        // Entries are placed at or after their ideal slot, which is within the
+        // initial width.
+        <Version as Writeable>::LEN
+            + <V1Header as Writeable>::LEN
+            + u64::try_from(initial_width + num_entries + 1 /* trailing */).unwrap() * Slot::LEN
+    }
+
     pub fn written_len(&self) -> u64 {
         let Self {
             version,
@@ -671,6 +716,7 @@ mod tests {
 
     /// [`Reader`] should behave like a [`HashMap`], with a caveat for collisions.
     fn do_hashmap_of_cids(reference: HashMap<Cid, HashSet<u64>>) {
+        let num_entries = reference.values().map(HashSet::len).sum();
         let subject = Reader::new(write_to_vec(|v| {
             let writer =
                 Builder::from_iter(reference.clone().into_iter().flat_map(|(hash, offsets)| {
@@ -678,6 +724,7 @@ mod tests {
                 }))
                 .into_writer();
             let expected_len = writer.written_len();
+            assert!(expected_len <= Writer::max_written_len(num_entries));
             block_on(writer.write_into(&mut *v))?;
             assert_eq!(expected_len as usize, v.len());
             Ok(())
@@ -698,6 +745,7 @@ mod tests {
     ///
     /// Additionally checks [`Reader::iter`]
     fn do_hashmap_of_hashes(reference: HashMap<NonMaximalU64, HashSet<u64>>) {
+        let num_entries = reference.values().map(HashSet::len).sum();
         let subject = Reader::new(write_to_vec(|v| {
             let writer =
                 Builder::from_iter(reference.clone().into_iter().flat_map(|(hash, offsets)| {
@@ -705,6 +753,7 @@ mod tests {
                 }))
                 .into_writer();
             let expected_len = writer.written_len();
+            assert!(expected_len <= Writer::max_written_len(num_entries));
             block_on(writer.write_into(&mut *v))?;
             assert_eq!(expected_len as usize, v.len());
             Ok(())
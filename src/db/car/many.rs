@@ -7,12 +7,32 @@
 //! requests are only forwarded to the writable store.
 //!
 //! A single z-frame cache is shared between all read-only stores.
+//!
+//! The order in which the writable store and the read-only stores are probed
+//! is configurable with [`ProbeOrder`]. Optionally, each side keeps a small
+//! negative cache of recently missed CIDs so that repeated misses skip the
+//! lookup entirely. Negative cache entries are invalidated on writes that go
+//! through [`ManyCar`]; writes made directly to the writable store are not
+//! observed.
+//!
+//! Each read-only store counts the reads it serves, see [`ManyCar::layer_stats`].
+//!
+//! Each read-only store reports its CAR version, see [`ManyCar::versions`]. In
+//! strict mode, stores with different CAR versions are rejected, see
+//! [`ManyCar::with_strict_versions`].
+//!
+//! Read-only stores may also be given a [`CidBloomFilter`](super::CidBloomFilter)
+//! when they are added, so that lookups skip the stores that definitely don't
+//! contain a CID.
+//!
+//! [`ManyCar::compact`] flattens the indexes of the read-only stores, so that
+//! lookups go straight to the first store holding a CID, see [`CompactIndex`].
 
-use super::{AnyCar, ZstdFrameCache};
+use super::{AnyCar, ZstdFrameCache, forest::summary};
 use crate::blocks::TipsetKey;
 use crate::db::{
-    BlockstoreWriteOpsSubscribable, EthMappingsStore, IndicesStore, MemoryDB, PersistentStore,
-    SettingsStore, SettingsStoreExt,
+    BlockstoreWriteOpsSubscribable, ColumnStats, EthMappingsStore, IndicesStore, MemoryDB,
+    PersistentStore, SettingsStore, SettingsStoreExt,
 };
 use crate::libp2p_bitswap::BitswapStoreReadWrite;
 use crate::rpc::eth::types::EthHash;
@@ -23,23 +43,130 @@ use crate::{blocks::Tipset, libp2p_bitswap::BitswapStoreRead};
 use anyhow::Context as _;
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
+use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ord;
 use std::collections::BinaryHeap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{self, AtomicU64};
 use std::{path::PathBuf, sync::Arc};
 
+/// The order in which [`ManyCar`] probes its stores on reads.
+#[derive(
+    Default,
+    PartialEq,
+    Eq,
+    Debug,
+    Clone,
+    Copy,
+    strum::Display,
+    strum::EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(test, derive(derive_quickcheck_arbitrary::Arbitrary))]
+pub enum ProbeOrder {
+    #[default]
+    /// Probe the writable store before the read-only `CAR`s. Suits nodes
+    /// whose data mostly lives in the database.
+    WriterFirst,
+    /// Probe the read-only `CAR`s before the writable store. Suits nodes
+    /// running mostly from snapshots.
+    ReadOnlyFirst,
+}
+
+/// A bounded set of recently missed [`Cid`]s. A capacity of zero disables the
+/// cache.
+struct NegativeCache(Option<Mutex<NegativeCacheInner>>);
+
+struct NegativeCacheInner {
+    misses: LruCache<Cid, ()>,
+    /// Bumped on every invalidation, so that a lookup racing with a write
+    /// doesn't record a stale miss.
+    generation: u64,
+}
+
+impl NegativeCache {
+    fn new(capacity: usize) -> Self {
+        Self(NonZeroUsize::new(capacity).map(|capacity| {
+            Mutex::new(NegativeCacheInner {
+                misses: LruCache::new(capacity),
+                generation: 0,
+            })
+        }))
+    }
+
+    /// Run `lookup` unless `k` is a known miss, and remember `k` if `lookup`
+    /// doesn't find it.
+    fn probe<T>(
+        &self,
+        k: &Cid,
+        lookup: impl FnOnce() -> anyhow::Result<Option<T>>,
+    ) -> anyhow::Result<Option<T>> {
+        let Some(inner) = &self.0 else {
+            return lookup();
+        };
+        let generation = {
+            let mut inner = inner.lock();
+            if inner.misses.get(k).is_some() {
+                return Ok(None);
+            }
+            inner.generation
+        };
+        let found = lookup()?;
+        if found.is_none() {
+            let mut inner = inner.lock();
+            if inner.generation == generation {
+                inner.misses.put(*k, ());
+            }
+        }
+        Ok(found)
+    }
+
+    fn invalidate(&self, k: &Cid) {
+        if let Some(inner) = &self.0 {
+            let mut inner = inner.lock();
+            inner.generation = inner.generation.wrapping_add(1);
+            inner.misses.pop(k);
+        }
+    }
+
+    fn invalidate_all(&self) {
+        if let Some(inner) = &self.0 {
+            let mut inner = inner.lock();
+            inner.generation = inner.generation.wrapping_add(1);
+            inner.misses.clear();
+        }
+    }
+}
+
 struct WithHeaviestEpoch {
     pub car: AnyCar<Box<dyn super::RandomAccessFileReader>>,
     epoch: ChainEpoch,
+    /// Empty if the store wasn't opened from a file.
+    path: PathBuf,
+    /// Number of reads served by this store.
+    hits: AtomicU64,
 }
 
 impl WithHeaviestEpoch {
-    pub fn new(car: AnyCar<Box<dyn super::RandomAccessFileReader>>) -> anyhow::Result<Self> {
+    pub fn new(
+        car: AnyCar<Box<dyn super::RandomAccessFileReader>>,
+        path: PathBuf,
+    ) -> anyhow::Result<Self> {
         let epoch = car
             .heaviest_tipset()
             .context("store doesn't have a heaviest tipset")?
             .epoch();
-        Ok(Self { car, epoch })
+        Ok(Self {
+            car,
+            epoch,
+            path,
+            hits: AtomicU64::new(0),
+        })
     }
 }
 
@@ -63,10 +190,25 @@ impl PartialEq for WithHeaviestEpoch {
     }
 }
 
+/// The position (in probe order) of the first read-only store holding a block
+/// with each hash [`summary`]. Hash summaries may collide, so a miss in that
+/// store falls back to probing every store.
+///
+/// The positions are only valid until the next store is added, which discards
+/// the index.
+struct CompactIndex(ahash::HashMap<u64, usize>);
+
 pub struct ManyCar<WriterT = MemoryDB> {
     shared_cache: Arc<Mutex<ZstdFrameCache>>,
     read_only: Arc<RwLock<BinaryHeap<WithHeaviestEpoch>>>,
+    /// Always locked after `read_only`, see [`ManyCar::compact`].
+    compact_index: RwLock<Option<CompactIndex>>,
     writer: WriterT,
+    probe_order: ProbeOrder,
+    writer_misses: NegativeCache,
+    read_only_misses: NegativeCache,
+    bloom_filter_false_positive_rate: Option<f64>,
+    strict_versions: bool,
 }
 
 impl<WriterT> ManyCar<WriterT> {
@@ -74,10 +216,49 @@ impl<WriterT> ManyCar<WriterT> {
         ManyCar {
             shared_cache: Arc::new(Mutex::new(ZstdFrameCache::default())),
             read_only: Arc::new(RwLock::new(BinaryHeap::default())),
+            compact_index: RwLock::new(None),
             writer,
+            probe_order: ProbeOrder::default(),
+            writer_misses: NegativeCache::new(0),
+            read_only_misses: NegativeCache::new(0),
+            bloom_filter_false_positive_rate: None,
+            strict_versions: false,
         }
     }
 
+    /// Set the order in which stores are probed on reads.
+    pub fn with_probe_order(mut self, probe_order: ProbeOrder) -> Self {
+        self.probe_order = probe_order;
+        self
+    }
+
+    /// Remember up to `capacity` recently missed CIDs for the writable store
+    /// and for the read-only stores each. A capacity of zero disables the
+    /// negative caches.
+    pub fn with_negative_cache(mut self, capacity: usize) -> Self {
+        self.writer_misses = NegativeCache::new(capacity);
+        self.read_only_misses = NegativeCache::new(capacity);
+        self
+    }
+
+    /// Build a bloom filter with the given false positive rate for each
+    /// read-only store added from now on, see [`AnyCar::with_bloom_filter`].
+    pub fn with_bloom_filters(mut self, false_positive_rate: f64) -> Self {
+        self.bloom_filter_false_positive_rate = Some(false_positive_rate);
+        self
+    }
+
+    /// Reject read-only stores whose CAR version differs from the stores
+    /// added before them, see [`AnyCar::version`].
+    pub fn with_strict_versions(mut self) -> Self {
+        self.strict_versions = true;
+        self
+    }
+
+    pub fn probe_order(&self) -> ProbeOrder {
+        self.probe_order
+    }
+
     pub fn writer(&self) -> &WriterT {
         &self.writer
     }
@@ -102,14 +283,46 @@ impl<WriterT> ManyCar<WriterT> {
         &self,
         any_car: AnyCar<ReaderT>,
     ) -> anyhow::Result<()> {
+        self.read_only_with_path(any_car, PathBuf::new())
+    }
+
+    /// Like [`Self::read_only`], for a store opened from the file at `path`, see
+    /// [`Self::layer_stats`].
+    pub fn read_only_with_path<ReaderT: super::RandomAccessFileReader>(
+        &self,
+        any_car: AnyCar<ReaderT>,
+        path: PathBuf,
+    ) -> anyhow::Result<()> {
+        // Writes go to the writable store, so reads can skip the write cache
+        let any_car = any_car.into_read_only();
+        let any_car = match self.bloom_filter_false_positive_rate {
+            Some(false_positive_rate) => any_car.with_bloom_filter(false_positive_rate)?,
+            None => any_car,
+        };
         let mut read_only = self.read_only.write();
+        if self.strict_versions
+            && let Some(layer) = read_only
+                .iter()
+                .find(|layer| layer.car.version() != any_car.version())
+        {
+            anyhow::bail!(
+                "can't mix CAR versions: {} is CARv{} but {} is CARv{}",
+                path.display(),
+                any_car.version(),
+                layer.path.display(),
+                layer.car.version()
+            );
+        }
         let key = read_only.len() as u64;
 
         read_only.push(WithHeaviestEpoch::new(
             any_car
                 .with_cache(self.shared_cache.clone(), key)
                 .into_dyn(),
+            path,
         )?);
+        *self.compact_index.write() = None;
+        self.read_only_misses.invalidate_all();
 
         Ok(())
     }
@@ -124,12 +337,65 @@ impl<WriterT> ManyCar<WriterT> {
 
     pub fn read_only_files(&self, files: impl Iterator<Item = PathBuf>) -> anyhow::Result<()> {
         for file in files {
-            self.read_only(AnyCar::new(EitherMmapOrRandomAccessFile::open(file)?)?)?;
+            let any_car = AnyCar::new(EitherMmapOrRandomAccessFile::open(&file)?)?;
+            self.read_only_with_path(any_car, file)?;
         }
 
         Ok(())
     }
 
+    /// Build a single index over the read-only stores, mapping each block to
+    /// the first store (in probe order) that holds it, and dropping the
+    /// entries of stores it shadows. Lookups then probe at most one store,
+    /// and misses probe none. No blocks are decompressed.
+    ///
+    /// Reads are served as usual while the index is built. Adding a store
+    /// discards the index, so call this again after [`Self::read_only`].
+    pub fn compact(&self) -> anyhow::Result<()> {
+        let read_only = self.read_only.read();
+        let mut index = ahash::HashMap::default();
+        for (position, layer) in read_only.as_slice().iter().enumerate() {
+            for hash in layer.car.hash_summaries()? {
+                index.entry(hash).or_insert(position);
+            }
+        }
+        // Still holding `read_only`, so no store was added in the meantime
+        *self.compact_index.write() = Some(CompactIndex(index));
+        Ok(())
+    }
+
+    /// Number of entries in the index built by [`Self::compact`], if any.
+    pub fn compact_index_len(&self) -> Option<usize> {
+        self.compact_index.read().as_ref().map(|it| it.0.len())
+    }
+
+    /// The number of reads served by each read-only store, in the order they
+    /// are probed, by file path. The path is empty for stores that weren't
+    /// opened from a file, see [`Self::read_only_with_path`].
+    pub fn layer_stats(&self) -> Vec<(PathBuf, u64)> {
+        self.read_only
+            .read()
+            .iter()
+            .map(|layer| {
+                (
+                    layer.path.clone(),
+                    layer.hits.load(atomic::Ordering::Relaxed),
+                )
+            })
+            .collect()
+    }
+
+    /// The CAR version of each read-only store, in the order they are probed,
+    /// by file path. The path is empty for stores that weren't opened from a
+    /// file, see [`Self::read_only_with_path`].
+    pub fn versions(&self) -> Vec<(PathBuf, u64)> {
+        self.read_only
+            .read()
+            .iter()
+            .map(|layer| (layer.path.clone(), layer.car.version()))
+            .collect()
+    }
+
     pub fn heaviest_tipset_key(&self) -> anyhow::Result<TipsetKey> {
         self.read_only
             .read()
@@ -166,30 +432,74 @@ impl TryFrom<Vec<PathBuf>> for ManyCar<MemoryDB> {
     }
 }
 
+impl<WriterT: Blockstore> ManyCar<WriterT> {
+    /// Errors from the writable store are treated as misses.
+    fn get_from_writer(&self, k: &Cid) -> Option<Vec<u8>> {
+        self.writer_misses
+            .probe(k, || self.writer.get(k))
+            .ok()
+            .flatten()
+    }
+
+    fn get_from_read_only(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        self.read_only_misses.probe(k, || {
+            let read_only = self.read_only.read();
+            if let Some(CompactIndex(index)) = &*self.compact_index.read() {
+                let Some(reader) = index
+                    .get(&summary(k))
+                    .and_then(|position| read_only.as_slice().get(*position))
+                else {
+                    return Ok(None);
+                };
+                if let Some(val) = reader.car.get(k)? {
+                    reader.hits.fetch_add(1, atomic::Ordering::Relaxed);
+                    return Ok(Some(val));
+                }
+                // A hash collision, fall back to probing every store
+            }
+            // Theoretically it should be easily parallelizable with `rayon`.
+            // In practice, there is a massive performance loss when providing
+            // more than a single reader.
+            for reader in read_only.iter() {
+                if !reader.car.might_contain(k) {
+                    continue;
+                }
+                if let Some(val) = reader.car.get(k)? {
+                    reader.hits.fetch_add(1, atomic::Ordering::Relaxed);
+                    return Ok(Some(val));
+                }
+            }
+            Ok(None)
+        })
+    }
+}
+
 impl<WriterT: Blockstore> Blockstore for ManyCar<WriterT> {
     fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
-        // Theoretically it should be easily parallelizable with `rayon`.
-        // In practice, there is a massive performance loss when providing
-        // more than a single reader.
-        if let Ok(Some(value)) = self.writer.get(k) {
-            return Ok(Some(value));
-        }
-        for reader in self.read_only.read().iter() {
-            if let Some(val) = reader.car.get(k)? {
-                return Ok(Some(val));
-            }
+        match self.probe_order {
+            ProbeOrder::WriterFirst => match self.get_from_writer(k) {
+                Some(value) => Ok(Some(value)),
+                None => self.get_from_read_only(k),
+            },
+            ProbeOrder::ReadOnlyFirst => match self.get_from_read_only(k)? {
+                Some(value) => Ok(Some(value)),
+                None => Ok(self.get_from_writer(k)),
+            },
         }
-        Ok(None)
     }
 
     fn put_keyed(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
-        self.writer.put_keyed(k, block)
+        self.writer.put_keyed(k, block)?;
+        self.writer_misses.invalidate(k);
+        Ok(())
     }
 }
 
 impl<WriterT: PersistentStore> PersistentStore for ManyCar<WriterT> {
     fn put_keyed_persistent(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
-        self.writer.put_keyed_persistent(k, block)
+        self.writer.put_keyed_persistent(k, block)?;
+        self.writer_misses.invalidate(k);
+        Ok(())
     }
 }
 
@@ -246,9 +556,17 @@ impl<WriterT: EthMappingsStore> EthMappingsStore for ManyCar<WriterT> {
         EthMappingsStore::get_message_cids(self.writer())
     }
 
+    fn get_tipset_keys(&self) -> anyhow::Result<Vec<TipsetKey>> {
+        EthMappingsStore::get_tipset_keys(self.writer())
+    }
+
     fn delete(&self, keys: Vec<EthHash>) -> anyhow::Result<()> {
         EthMappingsStore::delete(self.writer(), keys)
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        EthMappingsStore::stats(self.writer())
+    }
 }
 
 impl<WriterT: IndicesStore> IndicesStore for ManyCar<WriterT> {
@@ -263,6 +581,10 @@ impl<WriterT: IndicesStore> IndicesStore for ManyCar<WriterT> {
     fn exists(&self, key: &Cid) -> anyhow::Result<bool> {
         IndicesStore::exists(self.writer(), key)
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        IndicesStore::stats(self.writer())
+    }
 }
 
 impl<T: Blockstore + SettingsStore> super::super::HeaviestTipsetKeyProvider for ManyCar<T> {
@@ -316,6 +638,208 @@ mod tests {
         );
     }
 
+    #[test]
+    fn many_car_negative_cache_interleaved() {
+        for probe_order in [ProbeOrder::WriterFirst, ProbeOrder::ReadOnlyFirst] {
+            let many = ManyCar::new(MemoryDB::default())
+                .with_probe_order(probe_order)
+                .with_negative_cache(2);
+            let blocks = (0..4u8)
+                .map(|i| {
+                    let data = vec![i; 8];
+                    (
+                        Cid::new_v1(
+                            fvm_ipld_encoding::IPLD_RAW,
+                            MultihashCode::Blake2b256.digest(&data),
+                        ),
+                        data,
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            for (i, (cid, data)) in blocks.iter().enumerate() {
+                // Miss twice so that the CID is negatively cached
+                assert_eq!(many.get(cid).unwrap(), None);
+                assert_eq!(many.get(cid).unwrap(), None);
+                many.put_keyed(cid, data).unwrap();
+                assert_eq!(many.get(cid).unwrap().as_ref(), Some(data));
+                // Blocks written earlier must still be visible
+                for (cid, data) in &blocks[..=i] {
+                    assert_eq!(many.get(cid).unwrap().as_ref(), Some(data));
+                }
+                for (cid, _) in &blocks[i + 1..] {
+                    assert_eq!(many.get(cid).unwrap(), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn many_car_negative_cache_read_only() {
+        for probe_order in [ProbeOrder::WriterFirst, ProbeOrder::ReadOnlyFirst] {
+            let many = ManyCar::new(MemoryDB::default())
+                .with_probe_order(probe_order)
+                .with_negative_cache(16);
+            assert!(!many.has(&mainnet::GENESIS_CID).unwrap());
+            assert!(!many.has(&mainnet::GENESIS_CID).unwrap());
+            many.read_only(AnyCar::try_from(mainnet::DEFAULT_GENESIS).unwrap())
+                .unwrap();
+            assert!(many.has(&mainnet::GENESIS_CID).unwrap());
+        }
+    }
+
+    #[test]
+    fn many_car_bloom_filters() {
+        let chain4 = include_bytes!("../../../test-snapshots/chain4.car").as_slice();
+        let chain4_forest =
+            include_bytes!("../../../test-snapshots/chain4.forest.car.zst").as_slice();
+        let many = ManyCar::new(MemoryDB::default())
+            .with_bloom_filters(0.01)
+            .with_read_only(AnyCar::new(chain4_forest).unwrap())
+            .unwrap()
+            .with_read_only(AnyCar::try_from(calibnet::DEFAULT_GENESIS).unwrap())
+            .unwrap();
+        for plain in [
+            super::super::PlainCar::new(chain4).unwrap(),
+            super::super::PlainCar::new(calibnet::DEFAULT_GENESIS).unwrap(),
+        ] {
+            for cid in plain.cids() {
+                assert!(many.has(&cid).unwrap());
+            }
+        }
+        assert!(!many.has(&mainnet::GENESIS_CID).unwrap());
+    }
+
+    #[test]
+    fn many_car_layer_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain4 = dir.path().join("chain4.car");
+        std::fs::write(
+            &chain4,
+            include_bytes!("../../../test-snapshots/chain4.car"),
+        )
+        .unwrap();
+        let genesis = dir.path().join("calibnet_genesis.car");
+        std::fs::write(&genesis, calibnet::DEFAULT_GENESIS).unwrap();
+        let many = ManyCar::new(MemoryDB::default())
+            .with_read_only_files([chain4.clone(), genesis.clone()].into_iter())
+            .unwrap();
+        let hits = |path: &PathBuf| {
+            many.layer_stats()
+                .into_iter()
+                .find_map(|(it, hits)| (&it == path).then_some(hits))
+                .unwrap()
+        };
+        assert_eq!((hits(&chain4), hits(&genesis)), (0, 0));
+
+        let head = *many.heaviest_tipset_key().unwrap().to_cids().first();
+        for _ in 0..3 {
+            many.get(&calibnet::GENESIS_CID).unwrap().unwrap();
+        }
+        for _ in 0..2 {
+            many.get(&head).unwrap().unwrap();
+        }
+        // Misses aren't counted
+        assert!(!many.has(&mainnet::GENESIS_CID).unwrap());
+        assert_eq!((hits(&chain4), hits(&genesis)), (2, 3));
+    }
+
+    #[test]
+    fn many_car_versions() {
+        const PRAGMA: [u8; 11] = [
+            0x0a, 0xa1, 0x67, 0x76, 0x65, 0x72, 0x73, 0x69, 0x6f, 0x6e, 0x02,
+        ];
+        const HEADER_LEN: usize = 40;
+        let v1 = include_bytes!("../../../test-snapshots/chain4.car").as_slice();
+        let mut v2 = PRAGMA.to_vec();
+        v2.extend([0; 16]); // characteristics
+        v2.extend(((PRAGMA.len() + HEADER_LEN) as i64).to_le_bytes()); // data offset
+        v2.extend((v1.len() as i64).to_le_bytes()); // data size
+        v2.extend(0_i64.to_le_bytes()); // index offset
+        v2.extend(v1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let v1_path = dir.path().join("v1.car");
+        std::fs::write(&v1_path, v1).unwrap();
+        let v2_path = dir.path().join("v2.car");
+        std::fs::write(&v2_path, v2).unwrap();
+
+        let many = ManyCar::new(MemoryDB::default())
+            .with_read_only_files([v1_path.clone(), v2_path.clone()].into_iter())
+            .unwrap();
+        let mut versions = many.versions();
+        versions.sort();
+        assert_eq!(versions, vec![(v1_path.clone(), 1), (v2_path.clone(), 2)]);
+
+        let strict = ManyCar::new(MemoryDB::default()).with_strict_versions();
+        strict
+            .read_only_files([v1_path.clone()].into_iter())
+            .unwrap();
+        let err = strict
+            .read_only_files([v2_path.clone()].into_iter())
+            .unwrap_err();
+        assert!(err.to_string().contains("can't mix CAR versions"), "{err}");
+        assert_eq!(strict.versions(), vec![(v1_path.clone(), 1)]);
+        // Layers of the same version are still accepted
+        strict.read_only_files([v1_path].into_iter()).unwrap();
+        assert_eq!(strict.len(), 2);
+    }
+
+    #[test]
+    fn many_car_compact() {
+        use super::super::PlainCar;
+
+        let chain4 = include_bytes!("../../../test-snapshots/chain4.car").as_slice();
+        let chain4_forest =
+            include_bytes!("../../../test-snapshots/chain4.forest.car.zst").as_slice();
+        // chain4 is in two of the stores
+        let many = ManyCar::new(MemoryDB::default())
+            .with_read_only(AnyCar::new(chain4).unwrap())
+            .unwrap()
+            .with_read_only(AnyCar::new(chain4_forest).unwrap())
+            .unwrap()
+            .with_read_only(AnyCar::try_from(calibnet::DEFAULT_GENESIS).unwrap())
+            .unwrap();
+        let cids = PlainCar::new(chain4)
+            .unwrap()
+            .cids()
+            .into_iter()
+            .chain(PlainCar::new(calibnet::DEFAULT_GENESIS).unwrap().cids())
+            .collect::<Vec<_>>();
+        let missing = Cid::new_v1(
+            fvm_ipld_encoding::IPLD_RAW,
+            MultihashCode::Blake2b256.digest(b"missing"),
+        );
+        let read_all = || {
+            cids.iter()
+                .map(|cid| many.get(cid).unwrap())
+                .collect::<Vec<_>>()
+        };
+        let before = read_all();
+        assert!(before.iter().all(Option::is_some));
+
+        assert_eq!(many.compact_index_len(), None);
+        many.compact().unwrap();
+        let unique = cids.iter().collect::<std::collections::HashSet<_>>().len();
+        assert_eq!(many.compact_index_len(), Some(unique));
+        assert_eq!(read_all(), before);
+        assert_eq!(many.get(&missing).unwrap(), None);
+
+        // Adding a store discards the index
+        many.read_only(AnyCar::try_from(mainnet::DEFAULT_GENESIS).unwrap())
+            .unwrap();
+        assert_eq!(many.compact_index_len(), None);
+        let mainnet_genesis = PlainCar::new(mainnet::DEFAULT_GENESIS).unwrap();
+        for cid in mainnet_genesis.cids() {
+            assert_eq!(many.get(&cid).unwrap(), mainnet_genesis.get(&cid).unwrap());
+        }
+        many.compact().unwrap();
+        assert_eq!(read_all(), before);
+        for cid in mainnet_genesis.cids() {
+            assert_eq!(many.get(&cid).unwrap(), mainnet_genesis.get(&cid).unwrap());
+        }
+    }
+
     #[test]
     fn many_car_calibnet_heaviest() {
         let many = ManyCar::try_from(AnyCar::try_from(calibnet::DEFAULT_GENESIS).unwrap()).unwrap();
@@ -1,14 +1,26 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 mod any;
+mod bloom;
+mod concat;
+mod diff;
 pub mod forest;
 mod many;
 pub mod plain;
+mod recompress;
+mod repair;
+mod shard;
 
 pub use any::AnyCar;
+pub use bloom::CidBloomFilter;
+pub use concat::concat_raw;
+pub use diff::{CarDiff, diff};
 pub use forest::ForestCar;
-pub use many::ManyCar;
-pub use plain::PlainCar;
+pub use many::{ManyCar, ProbeOrder};
+pub use plain::{AsyncPlainCar, PlainCar, ReadOnlyCar, RootError};
+pub use recompress::recompress_in_place;
+pub use repair::repair_truncated;
+pub use shard::{Shard, shard};
 
 use ahash::HashMap;
 use cid::Cid;
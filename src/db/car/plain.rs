@@ -50,6 +50,26 @@
 //! > from a single root._
 //! - [CAR documentation](https://ipld.io/specs/transport/car/carv1/#determinism)
 //!
+//! [`PlainCar::is_deterministic`] checks whether a CAR follows this ordering.
+//!
+//! # External index
+//!
+//! [`PlainCar::export_index`] writes the block index to a sidecar file (conventionally
+//! `<car>.caridx`), so that [`PlainCar::new_with_external_index`] can skip scanning the CAR.
+//! Integers are little-endian, CIDs are in their binary form.
+//!
+//! ```text
+//! ┌─────────────────┬───────────────┬────────────────┬───────┬───────┐
+//! │magic: "CARIDX01"│car length: u64│entry count: u64│entry 0│entry 1│ ...
+//! └─────────────────┴───────────────┴────────────────┴───────┴───────┘
+//!
+//! ┌───┬──────────────────────┬──────────────────────┐
+//! │cid│block data offset: u64│block data length: u32│
+//! └───┴──────────────────────┴──────────────────────┘
+//! ```
+//!
+//! The car length guards against using an index with the wrong (or a modified) CAR.
+//!
 //! # Future work
 //! - [`fadvise`](https://linux.die.net/man/2/posix_fadvise)-based APIs to pre-fetch parts of the
 //!   file, to improve random access performance.
@@ -60,29 +80,37 @@
 //! - CARv2 support
 //! - A wrapper that abstracts over car formats for reading.
 
-use crate::cid_collections::{CidHashMap, hash_map::Entry as CidHashMapEntry};
+use crate::cid_collections::{CidHashMap, CidHashSet, hash_map::Entry as CidHashMapEntry};
 use crate::db::PersistentStore;
-use crate::utils::db::car_stream::{CarV1Header, CarV2Header};
+use crate::utils::db::car_stream::{CarBlock, CarV1Header, CarV2Header};
+use crate::utils::multihash::{MultihashAllowlist, prelude::*};
 use crate::{
-    blocks::{Tipset, TipsetKey},
-    utils::encoding::from_slice_with_fallback,
+    blocks::{CachingBlockHeader, Tipset, TipsetKey},
+    shim::clock::ChainEpoch,
+    utils::encoding::{extract_cids, from_slice_with_fallback},
 };
 use CidHashMapEntry::{Occupied, Vacant};
 use cid::Cid;
 use fvm_ipld_blockstore::Blockstore;
-use integer_encoding::{FixedIntReader, VarIntReader};
+use integer_encoding::{FixedIntReader, FixedIntWriter as _, VarIntReader};
 use nunny::Vec as NonEmpty;
 use parking_lot::RwLock;
 use positioned_io::ReadAt;
-use std::ops::DerefMut;
+use rayon::prelude::*;
+use std::ops::{DerefMut, Range};
 use std::{
     any::Any,
+    collections::BTreeMap,
+    fmt,
+    fs::File,
     io::{
-        self, BufReader,
+        self, BufReader, BufWriter,
         ErrorKind::{InvalidData, Unsupported},
-        Read, Seek, SeekFrom,
+        Read, Seek, SeekFrom, Write as _,
     },
     iter,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tracing::{debug, trace};
@@ -98,6 +126,7 @@ use tracing::{debug, trace};
 /// On creation, [`PlainCar`] builds an in-memory index of the [`Cid`]s in the file,
 /// and their offsets into that file.
 /// Note that it prepares its own buffer for doing so.
+/// If a CARv2 is fully-indexed, its embedded index is used instead of scanning the file.
 ///
 /// When a block is requested, [`PlainCar`] scrolls to that offset, and reads the block, on-demand.
 ///
@@ -115,6 +144,98 @@ pub struct PlainCar<ReaderT> {
     version: u64,
     header_v1: CarV1Header,
     header_v2: Option<CarV2Header>,
+    bloom_filter: Option<super::CidBloomFilter>,
+}
+
+/// How many bytes [`PlainCar::blocks`] reads at once.
+pub const DEFAULT_READ_AHEAD_WINDOW: usize = 1024 * 1024;
+
+/// Yields blocks at sorted `locations`, reading the data of consecutive blocks
+/// (and the frame headers between them) with one read while they fit in `window`.
+struct ReadAhead<'a, ReaderT> {
+    reader: &'a ReaderT,
+    locations: iter::Peekable<std::vec::IntoIter<(Cid, u64, u32)>>,
+    window: usize,
+    /// Data of the blocks in `pending`, starting at `buffer_offset` in the file.
+    buffer: Vec<u8>,
+    buffer_offset: u64,
+    pending: std::collections::VecDeque<(Cid, u64, u32)>,
+}
+
+impl<'a, ReaderT: ReadAt> ReadAhead<'a, ReaderT> {
+    fn new(reader: &'a ReaderT, locations: Vec<(Cid, u64, u32)>, window: usize) -> Self {
+        Self {
+            reader,
+            locations: locations.into_iter().peekable(),
+            window,
+            buffer: vec![],
+            buffer_offset: 0,
+            pending: Default::default(),
+        }
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        let Some(first @ (_, start, length)) = self.locations.next() else {
+            return Ok(());
+        };
+        let mut end = start + u64::from(length);
+        self.pending.push_back(first);
+        while let Some(&(_, offset, length)) = self.locations.peek() {
+            let next_end = offset + u64::from(length);
+            if next_end - start > self.window as u64 {
+                break;
+            }
+            end = next_end;
+            self.pending
+                .push_back(self.locations.next().expect("just peeked"));
+        }
+        self.buffer
+            .resize(usize::try_from(end - start).map_err(io::Error::other)?, 0);
+        self.buffer_offset = start;
+        self.reader.read_exact_at(start, &mut self.buffer)
+    }
+}
+
+impl<ReaderT: ReadAt> Iterator for ReadAhead<'_, ReaderT> {
+    type Item = io::Result<CarBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty()
+            && let Err(e) = self.fill()
+        {
+            self.pending.clear();
+            return Some(Err(e));
+        }
+        let (cid, offset, length) = self.pending.pop_front()?;
+        let start = (offset - self.buffer_offset) as usize;
+        let data = self.buffer[start..start + length as usize].to_vec();
+        Some(Ok(CarBlock { cid, data }))
+    }
+}
+
+/// Why the roots of a CAR don't form a tipset, see
+/// [`PlainCar::validate_roots_as_tipset`].
+#[derive(Debug, thiserror::Error)]
+pub enum RootError {
+    #[error("root {0} is not in the CAR")]
+    Missing(Cid),
+    #[error("root {cid} is not a block header")]
+    NotABlockHeader {
+        cid: Cid,
+        #[source]
+        source: anyhow::Error,
+    },
+    #[error("root {cid} is at epoch {epoch}, but root {first} is at epoch {first_epoch}")]
+    EpochMismatch {
+        cid: Cid,
+        epoch: ChainEpoch,
+        first: Cid,
+        first_epoch: ChainEpoch,
+    },
+    #[error("root {cid} doesn't have the same parents as root {first}")]
+    ParentsMismatch { cid: Cid, first: Cid },
+    #[error("failed to read the roots")]
+    Read(#[from] io::Error),
 }
 
 impl<ReaderT: super::RandomAccessFileReader> PlainCar<ReaderT> {
@@ -124,35 +245,120 @@ impl<ReaderT: super::RandomAccessFileReader> PlainCar<ReaderT> {
     ///   [`Blockstore`] API calls may panic if this is not upheld.
     #[tracing::instrument(level = "debug", skip_all)]
     pub fn new(reader: ReaderT) -> io::Result<Self> {
-        let mut cursor = positioned_io::Cursor::new(&reader);
-        let position = cursor.position();
-        let header_v2 = read_v2_header(&mut cursor)?;
-        let (limit_position, version) = if let Some(header_v2) = &header_v2 {
-            cursor.set_position(position.saturating_add(header_v2.data_offset as u64));
-            (
-                Some(
-                    cursor
-                        .stream_position()?
-                        .saturating_add(header_v2.data_size as u64),
-                ),
-                2,
-            )
-        } else {
-            cursor.set_position(position);
-            (None, 1)
-        };
+        Self::new_with_multihash_allowlist(reader, &MultihashAllowlist::default())
+    }
 
-        let header_v1 = read_v1_header(&mut cursor)?;
+    /// Like [`PlainCar::new`], but rejects the CAR if a block [`Cid`] uses a multihash
+    /// that is not in `allowlist`, instead of the default one.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn new_with_multihash_allowlist(
+        reader: ReaderT,
+        allowlist: &MultihashAllowlist,
+    ) -> io::Result<Self> {
+        Self::new_with_allowlist_and_progress(reader, allowlist, |_, _| {})
+    }
+
+    /// Like [`PlainCar::new`], but calls `progress` with the number of bytes
+    /// processed so far and the size of the file, every [`PROGRESS_INTERVAL`]
+    /// bytes while indexing, and once more with both equal to the size of the
+    /// file when done.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn new_with_progress(reader: ReaderT, progress: impl FnMut(u64, u64)) -> io::Result<Self> {
+        Self::new_with_allowlist_and_progress(reader, &MultihashAllowlist::default(), progress)
+    }
+
+    fn new_with_allowlist_and_progress(
+        reader: ReaderT,
+        allowlist: &MultihashAllowlist,
+        mut progress: impl FnMut(u64, u64),
+    ) -> io::Result<Self> {
+        let headers = Headers::read(&reader)?;
+        // Only a fully-indexed CARv2 index saves us the scan: otherwise we'd have to
+        // scan anyway, for blocks that aren't in the index
+        match (&headers.header_v2, headers.index_position) {
+            (Some(header_v2), Some(index_position)) if header_v2.is_fully_indexed() => {
+                if let Some(index) = read_embedded_index(&reader, &headers, allowlist)? {
+                    debug!(index_position, "trusting fully-indexed CARv2 index");
+                    let total = reader.size()?.unwrap_or(index_position);
+                    progress(total, total);
+                    return Self::with_index(reader, headers, index);
+                }
+                debug!(index_position, "unsupported CARv2 index format");
+            }
+            (Some(_), Some(index_position)) => {
+                debug!(
+                    index_position,
+                    "ignoring CARv2 index, it isn't fully-indexed"
+                )
+            }
+            (Some(_), None) => debug!("CARv2 has no index"),
+            (None, _) => {}
+        }
+        let cursor = positioned_io::Cursor::new_pos(&reader, headers.blocks_offset);
+        let limit_position = headers.limit_position;
         // When indexing, we perform small reads of the length and CID before seeking
         // Buffering these gives us a ~50% speedup (n=10): https://github.com/ChainSafe/forest/pull/3085#discussion_r1246897333
         let mut buf_reader = BufReader::with_capacity(1024, cursor);
 
         // now create the index
+        let total = match reader.size()? {
+            Some(size) => size,
+            None => limit_position.unwrap_or(0),
+        };
+        let mut reported = headers.blocks_offset;
         let index = iter::from_fn(|| {
-            read_block_data_location_and_skip(&mut buf_reader, limit_position).transpose()
+            let next =
+                read_block_data_location_and_skip(&mut buf_reader, limit_position, allowlist)
+                    .transpose();
+            if let Some(Ok((_, location))) = &next {
+                let position = location.offset + u64::from(location.length);
+                if position - reported >= PROGRESS_INTERVAL {
+                    reported = position;
+                    progress(position, total);
+                }
+            }
+            next
         })
         .collect::<Result<CidHashMap<_>, _>>()?;
+        progress(total, total);
+
+        Self::with_index(reader, headers, index)
+    }
+
+    /// Like [`PlainCar::new`], but loads the index written by [`PlainCar::export_index`]
+    /// from `index_path` instead of scanning the CAR.
+    ///
+    /// The index is rejected if it was built for a CAR of a different size, or points
+    /// outside of the blocks section.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn new_with_external_index(
+        reader: ReaderT,
+        index_path: impl AsRef<Path>,
+    ) -> io::Result<Self> {
+        let headers = Headers::read(&reader)?;
+        let car_length = reader
+            .size()?
+            .ok_or_else(|| io::Error::new(Unsupported, "CAR size is unknown"))?;
+        let blocks_end = headers.limit_position.unwrap_or(car_length);
+        let index = read_external_index(
+            BufReader::new(File::open(index_path)?),
+            car_length,
+            headers.blocks_offset..blocks_end,
+        )?;
+        Self::with_index(reader, headers, index)
+    }
 
+    fn with_index(
+        reader: ReaderT,
+        headers: Headers,
+        index: CidHashMap<UncompressedBlockDataLocation>,
+    ) -> io::Result<Self> {
+        let Headers {
+            header_v1,
+            header_v2,
+            version,
+            ..
+        } = headers;
         match index.len() {
             0 => Err(io::Error::new(
                 InvalidData,
@@ -167,6 +373,7 @@ impl<ReaderT: super::RandomAccessFileReader> PlainCar<ReaderT> {
                     version,
                     header_v1,
                     header_v2,
+                    bloom_filter: None,
                 })
             }
         }
@@ -184,16 +391,283 @@ impl<ReaderT: super::RandomAccessFileReader> PlainCar<ReaderT> {
         TipsetKey::from(self.roots().clone())
     }
 
+    /// Fails with a [`RootError`] if the roots don't form a tipset, see
+    /// [`Self::validate_roots_as_tipset`].
     pub fn heaviest_tipset(&self) -> anyhow::Result<Tipset> {
+        self.validate_roots_as_tipset()?;
         Tipset::load_required(self, &self.heaviest_tipset_key())
     }
 
+    /// Check that the roots are block headers in this CAR, at the same epoch and
+    /// with the same parents, so that [`Self::heaviest_tipset`] fails with a
+    /// precise error. Other tipset invariants are checked by [`Tipset::new`].
+    ///
+    /// Only the root blocks are read, see [`Self::read_root_blocks`].
+    pub fn validate_roots_as_tipset(&self) -> Result<(), RootError> {
+        if let Some(&missing) = self
+            .roots()
+            .iter()
+            .find(|cid| self.block_data_length(cid).is_none())
+        {
+            return Err(RootError::Missing(missing));
+        }
+        let mut first: Option<(Cid, CachingBlockHeader)> = None;
+        for (cid, data) in self.read_root_blocks()? {
+            let header = from_slice_with_fallback::<CachingBlockHeader>(&data)
+                .map_err(|source| RootError::NotABlockHeader { cid, source })?;
+            let Some((first_cid, first)) = &first else {
+                first = Some((cid, header));
+                continue;
+            };
+            if header.epoch != first.epoch {
+                return Err(RootError::EpochMismatch {
+                    cid,
+                    epoch: header.epoch,
+                    first: *first_cid,
+                    first_epoch: first.epoch,
+                });
+            }
+            if header.parents != first.parents {
+                return Err(RootError::ParentsMismatch {
+                    cid,
+                    first: *first_cid,
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// In an arbitrary order
-    #[cfg(test)]
     pub fn cids(&self) -> Vec<Cid> {
         self.index.read().keys().collect()
     }
 
+    /// Build a [`CidBloomFilter`](super::CidBloomFilter) from the index, so that
+    /// [`Self::might_contain`] can rule out most [`Cid`]s that aren't in the CAR.
+    pub fn with_bloom_filter(mut self, false_positive_rate: f64) -> Self {
+        let index = self.index.get_mut();
+        let mut bloom_filter = super::CidBloomFilter::new(index.len(), false_positive_rate);
+        for cid in index.keys() {
+            bloom_filter.insert(&cid);
+        }
+        self.bloom_filter = Some(bloom_filter);
+        self
+    }
+
+    /// Returns `false` if `k` is definitely not in the store. Always returns
+    /// `true` without a [bloom filter](Self::with_bloom_filter).
+    pub fn might_contain(&self, k: &Cid) -> bool {
+        self.bloom_filter
+            .as_ref()
+            .is_none_or(|bloom_filter| bloom_filter.might_contain(k))
+            || self.write_cache.read().contains_key(k)
+    }
+
+    /// The hash [summaries](super::forest::summary) of the blocks in the CAR,
+    /// including those in the write cache.
+    pub fn hash_summaries(&self) -> Vec<u64> {
+        let index = self.index.read();
+        let write_cache = self.write_cache.read();
+        index
+            .keys()
+            .chain(write_cache.keys())
+            .map(|cid| super::forest::summary(&cid))
+            .collect()
+    }
+
+    /// The number of blocks per power-of-two [`BucketRange`] of data size, computed from
+    /// the index without reading any block data. Blocks that were written to this store
+    /// are not included.
+    pub fn block_size_histogram(&self) -> BTreeMap<BucketRange, u64> {
+        let index = self.index.read();
+        let mut histogram = BTreeMap::new();
+        for cid in index.keys() {
+            let UncompressedBlockDataLocation { length, .. } =
+                index.get(&cid).expect("key was just listed");
+            *histogram.entry(BucketRange::of(*length)).or_default() += 1;
+        }
+        histogram
+    }
+
+    /// Length of the on-disk block data for `cid`, if it is indexed.
+    pub(super) fn block_data_length(&self, cid: &Cid) -> Option<u32> {
+        self.index.read().get(cid).map(|location| location.length)
+    }
+
+    /// The [roots](Self::roots) and their data, read straight from their indexed
+    /// locations without traversing the rest of the CAR. Fails with
+    /// [`io::ErrorKind::NotFound`] if a root isn't in the CAR.
+    pub fn read_root_blocks(&self) -> io::Result<Vec<(Cid, Vec<u8>)>> {
+        let index = self.index.read();
+        self.roots()
+            .iter()
+            .map(|&cid| {
+                let UncompressedBlockDataLocation { offset, length } =
+                    index.get(&cid).ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("root {cid} is not in the CAR"),
+                        )
+                    })?;
+                let mut data = vec![0; usize::try_from(*length).unwrap()];
+                self.reader.read_exact_at(*offset, &mut data)?;
+                Ok((cid, data))
+            })
+            .collect()
+    }
+
+    /// The blocks in the CAR, in the order they are in the file, read ahead in windows
+    /// of [`DEFAULT_READ_AHEAD_WINDOW`] bytes. Blocks that were written to this store
+    /// are not included.
+    pub fn blocks(&self) -> impl Iterator<Item = io::Result<CarBlock>> + '_ {
+        self.blocks_with_read_ahead(DEFAULT_READ_AHEAD_WINDOW)
+    }
+
+    /// Like [`Self::blocks`], but nearby blocks are fetched with a single read as long
+    /// as they span at most `window` bytes. A `window` of `0` reads each block
+    /// separately.
+    pub fn blocks_with_read_ahead(
+        &self,
+        window: usize,
+    ) -> impl Iterator<Item = io::Result<CarBlock>> + '_ {
+        ReadAhead::new(&self.reader, self.locations_in_file_order(|_| true), window)
+    }
+
+    /// The blocks whose [`Cid`] has the IPLD `codec`, e.g.
+    /// [`DAG_CBOR`](fvm_ipld_encoding::DAG_CBOR) or [`IPLD_RAW`](fvm_ipld_encoding::IPLD_RAW),
+    /// in the order they are in the CAR. The codec is part of the [`Cid`], so other blocks
+    /// are skipped without being read. Blocks that were written to this store are not
+    /// included.
+    pub fn blocks_by_codec(&self, codec: u64) -> impl Iterator<Item = io::Result<CarBlock>> + '_ {
+        ReadAhead::new(
+            &self.reader,
+            self.locations_in_file_order(|cid| cid.codec() == codec),
+            DEFAULT_READ_AHEAD_WINDOW,
+        )
+    }
+
+    fn locations_in_file_order(
+        &self,
+        mut filter: impl FnMut(&Cid) -> bool,
+    ) -> Vec<(Cid, u64, u32)> {
+        let index = self.index.read();
+        let mut locations = index
+            .keys()
+            .filter(|cid| filter(cid))
+            .map(|cid| {
+                let UncompressedBlockDataLocation { offset, length } =
+                    index.get(&cid).expect("key was just listed");
+                (cid, *offset, *length)
+            })
+            .collect::<Vec<_>>();
+        locations.sort_by_key(|(_, offset, _)| *offset);
+        locations
+    }
+
+    /// Writes the block index to `path`, see the [module documentation](mod@self) for
+    /// the format. Blocks that were written to this store are not included.
+    pub fn export_index(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let car_length = self
+            .reader
+            .size()?
+            .ok_or_else(|| io::Error::new(Unsupported, "CAR size is unknown"))?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        let index = self.index.read();
+        writer.write_all(&EXTERNAL_INDEX_MAGIC)?;
+        writer.write_fixedint(car_length)?;
+        writer.write_fixedint(index.len() as u64)?;
+        for cid in index.keys() {
+            let UncompressedBlockDataLocation { offset, length } =
+                index.get(&cid).expect("key was just listed");
+            cid.write_bytes(&mut writer)
+                .map_err(cid_error_to_io_error)?;
+            writer.write_fixedint(*offset)?;
+            writer.write_fixedint(*length)?;
+        }
+        writer.flush()
+    }
+
+    /// Reads every block from disk, in file order with read-ahead (see [`Self::blocks`]),
+    /// and checks in parallel that its data hashes to its [`Cid`], see
+    /// [`CarBlock::validate`](crate::utils::db::car_stream::CarBlock::validate).
+    /// Blocks that were written to this store are not included.
+    pub fn verify_all_hashes(&self) -> anyhow::Result<VerifyReport> {
+        let num_blocks = self.index.read().len();
+        let mismatches = self
+            .blocks()
+            .par_bridge()
+            .map(|block| -> anyhow::Result<_> {
+                let CarBlock { cid, data } = block?;
+                let Ok(code) = MultihashCode::try_from(cid.hash().code()) else {
+                    return Ok(Some((cid, None)));
+                };
+                // Compare the digests, as the CID version is not part of the hash
+                let digest = code.digest(&data);
+                if digest == *cid.hash() {
+                    return Ok(None);
+                }
+                Ok(Some((
+                    cid,
+                    Some(Cid::new(cid.version(), cid.codec(), digest)?),
+                )))
+            })
+            .filter_map(Result::transpose)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut mismatches = {
+            let index = self.index.read();
+            mismatches
+                .into_iter()
+                .map(|(cid, actual)| HashMismatch {
+                    cid,
+                    offset: index.get(&cid).expect("block was just read").offset,
+                    actual,
+                })
+                .collect::<Vec<_>>()
+        };
+        mismatches.sort_by_key(|mismatch| mismatch.offset);
+        let verified = num_blocks - mismatches.len();
+        Ok(VerifyReport {
+            verified,
+            mismatches,
+        })
+    }
+
+    /// Whether the CAR is filecoin-deterministic, see the
+    /// [module documentation](mod@self#block-ordering): it has a single root, and holds
+    /// exactly the blocks reachable from it, in the first-seen order of a depth-first
+    /// traversal that follows the links of each `DAG_CBOR` block in encoding order.
+    ///
+    /// Links to blocks that are not in the CAR are skipped. Blocks that were written to
+    /// this store are not considered.
+    pub fn is_deterministic(&self) -> anyhow::Result<bool> {
+        let [root] = self.roots().as_slice() else {
+            return Ok(false);
+        };
+        let index = self.index.read();
+        let mut seen = CidHashSet::new();
+        let mut last_offset = None;
+        // Children are pushed in reverse so that they are popped in encoding order.
+        let mut stack = vec![*root];
+        while let Some(cid) = stack.pop() {
+            let Some(UncompressedBlockDataLocation { offset, length }) = index.get(&cid) else {
+                continue;
+            };
+            if !seen.insert(cid) {
+                continue;
+            }
+            if last_offset.is_some_and(|last_offset| last_offset >= *offset) {
+                return Ok(false);
+            }
+            last_offset = Some(*offset);
+            if cid.codec() == fvm_ipld_encoding::DAG_CBOR {
+                let mut data = vec![0; usize::try_from(*length)?];
+                self.reader.read_exact_at(*offset, &mut data)?;
+                stack.extend(extract_cids(&data)?.into_iter().rev());
+            }
+        }
+        Ok(seen.len() == index.len())
+    }
+
     pub fn into_dyn(self) -> PlainCar<Box<dyn super::RandomAccessFileReader>> {
         PlainCar {
             reader: Box::new(self.reader),
@@ -202,6 +676,7 @@ impl<ReaderT: super::RandomAccessFileReader> PlainCar<ReaderT> {
             version: self.version,
             header_v1: self.header_v1,
             header_v2: self.header_v2,
+            bloom_filter: self.bloom_filter,
         }
     }
 }
@@ -213,6 +688,328 @@ impl TryFrom<&'static [u8]> for PlainCar<&'static [u8]> {
     }
 }
 
+/// A range of block sizes in bytes, `start..end`, see [`PlainCar::block_size_histogram`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BucketRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl BucketRange {
+    /// The bucket of a block of `size` bytes: `0..1` for empty blocks, `2^n..2^(n+1)`
+    /// otherwise.
+    pub fn of(size: u32) -> Self {
+        match size.checked_ilog2() {
+            None => Self { start: 0, end: 1 },
+            Some(n) => Self {
+                start: 1 << n,
+                end: 2 << n,
+            },
+        }
+    }
+}
+
+impl fmt::Display for BucketRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// Outcome of [`PlainCar::verify_all_hashes`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Number of blocks whose data matches their [`Cid`].
+    pub verified: usize,
+    /// Blocks whose data does not match their [`Cid`], by ascending offset.
+    pub mismatches: Vec<HashMismatch>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct HashMismatch {
+    pub cid: Cid,
+    /// Offset of the block data, see [`UncompressedBlockDataLocation`].
+    pub offset: u64,
+    /// The [`Cid`] of the block data, or [`None`] if the hash function is not supported.
+    pub actual: Option<Cid>,
+}
+
+/// How often [`PlainCar::new_with_progress`] reports progress, in bytes.
+pub const PROGRESS_INTERVAL: u64 = 1024 * 1024;
+
+/// The headers of a CAR, and where its block frames are.
+struct Headers {
+    header_v1: CarV1Header,
+    header_v2: Option<CarV2Header>,
+    version: u64,
+    /// Start of the CARv1 data, which CARv2 index offsets are relative to.
+    data_position: u64,
+    /// Offset of the first block frame.
+    blocks_offset: u64,
+    /// End of the block frames for CARv2, which may be followed by an index.
+    limit_position: Option<u64>,
+    /// Start of the CARv2 index, if there is one.
+    index_position: Option<u64>,
+}
+
+impl Headers {
+    fn read(reader: &impl ReadAt) -> io::Result<Self> {
+        let mut cursor = positioned_io::Cursor::new(reader);
+        let position = cursor.position();
+        let header_v2 = read_v2_header(&mut cursor)?;
+        let data_position = header_v2.as_ref().map_or(position, |it| {
+            position.saturating_add(it.data_offset as u64)
+        });
+        let (limit_position, version) = if let Some(header_v2) = &header_v2 {
+            cursor.set_position(position.saturating_add(header_v2.data_offset as u64));
+            (
+                Some(
+                    cursor
+                        .stream_position()?
+                        .saturating_add(header_v2.data_size as u64),
+                ),
+                2,
+            )
+        } else {
+            cursor.set_position(position);
+            (None, 1)
+        };
+        let header_v1 = read_v1_header(&mut cursor)?;
+        let index_position = header_v2
+            .as_ref()
+            .and_then(CarV2Header::index_position)
+            .map(|it| position.saturating_add(it));
+        Ok(Self {
+            header_v1,
+            header_v2,
+            version,
+            data_position,
+            blocks_offset: cursor.stream_position()?,
+            limit_position,
+            index_position,
+        })
+    }
+}
+
+const EXTERNAL_INDEX_MAGIC: [u8; 8] = *b"CARIDX01";
+
+/// The conventional path of the external index of the CAR at `car_path`, see
+/// [`PlainCar::export_index`].
+pub fn external_index_path(car_path: &Path) -> PathBuf {
+    let mut path = car_path.as_os_str().to_owned();
+    path.push(".caridx");
+    path.into()
+}
+
+/// Reads an index written by [`PlainCar::export_index`], checking that it belongs to a
+/// CAR of `car_length` bytes whose block frames span `blocks`.
+fn read_external_index(
+    mut reader: impl Read,
+    car_length: u64,
+    blocks: Range<u64>,
+) -> io::Result<CidHashMap<UncompressedBlockDataLocation>> {
+    let mut magic = [0; EXTERNAL_INDEX_MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if magic != EXTERNAL_INDEX_MAGIC {
+        return Err(io::Error::new(InvalidData, "not a CAR index file"));
+    }
+    let indexed_car_length: u64 = reader.read_fixedint()?;
+    if indexed_car_length != car_length {
+        return Err(io::Error::new(
+            InvalidData,
+            format!(
+                "index is for a CAR of {indexed_car_length} bytes, but the CAR has {car_length} bytes"
+            ),
+        ));
+    }
+    let num_entries: u64 = reader.read_fixedint()?;
+    let mut index = CidHashMap::new();
+    for _ in 0..num_entries {
+        let cid = Cid::read_bytes(&mut reader).map_err(cid_error_to_io_error)?;
+        let offset: u64 = reader.read_fixedint()?;
+        let length: u32 = reader.read_fixedint()?;
+        if offset < blocks.start || offset.saturating_add(u64::from(length)) > blocks.end {
+            return Err(io::Error::new(
+                InvalidData,
+                format!("index entry for {cid} points outside of the CAR blocks"),
+            ));
+        }
+        if index
+            .insert(cid, UncompressedBlockDataLocation { offset, length })
+            .is_some()
+        {
+            return Err(io::Error::new(
+                InvalidData,
+                format!("duplicate index entry for {cid}"),
+            ));
+        }
+    }
+    if reader.read(&mut [0])? != 0 {
+        return Err(io::Error::new(InvalidData, "trailing data in CAR index"));
+    }
+    Ok(index)
+}
+
+/// <https://ipld.io/specs/transport/car/carv2/#format-0x0400-indexsorted>
+const INDEX_SORTED: u64 = 0x0400;
+/// <https://ipld.io/specs/transport/car/carv2/#format-0x0401-multihashindexsorted>
+const MULTIHASH_INDEX_SORTED: u64 = 0x0401;
+
+/// How many blocks of a fully-indexed CARv2 have their data hashed when it's opened.
+const EMBEDDED_INDEX_SAMPLES: usize = 8;
+
+/// An entry of an embedded CARv2 index, which identifies a block by its multihash
+/// digest (and, for [`MULTIHASH_INDEX_SORTED`], multihash code) only.
+struct EmbeddedIndexEntry {
+    multihash_code: Option<u64>,
+    digest: Vec<u8>,
+    /// Of the block frame, relative to [`Headers::data_position`].
+    offset: u64,
+}
+
+/// Locates the blocks listed in the embedded index of a CARv2, without scanning
+/// the blocks section, or returns [`None`] if the index format isn't supported.
+///
+/// The index doesn't include [`Cid`]s or lengths, so the frame header of each block is
+/// read, and checked against the index. Only [`EMBEDDED_INDEX_SAMPLES`] blocks have
+/// their data hashed.
+fn read_embedded_index(
+    reader: &impl ReadAt,
+    headers: &Headers,
+    allowlist: &MultihashAllowlist,
+) -> io::Result<Option<CidHashMap<UncompressedBlockDataLocation>>> {
+    let Some(index_position) = headers.index_position else {
+        return Ok(None);
+    };
+    let Some(mut entries) = read_embedded_index_entries(BufReader::new(
+        positioned_io::Cursor::new_pos(reader, index_position),
+    ))?
+    else {
+        return Ok(None);
+    };
+    entries.sort_unstable_by_key(|entry| entry.offset);
+
+    let mut buf_reader = BufReader::with_capacity(1024, positioned_io::Cursor::new(reader));
+    let mut index = CidHashMap::new();
+    for EmbeddedIndexEntry {
+        multihash_code,
+        digest,
+        offset,
+    } in entries
+    {
+        let position = headers.data_position.saturating_add(offset);
+        if position < headers.blocks_offset {
+            return Err(io::Error::new(
+                InvalidData,
+                format!("CARv2 index entry at offset {offset} points into the header"),
+            ));
+        }
+        buf_reader.seek(SeekFrom::Start(position))?;
+        let (cid, location) =
+            read_block_data_location_and_skip(&mut buf_reader, headers.limit_position, allowlist)?
+                .ok_or_else(|| {
+                    io::Error::new(
+                        InvalidData,
+                        format!("CARv2 index entry at offset {offset} points past the blocks"),
+                    )
+                })?;
+        if cid.hash().digest() != digest
+            || multihash_code.is_some_and(|code| code != cid.hash().code())
+        {
+            return Err(io::Error::new(
+                InvalidData,
+                format!("CARv2 index entry at offset {offset} doesn't match block {cid}"),
+            ));
+        }
+        // Duplicate blocks are indexed more than once
+        if let Vacant(vacant) = index.entry(cid) {
+            vacant.insert(location);
+        }
+    }
+
+    let step = (index.len() / EMBEDDED_INDEX_SAMPLES).max(1);
+    for cid in index.keys().step_by(step).take(EMBEDDED_INDEX_SAMPLES) {
+        let UncompressedBlockDataLocation { offset, length } =
+            index.get(&cid).expect("key was just listed");
+        let Ok(code) = MultihashCode::try_from(cid.hash().code()) else {
+            continue;
+        };
+        let mut data = vec![0; *length as usize];
+        reader.read_exact_at(*offset, &mut data)?;
+        if code.digest(&data) != *cid.hash() {
+            return Err(io::Error::new(
+                InvalidData,
+                format!("block {cid} at offset {offset} doesn't match its CID"),
+            ));
+        }
+    }
+    Ok(Some(index))
+}
+
+/// ```text
+/// ┌──────────────┬────────────────────────────────────────────┐
+/// │varint: format│IndexSorted, or MultihashIndexSorted buckets│
+/// └──────────────┴────────────────────────────────────────────┘
+/// ```
+fn read_embedded_index_entries(
+    mut reader: impl Read,
+) -> io::Result<Option<Vec<EmbeddedIndexEntry>>> {
+    let mut entries = vec![];
+    match reader.read_varint::<u64>()? {
+        INDEX_SORTED => read_index_sorted(&mut reader, None, &mut entries)?,
+        MULTIHASH_INDEX_SORTED => {
+            let num_codes: u32 = reader.read_fixedint()?;
+            for _ in 0..num_codes {
+                let code: u64 = reader.read_fixedint()?;
+                read_index_sorted(&mut reader, Some(code), &mut entries)?;
+            }
+        }
+        _ => return Ok(None),
+    }
+    Ok(Some(entries))
+}
+
+/// ```text
+/// ┌────────────────────┬────────┬────────┐
+/// │bucket count: u32   │bucket 0│bucket 1│ ...
+/// └────────────────────┴────────┴────────┘
+///
+/// ┌──────────┬─────────────────┬───────┬───────┐
+/// │width: u32│byte length: u64 │entry 0│entry 1│ ...
+/// └──────────┴─────────────────┴───────┴───────┘
+///
+/// ┌──────────────────────────┬───────────┐
+/// │digest: (width - 8) bytes │offset: u64│
+/// └──────────────────────────┴───────────┘
+/// ```
+fn read_index_sorted(
+    mut reader: impl Read,
+    multihash_code: Option<u64>,
+    entries: &mut Vec<EmbeddedIndexEntry>,
+) -> io::Result<()> {
+    let num_buckets: u32 = reader.read_fixedint()?;
+    for _ in 0..num_buckets {
+        let width: u32 = reader.read_fixedint()?;
+        let byte_length: u64 = reader.read_fixedint()?;
+        let width = u64::from(width);
+        if width <= 8 || byte_length % width != 0 {
+            return Err(io::Error::new(
+                InvalidData,
+                format!("invalid CARv2 index bucket of {byte_length} bytes, with width {width}"),
+            ));
+        }
+        for _ in 0..byte_length / width {
+            let mut digest = vec![0; (width - 8) as usize];
+            reader.read_exact(&mut digest)?;
+            entries.push(EmbeddedIndexEntry {
+                multihash_code,
+                digest,
+                offset: reader.read_fixedint()?,
+            });
+        }
+    }
+    Ok(())
+}
+
 /// If you seek to `offset` (from the start of the file), and read `length` bytes,
 /// you should get data that corresponds to a [`Cid`] (but NOT the [`Cid`] itself).
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -272,6 +1069,115 @@ where
     }
 }
 
+/// A [`PlainCar`] that can't be written to, so that lookups go straight from the
+/// index to disk, without a write cache. [`ManyCar`](super::ManyCar) layers are
+/// read-only, see [`AnyCar::into_read_only`](super::AnyCar::into_read_only).
+pub struct ReadOnlyCar<ReaderT>(PlainCar<ReaderT>);
+
+impl<ReaderT> From<PlainCar<ReaderT>> for ReadOnlyCar<ReaderT> {
+    /// Blocks that were already written to `car` can't be read.
+    fn from(car: PlainCar<ReaderT>) -> Self {
+        Self(car)
+    }
+}
+
+impl<ReaderT: super::RandomAccessFileReader> ReadOnlyCar<ReaderT> {
+    pub fn roots(&self) -> &NonEmpty<Cid> {
+        self.0.roots()
+    }
+
+    pub fn version(&self) -> u64 {
+        self.0.version()
+    }
+
+    pub fn heaviest_tipset_key(&self) -> TipsetKey {
+        TipsetKey::from(self.roots().clone())
+    }
+
+    /// See [`PlainCar::heaviest_tipset`].
+    pub fn heaviest_tipset(&self) -> anyhow::Result<Tipset> {
+        self.0.validate_roots_as_tipset()?;
+        Tipset::load_required(self, &self.heaviest_tipset_key())
+    }
+
+    /// See [`PlainCar::with_bloom_filter`].
+    pub fn with_bloom_filter(self, false_positive_rate: f64) -> Self {
+        Self(self.0.with_bloom_filter(false_positive_rate))
+    }
+
+    /// See [`PlainCar::might_contain`].
+    pub fn might_contain(&self, k: &Cid) -> bool {
+        self.0.might_contain(k)
+    }
+
+    /// See [`PlainCar::hash_summaries`].
+    pub fn hash_summaries(&self) -> Vec<u64> {
+        self.0.hash_summaries()
+    }
+
+    pub fn into_dyn(self) -> ReadOnlyCar<Box<dyn super::RandomAccessFileReader>> {
+        ReadOnlyCar(self.0.into_dyn())
+    }
+}
+
+impl<ReaderT> Blockstore for ReadOnlyCar<ReaderT>
+where
+    ReaderT: ReadAt,
+{
+    #[tracing::instrument(level = "trace", skip(self))]
+    fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        let Some((offset, length)) = self
+            .0
+            .index
+            .read()
+            .get(k)
+            .map(|location| (location.offset, location.length))
+        else {
+            return Ok(None);
+        };
+        let mut data = vec![0; usize::try_from(length).unwrap()];
+        self.0.reader.read_exact_at(offset, &mut data)?;
+        Ok(Some(data))
+    }
+
+    fn put_keyed(&self, k: &Cid, _block: &[u8]) -> anyhow::Result<()> {
+        anyhow::bail!("can't write {k} to a read-only CAR")
+    }
+}
+
+/// An async wrapper around a [`PlainCar`], whose reads of the underlying file
+/// run on [`tokio::task::spawn_blocking`] so that they don't stall the runtime.
+pub struct AsyncPlainCar<ReaderT>(Arc<PlainCar<ReaderT>>);
+
+impl<ReaderT> Clone for AsyncPlainCar<ReaderT> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[allow(dead_code)]
+impl<ReaderT: super::RandomAccessFileReader> AsyncPlainCar<ReaderT> {
+    pub fn new(car: PlainCar<ReaderT>) -> Self {
+        Self(Arc::new(car))
+    }
+
+    pub fn roots(&self) -> &NonEmpty<Cid> {
+        self.0.roots()
+    }
+
+    /// See [`Blockstore::get`].
+    pub async fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        let (car, k) = (self.0.clone(), *k);
+        tokio::task::spawn_blocking(move || car.get(&k)).await?
+    }
+
+    /// See [`Blockstore::has`]. This only consults the in-memory index and
+    /// write cache.
+    pub async fn has(&self, k: &Cid) -> anyhow::Result<bool> {
+        Ok(self.0.index.read().contains_key(k) || self.0.write_cache.read().contains_key(k))
+    }
+}
+
 pub async fn write_skip_frame_header_async(
     mut writer: impl AsyncWrite + Unpin,
     data_len: u32,
@@ -369,20 +1275,33 @@ pub fn read_v2_header(mut reader: impl Read) -> io::Result<Option<CarV2Header>>
 ///        └───────────┴─────────┘
 /// ```
 #[tracing::instrument(level = "trace", skip_all, ret)]
-fn read_v1_header(mut reader: impl Read) -> io::Result<CarV1Header> {
+pub(super) fn read_v1_header(mut reader: impl Read) -> io::Result<CarV1Header> {
+    /// Same as [`CarV1Header`], but accepts empty roots so that we can report
+    /// a descriptive error for them.
+    #[derive(serde::Deserialize)]
+    struct LenientCarV1Header {
+        roots: Vec<Cid>,
+        version: u64,
+    }
+
     let header_len = reader.read_varint()?;
     let mut buffer = vec![0; header_len];
     reader.read_exact(&mut buffer)?;
-    let header: CarV1Header =
+    let LenientCarV1Header { roots, version } =
         from_slice_with_fallback(&buffer).map_err(|e| io::Error::new(InvalidData, e))?;
-    if header.version == 1 {
-        Ok(header)
-    } else {
-        Err(io::Error::new(
+    if version != 1 {
+        return Err(io::Error::new(
             Unsupported,
-            format!("unsupported CAR version {}", header.version),
-        ))
+            format!("unsupported CAR version {version}"),
+        ));
     }
+    let roots = NonEmpty::new(roots).map_err(|_| {
+        io::Error::new(
+            InvalidData,
+            "CAR header must contain at least one root, but the roots list is empty",
+        )
+    })?;
+    Ok(CarV1Header { roots, version })
 }
 
 /// Returns ([`Cid`], the `block data offset` and `block data length`)
@@ -404,6 +1323,7 @@ fn read_v1_header(mut reader: impl Read) -> io::Result<CarV1Header> {
 fn read_block_data_location_and_skip(
     mut reader: (impl Read + Seek),
     limit_position: Option<u64>,
+    allowlist: &MultihashAllowlist,
 ) -> io::Result<Option<(Cid, UncompressedBlockDataLocation)>> {
     if let Some(limit_position) = limit_position {
         if reader.stream_position()? >= limit_position {
@@ -416,6 +1336,9 @@ fn read_block_data_location_and_skip(
     let frame_body_offset = reader.stream_position()?;
     let mut reader = CountRead::new(&mut reader);
     let cid = Cid::read_bytes(&mut reader).map_err(cid_error_to_io_error)?;
+    allowlist
+        .check(&cid)
+        .map_err(|e| io::Error::new(InvalidData, e))?;
 
     // counting the read bytes saves us a syscall for finding block data offset
     let cid_length = reader.bytes_read();
@@ -444,7 +1367,7 @@ fn read_block_data_location_and_skip(
 ///        └───────────┼─────────────┘
 ///        reader end ►│
 /// ```
-fn read_varint_body_length_or_eof(mut reader: impl Read) -> io::Result<Option<u32>> {
+pub(super) fn read_varint_body_length_or_eof(mut reader: impl Read) -> io::Result<Option<u32>> {
     let mut byte = [0u8; 1]; // detect EOF
     match reader.read(&mut byte)? {
         0 => Ok(None),
@@ -486,13 +1409,14 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::PlainCar;
+    use super::{AsyncPlainCar, Headers, PlainCar, ReadOnlyCar, RootError, read_v2_header};
     use crate::utils::db::{
         car_stream::{CarStream, CarV1Header},
         car_util::load_car,
     };
     use futures::{TryStreamExt as _, executor::block_on};
     use fvm_ipld_blockstore::{Blockstore, MemoryBlockstore};
+    use integer_encoding::VarInt as _;
     use std::io::Cursor;
     use std::sync::LazyLock;
     use tokio::io::{AsyncBufRead, AsyncSeek, BufReader};
@@ -520,6 +1444,182 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_async_plain_car_does_not_block_the_executor() {
+        use positioned_io::{ReadAt, Size};
+        use std::sync::{Arc, Condvar, Mutex};
+        use std::time::Duration;
+
+        /// Reads block until the gate is open.
+        struct GatedReader {
+            inner: &'static [u8],
+            gate: Arc<(Mutex<bool>, Condvar)>,
+        }
+
+        impl GatedReader {
+            fn set(gate: &(Mutex<bool>, Condvar), open: bool) {
+                *gate.0.lock().unwrap() = open;
+                gate.1.notify_all();
+            }
+        }
+
+        impl ReadAt for GatedReader {
+            fn read_at(&self, pos: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+                let (open, _) = self
+                    .gate
+                    .1
+                    .wait_timeout_while(
+                        self.gate.0.lock().unwrap(),
+                        Duration::from_secs(10),
+                        |open| !*open,
+                    )
+                    .unwrap();
+                match *open {
+                    true => self.inner.read_at(pos, buf),
+                    false => Err(std::io::Error::other("the executor was blocked")),
+                }
+            }
+        }
+
+        impl Size for GatedReader {
+            fn size(&self) -> std::io::Result<Option<u64>> {
+                self.inner.size()
+            }
+        }
+
+        let gate = Arc::new((Mutex::new(true), Condvar::new()));
+        let car = AsyncPlainCar::new(
+            PlainCar::new(GatedReader {
+                inner: chain4_car(),
+                gate: gate.clone(),
+            })
+            .unwrap(),
+        );
+        let reference = PlainCar::new(chain4_car()).unwrap();
+        let root = *car.roots().first();
+
+        // The read can only complete once the other future has run on this
+        // (single-threaded) runtime.
+        GatedReader::set(&gate, false);
+        let (block, ()) = tokio::join!(car.get(&root), async {
+            tokio::task::yield_now().await;
+            GatedReader::set(&gate, true);
+        });
+        assert_eq!(block.unwrap(), reference.get(&root).unwrap());
+
+        assert!(car.has(&root).await.unwrap());
+        assert!(!car.has(&cid::Cid::default()).await.unwrap());
+        assert_eq!(car.get(&cid::Cid::default()).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_validate_roots_as_tipset() {
+        use crate::blocks::{RawBlockHeader, TipsetKey};
+        use crate::utils::multihash::prelude::*;
+
+        for car in [chain4_car(), crate::networks::calibnet::DEFAULT_GENESIS] {
+            PlainCar::new(car)
+                .unwrap()
+                .validate_roots_as_tipset()
+                .unwrap();
+        }
+
+        let block =
+            |header: &RawBlockHeader| (header.cid(), fvm_ipld_encoding::to_vec(header).unwrap());
+        let at_1 = block(&RawBlockHeader {
+            epoch: 1,
+            ..Default::default()
+        });
+        let at_2 = block(&RawBlockHeader {
+            epoch: 2,
+            ..Default::default()
+        });
+        let other_parents = block(&RawBlockHeader {
+            epoch: 1,
+            parents: TipsetKey::from(nunny::vec![at_2.0]),
+            ..Default::default()
+        });
+        let not_a_header = {
+            let data = b"not a header".to_vec();
+            (
+                cid::Cid::new_v1(
+                    fvm_ipld_encoding::IPLD_RAW,
+                    MultihashCode::Blake2b256.digest(&data),
+                ),
+                data,
+            )
+        };
+        let blocks = [&at_1, &at_2, &other_parents, &not_a_header];
+        let validate = |roots: &[cid::Cid]| {
+            PlainCar::new(car(roots, &blocks))
+                .unwrap()
+                .validate_roots_as_tipset()
+        };
+
+        validate(&[at_1.0]).unwrap();
+        assert!(matches!(
+            validate(&[at_1.0, at_2.0]),
+            Err(RootError::EpochMismatch { cid, epoch: 2, first, first_epoch: 1 })
+                if cid == at_2.0 && first == at_1.0
+        ));
+        assert!(matches!(
+            validate(&[at_1.0, other_parents.0]),
+            Err(RootError::ParentsMismatch { cid, first })
+                if cid == other_parents.0 && first == at_1.0
+        ));
+        assert!(matches!(
+            validate(&[at_1.0, not_a_header.0]),
+            Err(RootError::NotABlockHeader { cid, .. }) if cid == not_a_header.0
+        ));
+        let missing = cid::Cid::default();
+        assert!(matches!(
+            validate(&[at_1.0, missing]),
+            Err(RootError::Missing(cid)) if cid == missing
+        ));
+        // The error names both roots
+        let error = validate(&[at_1.0, at_2.0]).unwrap_err().to_string();
+        assert!(error.contains(&at_1.0.to_string()) && error.contains(&at_2.0.to_string()));
+        // `heaviest_tipset` reports the same error
+        let error = PlainCar::new(car(&[at_1.0, at_2.0], &blocks))
+            .unwrap()
+            .heaviest_tipset()
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<RootError>(),
+            Some(RootError::EpochMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_only() {
+        use crate::utils::multihash::prelude::*;
+
+        let car = chain4_car();
+        let plain = PlainCar::new(car).unwrap();
+        let read_only = ReadOnlyCar::from(PlainCar::new(car).unwrap());
+        assert_eq!(read_only.roots(), plain.roots());
+        assert_eq!(
+            read_only.heaviest_tipset().unwrap(),
+            plain.heaviest_tipset().unwrap()
+        );
+        for cid in plain.cids() {
+            assert_eq!(read_only.get(&cid).unwrap(), plain.get(&cid).unwrap());
+        }
+
+        let block = b"not in the CAR";
+        let cid = cid::Cid::new_v1(
+            fvm_ipld_encoding::IPLD_RAW,
+            MultihashCode::Blake2b256.digest(block),
+        );
+        assert_eq!(read_only.get(&cid).unwrap(), None);
+        read_only.put_keyed(&cid, block).unwrap_err();
+        assert_eq!(read_only.get(&cid).unwrap(), None);
+        // Even blocks that are already in the CAR can't be written
+        let existing = plain.roots().first();
+        let data = plain.get(existing).unwrap().unwrap();
+        read_only.put_keyed(existing, &data).unwrap_err();
+    }
+
     #[test]
     fn test_uncompressed_v2() {
         let car = carv2_car();
@@ -543,6 +1643,586 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_empty_roots() {
+        #[derive(serde::Serialize)]
+        struct RawCarV1Header {
+            roots: Vec<cid::Cid>,
+            version: u64,
+        }
+
+        let header = fvm_ipld_encoding::to_vec(&RawCarV1Header {
+            roots: vec![],
+            version: 1,
+        })
+        .unwrap();
+        let mut car = header.len().encode_var_vec();
+        car.extend(header);
+        car.extend_from_slice(&chain4_car()[chain4_header_len()..]);
+
+        let Err(error) = PlainCar::new(car) else {
+            panic!("CAR with empty roots must be rejected");
+        };
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("at least one root"), "{error}");
+    }
+
+    #[test]
+    fn test_external_index_roundtrip() {
+        for car in [chain4_car(), carv2_car()] {
+            let car_backed = PlainCar::new(car).unwrap();
+            let index_file = tempfile::NamedTempFile::new().unwrap();
+            car_backed.export_index(index_file.path()).unwrap();
+
+            let indexed = PlainCar::new_with_external_index(car, index_file.path()).unwrap();
+            assert_eq!(indexed.version(), car_backed.version());
+            assert_eq!(indexed.roots(), car_backed.roots());
+            assert_eq!(indexed.cids().len(), car_backed.cids().len());
+            for cid in car_backed.cids() {
+                assert_eq!(
+                    indexed.get(&cid).unwrap().unwrap(),
+                    car_backed.get(&cid).unwrap().unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_external_index_wrong_car() {
+        let index_file = tempfile::NamedTempFile::new().unwrap();
+        PlainCar::new(chain4_car())
+            .unwrap()
+            .export_index(index_file.path())
+            .unwrap();
+
+        let truncated = &chain4_car()[..chain4_car().len() - 1];
+        let Err(error) = PlainCar::new_with_external_index(truncated, index_file.path()) else {
+            panic!("index for a different CAR must be rejected");
+        };
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        let Err(error) = PlainCar::new_with_external_index(carv2_car(), index_file.path()) else {
+            panic!("index for a different CAR must be rejected");
+        };
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_new_with_progress() {
+        for car in [chain4_car(), carv2_car()] {
+            let mut calls = vec![];
+            let car_backed =
+                PlainCar::new_with_progress(car, |processed, total| calls.push((processed, total)))
+                    .unwrap();
+            assert_eq!(car_backed.cids(), PlainCar::new(car).unwrap().cids());
+
+            let size = car.len() as u64;
+            assert_eq!(calls.last(), Some(&(size, size)));
+            assert!(calls.iter().all(|(_, total)| *total == size));
+            assert!(calls.is_sorted());
+            // Periodically, not for every block
+            assert!(calls.len() as u64 <= size / PROGRESS_INTERVAL + 1);
+        }
+    }
+
+    #[test]
+    fn test_carv2_without_index() {
+        const PRAGMA: [u8; 11] = [
+            0x0a, 0xa1, 0x67, 0x76, 0x65, 0x72, 0x73, 0x69, 0x6f, 0x6e, 0x02,
+        ];
+        const HEADER_LEN: usize = 40;
+        let v1 = chain4_car();
+        let mut car = PRAGMA.to_vec();
+        car.extend([0; 16]); // characteristics
+        car.extend(((PRAGMA.len() + HEADER_LEN) as i64).to_le_bytes()); // data offset
+        car.extend((v1.len() as i64).to_le_bytes()); // data size
+        car.extend(0_i64.to_le_bytes()); // index offset
+        car.extend(v1);
+
+        let header_v2 = read_v2_header(car.as_slice()).unwrap().unwrap();
+        assert_eq!(header_v2.index_offset, 0);
+        assert_eq!(header_v2.index_position(), None);
+        let headers = Headers::read(&car).unwrap();
+        assert_eq!(headers.index_position, None);
+        assert_eq!(headers.limit_position, Some(car.len() as u64));
+
+        let carv2 = PlainCar::new(car).unwrap();
+        let carv1 = PlainCar::new(v1).unwrap();
+        assert_eq!(carv2.version(), 2);
+        assert_eq!(carv2.roots(), carv1.roots());
+        assert_eq!(carv2.cids().len(), carv1.cids().len());
+
+        // Unlike the fixture, which has an index
+        let headers = Headers::read(&carv2_car()).unwrap();
+        assert!(headers.index_position.is_some());
+        assert!(headers.index_position >= headers.limit_position);
+    }
+
+    #[test]
+    fn test_carv2_fully_indexed() {
+        use crate::utils::multihash::prelude::*;
+        use positioned_io::{ReadAt, Size};
+        use std::sync::Mutex;
+
+        /// Records the position of every read.
+        struct RecordingReader {
+            inner: Vec<u8>,
+            reads: Mutex<Vec<u64>>,
+        }
+
+        impl ReadAt for RecordingReader {
+            fn read_at(&self, pos: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.reads.lock().unwrap().push(pos);
+                self.inner.read_at(pos, buf)
+            }
+        }
+
+        impl Size for RecordingReader {
+            fn size(&self) -> std::io::Result<Option<u64>> {
+                self.inner.size()
+            }
+        }
+
+        let open = |car: Vec<u8>| {
+            PlainCar::new(RecordingReader {
+                inner: car,
+                reads: Mutex::default(),
+            })
+            .unwrap()
+        };
+        // The leftmost bit of the characteristics, after the pragma
+        let fully_indexed = |mut car: Vec<u8>| {
+            car[11] |= 0x80;
+            car
+        };
+
+        let scanned = PlainCar::new(carv2_car()).unwrap();
+        let indexed = open(fully_indexed(carv2_car().to_vec()));
+        assert!(indexed.header_v2.as_ref().unwrap().is_fully_indexed());
+        let (mut expected, mut actual) = (scanned.cids(), indexed.cids());
+        expected.sort();
+        actual.sort();
+        assert_eq!(expected, actual);
+        for cid in expected {
+            assert_eq!(scanned.get(&cid).unwrap(), indexed.get(&cid).unwrap());
+        }
+
+        // Add a block that isn't in the index, to the end of the blocks section
+        let unindexed = {
+            let data = b"not in the index".to_vec();
+            (
+                cid::Cid::new_v1(
+                    fvm_ipld_encoding::IPLD_RAW,
+                    MultihashCode::Blake2b256.digest(&data),
+                ),
+                data,
+            )
+        };
+        let frame = {
+            let mut body = unindexed.0.to_bytes();
+            body.extend(&unindexed.1);
+            [body.len().encode_var_vec(), body].concat()
+        };
+        let limit_position = Headers::read(&carv2_car()).unwrap().limit_position.unwrap() as usize;
+        let mut car = carv2_car()[..limit_position].to_vec();
+        car.extend(&frame);
+        car.extend(&carv2_car()[limit_position..]);
+        for field in [35..43, 43..51] {
+            // data size, index offset
+            let value = i64::from_le_bytes(car[field.clone()].try_into().unwrap());
+            car[field].copy_from_slice(&(value + frame.len() as i64).to_le_bytes());
+        }
+        let unindexed_frame = limit_position as u64..(limit_position + frame.len()) as u64;
+        let read_unindexed_frame = |car: &PlainCar<RecordingReader>| {
+            car.reader
+                .reads
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|pos| unindexed_frame.contains(pos))
+        };
+
+        let scanned = open(car.clone());
+        assert!(scanned.has(&unindexed.0).unwrap());
+        assert!(read_unindexed_frame(&scanned));
+
+        // Completeness is trusted, so the blocks aren't scanned
+        let indexed = open(fully_indexed(car.clone()));
+        assert!(!read_unindexed_frame(&indexed));
+        assert!(!indexed.has(&unindexed.0).unwrap());
+        assert_eq!(indexed.cids().len(), scanned.cids().len() - 1);
+
+        // Index entries are checked against the blocks they point to
+        let mut car = fully_indexed(car);
+        let index_position = Headers::read(&car).unwrap().index_position.unwrap() as usize;
+        // format, code count, code, bucket count, width, byte length
+        car[index_position + 2 + 4 + 8 + 4 + 4 + 8] ^= 0xFF;
+        let err = PlainCar::new(car).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("doesn't match block"), "{err}");
+    }
+
+    #[test]
+    fn test_verify_all_hashes() {
+        for car in [chain4_car(), carv2_car()] {
+            let car_backed = PlainCar::new(car).unwrap();
+            let report = car_backed.verify_all_hashes().unwrap();
+            assert_eq!(report.verified, car_backed.cids().len());
+            assert!(report.mismatches.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_verify_all_hashes_tampered() {
+        let clean = PlainCar::new(chain4_car()).unwrap();
+        let cid = clean.cids()[0];
+        let offset = clean.index.read().get(&cid).unwrap().offset;
+
+        let mut car = chain4_car().to_vec();
+        car[usize::try_from(offset).unwrap()] ^= 0xFF;
+        let tampered = PlainCar::new(car).unwrap();
+        let report = tampered.verify_all_hashes().unwrap();
+        assert_eq!(report.verified, clean.cids().len() - 1);
+        let [mismatch] = report.mismatches.as_slice() else {
+            panic!("expected exactly one mismatch: {report:?}");
+        };
+        assert_eq!(mismatch.cid, cid);
+        assert_eq!(mismatch.offset, offset);
+        assert!(mismatch.actual.is_some_and(|actual| actual != cid));
+    }
+
+    #[test]
+    fn test_verify_all_hashes_cid_v0() {
+        use crate::utils::multihash::prelude::*;
+
+        let block = |data: &[u8]| {
+            (
+                cid::Cid::new_v0(MultihashCode::Sha2_256.digest(data)).unwrap(),
+                data.to_vec(),
+            )
+        };
+        let valid = block(b"valid");
+        let tampered = (block(b"tampered").0, b"tampered!".to_vec());
+        let car_backed = PlainCar::new(car(&[valid.0], &[&valid, &tampered])).unwrap();
+        let report = car_backed.verify_all_hashes().unwrap();
+        assert_eq!(report.verified, 1);
+        let [mismatch] = report.mismatches.as_slice() else {
+            panic!("expected exactly one mismatch: {report:?}");
+        };
+        assert_eq!(mismatch.cid, tampered.0);
+        assert_eq!(mismatch.actual, Some(block(b"tampered!").0));
+    }
+
+    #[test]
+    fn test_is_deterministic() {
+        use crate::utils::multihash::prelude::*;
+        use fvm_ipld_encoding::{DAG_CBOR, IPLD_RAW};
+
+        let block = |codec, data: Vec<u8>| {
+            (
+                cid::Cid::new_v1(codec, MultihashCode::Blake2b256.digest(&data)),
+                data,
+            )
+        };
+        let node = |links: &[cid::Cid]| block(DAG_CBOR, fvm_ipld_encoding::to_vec(links).unwrap());
+        let missing = block(IPLD_RAW, b"not in the CAR".to_vec());
+        let leaf = block(IPLD_RAW, b"leaf".to_vec());
+        let c = node(&[missing.0]);
+        let a = node(&[c.0, leaf.0]);
+        let b = node(&[c.0]);
+        let root = node(&[a.0, b.0]);
+        let unreachable = block(IPLD_RAW, b"unreachable".to_vec());
+
+        let is_deterministic = |roots: &[cid::Cid], blocks: &[&(cid::Cid, Vec<u8>)]| {
+            PlainCar::new(car(roots, blocks))
+                .unwrap()
+                .is_deterministic()
+                .unwrap()
+        };
+        assert!(is_deterministic(&[root.0], &[&root, &a, &c, &leaf, &b]));
+        // Breadth-first
+        assert!(!is_deterministic(&[root.0], &[&root, &a, &b, &c, &leaf]));
+        // Links visited in reverse
+        assert!(!is_deterministic(&[root.0], &[&root, &b, &c, &a, &leaf]));
+        assert!(!is_deterministic(
+            &[root.0],
+            &[&root, &a, &c, &leaf, &b, &unreachable]
+        ));
+        assert!(!is_deterministic(
+            &[root.0, b.0],
+            &[&root, &a, &c, &leaf, &b]
+        ));
+    }
+
+    #[test]
+    fn test_is_deterministic_chain4() {
+        use crate::cid_collections::CidHashSet;
+        use crate::ipld::{DfsIter, Ipld};
+
+        let chain4 = PlainCar::new(chain4_car()).unwrap();
+        // Forest exports walk the chain one tipset at a time, emitting the messages and
+        // state of each tipset before its parents, whereas a depth-first traversal of the
+        // block header links reaches the parents first.
+        assert!(!chain4.is_deterministic().unwrap());
+
+        // The same blocks, in the first-seen order of a depth-first traversal
+        let root = *chain4.roots().first();
+        let mut seen = CidHashSet::new();
+        let mut blocks = vec![];
+        let mut dfs = DfsIter::from(root);
+        while let Some(ipld) = dfs.next() {
+            if let Ipld::Link(cid) = ipld
+                && let Some(data) = chain4.get(&cid).unwrap()
+                && seen.insert(cid)
+            {
+                dfs.walk_next(fvm_ipld_encoding::from_slice(&data).unwrap());
+                blocks.push((cid, data));
+            }
+        }
+        assert_eq!(blocks.len(), chain4.index.read().len());
+        let deterministic = |blocks: &[(cid::Cid, Vec<u8>)]| {
+            PlainCar::new(car(&[root], &blocks.iter().collect::<Vec<_>>()))
+                .unwrap()
+                .is_deterministic()
+                .unwrap()
+        };
+        assert!(deterministic(&blocks));
+        // Any other order isn't
+        let last = blocks.len() - 1;
+        blocks.swap(1, last);
+        assert!(!deterministic(&blocks));
+    }
+
+    #[test]
+    fn test_unexpected_multihash() {
+        use crate::utils::multihash::{MultihashAllowlist, prelude::*};
+
+        let data = b"hashed with BLAKE3";
+        let cid = cid::Cid::new_v1(
+            fvm_ipld_encoding::IPLD_RAW,
+            MultihashCode::Blake3_256.digest(data),
+        );
+        let mut car = chain4_car().to_vec();
+        let mut frame = cid.to_bytes();
+        frame.extend_from_slice(data);
+        car.extend(frame.len().encode_var_vec());
+        car.extend(frame);
+
+        let Err(error) = PlainCar::new(car.clone()) else {
+            panic!("CAR with a BLAKE3 CID must be rejected");
+        };
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("Blake3_256"), "{error}");
+        assert!(error.to_string().contains(&cid.to_string()), "{error}");
+
+        let allowlist = MultihashAllowlist::default().with(MultihashCode::Blake3_256);
+        let car_backed = PlainCar::new_with_multihash_allowlist(car, &allowlist).unwrap();
+        assert_eq!(car_backed.get(&cid).unwrap().unwrap(), data);
+    }
+
+    #[test]
+    fn test_blocks_by_codec() {
+        use crate::utils::multihash::prelude::*;
+        use fvm_ipld_encoding::{DAG_CBOR, IPLD_RAW};
+
+        let mut car = chain4_car().to_vec();
+        let mut raw = vec![];
+        for data in [b"first".as_slice(), b"second"] {
+            let cid = cid::Cid::new_v1(IPLD_RAW, MultihashCode::Blake2b256.digest(data));
+            let mut frame = cid.to_bytes();
+            frame.extend_from_slice(data);
+            car.extend(frame.len().encode_var_vec());
+            car.extend(frame);
+            raw.push((cid, data.to_vec()));
+        }
+        let car_backed = PlainCar::new(car).unwrap();
+
+        let dag_cbor = car_backed
+            .blocks_by_codec(DAG_CBOR)
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        let in_car_order = block_on(async {
+            CarStream::new(Cursor::new(chain4_car()))
+                .await?
+                .map_ok(|block| block.cid)
+                .try_collect::<Vec<_>>()
+                .await
+        })
+        .unwrap();
+        assert_eq!(
+            dag_cbor.iter().map(|block| block.cid).collect::<Vec<_>>(),
+            in_car_order
+        );
+        for block in &dag_cbor {
+            assert_eq!(
+                car_backed.get(&block.cid).unwrap(),
+                Some(block.data.clone())
+            );
+        }
+
+        let raw_blocks = car_backed
+            .blocks_by_codec(IPLD_RAW)
+            .map(|block| block.map(|block| (block.cid, block.data)))
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(raw_blocks, raw);
+        assert_eq!(dag_cbor.len() + raw_blocks.len(), car_backed.cids().len());
+
+        assert_eq!(
+            car_backed.blocks_by_codec(fvm_ipld_encoding::CBOR).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_blocks_read_ahead() {
+        let car_backed = PlainCar::new(chain4_car()).unwrap();
+        let per_block = car_backed
+            .blocks_with_read_ahead(0)
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(per_block.len(), car_backed.cids().len());
+        assert!(
+            per_block.is_sorted_by_key(|block| {
+                car_backed.index.read().get(&block.cid).unwrap().offset
+            })
+        );
+        for block in &per_block {
+            assert_eq!(
+                car_backed.get(&block.cid).unwrap(),
+                Some(block.data.clone())
+            );
+        }
+        for window in [1, 100, 4096, DEFAULT_READ_AHEAD_WINDOW, usize::MAX] {
+            let windowed = car_backed
+                .blocks_with_read_ahead(window)
+                .collect::<std::io::Result<Vec<_>>>()
+                .unwrap();
+            assert_eq!(windowed, per_block, "window = {window}");
+        }
+    }
+
+    #[test]
+    fn test_block_size_histogram() {
+        use super::BucketRange;
+
+        assert_eq!(BucketRange::of(0), BucketRange { start: 0, end: 1 });
+        assert_eq!(BucketRange::of(1), BucketRange { start: 1, end: 2 });
+        assert_eq!(
+            BucketRange::of(1023),
+            BucketRange {
+                start: 512,
+                end: 1024
+            }
+        );
+        assert_eq!(
+            BucketRange::of(1024),
+            BucketRange {
+                start: 1024,
+                end: 2048
+            }
+        );
+        assert_eq!(
+            BucketRange::of(u32::MAX),
+            BucketRange {
+                start: 1 << 31,
+                end: 1 << 32
+            }
+        );
+
+        let car_backed = PlainCar::new(chain4_car()).unwrap();
+        let histogram = car_backed
+            .block_size_histogram()
+            .into_iter()
+            .map(|(bucket, count)| (bucket.to_string(), count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            histogram,
+            [
+                ("1..2", 1),
+                ("2..4", 3),
+                ("4..8", 3),
+                ("8..16", 5),
+                ("16..32", 14),
+                ("32..64", 230),
+                ("64..128", 33),
+                ("128..256", 10),
+                ("256..512", 94),
+                ("512..1024", 478),
+                ("1024..2048", 315),
+                ("2048..4096", 4),
+                ("4096..8192", 15),
+                ("8192..16384", 17),
+            ]
+            .map(|(bucket, count)| (bucket.to_string(), count))
+        );
+        assert_eq!(
+            histogram.iter().map(|(_, count)| count).sum::<u64>(),
+            car_backed.cids().len() as u64
+        );
+    }
+
+    #[test]
+    fn test_read_root_blocks() {
+        use crate::blocks::CachingBlockHeader;
+        use crate::utils::multihash::prelude::*;
+
+        let car_backed = PlainCar::new(chain4_car()).unwrap();
+        let roots = car_backed.read_root_blocks().unwrap();
+        assert_eq!(
+            roots.iter().map(|(cid, _)| *cid).collect::<Vec<_>>(),
+            car_backed.roots().to_vec()
+        );
+        for (cid, data) in roots {
+            let header = fvm_ipld_encoding::from_slice::<CachingBlockHeader>(&data).unwrap();
+            assert_eq!(*header.cid(), cid);
+            assert_eq!(car_backed.get(&cid).unwrap(), Some(data));
+        }
+
+        let missing = cid::Cid::new_v1(
+            fvm_ipld_encoding::DAG_CBOR,
+            MultihashCode::Blake2b256.digest(b"missing"),
+        );
+        // An unrelated block, as a CARv1 must contain at least one
+        let data = fvm_ipld_encoding::to_vec(&"unrelated").unwrap();
+        let unrelated = (
+            cid::Cid::new_v1(
+                fvm_ipld_encoding::DAG_CBOR,
+                MultihashCode::Blake2b256.digest(&data),
+            ),
+            data,
+        );
+        let car_backed = PlainCar::new(car(&[missing], &[&unrelated])).unwrap();
+        assert_eq!(
+            car_backed.read_root_blocks().unwrap_err().kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    /// A CARv1 with `roots`, and `blocks` in the given order.
+    fn car(roots: &[cid::Cid], blocks: &[&(cid::Cid, Vec<u8>)]) -> Vec<u8> {
+        let header = fvm_ipld_encoding::to_vec(&CarV1Header {
+            roots: nunny::Vec::new(roots.to_vec()).unwrap(),
+            version: 1,
+        })
+        .unwrap();
+        let mut car = header.len().encode_var_vec();
+        car.extend(header);
+        for (cid, data) in blocks {
+            let mut frame = cid.to_bytes();
+            frame.extend_from_slice(data);
+            car.extend(frame.len().encode_var_vec());
+            car.extend(frame);
+        }
+        car
+    }
+
+    /// Length of the header frame of [`chain4_car`], including its varint prefix.
+    fn chain4_header_len() -> usize {
+        let (body_len, prefix_len) = usize::decode_var(chain4_car()).unwrap();
+        body_len + prefix_len
+    }
+
     fn reference(reader: impl AsyncBufRead + AsyncSeek + Unpin) -> MemoryBlockstore {
         let blockstore = MemoryBlockstore::new();
         block_on(load_car(&blockstore, reader)).unwrap();
@@ -0,0 +1,133 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Change the zstd compression level of an archive without writing an
+//! uncompressed copy to disk.
+
+use super::forest::{DEFAULT_FOREST_CAR_FRAME_SIZE, Encoder, new_forest_car_temp_path_in};
+use crate::utils::db::car_stream::CarStream;
+use anyhow::Context as _;
+use futures::TryStreamExt as _;
+use std::path::Path;
+use tokio::io::{AsyncBufRead, AsyncSeek, AsyncWrite, AsyncWriteExt as _, BufReader, BufWriter};
+
+/// Recompress the archive at `path` at zstd `level`, see [`recompress`], and
+/// replace it with the result, a `.forest.car.zst`.
+///
+/// The result is streamed to a temporary file next to `path`, which is then
+/// renamed over `path`, so the archive is left as it was on error.
+pub async fn recompress_in_place(path: impl AsRef<Path>, level: u16) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let temp_path = new_forest_car_temp_path_in(dir)?;
+    let input = BufReader::new(
+        tokio::fs::File::open(path)
+            .await
+            .with_context(|| format!("failed to open {}", path.display()))?,
+    );
+    let output = BufWriter::new(tokio::fs::File::create(&temp_path).await?);
+    recompress(input, output, level).await?;
+    temp_path.persist(path)?;
+    Ok(())
+}
+
+/// Stream the blocks of `input` into a `.forest.car.zst` written to `output`,
+/// compressed at zstd `level`. Roots and block order are preserved.
+///
+/// `input` may be a plain, zstd-compressed or `.forest.car.zst` archive.
+pub async fn recompress(
+    input: impl AsyncBufRead + AsyncSeek + Unpin,
+    mut output: impl AsyncWrite + Unpin,
+    level: u16,
+) -> anyhow::Result<()> {
+    let mut blocks = CarStream::new(input).await?;
+    let roots = blocks.header_v1.roots.clone();
+    let frames = Encoder::compress_stream(
+        DEFAULT_FOREST_CAR_FRAME_SIZE,
+        level,
+        blocks.map_err(anyhow::Error::from),
+    );
+    Encoder::write(&mut output, roots, frames).await?;
+    output.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::car::ForestCar;
+    use cid::Cid;
+    use std::io::Cursor;
+
+    async fn recompress_to_vec(input: &[u8], level: u16) -> Vec<u8> {
+        let mut output = vec![];
+        recompress(Cursor::new(input), &mut output, level)
+            .await
+            .unwrap();
+        output
+    }
+
+    async fn cids(car: &[u8]) -> Vec<Cid> {
+        CarStream::new(Cursor::new(car))
+            .await
+            .unwrap()
+            .map_ok(|block| block.cid)
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn recompress_low_to_high() {
+        let original = include_bytes!("../../../test-snapshots/chain4.car.zst").as_slice();
+        let fast = recompress_to_vec(original, 1).await;
+        let archival = recompress_to_vec(&fast, 19).await;
+
+        let expected = cids(original).await;
+        assert_eq!(cids(&fast).await, expected);
+        assert_eq!(cids(&archival).await, expected);
+
+        let fast = ForestCar::new(fast).unwrap();
+        let archival = ForestCar::new(archival).unwrap();
+        assert_eq!(archival.roots(), fast.roots());
+        assert_eq!(
+            archival.roots(),
+            &CarStream::new(Cursor::new(original))
+                .await
+                .unwrap()
+                .header_v1
+                .roots
+        );
+    }
+
+    #[tokio::test]
+    async fn recompress_in_place_chain4() {
+        let original = include_bytes!("../../../test-snapshots/chain4.car.zst").as_slice();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain4.car.zst");
+        std::fs::write(&path, original).unwrap();
+
+        recompress_in_place(&path, 19).await.unwrap();
+
+        let archival = std::fs::read(&path).unwrap();
+        assert_eq!(cids(&archival).await, cids(original).await);
+        ForestCar::new(archival).unwrap();
+        // The temporary file is gone
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn recompress_in_place_junk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("junk.car");
+        std::fs::write(&path, b"junk").unwrap();
+
+        recompress_in_place(&path, 19).await.unwrap_err();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"junk");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Recover CARv1 files whose writer was interrupted mid-frame.
+//!
+//! Such a file has a valid header and a sequence of complete block frames,
+//! followed by a partial frame that [`PlainCar::new`](super::PlainCar::new)
+//! chokes on. Trimming the partial frame yields a valid, if shorter, CAR.
+
+use super::plain::{read_v1_header, read_v2_header, read_varint_body_length_or_eof};
+use anyhow::Context as _;
+use std::fs::OpenOptions;
+use std::io::{BufReader, ErrorKind, Seek as _, SeekFrom};
+use std::path::Path;
+use tracing::info;
+
+/// Truncate the CARv1 file at `path` after its last complete frame, and
+/// return the new length. Complete files are left untouched.
+///
+/// Only frame boundaries are checked, the block data is not validated.
+pub fn repair_truncated(path: impl AsRef<Path>) -> anyhow::Result<u64> {
+    let path = path.as_ref();
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    let file_len = file.metadata()?.len();
+    let mut reader = BufReader::new(&file);
+
+    anyhow::ensure!(
+        read_v2_header(&mut reader)
+            .context("CAR header is truncated")?
+            .is_none(),
+        "repairing CARv2 files is not supported"
+    );
+    reader.seek(SeekFrom::Start(0))?;
+    read_v1_header(&mut reader).context("CAR header is truncated or invalid")?;
+
+    let mut last_complete_frame_end = reader.stream_position()?;
+    let mut num_blocks = 0_usize;
+    loop {
+        let body_length = match read_varint_body_length_or_eof(&mut reader) {
+            Ok(Some(body_length)) => body_length,
+            Ok(None) => break,
+            // The varint prefix itself is cut off
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        };
+        let frame_end = reader
+            .stream_position()?
+            .saturating_add(u64::from(body_length));
+        if frame_end > file_len {
+            break;
+        }
+        reader.seek(SeekFrom::Start(frame_end))?;
+        last_complete_frame_end = frame_end;
+        num_blocks += 1;
+    }
+    anyhow::ensure!(
+        num_blocks > 0,
+        "{} has no complete block frame",
+        path.display()
+    );
+
+    if last_complete_frame_end < file_len {
+        file.set_len(last_complete_frame_end)?;
+        info!(
+            "Truncated {} from {file_len} to {last_complete_frame_end} bytes",
+            path.display()
+        );
+    }
+    Ok(last_complete_frame_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::car::PlainCar;
+    use fvm_ipld_blockstore::Blockstore as _;
+
+    #[test]
+    fn repair_truncated_mid_frame() {
+        let car = zstd::decode_all(&include_bytes!("../../../test-snapshots/chain4.car.zst")[..])
+            .unwrap();
+        let original = PlainCar::new(car.as_slice()).unwrap();
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), &car[..car.len() / 2]).unwrap();
+        assert!(PlainCar::new(std::fs::File::open(file.path()).unwrap()).is_err());
+
+        let repaired_len = repair_truncated(file.path()).unwrap();
+        assert!(repaired_len <= car.len() as u64 / 2);
+        assert_eq!(std::fs::metadata(file.path()).unwrap().len(), repaired_len);
+
+        let repaired = PlainCar::new(std::fs::File::open(file.path()).unwrap()).unwrap();
+        assert_eq!(repaired.roots(), original.roots());
+        assert!(!repaired.cids().is_empty());
+        assert!(repaired.cids().len() < original.cids().len());
+        for cid in repaired.cids() {
+            assert_eq!(repaired.get(&cid).unwrap(), original.get(&cid).unwrap());
+        }
+
+        // Repairing a complete file is a no-op
+        assert_eq!(repair_truncated(file.path()).unwrap(), repaired_len);
+    }
+
+    #[test]
+    fn repair_truncated_header() {
+        let car = zstd::decode_all(&include_bytes!("../../../test-snapshots/chain4.car.zst")[..])
+            .unwrap();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), &car[..8]).unwrap();
+        assert!(repair_truncated(file.path()).is_err());
+        assert_eq!(std::fs::metadata(file.path()).unwrap().len(), 8);
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Split an archive into `.forest.car.zst` shards that each fit in a size
+//! limit, e.g. for storage with a maximum object size.
+//!
+//! Every shard has the roots of the input, and every block in a shard is
+//! reachable from those roots _within_ the shard: the parents of a block (up to
+//! the roots) are duplicated into each shard that needs them. So each shard can
+//! be loaded on its own, e.g. with [`ForestCar::new`](super::ForestCar::new).
+//!
+//! Blocks are assigned to shards in input order, and parents are taken from a
+//! depth-first traversal from the roots. Blocks that aren't reachable from the
+//! roots are kept, without parents.
+
+use super::AnyCar;
+use super::forest::{Encoder, FOREST_CAR_FILE_EXTENSION, new_forest_car_temp_path_in};
+use crate::cid_collections::{CidHashMap, CidHashSet};
+use crate::utils::db::car_stream::{CarBlock, CarStream, CarV1Header};
+use crate::utils::encoding::extract_cids;
+use anyhow::Context as _;
+use cid::Cid;
+use futures::TryStreamExt as _;
+use fvm_ipld_blockstore::Blockstore;
+use integer_encoding::VarInt as _;
+use nunny::Vec as NonEmpty;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncWriteExt as _, BufReader, BufWriter};
+
+/// A shard written by [`shard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shard {
+    pub path: PathBuf,
+    pub roots: NonEmpty<Cid>,
+    /// Number of blocks, including the roots and duplicated parents.
+    pub num_blocks: usize,
+    /// Size of the file.
+    pub len: u64,
+}
+
+/// Split the archive at `input` into `shard-NNNN.forest.car.zst` files in
+/// `output_dir`, each at most `max_bytes` long, and return the manifest of the
+/// written shards, in order.
+///
+/// Shards are filled based on the uncompressed size of their blocks, so they
+/// are usually well under `max_bytes`. It's an error if the roots, or a block
+/// and its parents, don't fit in a shard of their own.
+///
+/// `input` may be a plain, zstd-compressed or `.forest.car.zst` archive. The
+/// CIDs of its blocks are held in memory, but not their data.
+///
+/// Nothing is left in `output_dir` on error.
+pub async fn shard(
+    input: impl AsRef<Path>,
+    output_dir: impl AsRef<Path>,
+    max_bytes: u64,
+) -> anyhow::Result<Vec<Shard>> {
+    let (input, output_dir) = (input.as_ref(), output_dir.as_ref());
+    let car =
+        AnyCar::try_from(input).with_context(|| format!("failed to open {}", input.display()))?;
+
+    let mut blocks = CarStream::new(BufReader::new(tokio::fs::File::open(input).await?)).await?;
+    let roots = blocks.header_v1.roots.clone();
+    let mut order = vec![];
+    let mut frame_lens = CidHashMap::new();
+    while let Some(block) = blocks.try_next().await? {
+        if frame_lens.insert(block.cid, frame_len(&block)).is_none() {
+            order.push(block.cid);
+        }
+    }
+    for root in &roots {
+        anyhow::ensure!(
+            frame_lens.contains_key(root),
+            "root {root} is missing from {}",
+            input.display()
+        );
+    }
+    let parents = tree_parents(&car, &roots, &frame_lens)?;
+
+    let header_len = {
+        let header = fvm_ipld_encoding::to_vec(&CarV1Header {
+            roots: roots.clone(),
+            version: 1,
+        })?;
+        header.len().required_space() + header.len()
+    };
+    let roots_len = roots
+        .iter()
+        .filter_map(|it| frame_lens.get(it))
+        .sum::<u64>();
+    let estimate = |blocks_len: u64, num_blocks: usize| {
+        zstd::zstd_safe::compress_bound(header_len) as u64
+            + roots_len
+            + blocks_len
+            + Encoder::max_index_and_footer_len(roots.len() + num_blocks)
+    };
+    let total_len = |cids: &[Cid]| cids.iter().filter_map(|it| frame_lens.get(it)).sum::<u64>();
+    anyhow::ensure!(
+        estimate(0, 0) <= max_bytes,
+        "the roots alone don't fit in {max_bytes} bytes"
+    );
+
+    // Plan the blocks (besides the roots) of each shard
+    let root_set = roots.iter().copied().collect::<CidHashSet>();
+    let mut plans = vec![];
+    let mut plan = Plan::default();
+    let mut covered = CidHashSet::new();
+    for cid in order {
+        if root_set.contains(&cid) || covered.contains(&cid) {
+            continue;
+        }
+        let mut lineage = plan.lineage(cid, &parents, &root_set);
+        let mut lineage_len = total_len(&lineage);
+        if !plan.cids.is_empty()
+            && estimate(plan.len + lineage_len, plan.cids.len() + lineage.len()) > max_bytes
+        {
+            plans.push(std::mem::take(&mut plan));
+            lineage = plan.lineage(cid, &parents, &root_set);
+            lineage_len = total_len(&lineage);
+        }
+        anyhow::ensure!(
+            estimate(lineage_len, lineage.len()) <= max_bytes,
+            "block {cid} and its parents don't fit in {max_bytes} bytes"
+        );
+        plan.len += lineage_len;
+        for it in lineage {
+            covered.insert(it);
+            plan.included.insert(it);
+            plan.cids.push(it);
+        }
+    }
+    if !plan.cids.is_empty() || plans.is_empty() {
+        plans.push(plan);
+    }
+
+    // Shards are written to temporary files, and only persisted once they are
+    // all within the limit
+    let mut written = vec![];
+    for (ix, plan) in plans.into_iter().enumerate() {
+        let path = output_dir.join(format!("shard-{ix:04}{FOREST_CAR_FILE_EXTENSION}"));
+        let cids = roots.iter().copied().chain(plan.cids).collect::<Vec<_>>();
+        let num_blocks = cids.len();
+
+        let temp_path = new_forest_car_temp_path_in(output_dir)?;
+        let mut writer = BufWriter::new(tokio::fs::File::create(&temp_path).await?);
+        let blocks = futures::stream::iter(cids.into_iter().map(|cid| {
+            let data = car
+                .get(&cid)?
+                .with_context(|| format!("block {cid} is missing"))?;
+            anyhow::Ok(CarBlock { cid, data })
+        }));
+        Encoder::write(
+            &mut writer,
+            roots.clone(),
+            Encoder::compress_stream_default(blocks),
+        )
+        .await?;
+        writer.flush().await?;
+        drop(writer);
+
+        let len = std::fs::metadata(&temp_path)?.len();
+        anyhow::ensure!(
+            len <= max_bytes,
+            "{} would be {len} bytes, over the limit of {max_bytes} bytes",
+            path.display()
+        );
+        written.push((
+            temp_path,
+            Shard {
+                path,
+                roots: roots.clone(),
+                num_blocks,
+                len,
+            },
+        ));
+    }
+
+    let mut shards = vec![];
+    for (temp_path, shard) in written {
+        temp_path.persist(&shard.path)?;
+        shards.push(shard);
+    }
+    Ok(shards)
+}
+
+/// The blocks of a shard, besides the roots.
+#[derive(Default)]
+struct Plan {
+    cids: Vec<Cid>,
+    included: CidHashSet,
+    /// Total frame length of `cids`.
+    len: u64,
+}
+
+impl Plan {
+    /// `cid`, preceded by those of its parents (outermost first) that aren't
+    /// in the shard yet.
+    fn lineage(&self, cid: Cid, parents: &CidHashMap<Cid>, roots: &CidHashSet) -> Vec<Cid> {
+        let mut lineage = vec![cid];
+        let mut parent = parents.get(&cid);
+        while let Some(&it) = parent {
+            if roots.contains(&it) || self.included.contains(&it) {
+                break;
+            }
+            lineage.push(it);
+            parent = parents.get(&it);
+        }
+        lineage.reverse();
+        lineage
+    }
+}
+
+/// The parent of each block in a depth-first traversal from `roots`, following
+/// the links of `DAG_CBOR` blocks to blocks in `present`.
+fn tree_parents(
+    store: &impl Blockstore,
+    roots: &[Cid],
+    present: &CidHashMap<u64>,
+) -> anyhow::Result<CidHashMap<Cid>> {
+    let mut parents = CidHashMap::new();
+    let mut seen = roots.iter().copied().collect::<CidHashSet>();
+    let mut stack = roots.to_vec();
+    while let Some(cid) = stack.pop() {
+        if cid.codec() != fvm_ipld_encoding::DAG_CBOR {
+            continue;
+        }
+        let data = store
+            .get(&cid)?
+            .with_context(|| format!("block {cid} is missing"))?;
+        for link in extract_cids(&data)? {
+            if present.contains_key(&link) && seen.insert(link) {
+                parents.insert(link, cid);
+                stack.push(link);
+            }
+        }
+    }
+    Ok(parents)
+}
+
+/// Length of the varint-prefixed frame of `block` in an uncompressed CAR.
+fn frame_len(block: &CarBlock) -> u64 {
+    let len = block.cid.encoded_len() + block.data.len();
+    (len.required_space() + len) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::car::{ForestCar, PlainCar};
+
+    /// Every block of the CAR is reachable from its roots.
+    fn is_closed(car: &ForestCar<Vec<u8>>, cids: &CidHashSet) -> bool {
+        let mut seen = car.roots().iter().copied().collect::<CidHashSet>();
+        let mut stack = car.roots().to_vec();
+        while let Some(cid) = stack.pop() {
+            if cid.codec() != fvm_ipld_encoding::DAG_CBOR {
+                continue;
+            }
+            for link in extract_cids(&car.get(&cid).unwrap().unwrap()).unwrap() {
+                if cids.contains(&link) && seen.insert(link) {
+                    stack.push(link);
+                }
+            }
+        }
+        seen.len() == cids.len()
+    }
+
+    #[tokio::test]
+    async fn shard_chain4() {
+        let input = "test-snapshots/chain4.car";
+        let original = PlainCar::new(std::fs::read(input).unwrap()).unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        let max_bytes = std::fs::metadata(input).unwrap().len() * 2 / 3;
+
+        let shards = shard(input, output_dir.path(), max_bytes).await.unwrap();
+        assert_eq!(shards.len(), 2);
+
+        let mut covered = CidHashSet::new();
+        for shard in &shards {
+            let bytes = std::fs::read(&shard.path).unwrap();
+            assert_eq!(bytes.len() as u64, shard.len);
+            assert!(shard.len <= max_bytes);
+
+            let car = ForestCar::new(bytes).unwrap();
+            assert_eq!(car.roots(), original.roots());
+            assert_eq!(&shard.roots, original.roots());
+            assert_eq!(
+                car.heaviest_tipset().unwrap(),
+                original.heaviest_tipset().unwrap()
+            );
+
+            let cids = CarStream::new(std::io::Cursor::new(std::fs::read(&shard.path).unwrap()))
+                .await
+                .unwrap()
+                .map_ok(|block| block.cid)
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            assert_eq!(cids.len(), shard.num_blocks);
+            let cids = cids.into_iter().collect::<CidHashSet>();
+            assert!(is_closed(&car, &cids));
+            for cid in cids {
+                assert_eq!(car.get(&cid).unwrap(), original.get(&cid).unwrap());
+                covered.insert(cid);
+            }
+        }
+        assert_eq!(covered.len(), original.cids().len());
+    }
+
+    #[tokio::test]
+    async fn shard_fits_in_one() {
+        let input = "test-snapshots/chain4.car.zst";
+        let output_dir = tempfile::tempdir().unwrap();
+        let shards = shard(input, output_dir.path(), u64::MAX).await.unwrap();
+        assert_eq!(shards.len(), 1);
+        assert_eq!(
+            shards[0].path,
+            output_dir.path().join("shard-0000.forest.car.zst")
+        );
+    }
+
+    #[tokio::test]
+    async fn shard_too_small() {
+        let output_dir = tempfile::tempdir().unwrap();
+        shard("test-snapshots/chain4.car", output_dir.path(), 1024)
+            .await
+            .unwrap_err();
+        // Shards are planned before any is written, and nothing is left behind
+        assert_eq!(std::fs::read_dir(output_dir.path()).unwrap().count(), 0);
+    }
+}
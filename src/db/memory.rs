@@ -1,7 +1,7 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use super::{EthMappingsStore, SettingsStore, SettingsStoreExt};
+use super::{ColumnStats, EthMappingsStore, SettingsStore, SettingsStoreExt};
 use crate::blocks::TipsetKey;
 use crate::db::{IndicesStore, PersistentStore};
 use crate::libp2p_bitswap::{BitswapStoreRead, BitswapStoreReadWrite};
@@ -101,6 +101,17 @@ impl EthMappingsStore for MemoryDB {
         Ok(cids)
     }
 
+    fn get_tipset_keys(&self) -> anyhow::Result<Vec<TipsetKey>> {
+        let tipset_keys = self
+            .eth_mappings_db
+            .read()
+            .values()
+            .filter_map(|value| fvm_ipld_encoding::from_slice::<TipsetKey>(value).ok())
+            .collect();
+
+        Ok(tipset_keys)
+    }
+
     fn delete(&self, keys: Vec<EthHash>) -> anyhow::Result<()> {
         let mut lock = self.eth_mappings_db.write();
         for hash in keys.iter() {
@@ -108,6 +119,14 @@ impl EthMappingsStore for MemoryDB {
         }
         Ok(())
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        let mut stats = ColumnStats::default();
+        for value in self.eth_mappings_db.read().values() {
+            stats.record(std::mem::size_of::<EthHash>(), value.len());
+        }
+        Ok(stats)
+    }
 }
 
 impl IndicesStore for MemoryDB {
@@ -125,6 +144,14 @@ impl IndicesStore for MemoryDB {
     fn exists(&self, key: &Cid) -> anyhow::Result<bool> {
         Ok(self.indices_db.read().contains_key(key))
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        let mut stats = ColumnStats::default();
+        for (key, value) in self.indices_db.read().iter() {
+            stats.record(key.encoded_len(), value.len());
+        }
+        Ok(stats)
+    }
 }
 
 impl Blockstore for MemoryDB {
@@ -18,9 +18,11 @@ pub mod migration;
 
 use crate::blocks::TipsetKey;
 use crate::rpc::eth::types::EthHash;
+use ahash::HashMap;
 use anyhow::{Context as _, bail};
 use cid::Cid;
 use fvm_ipld_blockstore::{Blockstore, MemoryBlockstore};
+use parking_lot::Mutex;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::sync::Arc;
@@ -32,6 +34,12 @@ pub mod setting_keys {
     pub const HEAD_KEY: &str = "head";
     /// Key used to store the memory pool configuration in the settings store.
     pub const MPOOL_CONFIG_KEY: &str = "/mpool/config";
+    /// Key used to store the epoch up to which the `EthMappings` column is populated, see
+    /// [`crate::daemon::db_util::backfill_eth_mappings_if_behind`].
+    pub const ETH_MAPPINGS_CHECKPOINT_KEY: &str = "/eth_mappings/checkpoint";
+    /// Key used to record that the reverse tipset mappings of the `EthMappings` column have been
+    /// backfilled, see [`crate::daemon::db_util::backfill_tipset_eth_hashes`].
+    pub const ETH_MAPPINGS_REVERSE_INDEX_KEY: &str = "/eth_mappings/reverse_index";
 }
 
 /// Interface used to store and retrieve settings from the database.
@@ -101,6 +109,28 @@ impl<T: ?Sized + SettingsStore> SettingsStoreExt for T {
     }
 }
 
+/// Number of entries and approximate size of a key-value column.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ColumnStats {
+    pub entries: u64,
+    /// Approximate size of the stored keys and values, in bytes.
+    pub approximate_size_bytes: u64,
+}
+
+impl ColumnStats {
+    pub fn record(&mut self, key_len: usize, value_len: usize) {
+        self.entries += 1;
+        self.approximate_size_bytes += (key_len + value_len) as u64;
+    }
+}
+
+/// Statistics of the database columns backing the Ethereum RPC.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DbStats {
+    pub eth_mappings: ColumnStats,
+    pub indices: ColumnStats,
+}
+
 /// Interface used to store and retrieve Ethereum mappings from the database.
 /// To store IPLD blocks, use the `BlockStore` trait.
 pub trait EthMappingsStore {
@@ -118,8 +148,15 @@ pub trait EthMappingsStore {
     /// Returns all message CIDs with their timestamp.
     fn get_message_cids(&self) -> anyhow::Result<Vec<(Cid, u64)>>;
 
+    /// Returns all tipset keys. They are stored under the hash of their CID, see
+    /// [`crate::chain::ChainStore::put_tipset_key`].
+    fn get_tipset_keys(&self) -> anyhow::Result<Vec<TipsetKey>>;
+
     /// Deletes `keys` if keys exist in store.
     fn delete(&self, keys: Vec<EthHash>) -> anyhow::Result<()>;
+
+    /// Returns the number of entries and the approximate size of the store.
+    fn stats(&self) -> anyhow::Result<ColumnStats>;
 }
 
 impl<T: EthMappingsStore> EthMappingsStore for Arc<T> {
@@ -139,9 +176,17 @@ impl<T: EthMappingsStore> EthMappingsStore for Arc<T> {
         EthMappingsStore::get_message_cids(self.as_ref())
     }
 
+    fn get_tipset_keys(&self) -> anyhow::Result<Vec<TipsetKey>> {
+        EthMappingsStore::get_tipset_keys(self.as_ref())
+    }
+
     fn delete(&self, keys: Vec<EthHash>) -> anyhow::Result<()> {
         EthMappingsStore::delete(self.as_ref(), keys)
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        EthMappingsStore::stats(self.as_ref())
+    }
 }
 
 pub struct DummyStore {}
@@ -166,9 +211,17 @@ impl EthMappingsStore for DummyStore {
         bail!(INDEXER_ERROR)
     }
 
+    fn get_tipset_keys(&self) -> anyhow::Result<Vec<TipsetKey>> {
+        bail!(INDEXER_ERROR)
+    }
+
     fn delete(&self, _keys: Vec<EthHash>) -> anyhow::Result<()> {
         bail!(INDEXER_ERROR)
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        bail!(INDEXER_ERROR)
+    }
 }
 
 pub trait EthMappingsStoreExt {
@@ -189,12 +242,90 @@ impl<T: ?Sized + EthMappingsStore> EthMappingsStoreExt for T {
     }
 }
 
+/// The tipsets whose first block is the same, with their Ethereum block hash. This is the
+/// value of a reverse tipset mapping, see [`tipset_eth_hash_key`].
+type TipsetEthHashes = Vec<(TipsetKey, EthHash)>;
+
+/// The key of the reverse mapping from `tsk` to its Ethereum block hash in the `EthMappings`
+/// store, i.e. the hash of its first block. It doesn't collide with the forward mapping,
+/// which is keyed by the hash of the tipset key itself, and its value decodes neither as a
+/// [`TipsetKey`] nor as a message mapping.
+pub fn tipset_eth_hash_key(tsk: &TipsetKey) -> EthHash {
+    (*tsk.to_cids().first()).into()
+}
+
+/// Reads the Ethereum block hash of `tsk` from its reverse mapping, see [`tipset_eth_hash_key`].
+pub fn read_tipset_eth_hash(
+    db: &(impl EthMappingsStore + ?Sized),
+    tsk: &TipsetKey,
+) -> anyhow::Result<Option<EthHash>> {
+    Ok(db
+        .read_obj::<TipsetEthHashes>(&tipset_eth_hash_key(tsk))?
+        .and_then(|entries| {
+            entries
+                .into_iter()
+                .find_map(|(key, hash)| (&key == tsk).then_some(hash))
+        }))
+}
+
+/// Serializes the read-modify-write updates of the reverse tipset mappings, which can happen
+/// concurrently, e.g., during a backfill while the node is syncing.
+static TIPSET_ETH_HASHES_WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Writes the reverse mapping from `tsk` to its Ethereum block hash, see [`tipset_eth_hash_key`].
+pub fn write_tipset_eth_hash(
+    db: &(impl EthMappingsStore + ?Sized),
+    tsk: &TipsetKey,
+    hash: &EthHash,
+) -> anyhow::Result<()> {
+    let key = tipset_eth_hash_key(tsk);
+    let _guard = TIPSET_ETH_HASHES_WRITE_LOCK.lock();
+    let mut entries = db.read_obj::<TipsetEthHashes>(&key)?.unwrap_or_default();
+    if entries.iter().any(|(key, _)| key == tsk) {
+        return Ok(());
+    }
+    entries.push((tsk.clone(), hash.clone()));
+    db.write_obj(&key, &entries)
+}
+
+/// Like [`write_tipset_eth_hash`] for each of `tipset_keys`, with the hashes of the forward
+/// mappings, i.e. of the tipset keys. The tipsets sharing a reverse mapping are written at once.
+pub fn write_tipset_eth_hashes(
+    db: &(impl EthMappingsStore + ?Sized),
+    tipset_keys: &[TipsetKey],
+) -> anyhow::Result<()> {
+    let mut by_key: HashMap<EthHash, Vec<&TipsetKey>> = HashMap::default();
+    for tsk in tipset_keys {
+        by_key
+            .entry(tipset_eth_hash_key(tsk))
+            .or_default()
+            .push(tsk);
+    }
+    let _guard = TIPSET_ETH_HASHES_WRITE_LOCK.lock();
+    for (key, tsks) in by_key {
+        let mut entries = db.read_obj::<TipsetEthHashes>(&key)?.unwrap_or_default();
+        let len = entries.len();
+        for tsk in tsks {
+            if !entries.iter().any(|(key, _)| key == tsk) {
+                entries.push((tsk.clone(), tsk.cid()?.into()));
+            }
+        }
+        if entries.len() != len {
+            db.write_obj(&key, &entries)?;
+        }
+    }
+    Ok(())
+}
+
 pub trait IndicesStore {
     fn read_bin(&self, key: &Cid) -> anyhow::Result<Option<Vec<u8>>>;
 
     fn write_bin(&self, key: &Cid, value: &[u8]) -> anyhow::Result<()>;
 
     fn exists(&self, key: &Cid) -> anyhow::Result<bool>;
+
+    /// Returns the number of entries and the approximate size of the store.
+    fn stats(&self) -> anyhow::Result<ColumnStats>;
 }
 
 impl<T: IndicesStore> IndicesStore for Arc<T> {
@@ -209,6 +340,10 @@ impl<T: IndicesStore> IndicesStore for Arc<T> {
     fn exists(&self, key: &Cid) -> anyhow::Result<bool> {
         IndicesStore::exists(self.as_ref(), key)
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        IndicesStore::stats(self.as_ref())
+    }
 }
 
 pub trait IndicesStoreExt {
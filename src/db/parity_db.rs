@@ -1,7 +1,7 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use super::{EthMappingsStore, IndicesStore, PersistentStore, SettingsStore};
+use super::{ColumnStats, EthMappingsStore, IndicesStore, PersistentStore, SettingsStore};
 use crate::blocks::TipsetKey;
 use crate::db::{DBStatistics, parity_db_config::ParityDbConfig};
 use crate::libp2p_bitswap::{BitswapStoreRead, BitswapStoreReadWrite};
@@ -142,6 +142,17 @@ impl ParityDb {
             .map_err(|e| anyhow!("error from column {column}: {e}"))
     }
 
+    /// Walks the whole column. Keys of hash-indexed columns are reported as
+    /// their stored hashes.
+    fn column_stats(&self, column: DbColumn) -> anyhow::Result<ColumnStats> {
+        let mut stats = ColumnStats::default();
+        self.db.iter_column_while(column as u8, |val| {
+            stats.record(val.key.len(), val.value.len());
+            true
+        })?;
+        Ok(stats)
+    }
+
     fn write_to_column<K, V>(&self, key: K, value: V, column: DbColumn) -> anyhow::Result<()>
     where
         K: AsRef<[u8]>,
@@ -221,12 +232,30 @@ impl EthMappingsStore for ParityDb {
         Ok(cids)
     }
 
+    fn get_tipset_keys(&self) -> anyhow::Result<Vec<TipsetKey>> {
+        let mut tipset_keys = Vec::new();
+
+        self.db
+            .iter_column_while(DbColumn::EthMappings as u8, |val| {
+                if let Ok(tsk) = fvm_ipld_encoding::from_slice::<TipsetKey>(&val.value) {
+                    tipset_keys.push(tsk);
+                }
+                true
+            })?;
+
+        Ok(tipset_keys)
+    }
+
     fn delete(&self, keys: Vec<EthHash>) -> anyhow::Result<()> {
         Ok(self.db.commit_changes(keys.into_iter().map(|key| {
             let bytes = key.0.as_bytes().to_vec();
             (DbColumn::EthMappings as u8, Operation::Dereference(bytes))
         }))?)
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        self.column_stats(DbColumn::EthMappings)
+    }
 }
 
 impl IndicesStore for ParityDb {
@@ -244,6 +273,10 @@ impl IndicesStore for ParityDb {
             .map(|size| size.is_some())
             .context("error checking if key exists")
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        self.column_stats(DbColumn::Indices)
+    }
 }
 
 fn has_subscribers<T>(tx: &tokio::sync::broadcast::Sender<T>) -> bool {
@@ -1,7 +1,10 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use crate::db::{SettingsStore, SettingsStoreExt};
+use crate::db::{EthMappingsStore, IndicesStore, SettingsStore, SettingsStoreExt};
+use crate::rpc::eth::types::EthHash;
+use crate::utils::multihash::prelude::*;
+use cid::Cid;
 
 pub fn write_bin<DB>(db: &DB)
 where
@@ -64,3 +67,29 @@ where
     assert!(db.read_obj::<i32>(key).unwrap().is_none());
     assert!(db.require_obj::<i32>(key).is_err());
 }
+
+pub fn column_stats<DB>(db: &DB)
+where
+    DB: EthMappingsStore + IndicesStore,
+{
+    assert_eq!(EthMappingsStore::stats(db).unwrap().entries, 0);
+    assert_eq!(IndicesStore::stats(db).unwrap().entries, 0);
+
+    for i in 0..4u8 {
+        let cid = Cid::new_v1(
+            fvm_ipld_encoding::DAG_CBOR,
+            MultihashCode::Blake2b256.digest(&[i]),
+        );
+        EthMappingsStore::write_bin(db, &EthHash::from(cid), &[i; 10]).unwrap();
+        if i % 2 == 0 {
+            IndicesStore::write_bin(db, &cid, &[i; 20]).unwrap();
+        }
+    }
+
+    let eth_mappings = EthMappingsStore::stats(db).unwrap();
+    assert_eq!(eth_mappings.entries, 4);
+    assert!(eth_mappings.approximate_size_bytes >= 4 * 10);
+    let indices = IndicesStore::stats(db).unwrap();
+    assert_eq!(indices.entries, 2);
+    assert!(indices.approximate_size_bytes >= 2 * 20);
+}
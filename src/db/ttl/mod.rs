@@ -1,15 +1,19 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use crate::blocks::CachingBlockHeader;
+use crate::chain::ChainStore;
 use crate::eth::EthChainId;
 use crate::message::ChainMessage;
 use crate::rpc::eth::{eth_tx_from_signed_eth_message, types::EthHash};
 use crate::shim::clock::{ChainEpoch, EPOCH_DURATION_SECONDS};
+use ahash::HashSet;
 use fvm_ipld_blockstore::Blockstore;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::time::Duration;
 
-use super::EthMappingsStore;
+use super::{EthMappingsStore, tipset_eth_hash_key};
 
 pub struct EthMappingCollector<DB> {
     db: Arc<DB>,
@@ -79,6 +83,97 @@ impl<DB: Blockstore + EthMappingsStore + Sync + Send + 'static> EthMappingCollec
     }
 }
 
+/// Number of tipset mappings deleted at once by [`prune_tipset_mappings`].
+pub const DEFAULT_PRUNE_BATCH_SIZE: NonZeroUsize = NonZeroUsize::new(10_000).unwrap();
+
+/// How often [`run_tipset_mappings_pruner`] prunes the tipset mappings.
+const TIPSET_MAPPINGS_PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Outcome of [`prune_tipset_mappings`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Mappings within the retention window.
+    pub kept: usize,
+    /// Mappings that were deleted, including the [unresolved](Self::unresolved) ones.
+    pub pruned: usize,
+    /// Mappings whose tipset could not be loaded, e.g. because it was garbage collected.
+    /// They are pruned, as they can't be served anyway.
+    pub unresolved: usize,
+}
+
+/// Deletes the tipset mappings, see [`ChainStore::put_tipset_key`], whose tipset is
+/// more than `retention_epochs` older than `head_epoch`, `batch_size` at a time. The
+/// message mappings are left to [`EthMappingCollector`]. Their reverse mappings, see
+/// [`ChainStore::get_eth_hash_by_tipset_key`], are deleted along with them: those are
+/// shared by tipsets with the same first block, hence at the same epoch.
+pub fn prune_tipset_mappings<DB: Blockstore + EthMappingsStore>(
+    db: &DB,
+    head_epoch: ChainEpoch,
+    retention_epochs: ChainEpoch,
+    batch_size: NonZeroUsize,
+) -> anyhow::Result<PruneReport> {
+    let cutoff = head_epoch.saturating_sub(retention_epochs);
+    let mut report = PruneReport::default();
+    let mut prunable: Vec<EthHash> = vec![];
+    let mut reverse_keys = HashSet::default();
+    for tsk in db.get_tipset_keys()? {
+        // The blocks of a tipset are at the same epoch
+        match CachingBlockHeader::load(db, *tsk.to_cids().first()) {
+            Ok(Some(header)) if header.epoch >= cutoff => {
+                report.kept += 1;
+                continue;
+            }
+            Ok(Some(_)) => {}
+            Ok(None) | Err(_) => report.unresolved += 1,
+        }
+        prunable.push(tsk.cid()?.into());
+        reverse_keys.insert(tipset_eth_hash_key(&tsk));
+    }
+    report.pruned = prunable.len();
+    prunable.extend(reverse_keys);
+    for batch in prunable.chunks(batch_size.get()) {
+        db.delete(batch.to_vec())?;
+    }
+
+    if report.unresolved > 0 {
+        tracing::warn!(
+            "Pruned {} tipset mappings whose tipset could not be resolved",
+            report.unresolved
+        );
+    }
+    tracing::debug!(
+        "Pruned {} tipset mappings older than epoch {cutoff}, kept {}",
+        report.pruned,
+        report.kept
+    );
+    Ok(report)
+}
+
+/// Runs [`prune_tipset_mappings`] every [`TIPSET_MAPPINGS_PRUNE_INTERVAL`], relative to
+/// the chain head at the time.
+pub async fn run_tipset_mappings_pruner<DB>(
+    chain_store: Arc<ChainStore<DB>>,
+    retention_epochs: ChainEpoch,
+) -> anyhow::Result<()>
+where
+    DB: Blockstore + EthMappingsStore + Sync + Send + 'static,
+{
+    loop {
+        tokio::time::sleep(TIPSET_MAPPINGS_PRUNE_INTERVAL).await;
+
+        let chain_store = chain_store.clone();
+        tokio::task::spawn_blocking(move || {
+            prune_tipset_mappings(
+                chain_store.db.as_ref(),
+                chain_store.heaviest_tipset().epoch(),
+                retention_epochs,
+                DEFAULT_PRUNE_BATCH_SIZE,
+            )
+        })
+        .await??;
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::convert::TryFrom;
@@ -90,6 +185,7 @@ mod test {
     use crate::db::EthMappingsStore;
     use crate::db::EthMappingsStoreExt;
     use crate::db::MemoryDB;
+    use crate::db::{read_tipset_eth_hash, write_tipset_eth_hash};
     use crate::networks::calibnet::ETH_CHAIN_ID;
     use crate::test_utils::construct_eth_messages;
 
@@ -170,4 +266,95 @@ mod test {
         assert!(!blockstore.exists(&key0).unwrap());
         assert!(blockstore.exists(&key1).unwrap());
     }
+
+    #[test]
+    fn test_prune_tipset_mappings() {
+        use crate::blocks::{RawBlockHeader, TipsetKey};
+        use crate::db::tests::db_utils::parity::TempParityDB;
+
+        let db = TempParityDB::new();
+        let db = db.as_ref();
+        // Both directions, as in `ChainStore::put_tipset_key`
+        let put_tipset_key = |tsk: &TipsetKey| {
+            let hash = tsk.cid().unwrap().into();
+            db.write_obj(&hash, tsk).unwrap();
+            write_tipset_eth_hash(db, tsk, &hash).unwrap();
+        };
+
+        // A chain from epoch 0 to 9, with a mapping per tipset
+        let mut parents = RawBlockHeader::default().parents;
+        let mut tipset_keys = vec![];
+        for epoch in 0..10 {
+            let header = CachingBlockHeader::new(RawBlockHeader {
+                epoch,
+                parents: parents.clone(),
+                ..Default::default()
+            });
+            crate::chain::persist_objects(db, std::iter::once(&header)).unwrap();
+            parents = TipsetKey::from(nunny::vec![*header.cid()]);
+            put_tipset_key(&parents);
+            tipset_keys.push(parents.clone());
+        }
+        // A mapping to a tipset that isn't in the store
+        let missing = CachingBlockHeader::new(RawBlockHeader {
+            epoch: 9,
+            timestamp: 1,
+            ..Default::default()
+        });
+        let missing = TipsetKey::from(nunny::vec![*missing.cid()]);
+        put_tipset_key(&missing);
+        // A message mapping, left to the collector
+        let (_, secp) = construct_eth_messages(0);
+        let (_, tx) = eth_tx_from_signed_eth_message(&secp, ETH_CHAIN_ID).unwrap();
+        let message_key = tx.eth_hash().unwrap().into();
+        db.write_obj(&message_key, &(secp.cid(), 0_u64)).unwrap();
+
+        // The reverse mappings are told apart from the forward ones
+        assert_eq!(db.get_tipset_keys().unwrap().len(), 11);
+        assert_eq!(db.get_message_cids().unwrap().len(), 1);
+
+        // Whether `tsk` is mapped, checking that both directions agree
+        let is_mapped = |tsk: &TipsetKey| {
+            let hash = tsk.cid().unwrap().into();
+            let forward = db.read_obj::<TipsetKey>(&hash).unwrap();
+            let reverse = read_tipset_eth_hash(db, tsk).unwrap();
+            match (forward, reverse) {
+                (Some(forward), Some(reverse)) => {
+                    assert_eq!(&forward, tsk);
+                    assert_eq!(reverse, hash);
+                    true
+                }
+                (None, None) => false,
+                (forward, reverse) => panic!("inconsistent mappings: {forward:?}, {reverse:?}"),
+            }
+        };
+        assert!(tipset_keys.iter().all(is_mapped));
+
+        // Keeps epochs 5 to 9
+        let report = prune_tipset_mappings(db, 9, 4, NonZeroUsize::new(2).unwrap()).unwrap();
+        assert_eq!(
+            report,
+            PruneReport {
+                kept: 5,
+                pruned: 6,
+                unresolved: 1,
+            }
+        );
+        assert_eq!(
+            tipset_keys.iter().map(is_mapped).collect::<Vec<_>>(),
+            [[false; 5], [true; 5]].concat()
+        );
+        assert!(!is_mapped(&missing));
+        assert!(db.exists(&message_key).unwrap());
+        assert_eq!(db.get_tipset_keys().unwrap().len(), 5);
+
+        // Nothing left to prune
+        assert_eq!(
+            prune_tipset_mappings(db, 9, 4, DEFAULT_PRUNE_BATCH_SIZE).unwrap(),
+            PruneReport {
+                kept: 5,
+                ..Default::default()
+            }
+        );
+    }
 }
@@ -0,0 +1,324 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Portable archives of F3 finality certificates, to seed fresh nodes without
+//! catching up certificate-by-certificate, or to inspect what a node has seen.
+//!
+//! An archive is a sequence of varint frames (see [`crate::db::car::plain`]):
+//! a DAG-CBOR `[magic, version]` header frame, followed by one frame per
+//! certificate, holding its lotus JSON, in ascending instance order.
+//!
+//! The certificates themselves live in the sidecar's datastore under the F3
+//! root, and are read and written through the sidecar.
+
+use crate::rpc::f3::FinalityCertificate;
+use anyhow::Context as _;
+use integer_encoding::{VarIntReader as _, VarIntWriter as _};
+use std::io::{self, Read, Write};
+use std::ops::RangeInclusive;
+
+const ARCHIVE_MAGIC: &str = "forest-f3-certificates";
+const ARCHIVE_VERSION: u64 = 1;
+/// Certificates are a few KiB, anything much larger is corrupted.
+const MAX_FRAME_LENGTH: usize = 16 << 20;
+
+/// Checks that `next` directly follows `prev`: consecutive instances, and `next`
+/// finalizes on top of the head finalized by `prev`.
+pub fn check_continuity(
+    prev: &FinalityCertificate,
+    next: &FinalityCertificate,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        prev.instance.checked_add(1) == Some(next.instance),
+        "certificate for instance {} does not follow instance {}",
+        next.instance,
+        prev.instance
+    );
+    let (prev_head, next_base) = (prev.chain_head(), next.chain_base());
+    anyhow::ensure!(
+        prev_head.key == next_base.key && prev_head.epoch == next_base.epoch,
+        "EC chain of instance {} does not start at the head finalized by instance {}",
+        next.instance,
+        prev.instance
+    );
+    Ok(())
+}
+
+/// Tracks the instances seen so far, rejecting gaps and forks.
+#[derive(Default)]
+struct Continuity {
+    first: Option<u64>,
+    prev: Option<FinalityCertificate>,
+}
+
+impl Continuity {
+    fn push(&mut self, certificate: &FinalityCertificate) -> anyhow::Result<()> {
+        if let Some(prev) = &self.prev {
+            check_continuity(prev, certificate)?;
+        }
+        self.first.get_or_insert(certificate.instance);
+        self.prev = Some(certificate.clone());
+        Ok(())
+    }
+
+    fn range(&self) -> Option<RangeInclusive<u64>> {
+        Some(self.first?..=self.prev.as_ref()?.instance)
+    }
+}
+
+/// Writes `certificates`, which must be contiguous, as an archive. Returns the
+/// range of instances covered, if any.
+pub fn write_archive(
+    certificates: impl IntoIterator<Item = anyhow::Result<FinalityCertificate>>,
+    mut writer: impl Write,
+) -> anyhow::Result<Option<RangeInclusive<u64>>> {
+    write_frame(
+        &mut writer,
+        &fvm_ipld_encoding::to_vec(&(ARCHIVE_MAGIC, ARCHIVE_VERSION))?,
+    )?;
+    let mut continuity = Continuity::default();
+    for certificate in certificates {
+        let certificate = certificate?;
+        continuity.push(&certificate)?;
+        write_frame(&mut writer, &serde_json::to_vec(&certificate)?)?;
+    }
+    writer.flush()?;
+    Ok(continuity.range())
+}
+
+/// Reads the certificates of an archive written by [`write_archive`], checking
+/// their continuity.
+pub fn read_archive(
+    mut reader: impl Read,
+) -> anyhow::Result<impl Iterator<Item = anyhow::Result<FinalityCertificate>>> {
+    let header = read_frame(&mut reader)?.context("F3 certificate archive is empty")?;
+    let (magic, version): (String, u64) =
+        fvm_ipld_encoding::from_slice(&header).context("not an F3 certificate archive")?;
+    anyhow::ensure!(magic == ARCHIVE_MAGIC, "not an F3 certificate archive");
+    anyhow::ensure!(
+        version == ARCHIVE_VERSION,
+        "unsupported F3 certificate archive version {version}"
+    );
+    let mut continuity = Continuity::default();
+    Ok(std::iter::from_fn(move || {
+        read_frame(&mut reader)
+            .map_err(anyhow::Error::from)
+            .transpose()
+            .map(|frame| -> anyhow::Result<FinalityCertificate> {
+                let certificate: FinalityCertificate = serde_json::from_slice(&frame?)?;
+                continuity.push(&certificate)?;
+                Ok(certificate)
+            })
+    }))
+}
+
+fn write_frame(mut writer: impl Write, body: &[u8]) -> io::Result<()> {
+    writer.write_varint(body.len())?;
+    writer.write_all(body)
+}
+
+/// [`Ok(None)`] on EOF
+fn read_frame(mut reader: impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut byte = [0u8; 1]; // detect EOF
+    if reader.read(&mut byte)? == 0 {
+        return Ok(None);
+    }
+    let length: usize = (&byte[..]).chain(&mut reader).read_varint()?;
+    if length > MAX_FRAME_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {length} bytes exceeds the limit of {MAX_FRAME_LENGTH} bytes"),
+        ));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+/// Dumps the certificates stored under `f3_root` for `network` (the raw network
+/// name, e.g. `calibrationnet`) to `writer`, and returns the range of instances
+/// covered. The store is opened read-only, and is locked while the sidecar runs,
+/// so the sidecar must be stopped.
+pub fn export_certificates(
+    f3_root: &std::path::Path,
+    network: &str,
+    writer: impl Write,
+) -> anyhow::Result<Option<RangeInclusive<u64>>> {
+    let dump = tempfile::NamedTempFile::new()?;
+    sidecar::export_certificates(f3_root, network, dump.path())?;
+    let certificates = io::BufRead::lines(io::BufReader::new(dump.reopen()?))
+        .map(|line| -> anyhow::Result<FinalityCertificate> { Ok(serde_json::from_str(&line?)?) });
+    let range = write_archive(certificates, writer)?;
+    match &range {
+        Some(range) => tracing::info!("Exported F3 certificates for instances {range:?}"),
+        None => tracing::info!("No F3 certificates to export"),
+    }
+    Ok(range)
+}
+
+/// Appends the certificates of an archive written by [`export_certificates`] to
+/// the store under `f3_root`, skipping the ones it already has, and returns the
+/// range of instances in the archive. The sidecar must be stopped, and must have
+/// been run once for the store to exist.
+pub fn import_certificates(
+    f3_root: &std::path::Path,
+    network: &str,
+    reader: impl Read,
+) -> anyhow::Result<Option<RangeInclusive<u64>>> {
+    let mut dump = io::BufWriter::new(tempfile::NamedTempFile::new()?);
+    let mut range: Option<RangeInclusive<u64>> = None;
+    for certificate in read_archive(reader)? {
+        let certificate = certificate?;
+        let first = range.as_ref().map_or(certificate.instance, |r| *r.start());
+        range = Some(first..=certificate.instance);
+        serde_json::to_writer(&mut dump, &certificate)?;
+        dump.write_all(b"\n")?;
+    }
+    let dump = dump.into_inner().map_err(io::IntoInnerError::into_error)?;
+    sidecar::import_certificates(f3_root, network, dump.path())?;
+    match &range {
+        Some(range) => tracing::info!("Imported F3 certificates for instances {range:?}"),
+        None => tracing::info!("No F3 certificates to import"),
+    }
+    Ok(range)
+}
+
+#[cfg(all(f3sidecar, not(feature = "no-f3-sidecar")))]
+mod sidecar {
+    use super::super::go_ffi::*;
+    use std::path::Path;
+
+    pub fn export_certificates(f3_root: &Path, network: &str, path: &Path) -> anyhow::Result<()> {
+        check(GoF3NodeImpl::export_certificates(
+            f3_root.display().to_string(),
+            network.into(),
+            path.display().to_string(),
+        ))
+    }
+
+    pub fn import_certificates(f3_root: &Path, network: &str, path: &Path) -> anyhow::Result<()> {
+        check(GoF3NodeImpl::import_certificates(
+            f3_root.display().to_string(),
+            network.into(),
+            path.display().to_string(),
+        ))
+    }
+
+    fn check(error: String) -> anyhow::Result<()> {
+        anyhow::ensure!(error.is_empty(), "{error}");
+        Ok(())
+    }
+}
+
+#[cfg(not(all(f3sidecar, not(feature = "no-f3-sidecar"))))]
+mod sidecar {
+    use std::path::Path;
+
+    const NOT_COMPILED: &str = "the Forest binary is not compiled with f3-sidecar Go lib, which owns the certificate store";
+
+    pub fn export_certificates(_: &Path, _: &str, _: &Path) -> anyhow::Result<()> {
+        anyhow::bail!(NOT_COMPILED)
+    }
+
+    pub fn import_certificates(_: &Path, _: &str, _: &Path) -> anyhow::Result<()> {
+        anyhow::bail!(NOT_COMPILED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::TipsetKey;
+    use crate::rpc::f3::{ECTipSet, SupplementalData};
+    use crate::utils::multihash::prelude::*;
+    use cid::Cid;
+    use fil_actors_shared::fvm_ipld_bitfield::BitField;
+    use nunny::vec as nonempty;
+
+    fn tipset(epoch: i64) -> ECTipSet {
+        let cid = |data: String| {
+            Cid::new_v1(
+                fvm_ipld_encoding::DAG_CBOR,
+                MultihashCode::Blake2b256.digest(data.as_bytes()),
+            )
+        };
+        ECTipSet {
+            key: TipsetKey::from(nonempty![cid(format!("block {epoch}"))]),
+            epoch,
+            power_table: cid(format!("power table {epoch}")),
+            commitments: vec![0; 32],
+        }
+    }
+
+    /// Instance `i` finalizes epochs `10 * i..=10 * (i + 1)`.
+    fn certificate(instance: u64) -> FinalityCertificate {
+        let base = 10 * instance as i64;
+        FinalityCertificate {
+            instance,
+            ec_chain: nunny::vec![tipset(base), tipset(base + 5), tipset(base + 10)],
+            supplemental_data: SupplementalData {
+                commitments: vec![0; 32],
+                power_table: tipset(base + 10).power_table,
+            },
+            signers: BitField::try_from_bits([0, 2]).unwrap(),
+            signature: vec![instance as u8; 96],
+            power_table_delta: vec![],
+        }
+    }
+
+    #[test]
+    fn archive_roundtrip() {
+        let certificates = (5..=9).map(certificate).collect::<Vec<_>>();
+        let mut archive = vec![];
+        let range = write_archive(certificates.iter().cloned().map(Ok), &mut archive).unwrap();
+        assert_eq!(range, Some(5..=9));
+
+        let read = read_archive(archive.as_slice())
+            .unwrap()
+            .collect::<anyhow::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(read, certificates);
+    }
+
+    #[test]
+    fn archive_empty() {
+        let mut archive = vec![];
+        assert_eq!(write_archive([], &mut archive).unwrap(), None);
+        assert_eq!(read_archive(archive.as_slice()).unwrap().count(), 0);
+        assert!(read_archive(&b""[..]).is_err());
+    }
+
+    #[test]
+    fn archive_rejects_discontinuity() {
+        // A gap
+        let gap = [certificate(1), certificate(3)];
+        assert!(write_archive(gap.iter().cloned().map(Ok), vec![]).is_err());
+
+        // A fork
+        let mut fork = certificate(2);
+        fork.ec_chain = nunny::vec![tipset(11), tipset(25), tipset(30)];
+        assert!(check_continuity(&certificate(1), &fork).is_err());
+        assert!(check_continuity(&certificate(1), &certificate(2)).is_ok());
+
+        // An archive tampered with after writing
+        let mut archive = vec![];
+        write_archive([Ok(certificate(1))], &mut archive).unwrap();
+        write_frame(&mut archive, &serde_json::to_vec(&fork).unwrap()).unwrap();
+        let read = read_archive(archive.as_slice())
+            .unwrap()
+            .collect::<anyhow::Result<Vec<_>>>();
+        assert!(read.is_err());
+    }
+
+    #[test]
+    fn import_rejects_discontinuity_before_writing() {
+        let f3_root = tempfile::tempdir().unwrap();
+        let mut archive = vec![];
+        write_archive([Ok(certificate(1))], &mut archive).unwrap();
+        write_frame(&mut archive, &serde_json::to_vec(&certificate(3)).unwrap()).unwrap();
+        let error =
+            import_certificates(f3_root.path(), "calibrationnet", archive.as_slice()).unwrap_err();
+        assert!(error.to_string().contains("does not follow"), "{error}");
+        assert!(std::fs::read_dir(f3_root.path()).unwrap().next().is_none());
+    }
+}
@@ -17,5 +17,14 @@ pub trait GoF3Node {
         bootstrap_epoch: i64,
         finality: i64,
         f3_root: String,
+        log_level: String,
+        log_format: String,
+        log_file: String,
     ) -> bool;
+
+    fn stop() -> bool;
+
+    fn export_certificates(f3_root: String, network: String, path: String) -> String;
+
+    fn import_certificates(f3_root: String, network: String, path: String) -> String;
 }
@@ -0,0 +1,382 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Prometheus metrics of F3 progress.
+//!
+//! The sidecar is polled in the background, and the gauges hold the last known
+//! values, so that scraping never waits on the sidecar. The poller backs off
+//! while the sidecar is unreachable.
+
+use super::supervisor::{self, F3SidecarRunState, F3SidecarStatus};
+use crate::rpc::f3::{
+    F3GetLatestCertificate, F3GetProgress, F3InstanceProgress, FinalityCertificate,
+};
+use crate::shim::clock::ChainEpoch;
+use async_trait::async_trait;
+use prometheus_client::metrics::{counter::Counter, gauge::Gauge};
+use prometheus_client::registry::Registry;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// How often the sidecar is polled while it is up.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(15);
+/// Upper bound of the polling interval while the sidecar is down.
+pub const MAX_POLL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub static F3_METRICS: LazyLock<F3Metrics> = LazyLock::new(|| {
+    let metrics = F3Metrics::default();
+    metrics.register(&mut crate::metrics::default_registry());
+    metrics
+});
+
+#[derive(Debug, Default)]
+pub struct F3Metrics {
+    pub latest_certified_epoch: Gauge,
+    pub latest_certified_instance: Gauge,
+    pub current_instance: Gauge,
+    pub current_round: Gauge,
+    pub lag_epochs: Gauge,
+    pub sidecar_up: Gauge,
+    pub sidecar_uptime_seconds: Gauge,
+    pub sidecar_restarts: Counter,
+}
+
+impl F3Metrics {
+    fn register(&self, registry: &mut Registry) {
+        let registry = registry.sub_registry_with_prefix("f3");
+        registry.register(
+            "latest_certified_epoch",
+            "Head epoch of the latest F3 finality certificate",
+            self.latest_certified_epoch.clone(),
+        );
+        registry.register(
+            "latest_certified_instance",
+            "Instance of the latest F3 finality certificate",
+            self.latest_certified_instance.clone(),
+        );
+        registry.register(
+            "current_instance",
+            "F3 instance in progress",
+            self.current_instance.clone(),
+        );
+        registry.register(
+            "current_round",
+            "Round of the F3 instance in progress",
+            self.current_round.clone(),
+        );
+        registry.register(
+            "lag_epochs",
+            "Number of epochs between the chain head and the latest F3 finality certificate",
+            self.lag_epochs.clone(),
+        );
+        registry.register(
+            "sidecar_up",
+            "Whether the F3 sidecar is running and responding",
+            self.sidecar_up.clone(),
+        );
+        registry.register(
+            "sidecar_uptime_seconds",
+            "Time since the F3 sidecar was last (re)started",
+            self.sidecar_uptime_seconds.clone(),
+        );
+        registry.register(
+            "sidecar_restarts",
+            "Number of restarts of the F3 sidecar since the node started",
+            self.sidecar_restarts.clone(),
+        );
+    }
+
+    fn update_supervisor(&self, status: &F3SidecarStatus, uptime: Option<Duration>) {
+        // The supervisor counts restarts, catch up with it
+        let restarts = u64::from(status.restarts);
+        let counted = self.sidecar_restarts.get();
+        if restarts > counted {
+            self.sidecar_restarts.inc_by(restarts - counted);
+        }
+        self.sidecar_uptime_seconds
+            .set(uptime.map_or(0, |uptime| uptime.as_secs() as i64));
+    }
+
+    fn update_progress(
+        &self,
+        certificate: &FinalityCertificate,
+        progress: &F3InstanceProgress,
+        head_epoch: ChainEpoch,
+    ) {
+        self.latest_certified_instance
+            .set(certificate.instance as i64);
+        let head = certificate.chain_head();
+        self.latest_certified_epoch.set(head.epoch);
+        self.lag_epochs
+            .set(head_epoch.saturating_sub(head.epoch).max(0));
+        self.current_instance.set(progress.id as i64);
+        self.current_round.set(progress.round as i64);
+    }
+}
+
+/// Where F3 progress is read from, the sidecar RPC server in production.
+#[async_trait]
+pub trait F3ProgressSource: Send + Sync {
+    async fn latest_certificate(&self) -> anyhow::Result<FinalityCertificate>;
+    async fn progress(&self) -> anyhow::Result<F3InstanceProgress>;
+}
+
+/// The sidecar RPC server, at [`crate::rpc::f3::get_f3_rpc_endpoint`].
+pub struct SidecarRpc;
+
+#[async_trait]
+impl F3ProgressSource for SidecarRpc {
+    async fn latest_certificate(&self) -> anyhow::Result<FinalityCertificate> {
+        F3GetLatestCertificate::run().await
+    }
+
+    async fn progress(&self) -> anyhow::Result<F3InstanceProgress> {
+        F3GetProgress::run().await
+    }
+}
+
+/// Polls `source` forever, updating `metrics`. Each poll is followed by
+/// `interval`, doubled on every consecutive failure up to `max_interval`.
+pub async fn poll(
+    metrics: &F3Metrics,
+    source: &impl F3ProgressSource,
+    head_epoch: impl Fn() -> ChainEpoch,
+    interval: Duration,
+    max_interval: Duration,
+) {
+    let mut consecutive_failures = 0_u32;
+    loop {
+        let supervised = supervisor::F3_SIDECAR
+            .get()
+            .map(|handle| (handle.status(), handle.uptime()));
+        match poll_once(metrics, source, head_epoch(), supervised).await {
+            Ok(()) => consecutive_failures = 0,
+            Err(e) => {
+                if consecutive_failures == 0 {
+                    tracing::debug!("Failed to poll F3 progress: {e:#}");
+                }
+                consecutive_failures = consecutive_failures.saturating_add(1);
+            }
+        }
+        tokio::time::sleep(poll_delay(consecutive_failures, interval, max_interval)).await;
+    }
+}
+
+fn poll_delay(consecutive_failures: u32, interval: Duration, max_interval: Duration) -> Duration {
+    interval
+        .saturating_mul(2_u32.saturating_pow(consecutive_failures))
+        .min(max_interval)
+        .max(interval)
+}
+
+/// `supervised` is the status and uptime of the sidecar, see [`supervisor::F3Handle`], or
+/// [`None`] if it isn't supervised by this node.
+async fn poll_once(
+    metrics: &F3Metrics,
+    source: &impl F3ProgressSource,
+    head_epoch: ChainEpoch,
+    supervised: Option<(F3SidecarStatus, Option<Duration>)>,
+) -> anyhow::Result<()> {
+    let is_supervised = supervised.is_some();
+    let (status, uptime) = supervised.unwrap_or_default();
+    metrics.update_supervisor(&status, uptime);
+    // Don't bother the RPC server while the supervisor knows the sidecar is down
+    if is_supervised && status.state != F3SidecarRunState::Running {
+        metrics.sidecar_up.set(0);
+        anyhow::bail!("F3 sidecar is {}", status.state);
+    }
+    let result = async {
+        let certificate = source.latest_certificate().await?;
+        let progress = source.progress().await?;
+        anyhow::Ok((certificate, progress))
+    }
+    .await;
+    match result {
+        Ok((certificate, progress)) => {
+            metrics.update_progress(&certificate, &progress, head_epoch);
+            metrics.sidecar_up.set(1);
+            Ok(())
+        }
+        Err(e) => {
+            metrics.sidecar_up.set(0);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::TipsetKey;
+    use crate::rpc::f3::{ECTipSet, SupplementalData};
+    use crate::utils::multihash::prelude::*;
+    use anyhow::Context as _;
+    use cid::Cid;
+    use parking_lot::Mutex;
+
+    /// Serves the progress it holds, or fails if it holds none.
+    #[derive(Default)]
+    struct MockSidecar(Mutex<Option<(FinalityCertificate, F3InstanceProgress)>>);
+
+    #[async_trait]
+    impl F3ProgressSource for MockSidecar {
+        async fn latest_certificate(&self) -> anyhow::Result<FinalityCertificate> {
+            let state = self.0.lock();
+            Ok(state.as_ref().context("sidecar is down")?.0.clone())
+        }
+
+        async fn progress(&self) -> anyhow::Result<F3InstanceProgress> {
+            let state = self.0.lock();
+            Ok(state.as_ref().context("sidecar is down")?.1.clone())
+        }
+    }
+
+    fn tipset(epoch: ChainEpoch) -> ECTipSet {
+        let cid = Cid::new_v1(
+            fvm_ipld_encoding::DAG_CBOR,
+            MultihashCode::Blake2b256.digest(&epoch.to_be_bytes()),
+        );
+        ECTipSet {
+            key: TipsetKey::from(nunny::vec![cid]),
+            epoch,
+            power_table: cid,
+            commitments: vec![0; 32],
+        }
+    }
+
+    fn certificate(instance: u64, head_epoch: ChainEpoch) -> FinalityCertificate {
+        FinalityCertificate {
+            instance,
+            ec_chain: nunny::vec![tipset(head_epoch - 1), tipset(head_epoch)],
+            supplemental_data: SupplementalData {
+                commitments: vec![0; 32],
+                power_table: tipset(head_epoch).power_table,
+            },
+            signers: Default::default(),
+            signature: vec![],
+            power_table_delta: vec![],
+        }
+    }
+
+    fn progress(id: u64, round: u64) -> F3InstanceProgress {
+        F3InstanceProgress {
+            id,
+            round,
+            phase: 0,
+            input: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_once() {
+        let metrics = F3Metrics::default();
+        let sidecar = MockSidecar::default();
+
+        // Down
+        assert!(poll_once(&metrics, &sidecar, 1000, None).await.is_err());
+        assert_eq!(metrics.sidecar_up.get(), 0);
+        assert_eq!(metrics.latest_certified_epoch.get(), 0);
+
+        // Up
+        *sidecar.0.lock() = Some((certificate(42, 960), progress(43, 2)));
+        poll_once(&metrics, &sidecar, 1000, None).await.unwrap();
+        assert_eq!(metrics.sidecar_up.get(), 1);
+        assert_eq!(metrics.latest_certified_epoch.get(), 960);
+        assert_eq!(metrics.latest_certified_instance.get(), 42);
+        assert_eq!(metrics.current_instance.get(), 43);
+        assert_eq!(metrics.current_round.get(), 2);
+        assert_eq!(metrics.lag_epochs.get(), 40);
+
+        // Down again, the last known progress is kept
+        *sidecar.0.lock() = None;
+        assert!(poll_once(&metrics, &sidecar, 1010, None).await.is_err());
+        assert_eq!(metrics.sidecar_up.get(), 0);
+        assert_eq!(metrics.latest_certified_epoch.get(), 960);
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_supervised() {
+        let metrics = F3Metrics::default();
+        let sidecar = MockSidecar::default();
+        *sidecar.0.lock() = Some((certificate(42, 960), progress(43, 2)));
+        let status = |state| F3SidecarStatus {
+            state,
+            restarts: 1,
+            last_error: None,
+            launch_epoch: None,
+        };
+
+        // Running
+        let supervised = (
+            status(F3SidecarRunState::Running),
+            Some(Duration::from_secs(90)),
+        );
+        poll_once(&metrics, &sidecar, 1000, Some(supervised))
+            .await
+            .unwrap();
+        assert_eq!(metrics.sidecar_up.get(), 1);
+        assert_eq!(metrics.sidecar_restarts.get(), 1);
+        assert_eq!(metrics.sidecar_uptime_seconds.get(), 90);
+        assert_eq!(metrics.latest_certified_epoch.get(), 960);
+
+        // Known to be down, even though the RPC server would respond
+        *sidecar.0.lock() = Some((certificate(43, 970), progress(44, 0)));
+        let supervised = (status(F3SidecarRunState::Restarting), None);
+        assert!(
+            poll_once(&metrics, &sidecar, 1010, Some(supervised))
+                .await
+                .is_err()
+        );
+        assert_eq!(metrics.sidecar_up.get(), 0);
+        assert_eq!(metrics.sidecar_uptime_seconds.get(), 0);
+        assert_eq!(metrics.latest_certified_epoch.get(), 960);
+    }
+
+    #[test]
+    fn test_update_supervisor() {
+        let metrics = F3Metrics::default();
+        let status = F3SidecarStatus {
+            state: F3SidecarRunState::Running,
+            restarts: 3,
+            last_error: None,
+            launch_epoch: None,
+        };
+        metrics.update_supervisor(&status, Some(Duration::from_secs(90)));
+        assert_eq!(metrics.sidecar_restarts.get(), 3);
+        assert_eq!(metrics.sidecar_uptime_seconds.get(), 90);
+        metrics.update_supervisor(&status, None);
+        assert_eq!(metrics.sidecar_restarts.get(), 3);
+        assert_eq!(metrics.sidecar_uptime_seconds.get(), 0);
+        let status = F3SidecarStatus {
+            restarts: 5,
+            ..status
+        };
+        metrics.update_supervisor(&status, None);
+        assert_eq!(metrics.sidecar_restarts.get(), 5);
+    }
+
+    #[test]
+    fn test_poll_delay() {
+        let interval = Duration::from_secs(15);
+        let max = Duration::from_secs(60);
+        let delays = (0..5)
+            .map(|i| poll_delay(i, interval, max).as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [15, 30, 60, 60, 60]);
+        assert_eq!(poll_delay(u32::MAX, interval, max), max);
+    }
+
+    #[test]
+    fn test_register() {
+        let metrics = F3Metrics::default();
+        metrics.latest_certified_epoch.set(960);
+        let mut registry = Registry::default();
+        metrics.register(&mut registry);
+        let mut encoded = String::new();
+        prometheus_client::encoding::text::encode(&mut encoded, &registry).unwrap();
+        assert!(
+            encoded.contains("f3_latest_certified_epoch 960"),
+            "{encoded}"
+        );
+    }
+}
@@ -7,10 +7,200 @@
 mod go_ffi;
 #[cfg(all(f3sidecar, not(feature = "no-f3-sidecar")))]
 use go_ffi::*;
+mod certs;
+pub mod metrics;
+mod root_marker;
+mod sidecar_log;
+pub mod supervisor;
+
+pub use certs::{export_certificates, import_certificates};
+pub use root_marker::{F3RootMarker, check_f3_root};
+pub use sidecar_log::{F3LogFormat, F3LogLevel};
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
 
 use cid::Cid;
+use fvm_ipld_blockstore::Blockstore;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+
+use crate::{
+    chain::index::ResolveNullTipset,
+    networks::{ChainConfig, NetworkChain},
+    shim::clock::{ChainEpoch, EPOCHS_IN_DAY},
+    state_manager::StateManager,
+    utils::misc::env::{
+        Provenance, Provenanced, env_or_config, is_truthy, parse_env_value, try_parse_env_value,
+    },
+    utils::multihash::prelude::*,
+};
+
+pub const FOREST_F3_FINALITY: &str = "FOREST_F3_FINALITY";
+pub const FOREST_F3_BOOTSTRAP_EPOCH: &str = "FOREST_F3_BOOTSTRAP_EPOCH";
+pub const FOREST_F3_INITIAL_POWER_TABLE: &str = "FOREST_F3_INITIAL_POWER_TABLE";
+pub const FOREST_F3_SIDECAR_FFI_ENABLED: &str = "FOREST_F3_SIDECAR_FFI_ENABLED";
+pub const FOREST_F3_PERMANENTLY_DISABLED: &str = "FOREST_F3_PERMANENTLY_DISABLED";
+pub const FOREST_F3_ROOT: &str = "FOREST_F3_ROOT";
+pub const FOREST_F3_SIDECAR_RPC_ENDPOINT: &str = "FOREST_F3_SIDECAR_RPC_ENDPOINT";
+pub const FOREST_F3_LOG_LEVEL: &str = "FOREST_F3_LOG_LEVEL";
+
+/// The `[f3]` section of the daemon configuration. Every field is optional;
+/// the environment variables take precedence over the configured values,
+/// which in turn take precedence over the chain defaults.
+#[serde_as]
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, Default)]
+#[cfg_attr(test, derive(derive_quickcheck_arbitrary::Arbitrary))]
+#[serde(default)]
+pub struct F3Config {
+    /// Run the F3 sidecar. Overridden by `FOREST_F3_SIDECAR_FFI_ENABLED`.
+    pub enabled: Option<bool>,
+    /// Never run the F3 sidecar, whatever the other settings are. Overridden by
+    /// `FOREST_F3_PERMANENTLY_DISABLED`.
+    pub permanently_disabled: Option<bool>,
+    /// F3 finality. Overridden by `FOREST_F3_FINALITY`.
+    pub finality: Option<i64>,
+    /// F3 bootstrap epoch. Overridden by `FOREST_F3_BOOTSTRAP_EPOCH`.
+    pub bootstrap_epoch: Option<i64>,
+    /// CID of the F3 initial power table. Overridden by `FOREST_F3_INITIAL_POWER_TABLE`.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub initial_power_table: Option<Cid>,
+    /// Data directory of the F3 sidecar. Overridden by `FOREST_F3_ROOT`.
+    pub root: Option<PathBuf>,
+    /// Listening address of the F3 sidecar RPC server, e.g. `127.0.0.1:23456`.
+    /// Overridden by `FOREST_F3_SIDECAR_RPC_ENDPOINT`.
+    pub rpc_endpoint: Option<String>,
+    /// Minimum level of the sidecar logs. Overridden by `FOREST_F3_LOG_LEVEL`.
+    pub log_level: Option<F3LogLevel>,
+    /// How the sidecar logs are written, see [`F3LogFormat`].
+    pub log_format: Option<F3LogFormat>,
+    /// The sidecar starts once the chain head is within this many epochs of
+    /// the bootstrap epoch. Defaults to [`DEFAULT_F3_LAUNCH_MARGIN`].
+    pub launch_margin: Option<ChainEpoch>,
+}
+
+/// Default of [`F3Config::launch_margin`].
+pub const DEFAULT_F3_LAUNCH_MARGIN: ChainEpoch = EPOCHS_IN_DAY;
+
+/// The chain head epoch the sidecar is started at, see
+/// [`F3Config::launch_margin`]. With a disabled bootstrap epoch, the sidecar
+/// starts right away.
+pub fn sidecar_launch_epoch(bootstrap_epoch: ChainEpoch, launch_margin: ChainEpoch) -> ChainEpoch {
+    bootstrap_epoch.saturating_sub(launch_margin)
+}
 
-use crate::{networks::ChainConfig, utils::misc::env::is_env_set_and_truthy};
+impl F3Config {
+    /// Rejects values that would only fail later, deep inside the sidecar.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(finality) = self.finality {
+            anyhow::ensure!(
+                finality > 0,
+                "invalid f3.finality {finality}: a positive integer is expected"
+            );
+        }
+        if let Some(launch_margin) = self.launch_margin {
+            anyhow::ensure!(
+                launch_margin >= 0,
+                "invalid f3.launch_margin {launch_margin}: a non-negative integer is expected"
+            );
+        }
+        if let Some(root) = &self.root {
+            anyhow::ensure!(
+                !root.as_os_str().is_empty(),
+                "invalid f3.root: the path must not be empty"
+            );
+        }
+        if let Some(endpoint) = &self.rpc_endpoint {
+            anyhow::ensure!(
+                endpoint
+                    .rsplit_once(':')
+                    .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok()),
+                "invalid f3.rpc_endpoint {endpoint:?}: `host:port` is expected"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A malformed F3 environment variable, see [`validate_env`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct F3EnvWarning {
+    pub var: &'static str,
+    pub value: String,
+    pub reason: &'static str,
+}
+
+impl std::fmt::Display for F3EnvWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid {} value {:?}: {}",
+            self.var, self.value, self.reason
+        )
+    }
+}
+
+/// Checks that all F3 environment variables, when set, can be parsed. Unlike
+/// [`get_f3_sidecar_params`], which logs and skips malformed values one at a
+/// time as it reads them, this reports every problem up front.
+pub fn validate_env() -> Vec<F3EnvWarning> {
+    validate_vars(|var| std::env::var(var).ok())
+}
+
+fn validate_vars(get: impl Fn(&'static str) -> Option<String>) -> Vec<F3EnvWarning> {
+    // Parses like `get_f3_sidecar_params` does, so that empty values are not malformed
+    fn parses<T: std::str::FromStr>(value: &str, is_valid: impl FnOnce(&T) -> bool) -> bool {
+        try_parse_env_value(Some(value.to_owned()), is_valid).is_ok()
+    }
+    // Anything that isn't truthy is read as `false`, but only these spell it
+    fn is_boolean(value: &str) -> bool {
+        is_truthy(value) || matches!(value.to_lowercase().as_str(), "" | "0" | "false" | "no")
+    }
+    let checks: [(&'static str, fn(&str) -> bool, &'static str); 6] = [
+        (
+            FOREST_F3_FINALITY,
+            |v| parses(v, |&f: &i64| f > 0),
+            "a positive integer is expected",
+        ),
+        (
+            FOREST_F3_BOOTSTRAP_EPOCH,
+            |v| parses(v, |_: &i64| true),
+            "an integer is expected",
+        ),
+        (
+            FOREST_F3_INITIAL_POWER_TABLE,
+            |v| parses(v, |_: &Cid| true),
+            "a CID or an empty string is expected",
+        ),
+        (
+            FOREST_F3_SIDECAR_FFI_ENABLED,
+            is_boolean,
+            "a boolean (1/0, true/false, yes/no) is expected",
+        ),
+        (
+            FOREST_F3_PERMANENTLY_DISABLED,
+            is_boolean,
+            "a boolean (1/0, true/false, yes/no) is expected",
+        ),
+        (
+            FOREST_F3_LOG_LEVEL,
+            |v| parses(v, |_: &F3LogLevel| true),
+            "one of debug, info, warn or error is expected",
+        ),
+    ];
+    checks
+        .into_iter()
+        .filter_map(|(var, is_valid, reason)| {
+            let value = get(var)?;
+            (!is_valid(&value)).then_some(F3EnvWarning { var, value, reason })
+        })
+        .collect()
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct F3Options {
@@ -19,60 +209,481 @@ pub struct F3Options {
     pub initial_power_table: Option<Cid>,
 }
 
-pub fn get_f3_sidecar_params(chain_config: &ChainConfig) -> F3Options {
-    let chain_finality = std::env::var("FOREST_F3_FINALITY")
-        .ok()
-        .and_then(|v| match v.parse::<i64>() {
-            Ok(f) if f > 0 => Some(f),
-            _ => {
-                tracing::warn!(
-                    "Invalid FOREST_F3_FINALITY value {v}. A positive integer is expected."
-                );
-                None
+/// Bootstrap epoch that disables F3, e.g. for custom chains without F3.
+pub const F3_BOOTSTRAP_EPOCH_DISABLED: ChainEpoch = -1;
+
+/// Nonsensical [`F3Options`], see [`F3Options::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum F3OptionsError {
+    #[error("F3 finality must be positive, got {0}")]
+    NonPositiveFinality(i64),
+    #[error(
+        "F3 bootstrap epoch must be non-negative or {F3_BOOTSTRAP_EPOCH_DISABLED} (disabled), got {0}"
+    )]
+    InvalidBootstrapEpoch(ChainEpoch),
+    #[error("F3 finality {finality} is larger than the bootstrap epoch {bootstrap_epoch}")]
+    FinalityExceedsBootstrapEpoch {
+        finality: i64,
+        bootstrap_epoch: ChainEpoch,
+    },
+    #[error(
+        "F3 initial power table is unset, but the chain head {head_epoch} is already past the bootstrap epoch {bootstrap_epoch}"
+    )]
+    PowerTableUnsetAfterBootstrap {
+        bootstrap_epoch: ChainEpoch,
+        head_epoch: ChainEpoch,
+    },
+    #[error("F3 initial power table {0} must be a DAG-CBOR CID")]
+    InvalidPowerTableCodec(Cid),
+    #[error("F3 initial power table {0} must be a Blake2b-256 CID")]
+    InvalidPowerTableMultihash(Cid),
+}
+
+impl F3Options {
+    /// Rejects options that would make the Go sidecar fail with an obscure error.
+    /// `head_epoch` enables the checks against the current chain head, when
+    /// available.
+    pub fn validate(
+        &self,
+        chain_config: &ChainConfig,
+        head_epoch: Option<ChainEpoch>,
+    ) -> Result<(), F3OptionsError> {
+        let Self {
+            chain_finality,
+            bootstrap_epoch,
+            initial_power_table,
+        } = *self;
+        if chain_finality <= 0 {
+            return Err(F3OptionsError::NonPositiveFinality(chain_finality));
+        }
+        if bootstrap_epoch == F3_BOOTSTRAP_EPOCH_DISABLED {
+            return Ok(());
+        }
+        if bootstrap_epoch < 0 {
+            return Err(F3OptionsError::InvalidBootstrapEpoch(bootstrap_epoch));
+        }
+        if chain_finality > bootstrap_epoch {
+            return Err(F3OptionsError::FinalityExceedsBootstrapEpoch {
+                finality: chain_finality,
+                bootstrap_epoch,
+            });
+        }
+        match initial_power_table {
+            Some(cid) if cid.codec() != fvm_ipld_encoding::DAG_CBOR => {
+                return Err(F3OptionsError::InvalidPowerTableCodec(cid));
             }
-        })
-        .inspect(|i| {
-            tracing::info!("Using F3 finality {i} set by FOREST_F3_FINALITY");
-        })
-        .unwrap_or(chain_config.policy.chain_finality);
-    // This will be used post-bootstrap to hard-code the initial F3's initial power table CID.
-    // Read from an environment variable for now before the hard-coded value is determined.
-    let initial_power_table = match std::env::var("FOREST_F3_INITIAL_POWER_TABLE") {
-        Ok(i) if i.is_empty() => {
-            tracing::info!("F3 initial power table cid is unset by FOREST_F3_INITIAL_POWER_TABLE");
-            None
-        }
-        Ok(i) => {
-            if let Ok(cid) = i.parse() {
-                tracing::info!(
-                    "Using F3 initial power table cid {i} set by FOREST_F3_INITIAL_POWER_TABLE"
-                );
-                Some(cid)
-            } else {
-                tracing::warn!(
-                    "Invalid power table cid {i} set by FOREST_F3_INITIAL_POWER_TABLE, fallback to chain config"
-                );
-                chain_config.f3_initial_power_table
+            Some(cid) if cid.hash().code() != u64::from(MultihashCode::Blake2b256) => {
+                return Err(F3OptionsError::InvalidPowerTableMultihash(cid));
             }
+            Some(_) => {}
+            // Without a power table, the sidecar has to derive it from the state
+            // at `bootstrap_epoch - finality`, which is unlikely to still be
+            // around on a network that ships a hard-coded power table.
+            None => {
+                if let Some(head_epoch) = head_epoch
+                    && head_epoch > bootstrap_epoch
+                    && chain_config.f3_initial_power_table.is_some()
+                {
+                    return Err(F3OptionsError::PowerTableUnsetAfterBootstrap {
+                        bootstrap_epoch,
+                        head_epoch,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the effective [`F3Options`]. Each option is taken from the first of:
+/// 1. its environment variable, [`FOREST_F3_FINALITY`], [`FOREST_F3_BOOTSTRAP_EPOCH`]
+///    or [`FOREST_F3_INITIAL_POWER_TABLE`], unless it is malformed;
+/// 2. the `[f3]` section of the configuration, see [`F3Config`];
+/// 3. the chain configuration, i.e. the chain finality of its policy and the
+///    per-network [`F3Defaults`](crate::networks::F3Defaults).
+pub fn get_f3_sidecar_params(chain_config: &ChainConfig, f3_config: &F3Config) -> F3Options {
+    get_f3_sidecar_params_with_provenance(chain_config, f3_config).0
+}
+
+/// Where each of the [`F3Options`] comes from.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct F3OptionsProvenance {
+    pub chain_finality: Provenance,
+    pub bootstrap_epoch: Provenance,
+    pub initial_power_table: Provenance,
+    /// Environment variables that are set, but were ignored because they are
+    /// malformed, with their values. Empty values are not malformed: they unset
+    /// the initial power table, and are ignored silently for other options.
+    pub ignored_env: BTreeMap<&'static str, String>,
+}
+
+/// Where one of the [`F3Options`] comes from, as reported by `Forest.F3Status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum F3OptionSource {
+    /// The environment variable of this name.
+    EnvVar(String),
+    /// The `[f3]` section of the configuration.
+    Config,
+    /// The chain configuration.
+    ChainDefault,
+    /// Computed from the chain state.
+    Derived,
+}
+
+impl F3OptionSource {
+    fn new(provenance: Provenance, env_var: &str) -> Self {
+        match provenance {
+            Provenance::Env => Self::EnvVar(env_var.into()),
+            Provenance::Config => Self::Config,
+            Provenance::Default => Self::ChainDefault,
+            Provenance::Derived => Self::Derived,
+        }
+    }
+}
+
+impl std::fmt::Display for F3OptionSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EnvVar(name) => write!(f, "{name}"),
+            Self::Config => write!(f, "config"),
+            Self::ChainDefault => write!(f, "chain default"),
+            Self::Derived => write!(f, "derived"),
+        }
+    }
+}
+
+/// [`F3OptionsProvenance`], as reported by `Forest.F3Status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct F3OptionsReport {
+    pub finality: F3OptionSource,
+    pub bootstrap_epoch: F3OptionSource,
+    pub initial_power_table: F3OptionSource,
+    /// See [`F3OptionsProvenance::ignored_env`].
+    pub ignored_env: BTreeMap<String, String>,
+}
+
+impl From<&F3OptionsProvenance> for F3OptionsReport {
+    fn from(provenance: &F3OptionsProvenance) -> Self {
+        Self {
+            finality: F3OptionSource::new(provenance.chain_finality, FOREST_F3_FINALITY),
+            bootstrap_epoch: F3OptionSource::new(
+                provenance.bootstrap_epoch,
+                FOREST_F3_BOOTSTRAP_EPOCH,
+            ),
+            initial_power_table: F3OptionSource::new(
+                provenance.initial_power_table,
+                FOREST_F3_INITIAL_POWER_TABLE,
+            ),
+            ignored_env: provenance
+                .ignored_env
+                .iter()
+                .map(|(var, value)| (var.to_string(), value.clone()))
+                .collect(),
         }
-        _ => chain_config.f3_initial_power_table,
+    }
+}
+
+/// Like [`get_f3_sidecar_params`], also reporting whether each option comes from
+/// the environment, the `[f3]` configuration, or the chain defaults.
+pub fn get_f3_sidecar_params_with_provenance(
+    chain_config: &ChainConfig,
+    f3_config: &F3Config,
+) -> (F3Options, F3OptionsProvenance) {
+    f3_sidecar_params(chain_config, f3_config, |var| std::env::var(var).ok())
+}
+
+/// Like [`get_f3_sidecar_params_with_provenance`], with the provenance converted
+/// to an [`F3OptionsReport`], i.e. naming the environment variable each option
+/// was read from, if any.
+pub fn get_f3_sidecar_params_with_sources(
+    chain_config: &ChainConfig,
+    f3_config: &F3Config,
+) -> (F3Options, F3OptionsReport) {
+    f3_sidecar_params_with_sources(chain_config, f3_config, |var| std::env::var(var).ok())
+}
+
+fn f3_sidecar_params_with_sources(
+    chain_config: &ChainConfig,
+    f3_config: &F3Config,
+    env: impl Fn(&'static str) -> Option<String>,
+) -> (F3Options, F3OptionsReport) {
+    let (options, provenance) = f3_sidecar_params(chain_config, f3_config, env);
+    (options, F3OptionsReport::from(&provenance))
+}
+
+/// The precedence of [`get_f3_sidecar_params`], with the environment looked up by `env`.
+fn f3_sidecar_params(
+    chain_config: &ChainConfig,
+    f3_config: &F3Config,
+    env: impl Fn(&'static str) -> Option<String>,
+) -> (F3Options, F3OptionsProvenance) {
+    let defaults = chain_config.f3_defaults();
+    let chain_finality = Provenanced::resolve(
+        parse_env_value(
+            FOREST_F3_FINALITY,
+            env(FOREST_F3_FINALITY),
+            |&f: &i64| f > 0,
+            "a positive integer",
+        ),
+        f3_config.finality,
+        chain_config.policy.chain_finality,
+    )
+    .logged(FOREST_F3_FINALITY);
+
+    // This will be used post-bootstrap to hard-code the initial F3's initial power table CID.
+    // Read from an environment variable for now before the hard-coded value is determined.
+    let env_initial_power_table = match env(FOREST_F3_INITIAL_POWER_TABLE) {
+        // An empty value unsets the power table
+        Some(i) if i.is_empty() => Some(None),
+        i => parse_env_value(
+            FOREST_F3_INITIAL_POWER_TABLE,
+            i,
+            |_: &Cid| true,
+            "a CID or an empty string",
+        )
+        .map(Some),
     };
+    let initial_power_table = Provenanced::resolve(
+        env_initial_power_table,
+        f3_config.initial_power_table.map(Some),
+        defaults.initial_power_table,
+    );
+    if initial_power_table.provenance == Provenance::Env {
+        match &initial_power_table.value {
+            Some(cid) => tracing::info!(
+                "Using F3 initial power table cid {cid} set by {FOREST_F3_INITIAL_POWER_TABLE}"
+            ),
+            None => tracing::info!(
+                "F3 initial power table cid is unset by {FOREST_F3_INITIAL_POWER_TABLE}"
+            ),
+        }
+    }
 
-    let bootstrap_epoch = std::env::var("FOREST_F3_BOOTSTRAP_EPOCH")
-        .ok()
-        .and_then(|i| i.parse().ok())
-        .inspect(|i| {
-            tracing::info!("Using F3 bootstrap epoch {i} set by FOREST_F3_BOOTSTRAP_EPOCH")
-        })
-        .unwrap_or(chain_config.f3_bootstrap_epoch);
+    let bootstrap_epoch = Provenanced::resolve(
+        parse_env_value(
+            FOREST_F3_BOOTSTRAP_EPOCH,
+            env(FOREST_F3_BOOTSTRAP_EPOCH),
+            |_: &i64| true,
+            "an integer",
+        ),
+        f3_config.bootstrap_epoch,
+        defaults.bootstrap_epoch,
+    )
+    .logged(FOREST_F3_BOOTSTRAP_EPOCH);
+
+    let ignored_env = [
+        (FOREST_F3_FINALITY, chain_finality.provenance),
+        (FOREST_F3_BOOTSTRAP_EPOCH, bootstrap_epoch.provenance),
+        (
+            FOREST_F3_INITIAL_POWER_TABLE,
+            initial_power_table.provenance,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, provenance)| *provenance != Provenance::Env)
+    .filter_map(|(var, _)| Some((var, env(var).filter(|value| !value.is_empty())?)))
+    .collect();
+
+    (
+        F3Options {
+            chain_finality: chain_finality.value,
+            bootstrap_epoch: bootstrap_epoch.value,
+            initial_power_table: initial_power_table.value,
+        },
+        F3OptionsProvenance {
+            chain_finality: chain_finality.provenance,
+            bootstrap_epoch: bootstrap_epoch.provenance,
+            initial_power_table: initial_power_table.provenance,
+            ignored_env,
+        },
+    )
+}
 
-    F3Options {
+/// Computes the CID of the F3 initial power table from the chain state, the way the
+/// sidecar would: from the power table of the tipset `finality` epochs before the
+/// bootstrap epoch. Returns [`None`] if the chain has not passed the bootstrap epoch.
+pub async fn derive_initial_power_table<DB: Blockstore + Send + Sync + 'static>(
+    state_manager: &Arc<StateManager<DB>>,
+    options: &F3Options,
+) -> anyhow::Result<Option<Cid>> {
+    let F3Options {
         chain_finality,
         bootstrap_epoch,
-        initial_power_table,
+        ..
+    } = *options;
+    let head = state_manager.chain_store().heaviest_tipset();
+    if bootstrap_epoch < 0 || head.epoch() <= bootstrap_epoch {
+        return Ok(None);
+    }
+    let ts = state_manager.chain_store().chain_index.tipset_by_height(
+        bootstrap_epoch - chain_finality,
+        head,
+        ResolveNullTipset::TakeOlder,
+    )?;
+    let power_table = crate::rpc::f3::GetPowerTable::compute(state_manager, &ts).await?;
+    Ok(Some(crate::rpc::f3::make_power_table_cid(&power_table)?))
+}
+
+/// Uses the `derived` initial power table when none is configured, and warns when the
+/// configured one disagrees with it. The configured value still wins in that case.
+pub fn reconcile_initial_power_table(options: &mut F3Options, derived: Cid) {
+    match options.initial_power_table {
+        None => {
+            tracing::warn!(
+                "Using F3 initial power table cid {derived} derived from the chain state at epoch {}",
+                options.bootstrap_epoch - options.chain_finality
+            );
+            options.initial_power_table = Some(derived);
+        }
+        Some(configured) if configured != derived => {
+            tracing::warn!(
+                "The configured F3 initial power table cid {configured} does not match {derived} derived from the chain state at epoch {}. F3 may diverge from the network!",
+                options.bootstrap_epoch - options.chain_finality
+            );
+        }
+        Some(_) => {}
     }
 }
 
+/// Data directory of the F3 sidecar: `FOREST_F3_ROOT`, then `f3.root`, then
+/// [`default_f3_root`].
+pub fn get_f3_root(f3_config: &F3Config, data_dir: &Path, network: &NetworkChain) -> PathBuf {
+    f3_root(f3_config, default_f3_root(data_dir, network), |var| {
+        std::env::var(var).ok()
+    })
+}
+
+/// `{data_dir}/f3/{network}`, so that every network has its own certificate store.
+pub fn default_f3_root(data_dir: &Path, network: &NetworkChain) -> PathBuf {
+    data_dir.join("f3").join(network.to_string())
+}
+
+fn f3_root(
+    f3_config: &F3Config,
+    default: PathBuf,
+    env: impl Fn(&'static str) -> Option<String>,
+) -> PathBuf {
+    Provenanced::resolve(
+        parse_env_value(FOREST_F3_ROOT, env(FOREST_F3_ROOT), |_| true, "a path"),
+        f3_config.root.clone(),
+        default,
+    )
+    .value
+}
+
+/// Minimum level of the sidecar logs: `FOREST_F3_LOG_LEVEL`, then `f3.log_level`,
+/// then [`F3LogLevel::default`].
+pub fn get_f3_log_level(f3_config: &F3Config) -> F3LogLevel {
+    f3_log_level(f3_config, |var| std::env::var(var).ok())
+}
+
+fn f3_log_level(f3_config: &F3Config, env: impl Fn(&'static str) -> Option<String>) -> F3LogLevel {
+    env_or_config(
+        FOREST_F3_LOG_LEVEL,
+        env(FOREST_F3_LOG_LEVEL),
+        f3_config.log_level,
+        F3LogLevel::default(),
+    )
+    .value
+}
+
+/// F3 parameters the daemon started with, after applying the configuration
+/// and environment overrides.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct F3EffectiveParams {
+    pub options: F3Options,
+    pub provenance: F3OptionsProvenance,
+    pub root: PathBuf,
+}
+
+impl F3EffectiveParams {
+    /// Logs the options, and where each of them comes from, as a single
+    /// structured event.
+    pub fn log(&self) {
+        let Self {
+            options,
+            provenance,
+            root,
+        } = self;
+        let report = F3OptionsReport::from(provenance);
+        tracing::info!(
+            finality = options.chain_finality,
+            finality_source = %report.finality,
+            bootstrap_epoch = options.bootstrap_epoch,
+            bootstrap_epoch_source = %report.bootstrap_epoch,
+            initial_power_table = %options
+                .initial_power_table
+                .map(|cid| cid.to_string())
+                .unwrap_or_else(|| "unset".into()),
+            initial_power_table_source = %report.initial_power_table,
+            ignored_env = ?report.ignored_env,
+            root = %root.display(),
+            "F3 options"
+        );
+    }
+}
+
+static EFFECTIVE_F3_PARAMS: OnceLock<F3EffectiveParams> = OnceLock::new();
+
+/// Records the effective F3 parameters for [`get_effective_f3_params`]. Only the
+/// first call has an effect.
+pub fn set_effective_f3_params(params: F3EffectiveParams) {
+    let _ = EFFECTIVE_F3_PARAMS.set(params);
+}
+
+/// The F3 parameters recorded at daemon startup, if any.
+pub fn get_effective_f3_params() -> Option<&'static F3EffectiveParams> {
+    EFFECTIVE_F3_PARAMS.get()
+}
+
+pub const DEFAULT_F3_SIDECAR_RPC_ENDPOINT: &str = "127.0.0.1:23456";
+
+/// `f3.rpc_endpoint`, recorded once at daemon startup so that RPC clients of the
+/// sidecar don't need access to the configuration.
+static CONFIGURED_F3_RPC_ENDPOINT: OnceLock<String> = OnceLock::new();
+
+/// Records `f3.rpc_endpoint` for [`get_f3_rpc_endpoint`]. Only the first call has an effect.
+pub fn set_configured_f3_rpc_endpoint(f3_config: &F3Config) {
+    if let Some(endpoint) = &f3_config.rpc_endpoint {
+        let _ = CONFIGURED_F3_RPC_ENDPOINT.set(endpoint.clone());
+    }
+}
+
+/// Listening address of the F3 sidecar RPC server: `FOREST_F3_SIDECAR_RPC_ENDPOINT`,
+/// then `f3.rpc_endpoint`, then [`DEFAULT_F3_SIDECAR_RPC_ENDPOINT`].
+pub fn get_f3_rpc_endpoint() -> Cow<'static, str> {
+    f3_rpc_endpoint(
+        CONFIGURED_F3_RPC_ENDPOINT.get().map(String::as_str),
+        |var| std::env::var(var).ok(),
+    )
+}
+
+fn f3_rpc_endpoint(
+    configured: Option<&'static str>,
+    env: impl Fn(&'static str) -> Option<String>,
+) -> Cow<'static, str> {
+    match (env(FOREST_F3_SIDECAR_RPC_ENDPOINT), configured) {
+        (Some(endpoint), _) => Cow::Owned(endpoint),
+        (None, Some(endpoint)) => Cow::Borrowed(endpoint),
+        (None, None) => Cow::Borrowed(DEFAULT_F3_SIDECAR_RPC_ENDPOINT),
+    }
+}
+
+/// Applies `f3.enabled` and `f3.permanently_disabled` on top of the chain defaults.
+/// `FOREST_F3_SIDECAR_FFI_ENABLED` and `FOREST_F3_PERMANENTLY_DISABLED` still take
+/// precedence, see [`is_sidecar_ffi_enabled`].
+pub fn apply_f3_config(chain_config: &mut ChainConfig, f3_config: &F3Config) {
+    if let Some(enabled) = f3_config.enabled {
+        chain_config.f3_enabled = enabled;
+    }
+    if let Some(permanently_disabled) = f3_config.permanently_disabled {
+        chain_config.f3_permanently_disabled = permanently_disabled;
+    }
+}
+
+/// Starts the sidecar under a [`supervisor`]. Returns [`None`] when the sidecar
+/// is disabled, or not compiled in.
 pub fn run_f3_sidecar_if_enabled(
     chain_config: &ChainConfig,
     _rpc_endpoint: String,
@@ -82,52 +693,1000 @@ pub fn run_f3_sidecar_if_enabled(
     _bootstrap_epoch: i64,
     _finality: i64,
     _f3_root: String,
-) {
+    _log_level: F3LogLevel,
+    _log_format: F3LogFormat,
+    _head: tokio::sync::watch::Receiver<ChainEpoch>,
+    _launch_margin: ChainEpoch,
+) -> Option<supervisor::F3Handle> {
     if is_sidecar_ffi_enabled(chain_config) {
         #[cfg(all(f3sidecar, not(feature = "no-f3-sidecar")))]
         {
             tracing::info!("Starting F3 sidecar service ...");
-            GoF3NodeImpl::run(
-                _rpc_endpoint,
-                _jwt,
-                _f3_rpc_endpoint,
-                _initial_power_table,
-                _bootstrap_epoch,
-                _finality,
-                _f3_root,
-            );
+            record_rpc_endpoint(_f3_rpc_endpoint.clone());
+            return Some(supervisor::F3Handle::spawn_at_epoch(
+                Arc::new(GoF3Sidecar::<GoF3NodeImpl>::new(sidecar_args(
+                    _rpc_endpoint,
+                    _jwt,
+                    _f3_rpc_endpoint,
+                    _initial_power_table,
+                    _bootstrap_epoch,
+                    _finality,
+                    _f3_root,
+                    _log_level,
+                    _log_format,
+                ))),
+                supervisor::Backoff::default(),
+                _head,
+                sidecar_launch_epoch(_bootstrap_epoch, _launch_margin),
+            ));
         }
     }
+    None
 }
 
-/// Whether F3 sidecar via FFI is enabled.
-pub fn is_sidecar_ffi_enabled(chain_config: &ChainConfig) -> bool {
-    // Respect the environment variable when set, and fallback to chain config when not set.
-    let enabled =
-        is_env_set_and_truthy("FOREST_F3_SIDECAR_FFI_ENABLED").unwrap_or(chain_config.f3_enabled);
-    cfg_if::cfg_if! {
-        if #[cfg(all(f3sidecar, not(feature = "no-f3-sidecar")))] {
-            enabled
-        }
-        else {
-            if enabled {
-                tracing::info!("Failed to enable F3 sidecar, the Forest binary is not compiled with f3-sidecar Go lib");
+/// Listening address of the sidecar RPC server, recorded when the sidecar is started.
+static SIDECAR_RPC_ENDPOINT: OnceLock<String> = OnceLock::new();
+
+#[cfg(any(test, all(f3sidecar, not(feature = "no-f3-sidecar"))))]
+fn record_rpc_endpoint(endpoint: String) {
+    let _ = SIDECAR_RPC_ENDPOINT.set(endpoint);
+}
+
+/// Listening address of the F3 sidecar RPC server, once the sidecar has been
+/// started. Always [`None`] when the sidecar is not compiled in.
+pub fn rpc_endpoint() -> Option<String> {
+    if is_sidecar_ffi_compiled() {
+        SIDECAR_RPC_ENDPOINT.get().cloned()
+    } else {
+        None
+    }
+}
+
+/// Arguments of [`SidecarFfi::run`].
+#[cfg(any(test, all(f3sidecar, not(feature = "no-f3-sidecar"))))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct SidecarArgs {
+    rpc_endpoint: String,
+    jwt: String,
+    f3_rpc_endpoint: String,
+    initial_power_table: String,
+    bootstrap_epoch: i64,
+    finality: i64,
+    f3_root: String,
+    /// A `go-log` level.
+    log_level: String,
+    /// A `go-log` format.
+    log_format: String,
+    /// Where the sidecar writes its logs, stderr if empty. Set by [`GoF3Sidecar`]
+    /// when the logs are forwarded.
+    log_file: String,
+    /// Whether the logs are forwarded to [`tracing`], see [`F3LogFormat::Tracing`].
+    forward_logs: bool,
+}
+
+#[cfg(any(test, all(f3sidecar, not(feature = "no-f3-sidecar"))))]
+fn sidecar_args(
+    rpc_endpoint: String,
+    jwt: String,
+    f3_rpc_endpoint: String,
+    initial_power_table: String,
+    bootstrap_epoch: i64,
+    finality: i64,
+    f3_root: String,
+    log_level: F3LogLevel,
+    log_format: F3LogFormat,
+) -> SidecarArgs {
+    SidecarArgs {
+        rpc_endpoint,
+        jwt,
+        f3_rpc_endpoint,
+        initial_power_table,
+        bootstrap_epoch,
+        finality,
+        f3_root,
+        log_level: log_level.to_string(),
+        log_format: log_format.go_format().into(),
+        log_file: String::new(),
+        forward_logs: log_format == F3LogFormat::Tracing,
+    }
+}
+
+/// The entry points of the sidecar, stubbed in tests.
+#[cfg(any(test, all(f3sidecar, not(feature = "no-f3-sidecar"))))]
+trait SidecarFfi: 'static {
+    fn run(args: &SidecarArgs) -> bool;
+    fn stop() -> bool;
+}
+
+#[cfg(all(f3sidecar, not(feature = "no-f3-sidecar")))]
+impl SidecarFfi for GoF3NodeImpl {
+    fn run(args: &SidecarArgs) -> bool {
+        let args = args.clone();
+        <GoF3NodeImpl as GoF3Node>::run(
+            args.rpc_endpoint,
+            args.jwt,
+            args.f3_rpc_endpoint,
+            args.initial_power_table,
+            args.bootstrap_epoch,
+            args.finality,
+            args.f3_root,
+            args.log_level,
+            args.log_format,
+            args.log_file,
+        )
+    }
+
+    fn stop() -> bool {
+        <GoF3NodeImpl as GoF3Node>::stop()
+    }
+}
+
+#[cfg(any(test, all(f3sidecar, not(feature = "no-f3-sidecar"))))]
+struct GoF3Sidecar<F> {
+    args: SidecarArgs,
+    /// [`SidecarArgs::log_file`], created when the sidecar is first run and kept
+    /// across restarts.
+    log_file: OnceLock<String>,
+    ffi: std::marker::PhantomData<fn() -> F>,
+}
+
+#[cfg(any(test, all(f3sidecar, not(feature = "no-f3-sidecar"))))]
+impl<F: SidecarFfi> GoF3Sidecar<F> {
+    fn new(args: SidecarArgs) -> Self {
+        Self {
+            args,
+            log_file: OnceLock::new(),
+            ffi: std::marker::PhantomData,
+        }
+    }
+
+    fn log_file(&self) -> &str {
+        self.log_file.get_or_init(|| {
+            if !self.args.forward_logs {
+                return String::new();
             }
-            false
+            sidecar_log::spawn_forwarder().unwrap_or_else(|e| {
+                tracing::warn!("Failed to forward F3 sidecar logs, writing them to stderr: {e}");
+                String::new()
+            })
+        })
+    }
+}
+
+#[cfg(any(test, all(f3sidecar, not(feature = "no-f3-sidecar"))))]
+impl<F: SidecarFfi> supervisor::F3Sidecar for GoF3Sidecar<F> {
+    fn run(&self) -> anyhow::Result<()> {
+        let args = SidecarArgs {
+            log_file: self.log_file().to_owned(),
+            ..self.args.clone()
+        };
+        anyhow::ensure!(F::run(&args), "F3 sidecar exited with an error");
+        Ok(())
+    }
+
+    fn stop(&self) {
+        if !F::stop() {
+            tracing::warn!("F3 sidecar is not running");
         }
     }
 }
 
+/// Whether the Forest binary is compiled with the f3-sidecar Go lib.
+pub const fn is_sidecar_ffi_compiled() -> bool {
+    cfg!(all(f3sidecar, not(feature = "no-f3-sidecar")))
+}
+
+/// Whether F3 is force-disabled by `FOREST_F3_PERMANENTLY_DISABLED` or
+/// `f3.permanently_disabled`, overriding every other setting. This is a kill switch
+/// for incident response.
+pub fn is_f3_permanently_disabled(chain_config: &ChainConfig) -> bool {
+    f3_permanently_disabled(chain_config, |var| std::env::var(var).ok())
+}
+
+fn f3_permanently_disabled(
+    chain_config: &ChainConfig,
+    env: impl Fn(&'static str) -> Option<String>,
+) -> bool {
+    env(FOREST_F3_PERMANENTLY_DISABLED)
+        .map(|v| is_truthy(&v))
+        .unwrap_or(chain_config.f3_permanently_disabled)
+}
+
+/// Whether F3 sidecar via FFI is requested, regardless of [`is_sidecar_ffi_compiled`].
+pub fn is_sidecar_ffi_requested(chain_config: &ChainConfig) -> bool {
+    sidecar_ffi_requested(chain_config, |var| std::env::var(var).ok())
+}
+
+fn sidecar_ffi_requested(
+    chain_config: &ChainConfig,
+    env: impl Fn(&'static str) -> Option<String>,
+) -> bool {
+    if f3_permanently_disabled(chain_config, &env) {
+        return false;
+    }
+    // Respect the environment variable when set, and fallback to chain config when not set.
+    env(FOREST_F3_SIDECAR_FFI_ENABLED)
+        .map(|v| is_truthy(&v))
+        .unwrap_or(chain_config.f3_enabled)
+}
+
+/// Whether F3 sidecar via FFI is enabled.
+pub fn is_sidecar_ffi_enabled(chain_config: &ChainConfig) -> bool {
+    if is_f3_permanently_disabled(chain_config) {
+        static WARN_PERMANENTLY_DISABLED: std::sync::Once = std::sync::Once::new();
+        WARN_PERMANENTLY_DISABLED.call_once(|| {
+            tracing::warn!(
+                "F3 is permanently disabled by {FOREST_F3_PERMANENTLY_DISABLED} or f3.permanently_disabled, ignoring the other F3 settings"
+            )
+        });
+        return false;
+    }
+    let enabled = is_sidecar_ffi_requested(chain_config);
+    if enabled && !is_sidecar_ffi_compiled() {
+        tracing::info!(
+            "Failed to enable F3 sidecar, the Forest binary is not compiled with f3-sidecar Go lib"
+        );
+    }
+    enabled && is_sidecar_ffi_compiled()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ahash::HashMap;
+
+    /// A random CID, for `f3.initial_power_table`.
+    const CONFIG_CID: &str = "bafyreicmaj5hhoy5mgqvamfhgexxyergw7hdeshizghodwkjg6qmpoco7i";
+    /// A random CID, for `FOREST_F3_INITIAL_POWER_TABLE`.
+    const ENV_CID: &str = "bafy2bzacecuvzsffwxtryrojnnl5o7oceuxt35s2mgaqohmzjygvkiolfmgyu";
+
+    /// An environment holding only `vars`.
+    fn env(vars: &[(&'static str, &str)]) -> impl Fn(&'static str) -> Option<String> + use<> {
+        let vars: HashMap<_, _> = vars.iter().map(|(k, v)| (*k, v.to_string())).collect();
+        move |var| vars.get(var).cloned()
+    }
+
+    fn validate(vars: &[(&'static str, &str)]) -> Vec<F3EnvWarning> {
+        validate_vars(env(vars))
+    }
+
+    #[test]
+    fn test_validate_env_valid() {
+        assert!(validate(&[]).is_empty());
+        assert!(
+            validate(&[
+                (FOREST_F3_FINALITY, "900"),
+                (FOREST_F3_BOOTSTRAP_EPOCH, "-1"),
+                (
+                    FOREST_F3_INITIAL_POWER_TABLE,
+                    "bafyreicmaj5hhoy5mgqvamfhgexxyergw7hdeshizghodwkjg6qmpoco7i"
+                ),
+                (FOREST_F3_SIDECAR_FFI_ENABLED, "true"),
+            ])
+            .is_empty()
+        );
+        // Empty values are ignored, or unset the initial power table
+        assert!(
+            validate(&[
+                (FOREST_F3_FINALITY, ""),
+                (FOREST_F3_BOOTSTRAP_EPOCH, ""),
+                (FOREST_F3_INITIAL_POWER_TABLE, ""),
+                (FOREST_F3_SIDECAR_FFI_ENABLED, ""),
+                (FOREST_F3_PERMANENTLY_DISABLED, ""),
+                (FOREST_F3_LOG_LEVEL, ""),
+            ])
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_validate_env_malformed() {
+        let cases = [
+            (FOREST_F3_FINALITY, "abc"),
+            (FOREST_F3_FINALITY, "0"),
+            (FOREST_F3_FINALITY, "-5"),
+            (FOREST_F3_BOOTSTRAP_EPOCH, "1.5"),
+            (FOREST_F3_INITIAL_POWER_TABLE, "not-a-cid"),
+            (FOREST_F3_SIDECAR_FFI_ENABLED, "enabled"),
+            (FOREST_F3_PERMANENTLY_DISABLED, "maybe"),
+            (FOREST_F3_LOG_LEVEL, "verbose"),
+        ];
+        for (var, value) in cases {
+            let warnings = validate(&[(var, value)]);
+            assert_eq!(warnings.len(), 1, "{var}={value}");
+            assert_eq!(warnings[0].var, var);
+            assert_eq!(warnings[0].value, value);
+        }
+
+        let warnings = validate(&cases[..]);
+        assert_eq!(warnings.len(), 6, "one warning per variable");
+    }
+
+    #[test]
+    fn test_f3_precedence() {
+        use F3OptionSource::{ChainDefault, Config};
+
+        let config_cid: Cid = CONFIG_CID.parse().unwrap();
+        let env_cid: Cid = ENV_CID.parse().unwrap();
+        let from_env = |var: &str| F3OptionSource::EnvVar(var.into());
+        let sources = |finality, bootstrap_epoch, initial_power_table| F3OptionsReport {
+            finality,
+            bootstrap_epoch,
+            initial_power_table,
+            ignored_env: BTreeMap::new(),
+        };
+        let ignoring = |sources: F3OptionsReport, var: &str, value: &str| F3OptionsReport {
+            ignored_env: BTreeMap::from([(var.into(), value.into())]),
+            ..sources
+        };
+        let finality_config = F3Config {
+            finality: Some(200),
+            ..Default::default()
+        };
+        let bootstrap_epoch_config = F3Config {
+            bootstrap_epoch: Some(2000),
+            ..Default::default()
+        };
+        let power_table_config = F3Config {
+            initial_power_table: Some(config_cid),
+            ..Default::default()
+        };
+        let all_config = F3Config {
+            finality: Some(200),
+            bootstrap_epoch: Some(2000),
+            initial_power_table: Some(config_cid),
+            ..Default::default()
+        };
+
+        for (network, bootstrap_epoch, initial_power_table) in [
+            (
+                NetworkChain::Mainnet,
+                4_920_480,
+                Some("bafy2bzacecklgxd2eksmodvhgurqvorkg3wamgqkrunir3al2gchv2cikgmbu"),
+            ),
+            (
+                NetworkChain::Calibnet,
+                2_081_674,
+                Some("bafy2bzaceab236vmmb3n4q4tkvua2n4dphcbzzxerxuey3mot4g3cov5j3r2c"),
+            ),
+            (NetworkChain::Butterflynet, 1000, None),
+            (NetworkChain::Devnet("devnet".into()), 1000, None),
+        ] {
+            let chain_config = ChainConfig::from_chain(&network);
+            let defaults = F3Options {
+                chain_finality: chain_config.policy.chain_finality,
+                bootstrap_epoch,
+                initial_power_table: initial_power_table.map(|cid| cid.parse().unwrap()),
+            };
+            assert_eq!(defaults.validate(&chain_config, None), Ok(()), "{network}");
+            let with = |chain_finality, bootstrap_epoch, initial_power_table| F3Options {
+                chain_finality,
+                bootstrap_epoch,
+                initial_power_table,
+            };
+            let finality = |finality| {
+                with(
+                    finality,
+                    defaults.bootstrap_epoch,
+                    defaults.initial_power_table,
+                )
+            };
+            let bootstrap_epoch =
+                |epoch| with(defaults.chain_finality, epoch, defaults.initial_power_table);
+            let power_table = |cid| with(defaults.chain_finality, defaults.bootstrap_epoch, cid);
+
+            let cases: [(
+                &str,
+                &F3Config,
+                &[(&'static str, &str)],
+                F3Options,
+                F3OptionsReport,
+            ); 19] = [
+                (
+                    "chain defaults",
+                    &F3Config::default(),
+                    &[],
+                    defaults.clone(),
+                    sources(ChainDefault, ChainDefault, ChainDefault),
+                ),
+                (
+                    "finality from config",
+                    &finality_config,
+                    &[],
+                    finality(200),
+                    sources(Config, ChainDefault, ChainDefault),
+                ),
+                (
+                    "finality from env",
+                    &F3Config::default(),
+                    &[(FOREST_F3_FINALITY, "300")],
+                    finality(300),
+                    sources(from_env(FOREST_F3_FINALITY), ChainDefault, ChainDefault),
+                ),
+                (
+                    "finality from env over config",
+                    &finality_config,
+                    &[(FOREST_F3_FINALITY, "300")],
+                    finality(300),
+                    sources(from_env(FOREST_F3_FINALITY), ChainDefault, ChainDefault),
+                ),
+                (
+                    "malformed finality env",
+                    &finality_config,
+                    &[(FOREST_F3_FINALITY, "-300")],
+                    finality(200),
+                    ignoring(
+                        sources(Config, ChainDefault, ChainDefault),
+                        FOREST_F3_FINALITY,
+                        "-300",
+                    ),
+                ),
+                (
+                    "empty finality env",
+                    &finality_config,
+                    &[(FOREST_F3_FINALITY, "")],
+                    finality(200),
+                    sources(Config, ChainDefault, ChainDefault),
+                ),
+                (
+                    "bootstrap epoch from config",
+                    &bootstrap_epoch_config,
+                    &[],
+                    bootstrap_epoch(2000),
+                    sources(ChainDefault, Config, ChainDefault),
+                ),
+                (
+                    "bootstrap epoch from env",
+                    &F3Config::default(),
+                    &[(FOREST_F3_BOOTSTRAP_EPOCH, "3000")],
+                    bootstrap_epoch(3000),
+                    sources(
+                        ChainDefault,
+                        from_env(FOREST_F3_BOOTSTRAP_EPOCH),
+                        ChainDefault,
+                    ),
+                ),
+                (
+                    "bootstrap epoch from env over config",
+                    &bootstrap_epoch_config,
+                    &[(FOREST_F3_BOOTSTRAP_EPOCH, "3000")],
+                    bootstrap_epoch(3000),
+                    sources(
+                        ChainDefault,
+                        from_env(FOREST_F3_BOOTSTRAP_EPOCH),
+                        ChainDefault,
+                    ),
+                ),
+                (
+                    "malformed bootstrap epoch env",
+                    &bootstrap_epoch_config,
+                    &[(FOREST_F3_BOOTSTRAP_EPOCH, "3e3")],
+                    bootstrap_epoch(2000),
+                    ignoring(
+                        sources(ChainDefault, Config, ChainDefault),
+                        FOREST_F3_BOOTSTRAP_EPOCH,
+                        "3e3",
+                    ),
+                ),
+                (
+                    "power table from config",
+                    &power_table_config,
+                    &[],
+                    power_table(Some(config_cid)),
+                    sources(ChainDefault, ChainDefault, Config),
+                ),
+                (
+                    "power table from env",
+                    &F3Config::default(),
+                    &[(FOREST_F3_INITIAL_POWER_TABLE, ENV_CID)],
+                    power_table(Some(env_cid)),
+                    sources(
+                        ChainDefault,
+                        ChainDefault,
+                        from_env(FOREST_F3_INITIAL_POWER_TABLE),
+                    ),
+                ),
+                (
+                    "power table from env over config",
+                    &power_table_config,
+                    &[(FOREST_F3_INITIAL_POWER_TABLE, ENV_CID)],
+                    power_table(Some(env_cid)),
+                    sources(
+                        ChainDefault,
+                        ChainDefault,
+                        from_env(FOREST_F3_INITIAL_POWER_TABLE),
+                    ),
+                ),
+                (
+                    "malformed power table env",
+                    &power_table_config,
+                    &[(FOREST_F3_INITIAL_POWER_TABLE, "bafy")],
+                    power_table(Some(config_cid)),
+                    ignoring(
+                        sources(ChainDefault, ChainDefault, Config),
+                        FOREST_F3_INITIAL_POWER_TABLE,
+                        "bafy",
+                    ),
+                ),
+                (
+                    // Unlike other empty values, it counts as setting the option
+                    "power table unset by env",
+                    &power_table_config,
+                    &[(FOREST_F3_INITIAL_POWER_TABLE, "")],
+                    power_table(None),
+                    sources(
+                        ChainDefault,
+                        ChainDefault,
+                        from_env(FOREST_F3_INITIAL_POWER_TABLE),
+                    ),
+                ),
+                (
+                    "everything from config",
+                    &all_config,
+                    &[],
+                    with(200, 2000, Some(config_cid)),
+                    sources(Config, Config, Config),
+                ),
+                (
+                    "everything from env",
+                    &all_config,
+                    &[
+                        (FOREST_F3_FINALITY, "300"),
+                        (FOREST_F3_BOOTSTRAP_EPOCH, "3000"),
+                        (FOREST_F3_INITIAL_POWER_TABLE, ENV_CID),
+                    ],
+                    with(300, 3000, Some(env_cid)),
+                    sources(
+                        from_env(FOREST_F3_FINALITY),
+                        from_env(FOREST_F3_BOOTSTRAP_EPOCH),
+                        from_env(FOREST_F3_INITIAL_POWER_TABLE),
+                    ),
+                ),
+                (
+                    "each option from a different source",
+                    &bootstrap_epoch_config,
+                    &[(FOREST_F3_FINALITY, "300")],
+                    with(300, 2000, defaults.initial_power_table),
+                    sources(from_env(FOREST_F3_FINALITY), Config, ChainDefault),
+                ),
+                (
+                    "every malformed env",
+                    &all_config,
+                    &[
+                        (FOREST_F3_FINALITY, "0"),
+                        (FOREST_F3_BOOTSTRAP_EPOCH, "1.5"),
+                        (FOREST_F3_INITIAL_POWER_TABLE, "not-a-cid"),
+                    ],
+                    with(200, 2000, Some(config_cid)),
+                    F3OptionsReport {
+                        ignored_env: BTreeMap::from([
+                            (FOREST_F3_FINALITY.into(), "0".into()),
+                            (FOREST_F3_BOOTSTRAP_EPOCH.into(), "1.5".into()),
+                            (FOREST_F3_INITIAL_POWER_TABLE.into(), "not-a-cid".into()),
+                        ]),
+                        ..sources(Config, Config, Config)
+                    },
+                ),
+            ];
+            for (case, f3_config, vars, expected_options, expected_sources) in cases {
+                let (options, sources) =
+                    f3_sidecar_params_with_sources(&chain_config, f3_config, env(vars));
+                assert_eq!(options, expected_options, "{network}: {case}");
+                assert_eq!(sources, expected_sources, "{network}: {case}");
+            }
+        }
+
+        // The other sidecar settings
+        let chain_config = ChainConfig::calibnet();
+        assert_eq!(
+            default_f3_root(Path::new("/data"), &chain_config.network),
+            PathBuf::from("/data/f3/calibnet")
+        );
+        let f3_config = F3Config {
+            enabled: Some(false),
+            root: Some("/from/config".into()),
+            rpc_endpoint: Some("127.0.0.1:1000".into()),
+            log_level: Some(F3LogLevel::Warn),
+            ..Default::default()
+        };
+        let all_env = [
+            (FOREST_F3_ROOT, "/from/env"),
+            (FOREST_F3_SIDECAR_RPC_ENDPOINT, "127.0.0.1:3000"),
+            (FOREST_F3_LOG_LEVEL, "error"),
+        ];
+        let malformed_env = [(FOREST_F3_ROOT, ""), (FOREST_F3_LOG_LEVEL, "verbose")];
+        for (case, f3_config, configured_rpc_endpoint, vars, root, rpc_endpoint, log_level) in [
+            (
+                "defaults",
+                &F3Config::default(),
+                None,
+                &[][..],
+                "/default",
+                DEFAULT_F3_SIDECAR_RPC_ENDPOINT,
+                F3LogLevel::Info,
+            ),
+            (
+                "config",
+                &f3_config,
+                Some("127.0.0.1:1000"),
+                &[],
+                "/from/config",
+                "127.0.0.1:1000",
+                F3LogLevel::Warn,
+            ),
+            (
+                "env over config",
+                &f3_config,
+                Some("127.0.0.1:1000"),
+                &all_env,
+                "/from/env",
+                "127.0.0.1:3000",
+                F3LogLevel::Error,
+            ),
+            (
+                "malformed env",
+                &f3_config,
+                Some("127.0.0.1:1000"),
+                &malformed_env,
+                "/from/config",
+                "127.0.0.1:1000",
+                F3LogLevel::Warn,
+            ),
+        ] {
+            assert_eq!(
+                f3_root(f3_config, "/default".into(), env(vars)),
+                PathBuf::from(root),
+                "{case}"
+            );
+            assert_eq!(
+                f3_rpc_endpoint(configured_rpc_endpoint, env(vars)),
+                rpc_endpoint,
+                "{case}"
+            );
+            assert_eq!(f3_log_level(f3_config, env(vars)), log_level, "{case}");
+        }
+        assert!(chain_config.f3_enabled);
+        let mut configured_chain_config = chain_config.clone();
+        apply_f3_config(&mut configured_chain_config, &f3_config);
+        assert!(!configured_chain_config.f3_enabled);
+    }
+
+    #[test]
+    fn test_f3_options_report() {
+        let provenance = F3OptionsProvenance {
+            chain_finality: Provenance::Env,
+            bootstrap_epoch: Provenance::Config,
+            initial_power_table: Provenance::Derived,
+            ignored_env: BTreeMap::from([(FOREST_F3_BOOTSTRAP_EPOCH, "1.5".into())]),
+        };
+        let report = F3OptionsReport::from(&provenance);
+        assert_eq!(
+            report,
+            F3OptionsReport {
+                finality: F3OptionSource::EnvVar(FOREST_F3_FINALITY.into()),
+                bootstrap_epoch: F3OptionSource::Config,
+                initial_power_table: F3OptionSource::Derived,
+                ignored_env: BTreeMap::from([(FOREST_F3_BOOTSTRAP_EPOCH.into(), "1.5".into())]),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "Finality": { "EnvVar": FOREST_F3_FINALITY },
+                "BootstrapEpoch": "Config",
+                "InitialPowerTable": "Derived",
+                "IgnoredEnv": { FOREST_F3_BOOTSTRAP_EPOCH: "1.5" },
+            })
+        );
+        assert_eq!(
+            F3OptionsReport::from(&F3OptionsProvenance::default()).finality,
+            F3OptionSource::ChainDefault
+        );
+    }
+
+    #[test]
+    fn test_sidecar_args_plumbing() {
+        use parking_lot::Mutex;
+
+        static RUN_ARGS: Mutex<Vec<SidecarArgs>> = Mutex::new(vec![]);
+
+        struct StubFfi;
+
+        impl SidecarFfi for StubFfi {
+            fn run(args: &SidecarArgs) -> bool {
+                RUN_ARGS.lock().push(args.clone());
+                true
+            }
+
+            fn stop() -> bool {
+                true
+            }
+        }
+
+        let args = |log_level, log_format| {
+            sidecar_args(
+                "http://127.0.0.1:2345/rpc/v1".into(),
+                "jwt".into(),
+                "127.0.0.1:23456".into(),
+                String::new(),
+                100,
+                900,
+                "/var/lib/f3".into(),
+                log_level,
+                log_format,
+            )
+        };
+        for (log_level, log_format) in [
+            (F3LogLevel::Warn, F3LogFormat::Plaintext),
+            (F3LogLevel::Debug, F3LogFormat::Json),
+            (F3LogLevel::Error, F3LogFormat::Tracing),
+        ] {
+            let args = args(log_level, log_format);
+            // The pipe is only created once the sidecar is run
+            assert!(args.log_file.is_empty());
+            let sidecar = GoF3Sidecar::<StubFfi>::new(args);
+            // Restarts reuse the pipe
+            supervisor::F3Sidecar::run(&sidecar).unwrap();
+            supervisor::F3Sidecar::run(&sidecar).unwrap();
+        }
+
+        let run_args = std::mem::take(&mut *RUN_ARGS.lock());
+        let logging = run_args
+            .iter()
+            .step_by(2)
+            .map(|args| (args.log_level.as_str(), args.log_format.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            logging,
+            [("warn", "plaintext"), ("debug", "json"), ("error", "json")]
+        );
+        assert!(run_args[0].log_file.is_empty());
+        assert!(run_args[2].log_file.is_empty());
+        // Forwarded to `tracing` through a pipe
+        assert!(run_args[4].log_file.starts_with("/dev/fd/"));
+        for pair in run_args.chunks(2) {
+            assert_eq!(pair[0], pair[1]);
+        }
+        for args in &run_args {
+            assert_eq!(args.bootstrap_epoch, 100);
+            assert_eq!(args.finality, 900);
+            assert_eq!(args.f3_root, "/var/lib/f3");
+        }
+    }
+
+    #[test]
+    fn test_rpc_endpoint() {
+        record_rpc_endpoint("127.0.0.1:23456".into());
+        if is_sidecar_ffi_compiled() {
+            assert_eq!(rpc_endpoint().as_deref(), Some("127.0.0.1:23456"));
+        } else {
+            assert_eq!(rpc_endpoint(), None);
+        }
+    }
+
+    #[test]
+    fn test_reconcile_initial_power_table() {
+        let derived = Cid::new_v1(
+            fvm_ipld_encoding::DAG_CBOR,
+            MultihashCode::Blake2b256.digest(b"derived"),
+        );
+        let configured = Cid::new_v1(
+            fvm_ipld_encoding::DAG_CBOR,
+            MultihashCode::Blake2b256.digest(b"configured"),
+        );
+        let options = |initial_power_table| F3Options {
+            chain_finality: 900,
+            bootstrap_epoch: 2000,
+            initial_power_table,
+        };
+
+        let mut unset = options(None);
+        reconcile_initial_power_table(&mut unset, derived);
+        assert_eq!(unset, options(Some(derived)));
+
+        let mut mismatched = options(Some(configured));
+        reconcile_initial_power_table(&mut mismatched, derived);
+        assert_eq!(mismatched, options(Some(configured)));
+
+        let mut matching = options(Some(derived));
+        reconcile_initial_power_table(&mut matching, derived);
+        assert_eq!(matching, options(Some(derived)));
+    }
+
+    #[test]
+    fn test_f3_options_validate() {
+        let chain_config = ChainConfig::calibnet();
+        let valid = F3Options {
+            chain_finality: 900,
+            bootstrap_epoch: 2_081_674,
+            initial_power_table: chain_config.f3_initial_power_table,
+        };
+        assert_eq!(valid.validate(&chain_config, None), Ok(()));
+        assert_eq!(valid.validate(&chain_config, Some(3_000_000)), Ok(()));
+        let disabled = F3Options {
+            bootstrap_epoch: F3_BOOTSTRAP_EPOCH_DISABLED,
+            initial_power_table: None,
+            ..valid.clone()
+        };
+        assert_eq!(disabled.validate(&chain_config, Some(3_000_000)), Ok(()));
+        let not_bootstrapped_yet = F3Options {
+            initial_power_table: None,
+            ..valid.clone()
+        };
+        assert_eq!(
+            not_bootstrapped_yet.validate(&chain_config, Some(2_000_000)),
+            Ok(())
+        );
+
+        let raw_cid = Cid::new_v1(
+            fvm_ipld_encoding::IPLD_RAW,
+            MultihashCode::Blake2b256.digest(b"power table"),
+        );
+        let sha256_cid = Cid::new_v1(
+            fvm_ipld_encoding::DAG_CBOR,
+            MultihashCode::Sha2_256.digest(b"power table"),
+        );
+        let cases = [
+            (
+                F3Options {
+                    chain_finality: 0,
+                    ..valid.clone()
+                },
+                F3OptionsError::NonPositiveFinality(0),
+            ),
+            (
+                F3Options {
+                    chain_finality: -900,
+                    ..valid.clone()
+                },
+                F3OptionsError::NonPositiveFinality(-900),
+            ),
+            (
+                F3Options {
+                    bootstrap_epoch: -5,
+                    ..valid.clone()
+                },
+                F3OptionsError::InvalidBootstrapEpoch(-5),
+            ),
+            (
+                F3Options {
+                    bootstrap_epoch: 100,
+                    ..valid.clone()
+                },
+                F3OptionsError::FinalityExceedsBootstrapEpoch {
+                    finality: 900,
+                    bootstrap_epoch: 100,
+                },
+            ),
+            (
+                F3Options {
+                    initial_power_table: Some(raw_cid),
+                    ..valid.clone()
+                },
+                F3OptionsError::InvalidPowerTableCodec(raw_cid),
+            ),
+            (
+                F3Options {
+                    initial_power_table: Some(sha256_cid),
+                    ..valid.clone()
+                },
+                F3OptionsError::InvalidPowerTableMultihash(sha256_cid),
+            ),
+        ];
+        for (options, expected) in cases {
+            assert_eq!(options.validate(&chain_config, None), Err(expected));
+        }
+        assert_eq!(
+            not_bootstrapped_yet.validate(&chain_config, Some(3_000_000)),
+            Err(F3OptionsError::PowerTableUnsetAfterBootstrap {
+                bootstrap_epoch: 2_081_674,
+                head_epoch: 3_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_f3_config_validate() {
+        assert!(F3Config::default().validate().is_ok());
+        let valid = F3Config {
+            enabled: Some(true),
+            permanently_disabled: Some(false),
+            finality: Some(900),
+            bootstrap_epoch: Some(-1),
+            initial_power_table: None,
+            root: Some("/var/lib/f3".into()),
+            rpc_endpoint: Some("localhost:23456".into()),
+            log_level: Some(F3LogLevel::Debug),
+            log_format: Some(F3LogFormat::Json),
+            launch_margin: Some(0),
+        };
+        assert!(valid.validate().is_ok());
+        for invalid in [
+            F3Config {
+                finality: Some(0),
+                ..Default::default()
+            },
+            F3Config {
+                root: Some("".into()),
+                ..Default::default()
+            },
+            F3Config {
+                launch_margin: Some(-1),
+                ..Default::default()
+            },
+            F3Config {
+                rpc_endpoint: Some("localhost".into()),
+                ..Default::default()
+            },
+            F3Config {
+                rpc_endpoint: Some(":23456".into()),
+                ..Default::default()
+            },
+        ] {
+            assert!(invalid.validate().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn test_sidecar_launch_epoch() {
+        assert_eq!(sidecar_launch_epoch(2_081_674, 2880), 2_078_794);
+        assert_eq!(sidecar_launch_epoch(1000, 0), 1000);
+        assert_eq!(
+            sidecar_launch_epoch(F3_BOOTSTRAP_EPOCH_DISABLED, 2880),
+            -2881
+        );
+        assert_eq!(sidecar_launch_epoch(i64::MIN, 1), i64::MIN);
+    }
+
+    #[test]
+    fn test_f3_permanently_disabled() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |var: &'static str| {
+                vars.iter()
+                    .find(|(k, _)| *k == var)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        let mut chain_config = ChainConfig::calibnet();
+        chain_config.f3_enabled = true;
+        assert!(sidecar_ffi_requested(&chain_config, env(&[])));
+        assert!(sidecar_ffi_requested(
+            &chain_config,
+            env(&[(FOREST_F3_SIDECAR_FFI_ENABLED, "1")])
+        ));
+
+        // Wins over FOREST_F3_SIDECAR_FFI_ENABLED
+        let kill_switch = env(&[
+            (FOREST_F3_SIDECAR_FFI_ENABLED, "1"),
+            (FOREST_F3_PERMANENTLY_DISABLED, "1"),
+        ]);
+        assert!(f3_permanently_disabled(&chain_config, kill_switch));
+        assert!(!sidecar_ffi_requested(&chain_config, kill_switch));
+
+        // and so does f3.permanently_disabled, unless the variable says otherwise
+        apply_f3_config(
+            &mut chain_config,
+            &F3Config {
+                permanently_disabled: Some(true),
+                ..Default::default()
+            },
+        );
+        assert!(!sidecar_ffi_requested(
+            &chain_config,
+            env(&[(FOREST_F3_SIDECAR_FFI_ENABLED, "1")])
+        ));
+        assert!(sidecar_ffi_requested(
+            &chain_config,
+            env(&[(FOREST_F3_PERMANENTLY_DISABLED, "0")])
+        ));
+    }
 
     #[test]
     fn test_get_f3_sidecar_params() {
         let chain_config = ChainConfig::calibnet();
         // No environment variable overrides
         assert_eq!(
-            get_f3_sidecar_params(&chain_config),
+            get_f3_sidecar_params(&chain_config, &F3Config::default()),
             F3Options {
                 chain_finality: chain_config.policy.chain_finality,
                 bootstrap_epoch: chain_config.f3_bootstrap_epoch,
@@ -145,7 +1704,7 @@ mod tests {
             std::env::set_var("FOREST_F3_BOOTSTRAP_EPOCH", "100");
         }
         assert_eq!(
-            get_f3_sidecar_params(&chain_config),
+            get_f3_sidecar_params(&chain_config, &F3Config::default()),
             F3Options {
                 chain_finality: 100,
                 bootstrap_epoch: 100,
@@ -159,7 +1718,7 @@ mod tests {
         // Unset initial power table
         unsafe { std::env::set_var("FOREST_F3_INITIAL_POWER_TABLE", "") };
         assert_eq!(
-            get_f3_sidecar_params(&chain_config),
+            get_f3_sidecar_params(&chain_config, &F3Config::default()),
             F3Options {
                 chain_finality: 100,
                 bootstrap_epoch: 100,
@@ -0,0 +1,184 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! The F3 data directory holds the finality certificates of a single chain. To
+//! avoid feeding the certificates of a chain to the sidecar of another, e.g.
+//! after switching from calibnet to mainnet with the same `f3.root`, the chain
+//! is recorded in a marker file on first launch, and checked on every launch.
+
+use cid::Cid;
+use serde::{Deserialize, Serialize};
+use serde_with::{DisplayFromStr, serde_as};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+const MARKER_FILE_NAME: &str = "chain.json";
+
+/// The chain an F3 data directory belongs to.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct F3RootMarker {
+    pub network: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub genesis: Cid,
+}
+
+impl std::fmt::Display for F3RootMarker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (genesis {})", self.network, self.genesis)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum F3RootError {
+    #[error(
+        "F3 data directory {} belongs to {found}, not to {expected}. Use another `f3.root` for this chain, or pass `--f3-force-root` to reuse it",
+        root.display()
+    )]
+    Mismatch {
+        root: PathBuf,
+        found: F3RootMarker,
+        expected: F3RootMarker,
+    },
+    #[error("malformed F3 data directory marker {}: {source}", path.display())]
+    Malformed {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// What [`check_f3_root`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum F3RootCheck {
+    /// The marker did not exist, and was written.
+    Created,
+    /// The marker matches the chain.
+    Matched,
+    /// The marker was for another chain, and was overwritten because of `force`.
+    Forced,
+}
+
+/// Checks that `root` belongs to `expected`, creating `root` and its marker on
+/// first launch. With `force`, a marker for another chain is overwritten instead
+/// of rejected.
+pub fn check_f3_root(
+    root: &Path,
+    expected: &F3RootMarker,
+    force: bool,
+) -> Result<F3RootCheck, F3RootError> {
+    let path = root.join(MARKER_FILE_NAME);
+    let found =
+        match std::fs::read(&path) {
+            Ok(bytes) => Some(serde_json::from_slice::<F3RootMarker>(&bytes).map_err(
+                |source| F3RootError::Malformed {
+                    path: path.clone(),
+                    source,
+                },
+            )?),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+    let check = match found {
+        None => F3RootCheck::Created,
+        Some(found) if &found == expected => return Ok(F3RootCheck::Matched),
+        Some(found) if force => {
+            tracing::warn!(
+                "Reusing F3 data directory {} of {found} for {expected}",
+                root.display()
+            );
+            F3RootCheck::Forced
+        }
+        Some(found) => {
+            return Err(F3RootError::Mismatch {
+                root: root.to_owned(),
+                found,
+                expected: expected.clone(),
+            });
+        }
+    };
+    std::fs::create_dir_all(root)?;
+    std::fs::write(
+        &path,
+        serde_json::to_vec_pretty(expected).map_err(io::Error::from)?,
+    )?;
+    Ok(check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::multihash::prelude::*;
+
+    fn marker(network: &str) -> F3RootMarker {
+        F3RootMarker {
+            network: network.into(),
+            genesis: Cid::new_v1(
+                fvm_ipld_encoding::DAG_CBOR,
+                MultihashCode::Blake2b256.digest(network.as_bytes()),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_check_f3_root() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let root = data_dir.path().join("f3/calibnet");
+        let calibnet = marker("calibnet");
+        let mainnet = marker("mainnet");
+
+        // fresh
+        assert_eq!(
+            check_f3_root(&root, &calibnet, false).unwrap(),
+            F3RootCheck::Created
+        );
+        assert!(root.join(MARKER_FILE_NAME).is_file());
+
+        // reuse by the same chain
+        assert_eq!(
+            check_f3_root(&root, &calibnet, false).unwrap(),
+            F3RootCheck::Matched
+        );
+
+        // another network
+        let error = check_f3_root(&root, &mainnet, false).unwrap_err();
+        assert!(
+            matches!(&error, F3RootError::Mismatch { found, expected, .. } if *found == calibnet && *expected == mainnet),
+            "{error}"
+        );
+        assert!(error.to_string().contains("--f3-force-root"), "{error}");
+
+        // same network name, another genesis
+        let reset = F3RootMarker {
+            genesis: mainnet.genesis,
+            ..calibnet.clone()
+        };
+        assert!(matches!(
+            check_f3_root(&root, &reset, false),
+            Err(F3RootError::Mismatch { .. })
+        ));
+
+        // forced
+        assert_eq!(
+            check_f3_root(&root, &mainnet, true).unwrap(),
+            F3RootCheck::Forced
+        );
+        assert_eq!(
+            check_f3_root(&root, &mainnet, false).unwrap(),
+            F3RootCheck::Matched
+        );
+        assert!(check_f3_root(&root, &calibnet, false).is_err());
+    }
+
+    #[test]
+    fn test_check_f3_root_malformed() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join(MARKER_FILE_NAME), "not json").unwrap();
+        assert!(matches!(
+            check_f3_root(root.path(), &marker("calibnet"), false),
+            Err(F3RootError::Malformed { .. })
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Logging of the F3 sidecar.
+//!
+//! By default, the sidecar writes its logs as JSON lines into a pipe, and every
+//! line is re-emitted as a [`tracing`] event with the [`TARGET`] target, so that
+//! they can be filtered with `RUST_LOG`, e.g. `RUST_LOG=info,f3-sidecar=warn`.
+//! The `logger` field of the event holds the name of the Go logger.
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead as _};
+
+/// Target of the events forwarded from the sidecar.
+pub const TARGET: &str = "f3-sidecar";
+
+/// Minimum level of the sidecar's own logs, before they are forwarded.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+)]
+#[cfg_attr(test, derive(derive_quickcheck_arbitrary::Arbitrary))]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum F3LogLevel {
+    Debug,
+    #[default]
+    Info,
+    #[strum(to_string = "warn", serialize = "warning")]
+    Warn,
+    Error,
+}
+
+/// How the sidecar's logs are written.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+)]
+#[cfg_attr(test, derive(derive_quickcheck_arbitrary::Arbitrary))]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum F3LogFormat {
+    /// Forwarded to [`tracing`], see the [module documentation](self).
+    #[default]
+    Tracing,
+    /// Written to stderr by the sidecar as plain text, bypassing [`tracing`].
+    Plaintext,
+    /// Written to stderr by the sidecar as JSON lines, bypassing [`tracing`].
+    Json,
+}
+
+impl F3LogFormat {
+    /// The `go-log` format the sidecar is asked to write.
+    pub fn go_format(self) -> &'static str {
+        match self {
+            Self::Tracing | Self::Json => "json",
+            Self::Plaintext => "plaintext",
+        }
+    }
+}
+
+/// Creates a pipe for the sidecar to write its logs to, and a thread forwarding
+/// them to [`tracing`]. Returns the path of the write end of the pipe.
+pub fn spawn_forwarder() -> io::Result<String> {
+    use std::os::fd::IntoRawFd as _;
+
+    let (reader, writer) = io::pipe()?;
+    std::thread::Builder::new()
+        .name("f3-sidecar-log".into())
+        .spawn(move || {
+            for line in io::BufReader::new(reader).lines() {
+                match line {
+                    Ok(line) => forward(&line),
+                    Err(e) => {
+                        tracing::warn!("Failed to read F3 sidecar logs: {e}");
+                        break;
+                    }
+                }
+            }
+        })?;
+    // The write end stays open for the lifetime of the process, as the sidecar
+    // may be restarted.
+    Ok(format!("/dev/fd/{}", writer.into_raw_fd()))
+}
+
+/// A log line written by `go-log` in JSON format.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+struct GoLogLine {
+    level: String,
+    #[serde(default)]
+    logger: String,
+    #[serde(default)]
+    msg: String,
+}
+
+fn parse(line: &str) -> Option<(tracing::Level, GoLogLine)> {
+    let line: GoLogLine = serde_json::from_str(line).ok()?;
+    let level = match line.level.as_str() {
+        "debug" => tracing::Level::DEBUG,
+        "info" => tracing::Level::INFO,
+        "warn" => tracing::Level::WARN,
+        // `dpanic`, `panic` and `fatal`
+        _ => tracing::Level::ERROR,
+    };
+    Some((level, line))
+}
+
+fn forward(line: &str) {
+    let Some((level, GoLogLine { logger, msg, .. })) = parse(line) else {
+        // e.g. a panic
+        tracing::info!(target: TARGET, "{line}");
+        return;
+    };
+    match level {
+        tracing::Level::DEBUG => tracing::debug!(target: TARGET, logger = %logger, "{msg}"),
+        tracing::Level::INFO => tracing::info!(target: TARGET, logger = %logger, "{msg}"),
+        tracing::Level::WARN => tracing::warn!(target: TARGET, logger = %logger, "{msg}"),
+        _ => tracing::error!(target: TARGET, logger = %logger, "{msg}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr as _;
+
+    #[test]
+    fn test_parse_log_level() {
+        for (s, expected) in [
+            ("debug", F3LogLevel::Debug),
+            ("info", F3LogLevel::Info),
+            ("INFO", F3LogLevel::Info),
+            ("warn", F3LogLevel::Warn),
+            ("warning", F3LogLevel::Warn),
+            ("Error", F3LogLevel::Error),
+        ] {
+            assert_eq!(F3LogLevel::from_str(s).unwrap(), expected, "{s}");
+        }
+        for s in ["", "trace", "fatal", "verbose"] {
+            assert!(F3LogLevel::from_str(s).is_err(), "{s}");
+        }
+        // What `go-log` understands
+        assert_eq!(F3LogLevel::Warn.to_string(), "warn");
+        assert_eq!(F3LogLevel::default(), F3LogLevel::Info);
+    }
+
+    #[test]
+    fn test_parse_log_format() {
+        assert_eq!(F3LogFormat::default(), F3LogFormat::Tracing);
+        assert_eq!(F3LogFormat::from_str("json").unwrap(), F3LogFormat::Json);
+        assert_eq!(
+            F3LogFormat::from_str("Plaintext").unwrap(),
+            F3LogFormat::Plaintext
+        );
+        assert!(F3LogFormat::from_str("xml").is_err());
+    }
+
+    #[test]
+    fn test_parse_go_log_line() {
+        let (level, line) = parse(
+            r#"{"level":"warn","ts":"2025-01-01T00:00:00.000Z","logger":"f3/sidecar","caller":"sidecar/run.go:42","msg":"waiting for Forest RPC server"}"#,
+        )
+        .unwrap();
+        assert_eq!(level, tracing::Level::WARN);
+        assert_eq!(
+            line,
+            GoLogLine {
+                level: "warn".into(),
+                logger: "f3/sidecar".into(),
+                msg: "waiting for Forest RPC server".into(),
+            }
+        );
+        assert_eq!(
+            parse(r#"{"level":"fatal","msg":"boom"}"#).unwrap().0,
+            tracing::Level::ERROR
+        );
+        assert!(parse("panic: boom").is_none());
+    }
+}
@@ -0,0 +1,521 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Keeps the F3 sidecar alive.
+//!
+//! The sidecar runs on a blocking thread until it exits, which it should never
+//! do on its own. The supervisor restarts it with exponential backoff, gives up
+//! after [`Backoff::max_restarts`] consecutive failures, and publishes its
+//! state in [`sidecar_status`]. The sidecar is controlled with an [`F3Handle`].
+//!
+//! The first start can be delayed until the chain head reaches a given epoch,
+//! see [`F3Handle::spawn_at_epoch`], so that a node that is still far behind
+//! doesn't run a sidecar that can't make progress.
+
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tokio::task::AbortHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::lotus_json::lotus_json_with_self;
+use crate::shim::clock::ChainEpoch;
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, strum::Display,
+)]
+pub enum F3SidecarRunState {
+    /// The sidecar is disabled, or hasn't been started yet.
+    #[default]
+    NotStarted,
+    /// The sidecar starts once the chain head reaches
+    /// [`F3SidecarStatus::launch_epoch`].
+    WaitingForSync,
+    /// The sidecar is up.
+    Running,
+    /// The sidecar exited, and is waiting to be restarted.
+    Restarting,
+    /// The sidecar failed too many times in a row, and won't be restarted.
+    GaveUp,
+    /// The node is shutting down.
+    Stopped,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct F3SidecarStatus {
+    pub state: F3SidecarRunState,
+    /// Total number of restarts since the node started.
+    pub restarts: u32,
+    /// Why the sidecar last exited.
+    pub last_error: Option<String>,
+    /// The chain head epoch the sidecar waits for, see
+    /// [`F3SidecarRunState::WaitingForSync`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_epoch: Option<ChainEpoch>,
+}
+lotus_json_with_self!(F3SidecarStatus);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backoff {
+    /// Delay before the first restart, doubled on every consecutive failure.
+    pub initial: Duration,
+    /// Upper bound of the delay between restarts.
+    pub max: Duration,
+    /// Consecutive failures after which the supervisor gives up.
+    pub max_restarts: u32,
+    /// A sidecar that ran for at least this long is considered healthy, and
+    /// resets the count of consecutive failures.
+    pub reset_after: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(5 * 60),
+            max_restarts: 10,
+            reset_after: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before restart number `attempt`, counting from zero.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max)
+    }
+}
+
+/// How long the daemon waits for the sidecar to stop on shutdown.
+pub const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The F3 sidecar of this node, if it has been started.
+pub static F3_SIDECAR: OnceLock<F3Handle> = OnceLock::new();
+
+/// The state of the F3 sidecar supervisor.
+pub fn sidecar_status() -> F3SidecarStatus {
+    F3_SIDECAR.get().map(F3Handle::status).unwrap_or_default()
+}
+
+/// A sidecar that can be supervised. Implemented by the Go sidecar over FFI.
+pub trait F3Sidecar: Send + Sync + 'static {
+    /// Blocks until the sidecar exits.
+    fn run(&self) -> anyhow::Result<()>;
+    /// Asks the running sidecar to exit, making [`F3Sidecar::run`] return.
+    fn stop(&self);
+}
+
+/// Controls a supervised sidecar.
+pub struct F3Handle {
+    status: Arc<RwLock<F3SidecarStatus>>,
+    /// When the running sidecar was last (re)started.
+    running_since: Arc<RwLock<Option<Instant>>>,
+    shutdown: CancellationToken,
+    exited: watch::Receiver<bool>,
+    supervisor: AbortHandle,
+}
+
+impl F3Handle {
+    /// Run `sidecar` on a blocking thread, restarting it according to
+    /// `backoff` whenever it exits.
+    pub fn spawn<S: F3Sidecar>(sidecar: Arc<S>, backoff: Backoff) -> Self {
+        Self::spawn_inner(sidecar, backoff, None)
+    }
+
+    /// Like [`F3Handle::spawn`], but only once `head`, the epoch of the chain
+    /// head, reaches `launch_epoch`.
+    pub fn spawn_at_epoch<S: F3Sidecar>(
+        sidecar: Arc<S>,
+        backoff: Backoff,
+        head: watch::Receiver<ChainEpoch>,
+        launch_epoch: ChainEpoch,
+    ) -> Self {
+        Self::spawn_inner(sidecar, backoff, Some((head, launch_epoch)))
+    }
+
+    fn spawn_inner<S: F3Sidecar>(
+        sidecar: Arc<S>,
+        backoff: Backoff,
+        launch: Option<(watch::Receiver<ChainEpoch>, ChainEpoch)>,
+    ) -> Self {
+        let status = Arc::new(RwLock::new(F3SidecarStatus::default()));
+        let running_since = Arc::new(RwLock::new(None));
+        let shutdown = CancellationToken::new();
+        let (exited_tx, exited) = watch::channel(false);
+        let supervisor = tokio::spawn({
+            let status = status.clone();
+            let running_since = running_since.clone();
+            let shutdown = shutdown.clone();
+            async move {
+                let launched = match launch {
+                    Some((head, launch_epoch)) => {
+                        wait_for_launch(head, launch_epoch, &status, &shutdown).await
+                    }
+                    None => true,
+                };
+                if launched {
+                    supervise(sidecar, &backoff, &status, &running_since, &shutdown).await;
+                } else {
+                    status.write().state = F3SidecarRunState::Stopped;
+                }
+                *running_since.write() = None;
+                exited_tx.send_replace(true);
+            }
+        })
+        .abort_handle();
+        Self {
+            status,
+            running_since,
+            shutdown,
+            exited,
+            supervisor,
+        }
+    }
+
+    pub fn status(&self) -> F3SidecarStatus {
+        self.status.read().clone()
+    }
+
+    /// How long the sidecar has been up since it was last (re)started, or
+    /// [`None`] when it is not running.
+    pub fn uptime(&self) -> Option<Duration> {
+        self.running_since.read().map(|since| since.elapsed())
+    }
+
+    /// Whether the sidecar is up, as opposed to restarting or stopped.
+    pub fn is_running(&self) -> bool {
+        self.status.read().state == F3SidecarRunState::Running
+    }
+
+    /// Wait until the supervisor is done, either because of [`F3Handle::stop`]
+    /// or because it gave up on the sidecar.
+    pub async fn await_exit(&self) {
+        let mut exited = self.exited.clone();
+        // An error means that the supervisor has been aborted
+        let _ = exited.wait_for(|exited| *exited).await;
+    }
+
+    /// Ask the sidecar to exit, and wait up to `timeout` for it to do so.
+    /// Past the timeout, the supervisor is aborted and the sidecar thread is
+    /// left to die with the process. Returns `true` on a graceful stop.
+    pub async fn stop(&self, timeout: Duration) -> bool {
+        self.shutdown.cancel();
+        if tokio::time::timeout(timeout, self.await_exit())
+            .await
+            .is_ok()
+        {
+            return true;
+        }
+        tracing::warn!(
+            "F3 sidecar didn't stop within {}, abandoning it",
+            humantime::format_duration(timeout)
+        );
+        self.supervisor.abort();
+        self.status.write().state = F3SidecarRunState::Stopped;
+        *self.running_since.write() = None;
+        false
+    }
+}
+
+/// Waits until `head` reaches `launch_epoch`. Returns `false` if the node shuts
+/// down first.
+async fn wait_for_launch(
+    mut head: watch::Receiver<ChainEpoch>,
+    launch_epoch: ChainEpoch,
+    status: &RwLock<F3SidecarStatus>,
+    shutdown: &CancellationToken,
+) -> bool {
+    let head_epoch = *head.borrow_and_update();
+    if head_epoch >= launch_epoch {
+        return true;
+    }
+    tracing::info!(
+        "Delaying the F3 sidecar until the chain head reaches epoch {launch_epoch}, currently at {head_epoch}"
+    );
+    {
+        let mut status = status.write();
+        status.state = F3SidecarRunState::WaitingForSync;
+        status.launch_epoch = Some(launch_epoch);
+    }
+    let launched = tokio::select! {
+        // An error means that the chain head is no longer tracked
+        reached = head.wait_for(|epoch| *epoch >= launch_epoch) => reached.is_ok(),
+        _ = shutdown.cancelled() => false,
+    };
+    status.write().launch_epoch = None;
+    launched
+}
+
+async fn supervise<S: F3Sidecar>(
+    sidecar: Arc<S>,
+    backoff: &Backoff,
+    status: &RwLock<F3SidecarStatus>,
+    running_since: &RwLock<Option<Instant>>,
+    shutdown: &CancellationToken,
+) {
+    let mut consecutive_failures = 0;
+    loop {
+        status.write().state = F3SidecarRunState::Running;
+        let started_at = Instant::now();
+        *running_since.write() = Some(started_at);
+        let mut run = tokio::task::spawn_blocking({
+            let sidecar = sidecar.clone();
+            move || sidecar.run()
+        });
+        let exit = tokio::select! {
+            exit = &mut run => exit,
+            _ = shutdown.cancelled() => {
+                sidecar.stop();
+                if let Ok(Err(e)) = run.await {
+                    tracing::warn!("F3 sidecar failed while stopping: {e:#}");
+                }
+                break;
+            }
+        };
+        *running_since.write() = None;
+        let error = match exit {
+            Ok(Ok(())) => "F3 sidecar exited unexpectedly".to_string(),
+            Ok(Err(e)) => format!("F3 sidecar failed: {e:#}"),
+            Err(e) => format!("F3 sidecar panicked: {e}"),
+        };
+        tracing::warn!("{error}");
+        if started_at.elapsed() >= backoff.reset_after {
+            consecutive_failures = 0;
+        }
+        let give_up = consecutive_failures >= backoff.max_restarts;
+        {
+            let mut status = status.write();
+            status.last_error = Some(error);
+            status.state = if give_up {
+                F3SidecarRunState::GaveUp
+            } else {
+                F3SidecarRunState::Restarting
+            };
+        }
+        if give_up {
+            tracing::error!(
+                "F3 sidecar failed {consecutive_failures} times in a row, giving up. Restart the node to retry."
+            );
+            return;
+        }
+        let delay = backoff.delay(consecutive_failures);
+        tracing::info!(
+            "Restarting F3 sidecar in {}",
+            humantime::format_duration(delay)
+        );
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(delay) => {}
+        }
+        consecutive_failures += 1;
+        status.write().restarts += 1;
+    }
+    status.write().state = F3SidecarRunState::Stopped;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    /// Fails `failures` times, then runs until stopped. A sidecar that
+    /// doesn't `honor_stop` runs until `released`.
+    struct StubSidecar {
+        failures: u32,
+        honor_stop: bool,
+        runs: AtomicU32,
+        stopped: AtomicBool,
+        released: AtomicBool,
+    }
+
+    impl StubSidecar {
+        fn new(failures: u32, honor_stop: bool) -> Arc<Self> {
+            Arc::new(Self {
+                failures,
+                honor_stop,
+                runs: AtomicU32::new(0),
+                stopped: AtomicBool::new(false),
+                released: AtomicBool::new(false),
+            })
+        }
+
+        fn runs(&self) -> u32 {
+            self.runs.load(Ordering::SeqCst)
+        }
+    }
+
+    impl F3Sidecar for StubSidecar {
+        fn run(&self) -> anyhow::Result<()> {
+            let run = self.runs.fetch_add(1, Ordering::SeqCst);
+            if run < self.failures {
+                anyhow::bail!("crash #{run}");
+            }
+            while !(self.honor_stop && self.stopped.load(Ordering::SeqCst))
+                && !self.released.load(Ordering::SeqCst)
+            {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            Ok(())
+        }
+
+        fn stop(&self) {
+            self.stopped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn backoff(max_restarts: u32) -> Backoff {
+        Backoff {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(4),
+            max_restarts,
+            reset_after: Duration::from_secs(60),
+        }
+    }
+
+    async fn wait_until(condition: impl Fn() -> bool) {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+    #[test]
+    fn backoff_delay() {
+        let backoff = Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(10),
+            ..Default::default()
+        };
+        let delays = (0..6)
+            .map(|i| backoff.delay(i).as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [1, 2, 4, 8, 10, 10]);
+        assert_eq!(backoff.delay(u32::MAX), backoff.max);
+    }
+
+    #[tokio::test]
+    async fn handle_gives_up() {
+        let sidecar = StubSidecar::new(u32::MAX, true);
+        let handle = F3Handle::spawn(sidecar.clone(), backoff(3));
+        handle.await_exit().await;
+
+        assert_eq!(sidecar.runs(), 4);
+        assert!(!handle.is_running());
+        assert_eq!(handle.uptime(), None);
+        let status = handle.status();
+        assert_eq!(status.state, F3SidecarRunState::GaveUp);
+        assert_eq!(status.restarts, 3);
+        assert_eq!(
+            status.last_error.as_deref(),
+            Some("F3 sidecar failed: crash #3")
+        );
+    }
+
+    #[tokio::test]
+    async fn handle_restarts_then_stops() {
+        let sidecar = StubSidecar::new(2, true);
+        let handle = F3Handle::spawn(sidecar.clone(), backoff(3));
+        wait_until(|| sidecar.runs() == 3 && handle.is_running()).await;
+
+        assert!(handle.uptime().is_some());
+        let status = handle.status();
+        assert_eq!(status.restarts, 2);
+        assert_eq!(
+            status.last_error.as_deref(),
+            Some("F3 sidecar failed: crash #1")
+        );
+
+        assert!(handle.stop(Duration::from_secs(10)).await);
+        assert!(sidecar.stopped.load(Ordering::SeqCst));
+        assert!(!handle.is_running());
+        assert_eq!(handle.status().state, F3SidecarRunState::Stopped);
+        assert_eq!(handle.uptime(), None);
+        assert_eq!(sidecar.runs(), 3);
+    }
+
+    #[tokio::test]
+    async fn handle_stops_while_backing_off() {
+        let sidecar = StubSidecar::new(u32::MAX, true);
+        let backoff = Backoff {
+            initial: Duration::from_secs(3600),
+            ..backoff(3)
+        };
+        let handle = F3Handle::spawn(sidecar.clone(), backoff);
+        wait_until(|| handle.status().state == F3SidecarRunState::Restarting).await;
+
+        assert!(handle.stop(Duration::from_secs(10)).await);
+        let status = handle.status();
+        assert_eq!(status.state, F3SidecarRunState::Stopped);
+        assert_eq!(status.restarts, 0);
+        assert_eq!(sidecar.runs(), 1);
+    }
+
+    #[tokio::test]
+    async fn handle_waits_for_launch_epoch() {
+        let sidecar = StubSidecar::new(0, true);
+        let (head_tx, head) = watch::channel(100);
+        let handle = F3Handle::spawn_at_epoch(sidecar.clone(), backoff(3), head, 1000);
+        wait_until(|| handle.status().state == F3SidecarRunState::WaitingForSync).await;
+        assert_eq!(handle.status().launch_epoch, Some(1000));
+
+        for epoch in [500, 999] {
+            head_tx.send_replace(epoch);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            assert_eq!(sidecar.runs(), 0, "launched at epoch {epoch}");
+            assert_eq!(handle.status().state, F3SidecarRunState::WaitingForSync);
+        }
+
+        head_tx.send_replace(1000);
+        wait_until(|| handle.is_running()).await;
+        assert_eq!(handle.status().launch_epoch, None);
+        head_tx.send_replace(1001);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(sidecar.runs(), 1);
+
+        assert!(handle.stop(Duration::from_secs(10)).await);
+        assert_eq!(sidecar.runs(), 1);
+    }
+
+    #[tokio::test]
+    async fn handle_launches_immediately_past_launch_epoch() {
+        let sidecar = StubSidecar::new(0, true);
+        let (_head_tx, head) = watch::channel(1000);
+        let handle = F3Handle::spawn_at_epoch(sidecar.clone(), backoff(3), head, 1000);
+        wait_until(|| sidecar.runs() == 1 && handle.is_running()).await;
+        assert!(handle.stop(Duration::from_secs(10)).await);
+    }
+
+    #[tokio::test]
+    async fn handle_stops_while_waiting_for_launch_epoch() {
+        let sidecar = StubSidecar::new(0, true);
+        let (_head_tx, head) = watch::channel(0);
+        let handle = F3Handle::spawn_at_epoch(sidecar.clone(), backoff(3), head, 1000);
+        wait_until(|| handle.status().state == F3SidecarRunState::WaitingForSync).await;
+
+        assert!(handle.stop(Duration::from_secs(10)).await);
+        let status = handle.status();
+        assert_eq!(status.state, F3SidecarRunState::Stopped);
+        assert_eq!(status.launch_epoch, None);
+        assert_eq!(sidecar.runs(), 0);
+    }
+
+    #[tokio::test]
+    async fn handle_stop_times_out() {
+        let sidecar = StubSidecar::new(0, false);
+        let handle = F3Handle::spawn(sidecar.clone(), backoff(3));
+        wait_until(|| sidecar.runs() == 1 && handle.is_running()).await;
+
+        assert!(!handle.stop(Duration::from_millis(10)).await);
+        assert_eq!(handle.status().state, F3SidecarRunState::Stopped);
+        // Aborting the supervisor counts as an exit
+        handle.await_exit().await;
+
+        // Let the runtime shut down
+        sidecar.released.store(true, Ordering::SeqCst);
+    }
+}
@@ -79,6 +79,9 @@ mod test {
                 chain_indexer: ChainIndexerConfig {
                     enable_indexer: true,
                     gc_retention_epochs: None,
+                    eth_mappings_retention_epochs: None,
+                    auto_backfill: false,
+                    strict_eth_mappings: false,
                 },
                 client: Client {
                     healthcheck_address,
@@ -99,6 +99,8 @@ pub mod doctest_private {
 #[doc(hidden)]
 pub mod benchmark_private {
     pub use crate::db::car::forest;
+    pub use crate::db::car::plain;
+    pub use crate::shim::econ;
     pub use crate::utils::cid;
 }
 
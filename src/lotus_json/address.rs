@@ -4,13 +4,23 @@
 use super::*;
 use crate::shim::address::Address;
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
-#[schemars(rename = "Address")]
-pub struct AddressLotusJson(
-    #[schemars(with = "String")]
-    #[serde(with = "crate::lotus_json::stringify")]
-    Address,
-);
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct AddressLotusJson(#[serde(with = "crate::lotus_json::stringify")] Address);
+
+impl JsonSchema for AddressLotusJson {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Address".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        schemars::json_schema!({
+            "description": "A Filecoin address, with its network prefix, e.g. `f0100` or `t1...`",
+            "type": "string",
+            "format": "filecoin-address",
+            "examples": ["f00"]
+        })
+    }
+}
 
 impl HasLotusJson for Address {
     type LotusJson = AddressLotusJson;
@@ -0,0 +1,129 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Fixed-size byte arrays are standard base64 strings, like [`Vec<u8>`], but
+//! `null` is rejected, and so is input that doesn't decode to exactly `N` bytes.
+
+use super::*;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ByteArrayLotusJson<const N: usize>([u8; N]);
+
+impl<const N: usize> Serialize for ByteArrayLotusJson<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        base64_standard::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for ByteArrayLotusJson<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+
+        let bytes = base64_standard::deserialize(deserializer)?;
+        let len = bytes.len();
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| D::Error::custom(format!("expected {N} bytes, got {len}")))
+    }
+}
+
+impl<const N: usize> JsonSchema for ByteArrayLotusJson<N> {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        format!("Base64Bytes{N}").into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        // Standard base64 is padded, so the length of the string is known too
+        let encoded_len = N.div_ceil(3) * 4;
+        schemars::json_schema!({
+            "type": "string",
+            "contentEncoding": "base64",
+            "description": format!("exactly {N} bytes, base64-encoded"),
+            "minLength": encoded_len,
+            "maxLength": encoded_len,
+        })
+    }
+}
+
+impl<const N: usize> HasLotusJson for [u8; N] {
+    type LotusJson = ByteArrayLotusJson<N>;
+
+    #[cfg(test)]
+    fn snapshots() -> Vec<(serde_json::Value, Self)> {
+        unimplemented!("only [u8; 0], [u8; 1] and [u8; 32] are tested, below")
+    }
+
+    fn into_lotus_json(self) -> Self::LotusJson {
+        ByteArrayLotusJson(self)
+    }
+
+    fn from_lotus_json(ByteArrayLotusJson(bytes): Self::LotusJson) -> Self {
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::{Engine as _, general_purpose::STANDARD};
+
+    #[test]
+    fn snapshots() {
+        assert_one_snapshot(json!(""), [0_u8; 0]);
+        assert_one_snapshot(json!("Kg=="), [42_u8]);
+        assert_one_snapshot(
+            json!("AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA="),
+            std::array::from_fn::<u8, 32, _>(|i| i as u8 + 1),
+        );
+    }
+
+    #[test]
+    fn wrong_length() {
+        for (input, error) in [
+            ("", "expected 32 bytes, got 0"),
+            ("Kg==", "expected 32 bytes, got 1"),
+            (
+                // 33 bytes
+                "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyAh",
+                "expected 32 bytes, got 33",
+            ),
+        ] {
+            let err = serde_json::from_value::<ByteArrayLotusJson<32>>(json!(input)).unwrap_err();
+            assert!(err.to_string().contains(error), "{input}: {err}");
+        }
+    }
+
+    #[test]
+    fn null_is_rejected() {
+        serde_json::from_value::<ByteArrayLotusJson<0>>(json!(null)).unwrap_err();
+        serde_json::from_value::<ByteArrayLotusJson<32>>(json!(null)).unwrap_err();
+    }
+
+    #[test]
+    fn schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(ByteArrayLotusJson<32>)).unwrap();
+        assert_eq!(schema["minLength"], json!(44));
+        assert_eq!(schema["maxLength"], json!(44));
+        assert_eq!(STANDARD.encode([0; 32]).len(), 44);
+        let schema = serde_json::to_value(schemars::schema_for!(ByteArrayLotusJson<96>)).unwrap();
+        assert_eq!(schema["maxLength"], json!(128));
+        assert_eq!(STANDARD.encode([0; 96]).len(), 128);
+    }
+
+    quickcheck! {
+        fn quickcheck_32(bytes: Vec<u8>) -> () {
+            let mut val = [0; 32];
+            let len = bytes.len().min(32);
+            val[..len].copy_from_slice(&bytes[..len]);
+            assert_unchanged_via_json(val)
+        }
+
+        fn quickcheck_96(bytes: Vec<u8>) -> () {
+            let mut val = [0; 96];
+            let len = bytes.len().min(96);
+            val[..len].copy_from_slice(&bytes[..len]);
+            assert_unchanged_via_json(val)
+        }
+    }
+}
@@ -3,14 +3,76 @@
 
 use super::*;
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
-#[schemars(rename = "Cid")]
+/// Serialized as `{"/": "<cid>"}`, like Lotus. Bare `"<cid>"` strings, which some
+/// clients send, are accepted too.
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct CidLotusJson {
-    #[schemars(with = "String")]
     #[serde(rename = "/", with = "crate::lotus_json::stringify")]
     slash: ::cid::Cid,
 }
 
+impl<'de> Deserialize<'de> for CidLotusJson {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = ::cid::Cid;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str(r#"a CID, as {"/": "<cid>"} or "<cid>""#)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse()
+                    .map_err(|e| E::custom(format!("invalid CID {v:?}: {e}")))
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> Result<Self::Value, A::Error> {
+                #[derive(Deserialize)]
+                struct Canonical {
+                    #[serde(rename = "/", with = "crate::lotus_json::stringify")]
+                    slash: ::cid::Cid,
+                }
+                let Canonical { slash } =
+                    Deserialize::deserialize(serde::de::value::MapAccessDeserializer::new(map))?;
+                Ok(slash)
+            }
+        }
+
+        deserializer
+            .deserialize_any(Visitor)
+            .map(|slash| Self { slash })
+    }
+}
+
+impl JsonSchema for CidLotusJson {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Cid".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        schemars::json_schema!({
+            "description": "A content identifier, as an IPLD link",
+            "format": "cid",
+            "examples": [{"/": "baeaaaaa"}],
+            "anyOf": [
+                {
+                    "type": "object",
+                    "properties": { "/": { "type": "string" } },
+                    "required": ["/"]
+                },
+                {
+                    "description": "a bare CID, only accepted as input",
+                    "type": "string"
+                }
+            ]
+        })
+    }
+}
+
 impl HasLotusJson for ::cid::Cid {
     type LotusJson = CidLotusJson;
 
@@ -28,3 +90,75 @@ impl HasLotusJson for ::cid::Cid {
         slash
     }
 }
+
+// `Cid` is `Copy`, so there is nothing to borrow.
+impl HasLotusJsonRef for ::cid::Cid {
+    type LotusJsonRef<'a> = CidLotusJson;
+
+    fn to_lotus_json_ref(&self) -> Self::LotusJsonRef<'_> {
+        CidLotusJson { slash: *self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_strings_are_accepted() {
+        let cid = ::cid::Cid::default();
+        for input in [json!({"/": "baeaaaaa"}), json!("baeaaaaa")] {
+            let parsed = serde_json::from_value::<CidLotusJson>(input.clone()).unwrap();
+            assert_eq!(::cid::Cid::from_lotus_json(parsed.clone()), cid, "{input}");
+            // Output is always canonical
+            assert_eq!(
+                serde_json::to_value(parsed).unwrap(),
+                json!({"/": "baeaaaaa"})
+            );
+        }
+    }
+
+    #[test]
+    fn wrappers_accept_bare_strings() {
+        let cid = ::cid::Cid::default();
+        let option = serde_json::from_value::<LotusJson<Option<::cid::Cid>>>(json!("baeaaaaa"))
+            .unwrap()
+            .into_inner();
+        assert_eq!(option, Some(cid));
+        assert_eq!(
+            option.into_lotus_json_value().unwrap(),
+            json!({"/": "baeaaaaa"})
+        );
+        let vec = serde_json::from_value::<LotusJson<Vec<::cid::Cid>>>(json!([
+            "baeaaaaa",
+            {"/": "baeaaaaa"}
+        ]))
+        .unwrap()
+        .into_inner();
+        assert_eq!(vec, vec![cid, cid]);
+        assert_eq!(
+            vec.into_lotus_json_value().unwrap(),
+            json!([{"/": "baeaaaaa"}, {"/": "baeaaaaa"}])
+        );
+    }
+
+    #[test]
+    fn garbage_is_rejected() {
+        for input in [
+            json!("not a cid"),
+            json!(""),
+            json!({"/": "not a cid"}),
+            json!({"cid": "baeaaaaa"}),
+            json!(["baeaaaaa"]),
+            json!(1),
+        ] {
+            serde_json::from_value::<CidLotusJson>(input.clone())
+                .expect_err(&input.to_string());
+        }
+        let err = serde_json::from_value::<CidLotusJson>(json!("not a cid")).unwrap_err();
+        assert!(
+            err.to_string().starts_with(r#"invalid CID "not a cid""#),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use super::*;
+use crate::rpc::eth::types::StrictEthBytes;
+
+/// `0x`-prefixed lowercase hex, `"0x"` for empty bytes. Unlike
+/// [`hexify_vec_bytes`], odd-length and unprefixed input is rejected.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EthBytesLotusJson(Vec<u8>);
+
+impl Serialize for EthBytesLotusJson {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        hexify_vec_bytes::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EthBytesLotusJson {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+
+        let s = String::deserialize(deserializer)?;
+        let digits = s
+            .strip_prefix("0x")
+            .ok_or_else(|| D::Error::custom(format!("expected a 0x-prefixed hex string, got {s:?}")))?;
+        if digits.len() % 2 != 0 {
+            return Err(D::Error::custom(format!(
+                "expected an even number of hex digits, got {s:?}"
+            )));
+        }
+        if let Some(invalid) = digits
+            .chars()
+            .find(|c| !matches!(c, '0'..='9' | 'a'..='f'))
+        {
+            return Err(D::Error::custom(format!(
+                "invalid hex digit {invalid:?} in {s:?}, lowercase hex is expected"
+            )));
+        }
+        hex::decode(digits).map(Self).map_err(D::Error::custom)
+    }
+}
+
+impl JsonSchema for EthBytesLotusJson {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "EthHexBytes".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "pattern": "^0x[0-9a-f]*$"
+        })
+    }
+}
+
+impl HasLotusJson for StrictEthBytes {
+    type LotusJson = EthBytesLotusJson;
+
+    #[cfg(test)]
+    fn snapshots() -> Vec<(serde_json::Value, Self)> {
+        vec![
+            (json!("0x"), StrictEthBytes(vec![])),
+            (json!("0x2a"), StrictEthBytes(vec![0x2a])),
+            (
+                json!("0x0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20"),
+                StrictEthBytes((1..=32).collect()),
+            ),
+        ]
+    }
+
+    fn into_lotus_json(self) -> Self::LotusJson {
+        EthBytesLotusJson(self.0)
+    }
+
+    fn from_lotus_json(EthBytesLotusJson(bytes): Self::LotusJson) -> Self {
+        Self(bytes)
+    }
+}
+
+#[test]
+fn strict_deserialization() {
+    for (input, error) in [
+        ("", "0x-prefixed"),
+        ("2a", "0x-prefixed"),
+        ("0X2a", "0x-prefixed"),
+        ("0x0", "even number"),
+        ("0x2a4", "even number"),
+        ("0x2A", "invalid hex digit 'A'"),
+        ("0xzz", "invalid hex digit 'z'"),
+    ] {
+        let err = serde_json::from_value::<EthBytesLotusJson>(json!(input)).unwrap_err();
+        assert!(err.to_string().contains(error), "{input}: {err}");
+    }
+    serde_json::from_value::<EthBytesLotusJson>(json!(null)).unwrap_err();
+}
@@ -0,0 +1,194 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Round-trips payloads captured from Lotus through [`HasLotusJson`], and checks
+//! that the output matches the input.
+//!
+//! Fixtures live in `fixtures/<kind>/*.json`, one payload per file, and can be
+//! captured from a (mainnet) Lotus node with
+//! `forest-tool api dump-lotus-json-fixtures`.
+//!
+//! # Canonicalization
+//! Forest's output doesn't have to be byte-for-byte the same as Lotus'. These
+//! differences are allowed:
+//! - A `null` field in the fixture may be missing from the output. Go emits
+//!   `null` for nil pointers, where Forest skips [`None`]s.
+//! - `[]` and `null` are interchangeable. Go distinguishes nil and empty slices,
+//!   Forest doesn't.
+//! - [`Kind::ignored`] fields are removed from the fixture before comparing, for
+//!   data that Forest doesn't model, e.g. the `CID` that Lotus adds to messages.
+
+use super::*;
+use serde_json::Value;
+use std::path::Path;
+
+struct Kind {
+    /// Directory of the fixtures, under `fixtures/`.
+    dir: &'static str,
+    /// Payload -> `T::LotusJson` -> `T` -> payload.
+    round_trip: fn(Value) -> anyhow::Result<Value>,
+    /// JSON pointers to fields that Forest doesn't round-trip.
+    ignored: &'static [&'static str],
+}
+
+fn round_trip<T: HasLotusJson>(value: Value) -> anyhow::Result<Value> {
+    let lotus_json = serde_json::from_value::<T::LotusJson>(value)?;
+    Ok(T::from_lotus_json(lotus_json).into_lotus_json_value()?)
+}
+
+const KINDS: &[Kind] = &[
+    Kind {
+        dir: "actor_state",
+        round_trip: round_trip::<crate::shim::state_tree::ActorState>,
+        ignored: &[],
+    },
+    Kind {
+        dir: "message",
+        round_trip: round_trip::<crate::shim::message::Message>,
+        ignored: &["/CID"],
+    },
+    Kind {
+        dir: "receipt",
+        round_trip: round_trip::<crate::shim::executor::Receipt>,
+        ignored: &[],
+    },
+    Kind {
+        dir: "tipset",
+        round_trip: round_trip::<crate::blocks::Tipset>,
+        ignored: &[],
+    },
+];
+
+/// The JSON pointer to the first place where `output` differs from `fixture`,
+/// modulo the [canonicalization](self#canonicalization) rules.
+fn first_divergence(fixture: &Value, output: &Value) -> Option<String> {
+    fn go(fixture: &Value, output: &Value, pointer: &mut String) -> bool {
+        match (fixture, output) {
+            (Value::Object(fixture), Value::Object(output)) => {
+                let mut keys = fixture.keys().chain(output.keys()).collect::<Vec<_>>();
+                keys.sort();
+                keys.dedup();
+                keys.into_iter().any(|key| {
+                    let len = pointer.len();
+                    pointer.push('/');
+                    pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+                    let diverges = match (fixture.get(key), output.get(key)) {
+                        (Some(Value::Null), None) => false,
+                        (Some(fixture), Some(output)) => go(fixture, output, pointer),
+                        _ => true,
+                    };
+                    if !diverges {
+                        pointer.truncate(len);
+                    }
+                    diverges
+                })
+            }
+            (Value::Array(fixture), Value::Array(output)) => {
+                if fixture.len() != output.len() {
+                    return true;
+                }
+                itertools::zip_eq(fixture, output)
+                    .enumerate()
+                    .any(|(index, (fixture, output))| {
+                        let len = pointer.len();
+                        pointer.push_str(&format!("/{index}"));
+                        let diverges = go(fixture, output, pointer);
+                        if !diverges {
+                            pointer.truncate(len);
+                        }
+                        diverges
+                    })
+            }
+            (Value::Array(empty), Value::Null) | (Value::Null, Value::Array(empty)) => {
+                !empty.is_empty()
+            }
+            (fixture, output) => fixture != output,
+        }
+    }
+    let mut pointer = String::new();
+    go(fixture, output, &mut pointer).then_some(pointer)
+}
+
+fn remove(value: &mut Value, pointer: &str) {
+    let (parent, key) = pointer.rsplit_once('/').expect("pointers start with /");
+    if let Some(Value::Object(parent)) = value.pointer_mut(parent) {
+        parent.remove(&key.replace("~1", "/").replace("~0", "~"));
+    }
+}
+
+#[test]
+fn lotus_fixtures_round_trip() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/lotus_json/fixtures");
+    let mut failures = vec![];
+    for Kind {
+        dir,
+        round_trip,
+        ignored,
+    } in KINDS
+    {
+        let mut paths = std::fs::read_dir(root.join(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|it| it == "json"))
+            .collect::<Vec<_>>();
+        paths.sort();
+        assert!(!paths.is_empty(), "no fixtures in {dir}");
+        for path in paths {
+            let mut fixture =
+                serde_json::from_slice::<Value>(&std::fs::read(&path).unwrap()).unwrap();
+            for pointer in *ignored {
+                remove(&mut fixture, pointer);
+            }
+            let path = path.strip_prefix(&root).unwrap().display();
+            match round_trip(fixture.clone()) {
+                Ok(output) => {
+                    if let Some(pointer) = first_divergence(&fixture, &output) {
+                        let at = |value: &Value| match value.pointer(&pointer) {
+                            Some(it) => it.to_string(),
+                            None => "nothing".into(),
+                        };
+                        failures.push(format!(
+                            "{path}: diverges at {pointer:?}, expected {}, got {}",
+                            at(&fixture),
+                            at(&output)
+                        ));
+                    }
+                }
+                Err(e) => failures.push(format!("{path}: couldn't round-trip: {e}")),
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} fixture(s) failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn divergences() {
+    for (fixture, output, expected) in [
+        (json!({"A": 1}), json!({"A": 1}), None),
+        (json!({"A": null}), json!({}), None),
+        (json!({"A": []}), json!({"A": null}), None),
+        (json!({"A": null}), json!({"A": []}), None),
+        (json!({"A": 1}), json!({}), Some("/A")),
+        (json!({}), json!({"A": null}), Some("/A")),
+        (json!({"A": [1]}), json!({"A": null}), Some("/A")),
+        (json!({"A": [1, 2]}), json!({"A": [1]}), Some("/A")),
+        (
+            json!({"A": [{"B": 1}]}),
+            json!({"A": [{"B": 2}]}),
+            Some("/A/0/B"),
+        ),
+        (json!({"a/b": 1}), json!({"a/b": 2}), Some("/a~1b")),
+        (json!({"A": 1, "B": 1}), json!({"A": 2, "B": 2}), Some("/A")),
+    ] {
+        assert_eq!(
+            first_divergence(&fixture, &output).as_deref(),
+            expected,
+            "{fixture} vs {output}"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Maps are serialized as JSON objects, with keys in their canonical string
+//! form. Like Go's `encoding/json`, keys are sorted by that string form, and an
+//! empty map is serialized as `{}`. `null` is accepted as an empty map.
+
+use super::*;
+use crate::shim::address::Address;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A map key with a canonical string form.
+pub trait MapKey: Display + FromStr<Err: Display> {}
+
+impl MapKey for Address {}
+impl MapKey for ::cid::Cid {}
+impl MapKey for String {}
+
+/// The lotus JSON of a map from `K` to `V`, see the module documentation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapLotusJson<K, V>(Vec<(K, V)>);
+
+impl<K: MapKey, V: Serialize> Serialize for MapLotusJson<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entries = self
+            .0
+            .iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect::<Vec<_>>();
+        entries.sort_by(|(l, _), (r, _)| l.cmp(r));
+        serializer.collect_map(entries)
+    }
+}
+
+impl<'de, K: MapKey, V: Deserialize<'de>> Deserialize<'de> for MapLotusJson<K, V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<BTreeMap<String, V>>::deserialize(deserializer)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(k, v)| Ok((k.parse().map_err(serde::de::Error::custom)?, v)))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl<K, V: JsonSchema> JsonSchema for MapLotusJson<K, V> {
+    fn inline_schema() -> bool {
+        BTreeMap::<String, V>::inline_schema()
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        BTreeMap::<String, V>::schema_name()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        BTreeMap::<String, V>::schema_id()
+    }
+
+    fn json_schema(g: &mut SchemaGenerator) -> Schema {
+        BTreeMap::<String, V>::json_schema(g)
+    }
+}
+
+impl<K, V> HasLotusJson for BTreeMap<K, V>
+where
+    K: MapKey + Ord,
+    V: HasLotusJson,
+{
+    type LotusJson = MapLotusJson<K, V::LotusJson>;
+
+    #[cfg(test)]
+    fn snapshots() -> Vec<(serde_json::Value, Self)> {
+        unimplemented!("only BTreeMap<Cid, u64> is tested, below")
+    }
+
+    fn into_lotus_json(self) -> Self::LotusJson {
+        MapLotusJson(
+            self.into_iter()
+                .map(|(k, v)| (k, v.into_lotus_json()))
+                .collect(),
+        )
+    }
+
+    fn from_lotus_json(MapLotusJson(entries): Self::LotusJson) -> Self {
+        entries
+            .into_iter()
+            .map(|(k, v)| (k, V::from_lotus_json(v)))
+            .collect()
+    }
+}
+
+impl<K, V> HasLotusJson for HashMap<K, V>
+where
+    K: MapKey + Eq + Hash,
+    V: HasLotusJson,
+{
+    type LotusJson = MapLotusJson<K, V::LotusJson>;
+
+    #[cfg(test)]
+    fn snapshots() -> Vec<(serde_json::Value, Self)> {
+        unimplemented!("only HashMap<Address, TokenAmount> is tested, below")
+    }
+
+    fn into_lotus_json(self) -> Self::LotusJson {
+        MapLotusJson(
+            self.into_iter()
+                .map(|(k, v)| (k, v.into_lotus_json()))
+                .collect(),
+        )
+    }
+
+    fn from_lotus_json(MapLotusJson(entries): Self::LotusJson) -> Self {
+        entries
+            .into_iter()
+            .map(|(k, v)| (k, V::from_lotus_json(v)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shim::econ::TokenAmount;
+
+    #[test]
+    fn snapshots() {
+        assert_one_snapshot(json!({}), BTreeMap::<::cid::Cid, u64>::new());
+        assert_one_snapshot(
+            json!({"baeaaaaa": 1}),
+            BTreeMap::from_iter([(::cid::Cid::default(), 1_u64)]),
+        );
+        assert_one_snapshot(json!({}), HashMap::<Address, TokenAmount>::new());
+        // Keys are sorted by their string form, not by `Ord`
+        assert_one_snapshot(
+            json!({"f01": "1", "f0100": "100", "f02": "2"}),
+            HashMap::from_iter(
+                [1, 2, 100].map(|id| (Address::new_id(id), TokenAmount::from_atto(id))),
+            ),
+        );
+        assert_one_snapshot(
+            json!({"a": null, "b": [{"/": "baeaaaaa"}]}),
+            BTreeMap::from_iter([
+                (String::from("a"), vec![]),
+                (String::from("b"), vec![::cid::Cid::default()]),
+            ]),
+        );
+    }
+
+    #[test]
+    fn null_is_empty() {
+        let map = serde_json::from_value::<MapLotusJson<Address, String>>(json!(null)).unwrap();
+        assert!(HashMap::<Address, String>::from_lotus_json(map).is_empty());
+    }
+
+    #[test]
+    fn invalid_keys() {
+        serde_json::from_value::<MapLotusJson<Address, u64>>(json!({"not an address": 1}))
+            .unwrap_err();
+        serde_json::from_value::<MapLotusJson<::cid::Cid, u64>>(json!({"not a cid": 1}))
+            .unwrap_err();
+    }
+
+    quickcheck! {
+        fn btree_map_via_json(val: BTreeMap<::cid::Cid, u64>) -> () {
+            assert_unchanged_via_json(val)
+        }
+
+        fn hash_map_via_json(val: HashMap<Address, TokenAmount>) -> () {
+            assert_unchanged_via_json(val)
+        }
+
+        fn string_keys_via_json(val: BTreeMap<String, ::cid::Cid>) -> () {
+            assert_unchanged_via_json(val)
+        }
+    }
+}
@@ -1,52 +1,38 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use super::*;
+use super::{vec_u8::VecU8LotusJsonRef, *};
 
 use crate::shim::{address::Address, econ::TokenAmount, message::Message};
 use fvm_ipld_encoding::RawBytes;
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
-#[serde(rename_all = "PascalCase")]
-#[schemars(rename = "Message")]
-pub struct MessageLotusJson {
-    #[serde(default)]
-    version: u64,
-    #[schemars(with = "LotusJson<Address>")]
-    #[serde(with = "crate::lotus_json")]
-    to: Address,
-    #[schemars(with = "LotusJson<Address>")]
-    #[serde(with = "crate::lotus_json")]
-    from: Address,
-    #[serde(default)]
-    nonce: u64,
-    #[schemars(with = "LotusJson<TokenAmount>")]
-    #[serde(with = "crate::lotus_json", default)]
-    value: TokenAmount,
-    #[serde(default)]
-    gas_limit: u64,
-    #[schemars(with = "LotusJson<TokenAmount>")]
-    #[serde(with = "crate::lotus_json", default)]
-    gas_fee_cap: TokenAmount,
-    #[schemars(with = "LotusJson<TokenAmount>")]
-    #[serde(with = "crate::lotus_json", default)]
-    gas_premium: TokenAmount,
-    #[serde(default)]
-    method: u64,
-    #[schemars(with = "LotusJson<Option<RawBytes>>")]
-    #[serde(
-        with = "crate::lotus_json",
-        skip_serializing_if = "Option::is_none",
-        default
-    )]
-    params: Option<RawBytes>,
-}
-
-impl HasLotusJson for Message {
-    type LotusJson = MessageLotusJson;
-
-    #[cfg(test)]
-    fn snapshots() -> Vec<(serde_json::Value, Self)> {
+lotus_json_struct! {
+    #[derive(Debug, PartialEq, Clone)]
+    #[schemars(rename = "Message")]
+    pub struct MessageLotusJson for Message {
+        #[serde(default)]
+        raw version: u64,
+        lotus_json to: Address,
+        lotus_json from: Address,
+        #[serde(default)]
+        raw sequence as "Nonce": u64,
+        #[schemars(with = "LotusJson<TokenAmount>")]
+        #[serde(with = "super::token_amount::checked", default)]
+        raw value: TokenAmount,
+        #[serde(default)]
+        raw gas_limit: u64,
+        #[schemars(with = "LotusJson<TokenAmount>")]
+        #[serde(with = "super::token_amount::checked", default)]
+        raw gas_fee_cap: TokenAmount,
+        #[schemars(with = "LotusJson<TokenAmount>")]
+        #[serde(with = "super::token_amount::checked", default)]
+        raw gas_premium: TokenAmount,
+        #[serde(default)]
+        raw method_num as "Method": u64,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        or_default params: RawBytes,
+    }
+    snapshots {
         vec![(
             json!({
                 "From": "f00",
@@ -63,9 +49,29 @@ impl HasLotusJson for Message {
             Message::default(),
         )]
     }
+}
 
-    fn into_lotus_json(self) -> Self::LotusJson {
-        let Self {
+/// Borrowed [`MessageLotusJson`], which doesn't copy [`Message::params`].
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MessageLotusJsonRef<'a> {
+    version: u64,
+    to: LotusJson<Address>,
+    from: LotusJson<Address>,
+    nonce: u64,
+    value: LotusJson<TokenAmount>,
+    gas_limit: u64,
+    gas_fee_cap: LotusJson<TokenAmount>,
+    gas_premium: LotusJson<TokenAmount>,
+    method: u64,
+    params: VecU8LotusJsonRef<'a>,
+}
+
+impl HasLotusJsonRef for Message {
+    type LotusJsonRef<'a> = MessageLotusJsonRef<'a>;
+
+    fn to_lotus_json_ref(&self) -> Self::LotusJsonRef<'_> {
+        let Message {
             version,
             from,
             to,
@@ -77,44 +83,66 @@ impl HasLotusJson for Message {
             gas_fee_cap,
             gas_premium,
         } = self;
-        Self::LotusJson {
-            version,
-            to,
-            from,
-            nonce: sequence,
-            value,
-            gas_limit,
-            gas_fee_cap,
-            gas_premium,
-            method: method_num,
-            params: Some(params),
+        MessageLotusJsonRef {
+            version: *version,
+            to: LotusJson(*to),
+            from: LotusJson(*from),
+            nonce: *sequence,
+            value: LotusJson(value.clone()),
+            gas_limit: *gas_limit,
+            gas_fee_cap: LotusJson(gas_fee_cap.clone()),
+            gas_premium: LotusJson(gas_premium.clone()),
+            method: *method_num,
+            params: VecU8LotusJsonRef::new(params.bytes()),
         }
     }
+}
 
-    fn from_lotus_json(lotus_json: Self::LotusJson) -> Self {
-        let Self::LotusJson {
-            version,
-            to,
-            from,
-            nonce,
-            value,
-            gas_limit,
-            gas_fee_cap,
-            gas_premium,
-            method,
-            params,
-        } = lotus_json;
-        Self {
-            version,
-            from,
-            to,
-            sequence: nonce,
-            value,
-            method_num: method,
-            params: params.unwrap_or_default(),
-            gas_limit,
-            gas_fee_cap,
-            gas_premium,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shim::econ::{DEFAULT_MAX_SUPPLY_MULTIPLE, TOTAL_FILECOIN};
+
+    #[test]
+    fn token_amounts_are_bounded() {
+        let max = TOTAL_FILECOIN.atto() * DEFAULT_MAX_SUPPLY_MULTIPLE;
+        for field in ["Value", "GasFeeCap", "GasPremium"] {
+            let with = |atto: &num::BigInt| {
+                let mut json = Message::default().into_lotus_json_value().unwrap();
+                json[field] = json!(atto.to_string());
+                serde_json::from_value::<MessageLotusJson>(json)
+            };
+            with(&max).unwrap();
+            with(&-&max).unwrap();
+            let err = with(&(&max + 1)).unwrap_err();
+            assert!(err.to_string().contains("exceeds"), "{err}");
+            with(&-(&max + 1)).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn ref_matches_owned() {
+        for (_, message) in Message::snapshots() {
+            assert_ref_matches_owned(message);
+        }
+    }
+
+    quickcheck! {
+        fn ref_matches_owned_quickcheck(message: Message) -> () {
+            assert_ref_matches_owned(message)
         }
     }
+
+    #[test]
+    fn cbor_is_unbounded() {
+        let message = Message {
+            value: TokenAmount::from_atto(TOTAL_FILECOIN.atto() * 1_000),
+            ..Default::default()
+        };
+        let bytes = fvm_ipld_encoding::to_vec(&message).unwrap();
+        assert_eq!(
+            fvm_ipld_encoding::from_slice::<Message>(&bytes).unwrap(),
+            message
+        );
+    }
 }
@@ -88,7 +88,8 @@
 //! a call to [std::primitive::str::parse], which is unacceptable - malformed JSON could cause a crash!
 //!
 //! ### Location
-//! Prefer implementing in this module, as [`decl_and_test`] will handle `quickcheck`-ing and snapshot testing.
+//! Prefer implementing in this module, as [`decl_and_test`] will handle `quickcheck`-ing, snapshot and schema testing.
+//! Implementations that can't be declared there must be added to the test registry in `registry.rs`.
 //!
 //! If you require access to private fields, consider:
 //! - implementing an exhaustive helper method, e.g [`crate::beacon::BeaconEntry::into_parts`].
@@ -158,30 +159,30 @@ pub trait HasLotusJson: Sized {
     }
 }
 
+/// Serialize a domain type through its lotus JSON without consuming or cloning
+/// it, for types whose [`HasLotusJson::into_lotus_json`] would copy large
+/// payloads.
+///
+/// [`HasLotusJsonRef::LotusJsonRef`] MUST serialize byte-for-byte the same as
+/// [`HasLotusJson::LotusJson`], see [`assert_ref_matches_owned`].
+pub trait HasLotusJsonRef: HasLotusJson {
+    type LotusJsonRef<'a>: Serialize
+    where
+        Self: 'a;
+    fn to_lotus_json_ref(&self) -> Self::LotusJsonRef<'_>;
+}
+
 macro_rules! decl_and_test {
     ($($mod_name:ident for $domain_ty:ty),* $(,)?) => {
         $(
             mod $mod_name;
         )*
-        #[test]
-        fn all_snapshots() {
-            $(
-                print!("test snapshots for {}...", std::any::type_name::<$domain_ty>());
-                std::io::Write::flush(&mut std::io::stdout()).unwrap();
-                // ^ make sure the above line is flushed in case the test fails
-                assert_all_snapshots::<$domain_ty>();
-                println!("ok.");
-            )*
-        }
-        #[test]
-        fn all_quickchecks() {
-            $(
-                print!("quickcheck for {}...", std::any::type_name::<$domain_ty>());
-                std::io::Write::flush(&mut std::io::stdout()).unwrap();
-                // ^ make sure the above line is flushed in case the test fails
-                ::quickcheck::quickcheck(assert_unchanged_via_json::<$domain_ty> as fn(_));
-                println!("ok.");
-            )*
+        /// See [`registry`].
+        #[cfg(test)]
+        fn declared() -> Vec<registry::Registration> {
+            vec![$(
+                registry::register!($domain_ty: snapshots, quickcheck, schema)
+            ),*]
         }
     }
 }
@@ -197,10 +198,12 @@ decl_and_test!(
     cid for ::cid::Cid,
     duration for std::time::Duration,
     election_proof for crate::blocks::ElectionProof,
+    eth_bytes for crate::rpc::eth::types::StrictEthBytes,
     extended_sector_info for crate::shim::sector::ExtendedSectorInfo,
     gossip_block for crate::blocks::GossipBlock,
     key_info for crate::key_management::KeyInfo,
     message for crate::shim::message::Message,
+    non_negative_token_amount for crate::shim::econ::NonNegativeTokenAmount,
     po_st_proof for crate::shim::sector::PoStProof,
     registered_po_st_proof for crate::shim::sector::RegisteredPoStProof,
     registered_seal_proof for crate::shim::sector::RegisteredSealProof,
@@ -222,11 +225,13 @@ mod actor_states;
 mod allocation;
 mod beneficiary_term; // fil_actor_miner_state::v12::BeneficiaryTerm: !quickcheck::Arbitrary
 mod bit_field; //  fil_actors_shared::fvm_ipld_bitfield::BitField: !quickcheck::Arbitrary
+mod byte_array; // can't make snapshots of generic type
 mod bytecode_hash;
 mod entry;
 mod filter_estimate;
 mod hash_map;
 mod ipld; // NaN != NaN
+mod map; // can't make snapshots of generic type
 mod miner_info; // fil_actor_miner_state::v12::MinerInfo: !quickcheck::Arbitrary
 mod miner_power; // actors::miner::MinerInfo: !quickcheck::Arbitrary
 mod nonempty; // can't make snapshots of generic type
@@ -241,6 +246,15 @@ mod transient_data;
 mod vec; // can't make snapshots of generic type
 mod verifreg_claim;
 
+#[cfg(test)]
+mod fixtures;
+#[cfg(test)]
+mod registry;
+mod strict; // not an implementation
+
+pub use eth_bytes::EthBytesLotusJson;
+pub use strict::from_value_strict;
+pub use token_amount::as_hex_wei;
 pub use vec::*;
 
 #[cfg(any(test, doc))]
@@ -281,6 +295,23 @@ where
     assert_eq!(deserialized, val);
 }
 
+/// Check that [`HasLotusJsonRef::to_lotus_json_ref`] serializes `val` exactly
+/// like [`HasLotusJson::into_lotus_json`].
+#[cfg(any(test, doc))]
+pub fn assert_ref_matches_owned<T>(val: T)
+where
+    T: HasLotusJsonRef + Clone + std::fmt::Debug,
+{
+    let by_ref = serde_json::to_string(&val.to_lotus_json_ref()).unwrap();
+    let owned = serde_json::to_string(&val.clone().into_lotus_json()).unwrap();
+    assert_eq!(
+        by_ref,
+        owned,
+        "borrowed lotus JSON differs for {}: {val:?}",
+        std::any::type_name::<T>()
+    );
+}
+
 #[cfg(any(test, doc))]
 pub fn assert_unchanged_via_json<T>(val: T)
 where
@@ -460,6 +491,24 @@ where
     Ok(T::from_lotus_json(Deserialize::deserialize(deserializer)?))
 }
 
+/// Like `#[serde(with = "crate::lotus_json")]`, but serializes through
+/// [`HasLotusJsonRef`] rather than cloning the field.
+///
+/// MUST NOT be used in any `LotusJson` structs.
+pub mod by_ref {
+    use super::*;
+
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: HasLotusJsonRef,
+    {
+        value.to_lotus_json_ref().serialize(serializer)
+    }
+
+    pub use super::deserialize;
+}
+
 /// A domain struct that is (de) serialized through its lotus JSON representation.
 #[derive(
     Debug, Deserialize, From, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Clone,
@@ -507,11 +556,100 @@ macro_rules! lotus_json_with_self {
                     lotus_json
                 }
             }
+            impl $crate::lotus_json::HasLotusJsonRef for $domain_ty {
+                type LotusJsonRef<'a> = &'a Self;
+                fn to_lotus_json_ref(&self) -> Self::LotusJsonRef<'_> {
+                    self
+                }
+            }
         )*
     }
 }
 pub(crate) use lotus_json_with_self;
 
+/// Declare the lotus JSON `struct` of a domain `struct` with public fields, and
+/// implement [`HasLotusJson`] for the domain `struct`.
+///
+/// Fields are renamed to `PascalCase`, or to the name given with `as`, and are
+/// converted according to their mode:
+/// - `raw`: (de)serialized as is, or with a custom `#[serde(with = ...)]`.
+/// - `lotus_json`: (de)serialized as a [`LotusJson<T>`].
+/// - `or_default`: like `lotus_json`, but a `null` or missing field is
+///   deserialized as [`Default::default`].
+///
+/// Attributes on the `struct` and its fields are forwarded to the lotus JSON
+/// `struct`.
+///
+/// ```ignore
+/// lotus_json_struct! {
+///     #[schemars(rename = "Ticket")]
+///     pub struct TicketLotusJson for Ticket {
+///         lotus_json vrfproof as "VRFProof": VRFProof,
+///     }
+///     snapshots {
+///         vec![(json!({"VRFProof": null}), Ticket::default())]
+///     }
+/// }
+/// ```
+macro_rules! lotus_json_struct {
+    (@ty raw $ty:ty) => { $ty };
+    (@ty lotus_json $ty:ty) => { $crate::lotus_json::LotusJson<$ty> };
+    (@ty or_default $ty:ty) => { ::std::option::Option<$crate::lotus_json::LotusJson<$ty>> };
+    (@into raw $field:ident) => { $field };
+    (@into lotus_json $field:ident) => { $crate::lotus_json::LotusJson($field) };
+    (@into or_default $field:ident) => { Some($crate::lotus_json::LotusJson($field)) };
+    (@from raw $field:ident) => { $field };
+    (@from lotus_json $field:ident) => { $field.into_inner() };
+    (@from or_default $field:ident) => {
+        $field
+            .map($crate::lotus_json::LotusJson::into_inner)
+            .unwrap_or_default()
+    };
+    (
+        $(#[$struct_meta:meta])*
+        $vis:vis struct $lotus_json:ident for $domain:ty {
+            $(
+                $(#[$field_meta:meta])*
+                $mode:ident $field:ident $(as $rename:literal)?: $field_ty:ty
+            ),* $(,)?
+        }
+        snapshots $snapshots:block
+    ) => {
+        #[derive(::serde::Serialize, ::serde::Deserialize, ::schemars::JsonSchema)]
+        #[serde(rename_all = "PascalCase")]
+        $(#[$struct_meta])*
+        $vis struct $lotus_json {
+            $(
+                $(#[serde(rename = $rename)])?
+                $(#[$field_meta])*
+                $field: $crate::lotus_json::lotus_json_struct!(@ty $mode $field_ty),
+            )*
+        }
+
+        impl $crate::lotus_json::HasLotusJson for $domain {
+            type LotusJson = $lotus_json;
+
+            #[cfg(test)]
+            fn snapshots() -> Vec<(::serde_json::Value, Self)> $snapshots
+
+            fn into_lotus_json(self) -> Self::LotusJson {
+                let Self { $($field),* } = self;
+                Self::LotusJson {
+                    $($field: $crate::lotus_json::lotus_json_struct!(@into $mode $field)),*
+                }
+            }
+
+            fn from_lotus_json(lotus_json: Self::LotusJson) -> Self {
+                let Self::LotusJson { $($field),* } = lotus_json;
+                Self {
+                    $($field: $crate::lotus_json::lotus_json_struct!(@from $mode $field)),*
+                }
+            }
+        }
+    };
+}
+pub(crate) use lotus_json_struct;
+
 lotus_json_with_self!(
     u32,
     u64,
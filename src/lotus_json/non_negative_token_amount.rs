@@ -0,0 +1,71 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use super::*;
+use super::token_amount::TokenAmountLotusJson;
+use crate::shim::econ::{NonNegativeTokenAmount, TokenAmount};
+use serde::de::Error as _;
+
+/// Serialized like a [`TokenAmount`], but negative amounts are rejected.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NonNegativeTokenAmountLotusJson(NonNegativeTokenAmount);
+
+impl Serialize for NonNegativeTokenAmountLotusJson {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (*self.0).clone().into_lotus_json().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NonNegativeTokenAmountLotusJson {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let amount = TokenAmount::from_lotus_json(Deserialize::deserialize(deserializer)?);
+        NonNegativeTokenAmount::try_from(amount)
+            .map(Self)
+            .map_err(D::Error::custom)
+    }
+}
+
+// The same schema as `TokenAmount`, so that the API spec doesn't change.
+impl JsonSchema for NonNegativeTokenAmountLotusJson {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        TokenAmountLotusJson::schema_name()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        TokenAmountLotusJson::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        TokenAmountLotusJson::json_schema(generator)
+    }
+}
+
+impl HasLotusJson for NonNegativeTokenAmount {
+    type LotusJson = NonNegativeTokenAmountLotusJson;
+
+    #[cfg(test)]
+    fn snapshots() -> Vec<(serde_json::Value, Self)> {
+        vec![
+            (json!("0"), TokenAmount::from_atto(0).try_into().unwrap()),
+            (json!("1"), TokenAmount::from_atto(1).try_into().unwrap()),
+        ]
+    }
+
+    fn into_lotus_json(self) -> Self::LotusJson {
+        NonNegativeTokenAmountLotusJson(self)
+    }
+
+    fn from_lotus_json(lotus_json: Self::LotusJson) -> Self {
+        lotus_json.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negative() {
+        serde_json::from_value::<NonNegativeTokenAmountLotusJson>(json!("-1")).unwrap_err();
+    }
+}
@@ -1,13 +1,52 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+//! Non-empty vectors are plain JSON arrays. Unlike [`Vec<T>`], `null` is never
+//! emitted, and both `null` and `[]` are rejected.
+
 use super::*;
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct NonEmptyLotusJson<T>(nunny::Vec<T>);
+
+impl<T: Serialize> Serialize for NonEmptyLotusJson<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for NonEmptyLotusJson<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error as _;
+
+        match Option::<Vec<T>>::deserialize(deserializer)? {
+            Some(it) => nunny::Vec::new(it)
+                .map(Self)
+                .map_err(|_| D::Error::custom("expected a non-empty array, got []")),
+            None => Err(D::Error::custom("expected a non-empty array, got null")),
+        }
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for NonEmptyLotusJson<T> {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        format!("NonEmpty_Array_of_{}", T::schema_name()).into()
+    }
+
+    fn json_schema(g: &mut SchemaGenerator) -> Schema {
+        schemars::json_schema!({
+            "type": "array",
+            "items": g.subschema_for::<T>(),
+            "minItems": 1,
+        })
+    }
+}
+
 impl<T> HasLotusJson for nunny::Vec<T>
 where
     T: HasLotusJson,
 {
-    type LotusJson = nunny::Vec<<T as HasLotusJson>::LotusJson>;
+    type LotusJson = NonEmptyLotusJson<<T as HasLotusJson>::LotusJson>;
 
     #[cfg(test)]
     fn snapshots() -> Vec<(serde_json::Value, Self)> {
@@ -15,12 +54,14 @@ where
     }
 
     fn into_lotus_json(self) -> Self::LotusJson {
-        self.into_iter_ne()
-            .map(HasLotusJson::into_lotus_json)
-            .collect_vec()
+        NonEmptyLotusJson(
+            self.into_iter_ne()
+                .map(HasLotusJson::into_lotus_json)
+                .collect_vec(),
+        )
     }
 
-    fn from_lotus_json(lotus_json: Self::LotusJson) -> Self {
+    fn from_lotus_json(NonEmptyLotusJson(lotus_json): Self::LotusJson) -> Self {
         lotus_json
             .into_iter_ne()
             .map(HasLotusJson::from_lotus_json)
@@ -40,6 +81,25 @@ mod tests {
         assert_one_snapshot(json!([{"/": "baeaaaaa"}]), nonempty![::cid::Cid::default()]);
     }
 
+    #[test]
+    fn empty_is_rejected() {
+        for (input, error) in [
+            (json!([]), "expected a non-empty array, got []"),
+            (json!(null), "expected a non-empty array, got null"),
+        ] {
+            let err = serde_json::from_value::<LotusJson<nunny::Vec<Cid>>>(input).unwrap_err();
+            assert_eq!(err.to_string(), error);
+        }
+    }
+
+    #[test]
+    fn schema() {
+        let schema =
+            serde_json::to_value(schemars::schema_for!(LotusJson<nunny::Vec<Cid>>)).unwrap();
+        assert_eq!(schema["type"], json!("array"));
+        assert_eq!(schema["minItems"], json!(1));
+    }
+
     #[quickcheck]
     fn assert_unchanged(it: nunny::Vec<Cid>) {
         assert_unchanged_via_json(it)
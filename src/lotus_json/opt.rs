@@ -26,6 +26,20 @@ where
     }
 }
 
+impl<T> HasLotusJsonRef for Option<T>
+where
+    T: HasLotusJsonRef,
+{
+    type LotusJsonRef<'a>
+        = Option<T::LotusJsonRef<'a>>
+    where
+        T: 'a;
+
+    fn to_lotus_json_ref(&self) -> Self::LotusJsonRef<'_> {
+        self.as_ref().map(T::to_lotus_json_ref)
+    }
+}
+
 #[test]
 fn shapshots() {
     assert_one_snapshot(json!({"/": "baeaaaaa"}), Some(::cid::Cid::default()));
@@ -37,4 +51,8 @@ quickcheck! {
     fn quickcheck(val: Option<::cid::Cid>) -> () {
         assert_unchanged_via_json(val)
     }
+
+    fn ref_matches_owned(val: Option<Vec<u8>>) -> () {
+        assert_ref_matches_owned(val)
+    }
 }
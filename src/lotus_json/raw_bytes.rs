@@ -1,7 +1,10 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use super::{vec_u8::VecU8LotusJson, *};
+use super::{
+    vec_u8::{VecU8LotusJson, VecU8LotusJsonRef},
+    *,
+};
 use fvm_ipld_encoding::RawBytes;
 
 #[test]
@@ -14,6 +17,10 @@ quickcheck! {
     fn quickcheck(val: Vec<u8>) -> () {
         assert_unchanged_via_json(RawBytes::new(val))
     }
+
+    fn ref_matches_owned(val: Vec<u8>) -> () {
+        assert_ref_matches_owned(RawBytes::new(val))
+    }
 }
 
 impl HasLotusJson for RawBytes {
@@ -35,3 +42,11 @@ impl HasLotusJson for RawBytes {
         Self::from(Vec::from_lotus_json(value))
     }
 }
+
+impl HasLotusJsonRef for RawBytes {
+    type LotusJsonRef<'a> = VecU8LotusJsonRef<'a>;
+
+    fn to_lotus_json_ref(&self) -> Self::LotusJsonRef<'_> {
+        VecU8LotusJsonRef::new(self.bytes())
+    }
+}
@@ -4,7 +4,7 @@
 use ::cid::Cid;
 use fvm_ipld_encoding::RawBytes;
 
-use super::*;
+use super::{vec_u8::VecU8LotusJsonRef, *};
 use crate::shim::executor::Receipt;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
@@ -85,11 +85,47 @@ impl HasLotusJson for Receipt {
     }
 }
 
+/// Borrowed [`ReceiptLotusJson`].
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReceiptLotusJsonRef<'a> {
+    exit_code: u32,
+    r#return: VecU8LotusJsonRef<'a>,
+    gas_used: u64,
+    #[serde(serialize_with = "crate::lotus_json::serialize")]
+    events_root: Option<Cid>,
+}
+
+impl HasLotusJsonRef for Receipt {
+    type LotusJsonRef<'a> = ReceiptLotusJsonRef<'a>;
+
+    fn to_lotus_json_ref(&self) -> Self::LotusJsonRef<'_> {
+        let return_data = match self {
+            Receipt::V2(v2) => &v2.return_data,
+            Receipt::V3(v3) => &v3.return_data,
+            Receipt::V4(v4) => &v4.return_data,
+        };
+        ReceiptLotusJsonRef {
+            exit_code: self.exit_code().value(),
+            r#return: VecU8LotusJsonRef::new(return_data.bytes()),
+            gas_used: self.gas_used(),
+            events_root: self.events_root(),
+        }
+    }
+}
+
 #[test]
 fn shapshots() {
     assert_all_snapshots::<Receipt>()
 }
 
+#[test]
+fn ref_matches_owned() {
+    for (_, receipt) in Receipt::snapshots() {
+        assert_ref_matches_owned(receipt);
+    }
+}
+
 /// [Receipt] knows if it is `V2` or `V3`, but there's no way for
 /// the serialized representation to retain that information,
 /// so [`assert_unchanged_via_json`] tests with arbitrary input will fail.
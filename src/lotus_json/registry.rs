@@ -0,0 +1,193 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! A central list of [`HasLotusJson`] implementations, and the checks that are
+//! run against each of them.
+//!
+//! [`HasLotusJson`] isn't object safe, so each implementation is registered as
+//! a [`Registration`]: its type name, and its checks as type-erased `fn()`s.
+//! Every check runs even if an earlier one fails, and failures are reported by
+//! type name.
+//!
+//! Types in [`decl_and_test`] are registered for every check automatically.
+//! Others are registered in [`registry`] with the checks they support. If a
+//! check is skipped, you MUST document why.
+
+use super::*;
+use std::panic::catch_unwind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// [`assert_all_snapshots`]
+    Snapshots,
+    /// [`assert_unchanged_via_json`] with arbitrary values
+    Quickcheck,
+    /// [`assert_schema`]
+    Schema,
+}
+
+pub struct Registration {
+    pub type_name: &'static str,
+    pub checks: Vec<(Check, fn())>,
+}
+
+/// `register!(Type: snapshots, quickcheck, schema)`, with any subset of checks.
+macro_rules! register {
+    (@check snapshots $ty:ty) => {
+        ($crate::lotus_json::registry::Check::Snapshots, (|| {
+            $crate::lotus_json::assert_all_snapshots::<$ty>()
+        }) as fn())
+    };
+    (@check quickcheck $ty:ty) => {
+        ($crate::lotus_json::registry::Check::Quickcheck, (|| {
+            ::quickcheck::quickcheck($crate::lotus_json::assert_unchanged_via_json::<$ty> as fn(_))
+        }) as fn())
+    };
+    (@check schema $ty:ty) => {
+        ($crate::lotus_json::registry::Check::Schema, (|| {
+            $crate::lotus_json::registry::assert_schema::<$ty>()
+        }) as fn())
+    };
+    ($ty:ty: $($check:ident),+ $(,)?) => {
+        $crate::lotus_json::registry::Registration {
+            type_name: std::any::type_name::<$ty>(),
+            checks: vec![$($crate::lotus_json::registry::register!(@check $check $ty)),+],
+        }
+    };
+}
+pub(crate) use register;
+
+/// The schema of `T::LotusJson` can be generated, is named, and is a valid
+/// (object or boolean) JSON schema.
+///
+/// Its `examples`, if any, must round-trip through `T::LotusJson` unchanged,
+/// so that they can't drift from the snapshots they're taken from.
+pub fn assert_schema<T>()
+where
+    T: HasLotusJson,
+    T::LotusJson: JsonSchema,
+{
+    assert!(
+        !T::LotusJson::schema_name().is_empty(),
+        "the schema has no name"
+    );
+    let schema = SchemaGenerator::default().into_root_schema_for::<T::LotusJson>();
+    let schema = serde_json::to_value(schema).unwrap();
+    assert!(
+        schema.is_object() || schema.is_boolean(),
+        "not a JSON schema: {schema}"
+    );
+    let examples = match schema.get("examples") {
+        Some(serde_json::Value::Array(it)) => it.as_slice(),
+        Some(other) => panic!("examples must be an array, not {other}"),
+        None => &[],
+    };
+    for example in examples {
+        let lotus_json = serde_json::from_value::<T::LotusJson>(example.clone())
+            .unwrap_or_else(|e| panic!("couldn't deserialize the example {example}: {e}"));
+        assert_eq!(
+            &serde_json::to_value(lotus_json).unwrap(),
+            example,
+            "the example doesn't round-trip"
+        );
+    }
+}
+
+/// Every registered implementation.
+pub fn registry() -> Vec<Registration> {
+    use crate::shim::actors;
+
+    let mut registry = super::declared();
+    registry.extend([
+        // !quickcheck::Arbitrary
+        register!(actors::verifreg::Allocation: snapshots, schema),
+        register!(fil_actor_miner_state::v12::BeneficiaryTerm: snapshots, schema),
+        register!(fil_actors_shared::fvm_ipld_bitfield::BitField: snapshots, schema),
+        register!(actors::miner::MinerInfo: snapshots, schema),
+        register!(fil_actor_miner_state::v12::PendingBeneficiaryChange: snapshots, schema),
+        register!(actors::power::Claim: snapshots, schema),
+        register!(fvm_ipld_encoding::RawBytes: snapshots, schema),
+        // Round-tripping is lossy for NaN, see the module
+        register!(ipld_core::ipld::Ipld: snapshots, schema),
+        // Round-tripping is lossy for the version, see the module
+        register!(crate::shim::executor::Receipt: snapshots, schema),
+        // Generic types only have snapshots for some instantiations, which
+        // their modules test
+        register!([u8; 32]: schema),
+        register!(Option<::cid::Cid>: schema),
+        register!(Vec<::cid::Cid>: schema),
+        register!(nunny::Vec<::cid::Cid>: schema),
+        // TODO(forest): these have no snapshots, or their snapshots are untested
+        //               and their domain types are !PartialEq
+        register!(fil_actor_evm_state::v16::BytecodeHash: schema),
+        register!(actors::cron::Entry: schema),
+        register!(fil_actors_shared::v16::reward::FilterEstimate: schema),
+        register!(actors::miner::MinerPower: schema),
+        register!(fil_actors_shared::frc46_token::token::state::TokenState: schema),
+        register!(actors::evm::TombstoneState: schema),
+        register!(fil_actor_evm_state::v16::TransientData: schema),
+        register!(fil_actor_evm_state::v16::TransientDataLifespan: schema),
+        register!(actors::verifreg::Claim: schema),
+    ]);
+    registry
+}
+
+fn run(check: Check) {
+    let registry = registry();
+    let mut failures = vec![];
+    for Registration { type_name, checks } in &registry {
+        for (_, f) in checks.iter().filter(|(it, _)| *it == check) {
+            if let Err(panic) = catch_unwind(*f) {
+                let message = panic
+                    .downcast_ref::<String>()
+                    .map(String::as_str)
+                    .or_else(|| panic.downcast_ref::<&str>().copied())
+                    .unwrap_or("<non-string panic>");
+                failures.push(format!("{type_name}: {message}"));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{check:?} failed for {} type(s):\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn registered_snapshots() {
+    run(Check::Snapshots)
+}
+
+#[test]
+fn registered_quickchecks() {
+    run(Check::Quickcheck)
+}
+
+#[test]
+fn registered_schemas() {
+    run(Check::Schema)
+}
+
+#[test]
+fn registered_once() {
+    let mut names = registry()
+        .into_iter()
+        .map(|it| it.type_name)
+        .collect::<Vec<_>>();
+    names.sort();
+    let len = names.len();
+    names.dedup();
+    assert_eq!(names.len(), len, "a type is registered twice");
+}
+
+#[test]
+fn every_type_has_a_schema_check() {
+    for Registration { type_name, checks } in registry() {
+        assert!(
+            checks.iter().any(|(check, _)| *check == Check::Schema),
+            "{type_name} has no schema check"
+        );
+    }
+}
@@ -0,0 +1,303 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Strict deserialization from a [`serde_json::Value`], where unknown fields are
+//! rejected rather than ignored, at any depth.
+//!
+//! This is opt-in, because other implementations (and some clients) legitimately
+//! send fields that we don't know about.
+//!
+//! Unknown fields are caught wherever serde tells the deserializer which fields
+//! are accepted, i.e. in derived [`Deserialize`] implementations for structs.
+//! Types that buffer their input first, like untagged enums and structs with
+//! `#[serde(flatten)]` fields, are lenient from that point down.
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, Error as _, IntoDeserializer as _,
+    Unexpected, Visitor,
+};
+use serde_json::{Error, Value};
+
+/// Like [`serde_json::from_value`], but fails on unknown struct fields, with an
+/// error naming the unknown field and the accepted ones.
+pub fn from_value_strict<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    T::deserialize(Strict(value))
+}
+
+struct Strict(Value);
+
+impl<'de> Deserializer<'de> for Strict {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Array(it) => {
+                let len = it.len();
+                let mut seq = Seq(it.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                match seq.0.len() {
+                    0 => Ok(value),
+                    _ => Err(Error::invalid_length(len, &"fewer elements in array")),
+                }
+            }
+            Value::Object(it) => {
+                let len = it.len();
+                let mut map = Map {
+                    iter: it.into_iter(),
+                    value: None,
+                };
+                let value = visitor.visit_map(&mut map)?;
+                match map.iter.len() {
+                    0 => Ok(value),
+                    _ => Err(Error::invalid_length(len, &"fewer elements in map")),
+                }
+            }
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            it => visitor.visit_some(Strict(it)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if let Value::Object(it) = &self.0
+            && let Some(unknown) = it.keys().find(|key| !fields.contains(&key.as_str()))
+        {
+            return Err(Error::unknown_field(unknown, fields));
+        }
+        self.deserialize_any(visitor)
+    }
+
+    // Enum payloads are lenient
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier
+    }
+}
+
+struct Seq(std::vec::IntoIter<Value>);
+
+impl<'de> de::SeqAccess<'de> for Seq {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|it| seed.deserialize(Strict(it)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct Map {
+    iter: serde_json::map::IntoIter,
+    value: Option<Value>,
+}
+
+impl<'de> de::MapAccess<'de> for Map {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(Key(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(Strict(value)),
+            None => Err(Error::custom("value is missing")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Map keys are strings, but like in [`serde_json`], they may be quoted integers.
+struct Key(String);
+
+macro_rules! deserialize_integer_key {
+    ($($method:ident => $visit:ident),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0.parse() {
+                    Ok(it) => visitor.$visit(it),
+                    Err(_) => Err(Error::invalid_type(Unexpected::Str(&self.0), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Key {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    deserialize_integer_key! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.0
+            .into_deserializer()
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Outer {
+        gas_limit: Option<u64>,
+        #[serde(default)]
+        inner: Vec<Inner>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Inner {
+        to: String,
+    }
+
+    #[test]
+    fn known_fields() {
+        let value = json!({"GasLimit": 1, "Inner": [{"To": "f01"}]});
+        let expected = Outer {
+            gas_limit: Some(1),
+            inner: vec![Inner { to: "f01".into() }],
+        };
+        assert_eq!(from_value_strict::<Outer>(value.clone()).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<Outer>(value).unwrap(), expected);
+    }
+
+    #[test]
+    fn unknown_fields() {
+        for (value, error) in [
+            (
+                json!({"GasLimt": 1}),
+                "unknown field `GasLimt`, expected `GasLimit` or `Inner`",
+            ),
+            (
+                json!({"Inner": [{"To": "f01", "From": "f02"}]}),
+                "unknown field `From`, expected `To`",
+            ),
+        ] {
+            let err = from_value_strict::<Outer>(value.clone()).unwrap_err();
+            assert_eq!(err.to_string(), error);
+            // Lenient parsing ignores them
+            serde_json::from_value::<Outer>(value).unwrap();
+        }
+    }
+
+    #[test]
+    fn same_as_lenient() {
+        #[track_caller]
+        fn check<T: DeserializeOwned + PartialEq + std::fmt::Debug>(value: Value) {
+            match (
+                serde_json::from_value::<T>(value.clone()),
+                from_value_strict::<T>(value.clone()),
+            ) {
+                (Ok(lenient), Ok(strict)) => assert_eq!(lenient, strict, "{value}"),
+                (Err(_), Err(_)) => {}
+                (lenient, strict) => panic!("{value}: {lenient:?} != {strict:?}"),
+            }
+        }
+
+        check::<HashMap<u64, String>>(json!({"1": "a", "2": "b"}));
+        check::<HashMap<u64, String>>(json!({"a": "b"}));
+        check::<(u8, u8)>(json!([1, 2]));
+        check::<(u8, u8)>(json!([1, 2, 3]));
+        check::<Option<Vec<i64>>>(json!(null));
+        check::<Option<Vec<i64>>>(json!([-1, 1]));
+        check::<f64>(json!(1));
+        check::<char>(json!("c"));
+        check::<()>(json!(null));
+        check::<crate::lotus_json::LotusJson<::cid::Cid>>(json!({"/": "baeaaaaa"}));
+        check::<crate::lotus_json::LotusJson<::cid::Cid>>(json!("baeaaaaa"));
+    }
+}
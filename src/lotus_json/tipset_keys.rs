@@ -6,7 +6,7 @@ use crate::blocks::TipsetKey;
 use ::cid::Cid;
 
 impl HasLotusJson for TipsetKey {
-    type LotusJson = nunny::Vec<<Cid as HasLotusJson>::LotusJson>;
+    type LotusJson = <nunny::Vec<Cid> as HasLotusJson>::LotusJson;
 
     #[cfg(test)]
     fn snapshots() -> Vec<(serde_json::Value, Self)> {
@@ -17,17 +17,10 @@ impl HasLotusJson for TipsetKey {
     }
 
     fn into_lotus_json(self) -> Self::LotusJson {
-        self.into_cids()
-            .into_iter_ne()
-            .map(Cid::into_lotus_json)
-            .collect_vec()
+        self.into_cids().into_lotus_json()
     }
 
     fn from_lotus_json(lotus_json: Self::LotusJson) -> Self {
-        lotus_json
-            .into_iter_ne()
-            .map(Cid::from_lotus_json)
-            .collect_vec()
-            .into()
+        nunny::Vec::<Cid>::from_lotus_json(lotus_json).into()
     }
 }
@@ -5,21 +5,43 @@ use super::*;
 use crate::shim::econ::TokenAmount;
 use num::BigInt;
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(transparent)] // name the field for clarity
-#[schemars(rename = "TokenAmount")]
 pub struct TokenAmountLotusJson {
-    #[schemars(with = "LotusJson<BigInt>")]
     #[serde(with = "crate::lotus_json")]
     attos: BigInt,
 }
 
+impl JsonSchema for TokenAmountLotusJson {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "TokenAmount".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        schemars::json_schema!({
+            "description": "An amount of attoFIL (10^-18 FIL), as a decimal integer string",
+            "type": "string",
+            "format": "bigint",
+            "pattern": "^-?[0-9]+$",
+            "examples": ["1", "2000000000000000000000000000"]
+        })
+    }
+}
+
 impl HasLotusJson for TokenAmount {
     type LotusJson = TokenAmountLotusJson;
 
     #[cfg(test)]
     fn snapshots() -> Vec<(serde_json::Value, Self)> {
-        vec![(json!("1"), TokenAmount::from_atto(1))]
+        vec![
+            (json!("0"), TokenAmount::from_atto(0)),
+            (json!("1"), TokenAmount::from_atto(1)),
+            (json!("-1"), TokenAmount::from_atto(-1)),
+            (
+                json!("2000000000000000000000000000"),
+                crate::shim::econ::TOTAL_FILECOIN.clone(),
+            ),
+        ]
     }
 
     fn into_lotus_json(self) -> Self::LotusJson {
@@ -33,3 +55,75 @@ impl HasLotusJson for TokenAmount {
         Self::from_atto(attos)
     }
 }
+
+/// Like `#[serde(with = "crate::lotus_json")]`, but deserialization rejects
+/// amounts that [`TokenAmount::try_from_atto_checked`] rejects. Use it for
+/// untrusted inputs, e.g. RPC parameters.
+pub mod checked {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &TokenAmount, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::lotus_json::serialize(value, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TokenAmount, D::Error> {
+        let TokenAmountLotusJson { attos } = Deserialize::deserialize(deserializer)?;
+        TokenAmount::try_from_atto_checked(attos).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Usage: `#[serde(with = "crate::lotus_json::as_hex_wei")]`
+///
+/// (De)serializes a [`TokenAmount`] as a `0x`-prefixed hex string of wei, where
+/// one attoFIL is one wei, as returned by e.g. `eth_getBalance`.
+#[allow(dead_code)]
+pub mod as_hex_wei {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &TokenAmount, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::lotus_json::hexify::serialize(&value.to_wei(), serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TokenAmount, D::Error> {
+        crate::lotus_json::hexify::deserialize::<BigInt, _>(deserializer).map(TokenAmount::from_wei)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shim::econ::NonNegativeTokenAmount;
+    use num_traits::Zero as _;
+    use quickcheck_macros::quickcheck;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Balance(#[serde(with = "as_hex_wei")] TokenAmount);
+
+    #[test]
+    fn as_hex_wei_snapshots() {
+        for (json, balance) in [
+            (json!("0x0"), TokenAmount::zero()),
+            (json!("0x1"), TokenAmount::from_atto(1)),
+            (json!("0xde0b6b3a7640000"), TokenAmount::from_whole(1)),
+            (
+                json!("0x6765c793fa10079d0000000"),
+                crate::shim::econ::TOTAL_FILECOIN.clone(),
+            ),
+        ] {
+            assert_eq!(serde_json::to_value(Balance(balance.clone())).unwrap(), json);
+            assert_eq!(
+                serde_json::from_value::<Balance>(json).unwrap(),
+                Balance(balance)
+            );
+        }
+        serde_json::from_value::<Balance>(json!("1")).unwrap_err();
+        serde_json::from_value::<Balance>(json!("0x")).unwrap_err();
+    }
+
+    #[quickcheck]
+    fn as_hex_wei_roundtrip(balance: NonNegativeTokenAmount) {
+        let balance = Balance(balance.into_inner());
+        let json = serde_json::to_value(&balance).unwrap();
+        assert_eq!(serde_json::from_value::<Balance>(json).unwrap(), balance);
+    }
+}
@@ -33,6 +33,40 @@ where
     }
 }
 
+impl<T> HasLotusJsonRef for Vec<T>
+where
+    T: HasLotusJsonRef + Clone,
+{
+    type LotusJsonRef<'a>
+        = VecLotusJsonRef<'a, T>
+    where
+        T: 'a;
+
+    fn to_lotus_json_ref(&self) -> Self::LotusJsonRef<'_> {
+        VecLotusJsonRef(self)
+    }
+}
+
+/// Borrowed lotus JSON of a `Vec<T>`: `null` if empty, else an array.
+pub struct VecLotusJsonRef<'a, T>(&'a [T]);
+
+impl<T: HasLotusJsonRef> Serialize for VecLotusJsonRef<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.is_empty() {
+            true => serializer.serialize_none(),
+            false => serializer.serialize_some(&Items(self.0)),
+        }
+    }
+}
+
+struct Items<'a, T>(&'a [T]);
+
+impl<T: HasLotusJsonRef> Serialize for Items<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(T::to_lotus_json_ref))
+    }
+}
+
 // an empty `Vec<T>` serializes into `null` lotus json by default,
 // while an empty `NotNullVec<T>` serializes into `[]`
 // this is a temporary workaround and will likely be deprecated once
@@ -70,4 +104,8 @@ quickcheck! {
     fn quickcheck(val: Vec<::cid::Cid>) -> () {
         assert_unchanged_via_json(val)
     }
+
+    fn ref_matches_owned(val: Vec<Option<::cid::Cid>>) -> () {
+        assert_ref_matches_owned(val)
+    }
 }
@@ -2,6 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use super::*;
+use base64::engine::{Engine as _, general_purpose::STANDARD};
+
+/// Standard base64, with padding.
+const BASE64_PATTERN: &str = "^([A-Za-z0-9+/]{4})*([A-Za-z0-9+/]{2}==|[A-Za-z0-9+/]{3}=)?$";
+
+/// Malformed input is truncated to this many characters in error messages.
+const MAX_SAMPLE_LEN: usize = 32;
 
 // This code looks odd so we can
 // - use #[serde(with = "...")]
@@ -16,13 +23,33 @@ impl JsonSchema for VecU8LotusJson {
 
     fn json_schema(_: &mut schemars::SchemaGenerator) -> Schema {
         schemars::json_schema!({
-            "type": ["string", "null"]
+            "description": "Bytes as standard base64 with padding, or `null` if there are none",
+            "type": ["string", "null"],
+            "format": "byte",
+            "contentEncoding": "base64",
+            "pattern": BASE64_PATTERN,
+            "examples": ["aGVsbG8gd29ybGQh", null]
         })
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
-struct Inner(#[serde(with = "base64_standard")] Vec<u8>);
+#[derive(Debug, PartialEq, Clone, Serialize)]
+struct Inner(#[serde(serialize_with = "base64_standard::serialize")] Vec<u8>);
+
+// Anything but a string (or `null`, see `VecU8LotusJson`) is rejected by
+// `String::deserialize`.
+impl<'de> Deserialize<'de> for Inner {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        STANDARD.decode(&s).map(Self).map_err(|e| {
+            let sample = match s.char_indices().nth(MAX_SAMPLE_LEN) {
+                Some((end, _)) => format!("{:?}...", &s[..end]),
+                None => format!("{s:?}"),
+            };
+            serde::de::Error::custom(format!("expected standard base64, got {sample}: {e}"))
+        })
+    }
+}
 
 impl HasLotusJson for Vec<u8> {
     type LotusJson = VecU8LotusJson;
@@ -49,3 +76,87 @@ impl HasLotusJson for Vec<u8> {
         }
     }
 }
+
+/// Borrowed [`VecU8LotusJson`].
+pub struct VecU8LotusJsonRef<'a>(&'a [u8]);
+
+impl<'a> VecU8LotusJsonRef<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl Serialize for VecU8LotusJsonRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.is_empty() {
+            true => serializer.serialize_none(),
+            false => serializer.serialize_some(&Base64Ref(self.0)),
+        }
+    }
+}
+
+struct Base64Ref<'a>(&'a [u8]);
+
+impl Serialize for Base64Ref<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        base64_standard::serialize(self.0, serializer)
+    }
+}
+
+impl HasLotusJsonRef for Vec<u8> {
+    type LotusJsonRef<'a> = VecU8LotusJsonRef<'a>;
+
+    fn to_lotus_json_ref(&self) -> Self::LotusJsonRef<'_> {
+        VecU8LotusJsonRef(self)
+    }
+}
+
+#[test]
+fn schema() {
+    let schema = serde_json::to_value(schemars::schema_for!(VecU8LotusJson)).unwrap();
+    assert_eq!(schema["type"], json!(["string", "null"]));
+    assert_eq!(schema["contentEncoding"], json!("base64"));
+    let pattern = regex::Regex::new(schema["pattern"].as_str().unwrap()).unwrap();
+    for valid in ["", "aA==", "aGk=", "aGVsbG8gd29ybGQh", "+/+/"] {
+        assert!(pattern.is_match(valid), "{valid}");
+        STANDARD.decode(valid).unwrap();
+    }
+    for invalid in ["a", "aA=", "aGk", "a-_b", "aGVsbG8=d29y"] {
+        assert!(!pattern.is_match(invalid), "{invalid}");
+        STANDARD.decode(invalid).unwrap_err();
+    }
+}
+
+#[test]
+fn invalid_input() {
+    let error = |value| {
+        serde_json::from_value::<VecU8LotusJson>(value)
+            .unwrap_err()
+            .to_string()
+    };
+    let e = error(json!("not base64!"));
+    assert!(
+        e.starts_with(r#"expected standard base64, got "not base64!": "#),
+        "{e}"
+    );
+    // Long input is truncated
+    let e = error(json!(format!("{}!", "a".repeat(100))));
+    assert!(
+        e.starts_with(&format!(
+            r#"expected standard base64, got "{}"...: "#,
+            "a".repeat(MAX_SAMPLE_LEN)
+        )),
+        "{e}"
+    );
+    // Only strings and null are accepted
+    for value in [json!(1), json!([1, 2]), json!({"bytes": "aGk="})] {
+        error(value);
+    }
+}
+
+#[cfg(test)]
+quickcheck! {
+    fn ref_matches_owned(val: Vec<u8>) -> () {
+        assert_ref_matches_owned(val)
+    }
+}
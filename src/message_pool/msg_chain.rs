@@ -450,14 +450,12 @@ where
             break;
         }
 
-        let required = m.required_funds();
-        if balance < required {
+        let Some(remaining) = balance.checked_sub(&m.required_funds()) else {
             break;
-        }
-
-        balance -= required;
-        let value = m.value();
-        balance -= value;
+        };
+        // Like Lotus, the value is not checked; a negative balance excludes the next
+        // message.
+        balance = remaining - &m.value();
 
         let gas_reward = get_gas_reward(m, base_fee);
         rewards.push(gas_reward);
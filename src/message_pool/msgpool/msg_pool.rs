@@ -19,7 +19,7 @@ use crate::networks::{ChainConfig, NEWEST_NETWORK_VERSION};
 use crate::shim::{
     address::Address,
     crypto::{Signature, SignatureType},
-    econ::TokenAmount,
+    econ::{Rounding, TokenAmount},
     gas::{Gas, price_list_by_network_version},
 };
 use crate::state_manager::is_valid_for_sending;
@@ -40,8 +40,7 @@ use crate::message_pool::{
     errors::Error,
     head_change, metrics,
     msgpool::{
-        BASE_FEE_LOWER_BOUND_FACTOR_CONSERVATIVE, RBF_DENOM, RBF_NUM, recover_sig,
-        republish_pending_messages,
+        BASE_FEE_LOWER_BOUND_FACTOR_CONSERVATIVE, RBF_BPS, recover_sig, republish_pending_messages,
     },
     provider::Provider,
     utils::get_base_fee_lower_bound,
@@ -114,7 +113,7 @@ impl MsgSet {
             if m.cid() != exms.cid() {
                 let premium = &exms.message().gas_premium;
                 let min_price = premium.clone()
-                    + ((premium * RBF_NUM).div_floor(RBF_DENOM))
+                    + premium.bps(RBF_BPS, Rounding::Floor)
                     + TokenAmount::from_atto(1u8);
                 if m.message().gas_premium <= min_price {
                     return Err(Error::GasPriceTooLow);
@@ -1,12 +1,15 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use crate::db::car::ManyCar;
 use prometheus_client::{
     collector::Collector,
-    encoding::{DescriptorEncoder, EncodeMetric},
-    metrics::gauge::Gauge,
+    encoding::{DescriptorEncoder, EncodeLabelSet, EncodeMetric},
+    metrics::{counter::Counter, family::Family, gauge::Gauge},
 };
+use std::fmt;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tracing::error;
 
 #[derive(Debug)]
@@ -49,3 +52,48 @@ impl Collector for DBCollector {
         Ok(())
     }
 }
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct CarLayerLabel {
+    path: String,
+}
+
+/// Collects the number of reads served by each snapshot `CAR` of a [`ManyCar`],
+/// see [`ManyCar::layer_stats`].
+pub struct CarLayerReadsCollector<WriterT> {
+    db: Arc<ManyCar<WriterT>>,
+}
+
+impl<WriterT> CarLayerReadsCollector<WriterT> {
+    pub fn new(db: Arc<ManyCar<WriterT>>) -> Self {
+        Self { db }
+    }
+}
+
+impl<WriterT> fmt::Debug for CarLayerReadsCollector<WriterT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CarLayerReadsCollector")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<WriterT: Send + Sync + 'static> Collector for CarLayerReadsCollector<WriterT> {
+    fn encode(&self, mut encoder: DescriptorEncoder) -> Result<(), std::fmt::Error> {
+        let reads = Family::<CarLayerLabel, Counter>::default();
+        for (path, hits) in self.db.layer_stats() {
+            reads
+                .get_or_create(&CarLayerLabel {
+                    path: path.display().to_string(),
+                })
+                .inc_by(hits);
+        }
+        let metric_encoder = encoder.encode_descriptor(
+            "car_db_layer_reads",
+            "Number of block reads served by each snapshot CAR",
+            None,
+            reads.metric_type(),
+        )?;
+        reads.encode(metric_encoder)?;
+        Ok(())
+    }
+}
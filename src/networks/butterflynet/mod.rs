@@ -17,8 +17,8 @@ use crate::{
 };
 
 use super::{
-    DrandPoint, Height, HeightInfo, NetworkChain, actors_bundle::ACTOR_BUNDLES_METADATA,
-    drand::DRAND_QUICKNET, parse_bootstrap_peers,
+    DrandPoint, F3Defaults, Height, HeightInfo, NetworkChain,
+    actors_bundle::ACTOR_BUNDLES_METADATA, drand::DRAND_QUICKNET, parse_bootstrap_peers,
 };
 
 /// Well-known network names.
@@ -75,6 +75,13 @@ pub const ETH_CHAIN_ID: EthChainId = 3141592;
 
 pub const BREEZE_GAS_TAMPING_DURATION: i64 = 120;
 
+pub static F3_DEFAULTS: LazyLock<F3Defaults> = LazyLock::new(|| F3Defaults {
+    enabled: true,
+    consensus: true,
+    bootstrap_epoch: 1000,
+    initial_power_table: None,
+});
+
 /// Height epochs.
 pub static HEIGHT_INFOS: LazyLock<HashMap<Height, HeightInfo>> = LazyLock::new(|| {
     HashMap::from_iter([
@@ -14,7 +14,7 @@ use crate::{
 };
 
 use super::{
-    DrandPoint, Height, HeightInfo, NetworkChain,
+    DrandPoint, F3Defaults, Height, HeightInfo, NetworkChain,
     actors_bundle::ACTOR_BUNDLES_METADATA,
     drand::{DRAND_MAINNET, DRAND_QUICKNET},
     parse_bootstrap_peers,
@@ -50,6 +50,18 @@ pub const ETH_CHAIN_ID: EthChainId = 314159;
 
 pub const BREEZE_GAS_TAMPING_DURATION: i64 = 120;
 
+pub static F3_DEFAULTS: LazyLock<F3Defaults> = LazyLock::new(|| F3Defaults {
+    // Enable after `initial_power_table` is determined and set to avoid GC hell
+    // (state tree of epoch 2_081_674 - 900 has to be present in the database if `initial_power_table` is not set)
+    enabled: true,
+    consensus: true,
+    // 2024-10-24T13:30:00Z
+    bootstrap_epoch: 2_081_674,
+    initial_power_table: Some(
+        Cid::from_str("bafy2bzaceab236vmmb3n4q4tkvua2n4dphcbzzxerxuey3mot4g3cov5j3r2c").unwrap(),
+    ),
+});
+
 /// Height epochs.
 pub static HEIGHT_INFOS: LazyLock<HashMap<Height, HeightInfo>> = LazyLock::new(|| {
     HashMap::from_iter([
@@ -8,8 +8,8 @@ use std::sync::LazyLock;
 use crate::{eth::EthChainId, make_height, shim::version::NetworkVersion};
 
 use super::{
-    DrandPoint, Height, HeightInfo, NetworkChain, actors_bundle::ACTOR_BUNDLES_METADATA,
-    drand::DRAND_QUICKNET, get_upgrade_height_from_env,
+    DrandPoint, F3Defaults, Height, HeightInfo, NetworkChain,
+    actors_bundle::ACTOR_BUNDLES_METADATA, drand::DRAND_QUICKNET, get_upgrade_height_from_env,
 };
 
 // https://github.com/ethereum-lists/chains/blob/6b1e3ccad1cfcaae5aa1ab917960258f0ef1a6b6/_data/chains/eip155-31415926.json
@@ -17,6 +17,16 @@ pub const ETH_CHAIN_ID: EthChainId = 31415926;
 
 pub const BREEZE_GAS_TAMPING_DURATION: i64 = 0;
 
+/// F3 is opt-in on devnets, with `FOREST_F3_SIDECAR_FFI_ENABLED=1` or `f3.enabled`.
+/// It then bootstraps shortly after the default chain finality of 900 epochs, like on
+/// Lotus 2k devnets, deriving the initial power table from the chain state.
+pub static F3_DEFAULTS: LazyLock<F3Defaults> = LazyLock::new(|| F3Defaults {
+    enabled: false,
+    consensus: false,
+    bootstrap_epoch: 1000,
+    initial_power_table: None,
+});
+
 pub static GENESIS_NETWORK_VERSION: LazyLock<NetworkVersion> = LazyLock::new(|| {
     if let Ok(version) = std::env::var("FOREST_GENESIS_NETWORK_VERSION") {
         NetworkVersion::from(
@@ -16,7 +16,7 @@ use std::str::FromStr;
 use std::sync::LazyLock;
 
 use super::{
-    DrandPoint, Height, HeightInfo, NetworkChain,
+    DrandPoint, F3Defaults, Height, HeightInfo, NetworkChain,
     actors_bundle::ACTOR_BUNDLES_METADATA,
     drand::{DRAND_INCENTINET, DRAND_MAINNET, DRAND_QUICKNET},
     parse_bootstrap_peers,
@@ -51,6 +51,16 @@ pub const ETH_CHAIN_ID: EthChainId = 314;
 
 pub const BREEZE_GAS_TAMPING_DURATION: i64 = 120;
 
+pub static F3_DEFAULTS: LazyLock<F3Defaults> = LazyLock::new(|| F3Defaults {
+    enabled: true,
+    consensus: true,
+    // April 29 at 10:00 UTC
+    bootstrap_epoch: 4920480,
+    initial_power_table: Some(
+        Cid::from_str("bafy2bzacecklgxd2eksmodvhgurqvorkg3wamgqkrunir3al2gchv2cikgmbu").unwrap(),
+    ),
+});
+
 /// Height epochs.
 pub static HEIGHT_INFOS: LazyLock<HashMap<Height, HeightInfo>> = LazyLock::new(|| {
     HashMap::from_iter([
@@ -233,6 +233,19 @@ struct DrandPoint<'a> {
     pub config: &'a LazyLock<DrandConfig<'a>>,
 }
 
+/// Hard-coded F3 parameters of a network, e.g. [`mainnet::F3_DEFAULTS`]. They seed the
+/// `f3_*` fields of [`ChainConfig`], see [`crate::f3::get_f3_sidecar_params`] for how they
+/// can be overridden.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct F3Defaults {
+    pub enabled: bool,
+    /// Whether F3 should checkpoint tipsets finalized by F3. This has no effect if F3 is
+    /// not enabled.
+    pub consensus: bool,
+    pub bootstrap_epoch: ChainEpoch,
+    pub initial_power_table: Option<Cid>,
+}
+
 /// Defines all network configuration parameters.
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 #[cfg_attr(test, derive(derive_quickcheck_arbitrary::Arbitrary))]
@@ -261,6 +274,8 @@ pub struct ChainConfig {
     // See FIP-0100 and https://github.com/filecoin-project/lotus/pull/12938 for why this exists
     pub upgrade_teep_initial_fil_reserved: Option<TokenAmount>,
     pub f3_enabled: bool,
+    /// Overrides [`Self::f3_enabled`], see [`crate::f3::is_f3_permanently_disabled`].
+    pub f3_permanently_disabled: bool,
     // F3Consensus set whether F3 should checkpoint tipsets finalized by F3. This flag has no effect if F3 is not enabled.
     pub f3_consensus: bool,
     pub f3_bootstrap_epoch: i64,
@@ -289,15 +304,11 @@ impl ChainConfig {
             // 1 year on mainnet
             fip0081_ramp_duration_epochs: 365 * EPOCHS_IN_DAY as u64,
             upgrade_teep_initial_fil_reserved: None,
-            f3_enabled: true,
-            f3_consensus: true,
-            // April 29 at 10:00 UTC
-            f3_bootstrap_epoch: 4920480,
-            f3_initial_power_table: Some(
-                "bafy2bzacecklgxd2eksmodvhgurqvorkg3wamgqkrunir3al2gchv2cikgmbu"
-                    .parse()
-                    .expect("invalid f3_initial_power_table"),
-            ),
+            f3_enabled: F3_DEFAULTS.enabled,
+            f3_permanently_disabled: false,
+            f3_consensus: F3_DEFAULTS.consensus,
+            f3_bootstrap_epoch: F3_DEFAULTS.bootstrap_epoch,
+            f3_initial_power_table: F3_DEFAULTS.initial_power_table,
             enable_indexer: false,
             enable_receipt_event_caching: true,
         }
@@ -323,17 +334,11 @@ impl ChainConfig {
             fip0081_ramp_duration_epochs: 3 * EPOCHS_IN_DAY as u64,
             // FIP-0100: 300M -> 1.2B FIL
             upgrade_teep_initial_fil_reserved: Some(TokenAmount::from_whole(1_200_000_000)),
-            // Enable after `f3_initial_power_table` is determined and set to avoid GC hell
-            // (state tree of epoch 2_081_674 - 900 has to be present in the database if `f3_initial_power_table` is not set)
-            f3_enabled: true,
-            f3_consensus: true,
-            // 2024-10-24T13:30:00Z
-            f3_bootstrap_epoch: 2_081_674,
-            f3_initial_power_table: Some(
-                "bafy2bzaceab236vmmb3n4q4tkvua2n4dphcbzzxerxuey3mot4g3cov5j3r2c"
-                    .parse()
-                    .expect("invalid f3_initial_power_table"),
-            ),
+            f3_enabled: F3_DEFAULTS.enabled,
+            f3_permanently_disabled: false,
+            f3_consensus: F3_DEFAULTS.consensus,
+            f3_bootstrap_epoch: F3_DEFAULTS.bootstrap_epoch,
+            f3_initial_power_table: F3_DEFAULTS.initial_power_table,
             enable_indexer: false,
             enable_receipt_event_caching: true,
         }
@@ -356,10 +361,11 @@ impl ChainConfig {
             fip0081_ramp_duration_epochs: env_or_default(ENV_PLEDGE_RULE_RAMP, 200),
             // FIP-0100: 300M -> 1.4B FIL
             upgrade_teep_initial_fil_reserved: Some(TokenAmount::from_whole(1_400_000_000)),
-            f3_enabled: false,
-            f3_consensus: false,
-            f3_bootstrap_epoch: -1,
-            f3_initial_power_table: None,
+            f3_enabled: F3_DEFAULTS.enabled,
+            f3_permanently_disabled: false,
+            f3_consensus: F3_DEFAULTS.consensus,
+            f3_bootstrap_epoch: F3_DEFAULTS.bootstrap_epoch,
+            f3_initial_power_table: F3_DEFAULTS.initial_power_table,
             enable_indexer: false,
             enable_receipt_event_caching: true,
         }
@@ -388,10 +394,11 @@ impl ChainConfig {
             ),
             // FIP-0100: 300M -> 1.6B FIL
             upgrade_teep_initial_fil_reserved: Some(TokenAmount::from_whole(1_600_000_000)),
-            f3_enabled: true,
-            f3_consensus: true,
-            f3_bootstrap_epoch: 1000,
-            f3_initial_power_table: None,
+            f3_enabled: F3_DEFAULTS.enabled,
+            f3_permanently_disabled: false,
+            f3_consensus: F3_DEFAULTS.consensus,
+            f3_bootstrap_epoch: F3_DEFAULTS.bootstrap_epoch,
+            f3_initial_power_table: F3_DEFAULTS.initial_power_table,
             enable_indexer: false,
             enable_receipt_event_caching: true,
         }
@@ -409,6 +416,17 @@ impl ChainConfig {
         }
     }
 
+    /// The F3 parameters of this chain, i.e. the [`F3Defaults`] of its network, unless
+    /// overridden by a custom chain configuration.
+    pub fn f3_defaults(&self) -> F3Defaults {
+        F3Defaults {
+            enabled: self.f3_enabled,
+            consensus: self.f3_consensus,
+            bootstrap_epoch: self.f3_bootstrap_epoch,
+            initial_power_table: self.f3_initial_power_table,
+        }
+    }
+
     /// Returns the network version at the given epoch.
     /// If the epoch is before the first upgrade, the genesis network version is returned.
     pub fn network_version(&self, epoch: ChainEpoch) -> NetworkVersion {
@@ -161,6 +161,7 @@ from2internal! {
     crate::key_management::Error,
     crate::libp2p::ParseError,
     crate::message_pool::Error,
+    crate::shim::econ::NegativeTokenAmountError,
     crate::state_manager::Error,
     fil_actors_shared::fvm_ipld_amt::Error,
     futures::channel::oneshot::Canceled,
@@ -797,13 +797,13 @@ fn load_api_messages_from_tipset(
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct BlockMessages {
-    #[serde(rename = "BlsMessages", with = "crate::lotus_json")]
+    #[serde(rename = "BlsMessages", with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<Vec<Message>>")]
     pub bls_msg: Vec<Message>,
     #[serde(rename = "SecpkMessages", with = "crate::lotus_json")]
     #[schemars(with = "LotusJson<Vec<SignedMessage>>")]
     pub secp_msg: Vec<SignedMessage>,
-    #[serde(rename = "Cids", with = "crate::lotus_json")]
+    #[serde(rename = "Cids", with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<Vec<Cid>>")]
     pub cids: Vec<Cid>,
 }
@@ -815,7 +815,7 @@ pub struct ApiReceipt {
     // Exit status of message execution
     pub exit_code: ExitCode,
     // `Return` value if the exit code is zero
-    #[serde(rename = "Return", with = "crate::lotus_json")]
+    #[serde(rename = "Return", with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<RawBytes>")]
     pub return_data: RawBytes,
     // Non-negative value of GasUsed
@@ -833,7 +833,7 @@ pub struct ApiMessage {
     #[serde(with = "crate::lotus_json")]
     #[schemars(with = "LotusJson<Cid>")]
     pub cid: Cid,
-    #[serde(with = "crate::lotus_json")]
+    #[serde(with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<Message>")]
     pub message: Message,
 }
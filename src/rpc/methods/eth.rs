@@ -42,7 +42,7 @@ use crate::shim::actors::is_evm_actor;
 use crate::shim::actors::system;
 use crate::shim::address::{Address as FilecoinAddress, Protocol};
 use crate::shim::crypto::Signature;
-use crate::shim::econ::{BLOCK_GAS_LIMIT, TokenAmount};
+use crate::shim::econ::{BLOCK_GAS_LIMIT, TokenAmount, TokenAmountOutOfRangeError};
 use crate::shim::error::ExitCode;
 use crate::shim::executor::Receipt;
 use crate::shim::fvm_shared_latest::MethodNum;
@@ -53,7 +53,7 @@ use crate::shim::trace::{CallReturn, ExecutionEvent};
 use crate::shim::{clock::ChainEpoch, state_tree::StateTree};
 use crate::utils::db::BlockstoreExt as _;
 use crate::utils::encoding::from_slice_with_fallback;
-use crate::utils::misc::env::env_or_default;
+use crate::utils::misc::env::env_or;
 use crate::utils::multihash::prelude::*;
 use ahash::HashSet;
 use anyhow::{Context, Error, Result, anyhow, bail, ensure};
@@ -74,7 +74,7 @@ use tracing::log;
 use utils::{decode_payload, lookup_eth_address};
 
 static FOREST_TRACE_FILTER_MAX_RESULT: LazyLock<u64> =
-    LazyLock::new(|| env_or_default("FOREST_TRACE_FILTER_MAX_RESULT", 500));
+    LazyLock::new(|| env_or("FOREST_TRACE_FILTER_MAX_RESULT", 500));
 
 const MASKED_ID_PREFIX: [u8; 12] = [0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
 
@@ -142,6 +142,17 @@ impl From<&TokenAmount> for EthBigInt {
     }
 }
 
+/// Fails for values that aren't an Ethereum `uint256`, i.e. negative values or
+/// values of `2^256` or more.
+impl TryFrom<EthBigInt> for TokenAmount {
+    type Error = TokenAmountOutOfRangeError;
+    fn try_from(EthBigInt(wei): EthBigInt) -> Result<Self, Self::Error> {
+        Ok(TokenAmount::from_eth_big_int(
+            &TokenAmount::from_wei(wei).to_eth_bytes()?,
+        ))
+    }
+}
+
 type GasPriceResult = EthBigInt;
 
 #[derive(PartialEq, Debug, Deserialize, Serialize, Default, Clone, JsonSchema)]
@@ -851,7 +862,7 @@ impl RpcMethod<2> for EthGetBalance {
         )?;
         let state = StateTree::new_from_root(ctx.store_owned(), ts.parent_state())?;
         let actor = state.get_required_actor(&fil_addr)?;
-        Ok(EthBigInt(actor.balance.atto().clone()))
+        Ok(EthBigInt(actor.balance.to_wei()))
     }
 }
 
@@ -1213,7 +1224,13 @@ fn new_eth_tx_from_message_lookup<DB: Blockstore>(
         .chain_store()
         .load_required_tipset_or_heaviest(ts.parents())?;
 
-    let parent_ts_cid = parent_ts.key().cid()?;
+    let parent_ts_hash = match ctx
+        .chain_store()
+        .get_eth_hash_by_tipset_key(parent_ts.key())?
+    {
+        Some(hash) => hash,
+        None => parent_ts.key().cid()?.into(),
+    };
 
     // Lookup the transaction index
     let tx_index = tx_index.map_or_else(
@@ -1232,7 +1249,7 @@ fn new_eth_tx_from_message_lookup<DB: Blockstore>(
     let state = StateTree::new_from_root(ctx.store().into(), ts.parent_state())?;
 
     Ok(ApiEthTx {
-        block_hash: parent_ts_cid.into(),
+        block_hash: parent_ts_hash,
         block_number: (parent_ts.epoch() as u64).into(),
         transaction_index: tx_index.into(),
         ..new_eth_tx_from_signed_message(&smsg, &state, ctx.chain_config().eth_chain_id)?
@@ -1374,13 +1391,23 @@ pub async fn block_from_filecoin_tipset<DB: Blockstore + Send + Sync + 'static>(
     tipset: Arc<Tipset>,
     full_tx_info: bool,
 ) -> Result<Block> {
-    let parent_cid = tipset.parents().cid()?;
+    let parent_hash = match data
+        .chain_store()
+        .get_eth_hash_by_tipset_key(tipset.parents())?
+    {
+        Some(hash) => hash,
+        None => tipset.parents().cid()?.into(),
+    };
 
     let block_number = EthUint64(tipset.epoch() as u64);
 
-    let tsk = tipset.key();
-    let block_cid = tsk.cid()?;
-    let block_hash: EthHash = block_cid.into();
+    let block_hash = match data
+        .chain_store()
+        .get_eth_hash_by_tipset_key(tipset.key())?
+    {
+        Some(hash) => hash,
+        None => tipset.key().cid()?.into(),
+    };
 
     let (state_root, msgs_and_receipts) = execute_tipset(&data, &tipset).await?;
 
@@ -1416,7 +1443,7 @@ pub async fn block_from_filecoin_tipset<DB: Blockstore + Send + Sync + 'static>(
     Ok(Block {
         hash: block_hash,
         number: block_number,
-        parent_hash: parent_cid.into(),
+        parent_hash,
         timestamp: EthUint64(tipset.block_headers().first().timestamp),
         base_fee_per_gas: tipset
             .block_headers()
@@ -2755,7 +2782,7 @@ impl RpcMethod<1> for EthSendRawTransaction {
     const API_PATHS: BitFlags<ApiPaths> = ApiPaths::all();
     const PERMISSION: Permission = Permission::Read;
 
-    type Params = (EthBytes,);
+    type Params = (StrictEthBytes,);
     type Ok = EthHash;
 
     async fn handle(
@@ -3204,8 +3231,10 @@ async fn trace_block<B: Blockstore + Send + Sync + 'static>(
     )?;
     let (state_root, trace) = ctx.state_manager.execution_trace(&ts)?;
     let state = StateTree::new_from_root(ctx.store_owned(), &state_root)?;
-    let cid = ts.key().cid()?;
-    let block_hash: EthHash = cid.into();
+    let block_hash = match ctx.chain_store().get_eth_hash_by_tipset_key(ts.key())? {
+        Some(hash) => hash,
+        None => ts.key().cid()?.into(),
+    };
     let mut all_traces = vec![];
     let mut msg_idx = 0;
     for ir in trace.into_iter() {
@@ -3485,6 +3514,20 @@ mod test {
         assert_eq!(r.0, decoded.0);
     }
 
+    #[test]
+    fn eth_big_int_to_token_amount() {
+        let max = (BigInt::from(1) << 256) - 1;
+        for wei in [BigInt::zero(), BigInt::from(1), max.clone()] {
+            assert_eq!(
+                TokenAmount::try_from(EthBigInt(wei.clone())),
+                Ok(TokenAmount::from_wei(wei))
+            );
+        }
+        for wei in [BigInt::from(-1), max + 1] {
+            TokenAmount::try_from(EthBigInt(wei)).unwrap_err();
+        }
+    }
+
     #[test]
     fn test_abi_encoding() {
         const EXPECTED: &str = "000000000000000000000000000000000000000000000000000000000000001600000000000000000000000000000000000000000000000000000000000000510000000000000000000000000000000000000000000000000000000000000060000000000000000000000000000000000000000000000000000000000000001b1111111111111111111020200301000000044444444444444444010000000000";
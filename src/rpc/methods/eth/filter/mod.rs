@@ -26,6 +26,7 @@ use super::get_tipset_from_hash;
 use crate::blocks::Tipset;
 use crate::blocks::TipsetKey;
 use crate::chain::index::ResolveNullTipset;
+use crate::chain::store::events_index::{EventsIndexFilter, TOPIC0_KEY};
 use crate::cli_shared::cli::EventsConfig;
 use crate::rpc::eth::EVM_WORD_LENGTH;
 use crate::rpc::eth::filter::event::*;
@@ -38,8 +39,9 @@ use crate::rpc::types::{Event, EventEntry};
 use crate::shim::address::Address;
 use crate::shim::clock::ChainEpoch;
 use crate::shim::executor::Entry;
+use crate::shim::fvm_shared_latest::ActorID;
 use crate::state_manager::StateEvents;
-use crate::utils::misc::env::env_or_default;
+use crate::utils::misc::env::env_or;
 use ahash::AHashMap as HashMap;
 use anyhow::{Context, Error, anyhow, bail, ensure};
 use cid::Cid;
@@ -108,7 +110,7 @@ impl EthEventHandler {
     }
 
     pub fn from_config(config: &EventsConfig) -> Self {
-        let max_filters: usize = env_or_default("FOREST_MAX_FILTERS", 100);
+        let max_filters: usize = env_or("FOREST_MAX_FILTERS", 100);
         let max_filter_results = std::env::var("FOREST_MAX_FILTER_RESULTS")
             .ok()
             .and_then(|v| match v.parse::<usize>() {
@@ -394,6 +396,30 @@ impl EthEventHandler {
         Ok(chain_events)
     }
 
+    /// The [`EventsIndexFilter`] of `pf`, to skip the tipsets without matching events. The
+    /// addresses are resolved to actor IDs with `lookup_id`, and no tipset is skipped
+    /// because of its emitters if any of them can't be resolved.
+    fn events_index_filter(
+        pf: &ParsedFilter,
+        lookup_id: impl Fn(&Address) -> Option<ActorID>,
+    ) -> EventsIndexFilter {
+        let emitters = if pf.addresses.is_empty() {
+            None
+        } else {
+            pf.addresses
+                .iter()
+                .map(lookup_id)
+                .collect::<Option<Vec<_>>>()
+        };
+        let topic0s = pf.keys.get(TOPIC0_KEY).map(|blocks| {
+            blocks
+                .iter()
+                .map(|block| block.value.clone())
+                .collect::<Vec<_>>()
+        });
+        EventsIndexFilter { emitters, topic0s }
+    }
+
     pub async fn get_events_for_parsed_filter<DB: Blockstore + Send + Sync + 'static>(
         &self,
         ctx: &Ctx<DB>,
@@ -433,12 +459,25 @@ impl EthEventHandler {
                     ctx.chain_store().heaviest_tipset(),
                     ResolveNullTipset::TakeOlder,
                 )?;
+                let index_filter = Self::events_index_filter(pf, |addr| {
+                    ctx.state_manager
+                        .lookup_id(addr, &max_tipset)
+                        .ok()
+                        .flatten()
+                        .and_then(|id_addr| id_addr.id().ok())
+                });
                 for tipset in max_tipset
                     .as_ref()
                     .clone()
                     .chain(&ctx.store())
                     .take_while(|ts| ts.epoch() >= *range.start())
                 {
+                    if !ctx
+                        .chain_store()
+                        .may_have_matching_events(&tipset, &index_filter)?
+                    {
+                        continue;
+                    }
                     let tipset = Arc::new(tipset);
                     Self::collect_events(
                         ctx,
@@ -771,6 +810,7 @@ mod tests {
 
     use super::*;
     use crate::rpc::eth::{EthAddress, EthFilterSpec, EthTopicSpec};
+    use crate::shim::executor::StampedEvent;
     use std::str::FromStr;
 
     #[test]
@@ -1477,4 +1517,144 @@ mod tests {
 
         assert!(!filter3.matches(&addr0, &entries0).unwrap());
     }
+
+    fn stamped_event(emitter: ActorID, topic0: u8) -> StampedEvent {
+        StampedEvent::new(
+            emitter,
+            vec![
+                Entry::new(
+                    Flags::FLAG_INDEXED_ALL,
+                    TOPIC0_KEY.into(),
+                    IPLD_RAW,
+                    vec![topic0; 32],
+                ),
+                Entry::new(Flags::FLAG_INDEXED_ALL, "d".into(), IPLD_RAW, vec![0; 4]),
+            ],
+        )
+    }
+
+    fn parsed_filter(emitters: &[ActorID], topic0s: Option<&[u8]>) -> ParsedFilter {
+        let mut keys: AHashMap<String, Vec<ActorEventBlock>> = Default::default();
+        if let Some(topic0s) = topic0s {
+            keys.insert(
+                TOPIC0_KEY.into(),
+                topic0s
+                    .iter()
+                    .map(|topic0| ActorEventBlock {
+                        codec: IPLD_RAW,
+                        value: vec![*topic0; 32],
+                    })
+                    .collect(),
+            );
+        }
+        ParsedFilter {
+            tipsets: ParsedFilterTipsets::Range(0..=0),
+            addresses: emitters.iter().copied().map(Address::new_id).collect(),
+            keys,
+        }
+    }
+
+    /// The `(tipset key, message index, event index)` of the events matching `pf` in the
+    /// tipsets for which `scan` returns `true`.
+    fn query(
+        chain: &[(Tipset, Vec<Vec<StampedEvent>>)],
+        pf: &ParsedFilter,
+        mut scan: impl FnMut(&Tipset) -> bool,
+    ) -> Vec<(TipsetKey, usize, usize)> {
+        let mut matched = vec![];
+        for (ts, events) in chain {
+            if !scan(ts) {
+                continue;
+            }
+            for (msg_idx, events) in events.iter().enumerate() {
+                for (event_idx, event) in events.iter().enumerate() {
+                    let emitter = Address::new_id(event.emitter());
+                    if pf.matches(&emitter, &event.event().entries()).unwrap() {
+                        matched.push((ts.key().clone(), msg_idx, event_idx));
+                    }
+                }
+            }
+        }
+        matched
+    }
+
+    #[test]
+    fn test_events_index_matches_brute_force() {
+        use crate::blocks::{Chain4U, chain4u};
+        use crate::chain::store::events_index::{may_have_matching_events, write_tipset_events};
+        use crate::db::MemoryDB;
+
+        let c4u = Chain4U::new();
+        chain4u! {
+            in c4u;
+            t0 @ [_genesis]
+            -> t1 @ [a]
+            -> t2 @ [b, c]
+            -> t3 @ [d]
+            -> t4 @ [e]
+            -> t5 @ [f]
+        };
+        // A fork at the epoch of `t3`
+        chain4u! {
+            from [b, c] in c4u;
+            t3_fork @ [_d_fork]
+        };
+        let chain = vec![
+            (t0.clone(), vec![]),
+            (
+                t1.clone(),
+                vec![vec![stamped_event(1000, 1)], vec![stamped_event(1001, 2)]],
+            ),
+            (
+                t2.clone(),
+                vec![vec![stamped_event(1000, 2), stamped_event(1002, 3)]],
+            ),
+            (t3.clone(), vec![vec![], vec![stamped_event(1001, 1)]]),
+            (t3_fork.clone(), vec![vec![stamped_event(1000, 3)]]),
+            (t4.clone(), vec![vec![stamped_event(1002, 1)]]),
+            (
+                t5.clone(),
+                vec![vec![stamped_event(1000, 1), stamped_event(1000, 2)]],
+            ),
+        ];
+        let db = MemoryDB::default();
+        for (ts, events) in &chain {
+            // Unindexed tipsets are always scanned
+            if ts != t4 {
+                write_tipset_events(&db, ts, events).unwrap();
+                // Idempotent
+                write_tipset_events(&db, ts, events).unwrap();
+            }
+        }
+
+        for pf in [
+            parsed_filter(&[], None),
+            parsed_filter(&[1000], None),
+            parsed_filter(&[1001, 1002], None),
+            parsed_filter(&[], Some(&[1])),
+            parsed_filter(&[1000], Some(&[2, 3])),
+            parsed_filter(&[1001], Some(&[3])),
+            parsed_filter(&[9999], None),
+        ] {
+            let index_filter = EthEventHandler::events_index_filter(&pf, |addr| addr.id().ok());
+            let brute_force = query(&chain, &pf, |_| true);
+            let mut scanned = 0;
+            let indexed = query(&chain, &pf, |ts| {
+                let scan = may_have_matching_events(&db, ts, &index_filter).unwrap();
+                scanned += usize::from(scan);
+                scan
+            });
+            assert_eq!(indexed, brute_force, "{pf:?}");
+            if index_filter.is_wildcard() {
+                assert_eq!(scanned, chain.len());
+            } else {
+                assert!(scanned < chain.len(), "{pf:?}");
+            }
+        }
+
+        // Emitters that can't be resolved don't rule out any tipset
+        let pf = parsed_filter(&[1000], None);
+        let index_filter = EthEventHandler::events_index_filter(&pf, |_| None);
+        assert!(index_filter.is_wildcard());
+    }
 }
@@ -53,6 +53,19 @@ impl FromStr for EthBytes {
     }
 }
 
+/// Like [`EthBytes`], but its lotus JSON only accepts `0x`-prefixed lowercase
+/// hex with an even number of digits, see
+/// [`EthBytesLotusJson`](crate::lotus_json::EthBytesLotusJson).
+#[derive(Eq, Hash, PartialEq, Debug, Default, Clone, derive_more::From, derive_more::Into)]
+#[cfg_attr(test, derive(derive_quickcheck_arbitrary::Arbitrary))]
+pub struct StrictEthBytes(pub Vec<u8>);
+
+impl From<StrictEthBytes> for EthBytes {
+    fn from(StrictEthBytes(bytes): StrictEthBytes) -> Self {
+        Self(bytes)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GetBytecodeReturn(pub Option<Cid>);
 
@@ -352,7 +365,7 @@ impl TryFrom<EthCallMessage> for Message {
         Ok(Message {
             from,
             to,
-            value: tx.value.unwrap_or_default().0.into(),
+            value: tx.value.unwrap_or_default().try_into()?,
             method_num,
             params,
             gas_limit: BLOCK_GAS_LIMIT,
@@ -11,7 +11,8 @@ mod types;
 mod util;
 
 pub use self::types::{
-    F3InstanceProgress, F3LeaseManager, F3Manifest, F3PowerEntry, FinalityCertificate,
+    ECTipSet, F3InstanceProgress, F3LeaseManager, F3Manifest, F3NotReadyReason, F3PowerEntry,
+    F3ReadyResult, F3StatusResult, FinalityCertificate, SupplementalData, make_power_table_cid,
 };
 use self::{types::*, util::*};
 use super::wallet::WalletSign;
@@ -23,6 +24,7 @@ use crate::{
         BlockstoreReadCacheStats as _, BlockstoreWithReadCache, DefaultBlockstoreReadCacheStats,
         LruBlockstoreReadCache,
     },
+    f3::supervisor::{F3SidecarRunState, F3SidecarStatus},
     libp2p::{NetRPCMethods, NetworkMessage},
     lotus_json::HasLotusJson as _,
     rpc::{ApiPaths, Ctx, Permission, RpcMethod, ServerError, types::ApiTipsetKey},
@@ -31,6 +33,7 @@ use crate::{
         clock::ChainEpoch,
         crypto::Signature,
     },
+    state_manager::StateManager,
     utils::misc::env::is_env_set_and_truthy,
 };
 use crate::{
@@ -58,6 +61,7 @@ use std::{
     fmt::Display,
     str::FromStr as _,
     sync::{Arc, LazyLock, OnceLock},
+    time::Duration,
 };
 
 pub static F3_LEASE_MANAGER: OnceLock<F3LeaseManager> = OnceLock::new();
@@ -160,8 +164,9 @@ impl RpcMethod<1> for GetParent {
 pub enum GetPowerTable {}
 
 impl GetPowerTable {
-    async fn compute(
-        ctx: &Ctx<impl Blockstore + Send + Sync + 'static>,
+    /// Computes the F3 power table from the state of `ts`, sorted as go-f3 expects.
+    pub async fn compute<DB: Blockstore + Send + Sync + 'static>(
+        state_manager: &Arc<StateManager<DB>>,
         ts: &Arc<Tipset>,
     ) -> anyhow::Result<Vec<F3PowerEntry>> {
         // The RAM overhead on mainnet is ~14MiB
@@ -173,7 +178,7 @@ impl GetPowerTable {
             )
         });
         let db = BlockstoreWithReadCache::new(
-            ctx.store_owned(),
+            state_manager.blockstore_owned(),
             BLOCKSTORE_CACHE.clone(),
             Some(DefaultBlockstoreReadCacheStats::default()),
         );
@@ -197,8 +202,7 @@ impl GetPowerTable {
                         return Ok(());
                     }
                     let power = claim.quality_adj_power.clone();
-                    let miner_state: miner::State = ctx
-                        .state_manager
+                    let miner_state: miner::State = state_manager
                         .get_actor_state_from_address($ts, &miner.into())
                         .map_err(map_err)?;
                     let debt = miner_state.fee_debt();
@@ -217,7 +221,7 @@ impl GetPowerTable {
             };
         }
 
-        let state: power::State = ctx.state_manager.get_actor_state(ts)?;
+        let state: power::State = state_manager.get_actor_state(ts)?;
         let mut id_power_worker_mappings = vec![];
         match &state {
             power::State::V8(s) => {
@@ -237,7 +241,7 @@ impl GetPowerTable {
 
                     let id = miner.id().map_err(map_err)?;
                     let ok = s.miner_nominal_power_meets_consensus_minimum(
-                        &from_policy_v13_to_v9(&ctx.chain_config().policy),
+                        &from_policy_v13_to_v9(&state_manager.chain_config().policy),
                         &db,
                         &miner.into(),
                     )?;
@@ -245,8 +249,7 @@ impl GetPowerTable {
                         return Ok(());
                     }
                     let power = claim.quality_adj_power.clone();
-                    let miner_state: miner::State = ctx
-                        .state_manager
+                    let miner_state: miner::State = state_manager
                         .get_actor_state_from_address(ts, &miner)
                         .map_err(map_err)?;
                     let debt = miner_state.fee_debt();
@@ -280,7 +283,7 @@ impl GetPowerTable {
 
                     let id = miner.id().map_err(map_err)?;
                     let ok = s.miner_nominal_power_meets_consensus_minimum(
-                        &from_policy_v13_to_v9(&ctx.chain_config().policy),
+                        &from_policy_v13_to_v9(&state_manager.chain_config().policy),
                         &db,
                         &miner.into(),
                     )?;
@@ -288,8 +291,7 @@ impl GetPowerTable {
                         return Ok(());
                     }
                     let power = claim.quality_adj_power.clone();
-                    let miner_state: miner::State = ctx
-                        .state_manager
+                    let miner_state: miner::State = state_manager
                         .get_actor_state_from_address(ts, &miner)
                         .map_err(map_err)?;
                     let debt = miner_state.fee_debt();
@@ -323,7 +325,7 @@ impl GetPowerTable {
 
                     let id = miner.id().map_err(map_err)?;
                     let (_, ok) = s.miner_nominal_power_meets_consensus_minimum(
-                        &from_policy_v13_to_v10(&ctx.chain_config().policy),
+                        &from_policy_v13_to_v10(&state_manager.chain_config().policy),
                         &db,
                         id,
                     )?;
@@ -331,8 +333,7 @@ impl GetPowerTable {
                         return Ok(());
                     }
                     let power = claim.quality_adj_power.clone();
-                    let miner_state: miner::State = ctx
-                        .state_manager
+                    let miner_state: miner::State = state_manager
                         .get_actor_state_from_address(ts, &miner)
                         .map_err(map_err)?;
                     let debt = miner_state.fee_debt();
@@ -366,7 +367,7 @@ impl GetPowerTable {
 
                     let id = miner.id().map_err(map_err)?;
                     let (_, ok) = s.miner_nominal_power_meets_consensus_minimum(
-                        &from_policy_v13_to_v11(&ctx.chain_config().policy),
+                        &from_policy_v13_to_v11(&state_manager.chain_config().policy),
                         &db,
                         id,
                     )?;
@@ -374,8 +375,7 @@ impl GetPowerTable {
                         return Ok(());
                     }
                     let power = claim.quality_adj_power.clone();
-                    let miner_state: miner::State = ctx
-                        .state_manager
+                    let miner_state: miner::State = state_manager
                         .get_actor_state_from_address(ts, &miner)
                         .map_err(map_err)?;
                     let debt = miner_state.fee_debt();
@@ -398,7 +398,7 @@ impl GetPowerTable {
                     id_power_worker_mappings,
                     &ts,
                     s,
-                    &from_policy_v13_to_v12(&ctx.chain_config().policy)
+                    &from_policy_v13_to_v12(&state_manager.chain_config().policy)
                 );
             }
             power::State::V13(s) => {
@@ -407,7 +407,7 @@ impl GetPowerTable {
                     id_power_worker_mappings,
                     &ts,
                     s,
-                    &ctx.chain_config().policy
+                    &state_manager.chain_config().policy
                 );
             }
             power::State::V14(s) => {
@@ -416,7 +416,7 @@ impl GetPowerTable {
                     id_power_worker_mappings,
                     &ts,
                     s,
-                    &from_policy_v13_to_v14(&ctx.chain_config().policy)
+                    &from_policy_v13_to_v14(&state_manager.chain_config().policy)
                 );
             }
             power::State::V15(s) => {
@@ -425,7 +425,7 @@ impl GetPowerTable {
                     id_power_worker_mappings,
                     &ts,
                     s,
-                    &from_policy_v13_to_v15(&ctx.chain_config().policy)
+                    &from_policy_v13_to_v15(&state_manager.chain_config().policy)
                 );
             }
             power::State::V16(s) => {
@@ -434,14 +434,13 @@ impl GetPowerTable {
                     id_power_worker_mappings,
                     &ts,
                     s,
-                    &from_policy_v13_to_v16(&ctx.chain_config().policy)
+                    &from_policy_v13_to_v16(&state_manager.chain_config().policy)
                 );
             }
         }
         let mut power_entries = vec![];
         for (id, power, worker) in id_power_worker_mappings {
-            let waddr = ctx
-                .state_manager
+            let waddr = state_manager
                 .resolve_to_deterministic_address(worker, ts.clone())
                 .await?;
             if waddr.protocol() != Protocol::BLS {
@@ -485,7 +484,7 @@ impl RpcMethod<1> for GetPowerTable {
 
         let start = std::time::Instant::now();
         let ts = ctx.chain_index().load_required_tipset(&tsk)?;
-        let power_entries = Self::compute(&ctx, &ts).await?;
+        let power_entries = Self::compute(&ctx.state_manager, &ts).await?;
         tracing::debug!(epoch=%ts.epoch(), %tsk, "F3.GetPowerTable, took {}", humantime::format_duration(start.elapsed()));
         cache.push(tsk, power_entries.clone());
         Ok(power_entries)
@@ -662,6 +661,19 @@ impl RpcMethod<1> for F3GetCertificate {
 
 /// returns the latest finality certificate
 pub enum F3GetLatestCertificate {}
+
+impl F3GetLatestCertificate {
+    pub async fn run() -> anyhow::Result<FinalityCertificate> {
+        Self::run_at(&get_f3_rpc_endpoint()).await
+    }
+
+    async fn run_at(rpc_endpoint: &str) -> anyhow::Result<FinalityCertificate> {
+        let client = rpc_http_client(rpc_endpoint)?;
+        let response = client.request(Self::NAME, ArrayParams::new()).await?;
+        Ok(response)
+    }
+}
+
 impl RpcMethod<0> for F3GetLatestCertificate {
     const NAME: &'static str = "Filecoin.F3GetLatestCertificate";
     const PARAM_NAMES: [&'static str; 0] = [];
@@ -672,9 +684,7 @@ impl RpcMethod<0> for F3GetLatestCertificate {
     type Ok = FinalityCertificate;
 
     async fn handle(_: Ctx<impl Blockstore>, _: Self::Params) -> Result<Self::Ok, ServerError> {
-        let client = get_rpc_http_client()?;
-        let response = client.request(Self::NAME, ArrayParams::new()).await?;
-        Ok(response)
+        Ok(Self::run().await?)
     }
 }
 
@@ -770,11 +780,151 @@ impl RpcMethod<0> for F3IsRunning {
     }
 }
 
+pub enum F3Status {}
+
+impl RpcMethod<0> for F3Status {
+    const NAME: &'static str = "Forest.F3Status";
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_PATHS: BitFlags<ApiPaths> = ApiPaths::all();
+    const PERMISSION: Permission = Permission::Read;
+    const DESCRIPTION: Option<&'static str> =
+        Some("Returns the effective F3 parameters of the node, and the state of the F3 sidecar.");
+
+    type Params = ();
+    type Ok = F3StatusResult;
+
+    async fn handle(ctx: Ctx<impl Blockstore>, (): Self::Params) -> Result<Self::Ok, ServerError> {
+        let (options, root, sources) = match crate::f3::get_effective_f3_params() {
+            Some(params) => (
+                params.options.clone(),
+                Some(params.root.clone()),
+                Some((&params.provenance).into()),
+            ),
+            // F3 has not been set up, e.g. in stateless mode
+            None => {
+                let (options, sources) = crate::f3::get_f3_sidecar_params_with_sources(
+                    ctx.chain_config(),
+                    &Default::default(),
+                );
+                (options, None, Some(sources))
+            }
+        };
+        Ok(F3StatusResult::new(
+            &options,
+            crate::f3::is_sidecar_ffi_compiled(),
+            crate::f3::is_sidecar_ffi_requested(ctx.chain_config()),
+            crate::f3::supervisor::sidecar_status(),
+            crate::f3::rpc_endpoint().unwrap_or_else(|| get_f3_rpc_endpoint().into_owned()),
+            root,
+            sources,
+        ))
+    }
+}
+
+/// Number of epochs the latest finality certificate may be behind the chain head
+/// for `Forest.F3Ready` to report F3 as ready.
+pub const F3_READY_MAX_LAG: ChainEpoch = 30;
+/// How long `Forest.F3Ready` waits for the sidecar.
+const F3_READY_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub enum F3Ready {}
+
+impl F3Ready {
+    /// Checks the sidecar at `rpc_endpoint`, giving up after `timeout` so that
+    /// a wedged sidecar doesn't hang the RPC server.
+    async fn check(
+        ffi_compiled: bool,
+        enabled: bool,
+        sidecar: &F3SidecarStatus,
+        rpc_endpoint: Option<&str>,
+        head_epoch: ChainEpoch,
+        timeout: Duration,
+    ) -> F3ReadyResult {
+        use F3NotReadyReason::*;
+
+        if !ffi_compiled {
+            return F3ReadyResult::not_ready(NotCompiled);
+        }
+        if !enabled {
+            return F3ReadyResult::not_ready(Disabled);
+        }
+        match sidecar.state {
+            F3SidecarRunState::Running => {}
+            F3SidecarRunState::NotStarted
+            | F3SidecarRunState::WaitingForSync
+            | F3SidecarRunState::Restarting => {
+                return F3ReadyResult::not_ready(Starting {
+                    state: sidecar.state,
+                });
+            }
+            F3SidecarRunState::GaveUp | F3SidecarRunState::Stopped => {
+                return F3ReadyResult::not_ready(Unreachable {
+                    error: sidecar
+                        .last_error
+                        .clone()
+                        .unwrap_or_else(|| format!("F3 sidecar is {}", sidecar.state)),
+                });
+            }
+        }
+        let Some(rpc_endpoint) = rpc_endpoint else {
+            return F3ReadyResult::not_ready(Starting {
+                state: F3SidecarRunState::NotStarted,
+            });
+        };
+        let certificate =
+            match tokio::time::timeout(timeout, F3GetLatestCertificate::run_at(rpc_endpoint)).await
+            {
+                Ok(Ok(certificate)) => certificate,
+                Ok(Err(e)) => {
+                    return F3ReadyResult::not_ready(Unreachable {
+                        error: e.to_string(),
+                    });
+                }
+                Err(_) => {
+                    return F3ReadyResult::not_ready(Unreachable {
+                        error: format!("F3 sidecar did not answer within {timeout:?}"),
+                    });
+                }
+            };
+        let certified = certificate.chain_head();
+        let lag = head_epoch.saturating_sub(certified.epoch);
+        if lag > F3_READY_MAX_LAG {
+            return F3ReadyResult::not_ready(Lagging { epochs: lag });
+        }
+        F3ReadyResult::ready()
+    }
+}
+
+impl RpcMethod<0> for F3Ready {
+    const NAME: &'static str = "Forest.F3Ready";
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_PATHS: BitFlags<ApiPaths> = ApiPaths::all();
+    const PERMISSION: Permission = Permission::Read;
+    const DESCRIPTION: Option<&'static str> = Some(
+        "Returns whether the F3 sidecar is up and keeps up with the chain head, or why it doesn't.",
+    );
+
+    type Params = ();
+    type Ok = F3ReadyResult;
+
+    async fn handle(ctx: Ctx<impl Blockstore>, (): Self::Params) -> Result<Self::Ok, ServerError> {
+        Ok(Self::check(
+            crate::f3::is_sidecar_ffi_compiled(),
+            crate::f3::is_sidecar_ffi_requested(ctx.chain_config()),
+            &crate::f3::supervisor::sidecar_status(),
+            crate::f3::rpc_endpoint().as_deref(),
+            ctx.chain_store().heaviest_tipset().epoch(),
+            F3_READY_TIMEOUT,
+        )
+        .await)
+    }
+}
+
 /// See <https://github.com/filecoin-project/lotus/blob/master/documentation/en/api-v1-unstable-methods.md#F3GetProgress>
 pub enum F3GetProgress {}
 
 impl F3GetProgress {
-    async fn run() -> anyhow::Result<F3InstanceProgress> {
+    pub async fn run() -> anyhow::Result<F3InstanceProgress> {
         let client = get_rpc_http_client()?;
         let response = client.request(Self::NAME, ArrayParams::new()).await?;
         Ok(response)
@@ -912,15 +1062,183 @@ impl RpcMethod<1> for F3Participate {
 }
 
 pub fn get_f3_rpc_endpoint() -> Cow<'static, str> {
-    if let Ok(host) = std::env::var("FOREST_F3_SIDECAR_RPC_ENDPOINT") {
-        Cow::Owned(host)
-    } else {
-        Cow::Borrowed("127.0.0.1:23456")
-    }
+    crate::f3::get_f3_rpc_endpoint()
 }
 
 fn get_rpc_http_client() -> anyhow::Result<jsonrpsee::http_client::HttpClient> {
-    let client = jsonrpsee::http_client::HttpClientBuilder::new()
-        .build(format!("http://{}", get_f3_rpc_endpoint()))?;
+    rpc_http_client(&get_f3_rpc_endpoint())
+}
+
+fn rpc_http_client(rpc_endpoint: &str) -> anyhow::Result<jsonrpsee::http_client::HttpClient> {
+    let client =
+        jsonrpsee::http_client::HttpClientBuilder::new().build(format!("http://{rpc_endpoint}"))?;
     Ok(client)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::multihash::prelude::*;
+    use cid::Cid;
+    use jsonrpsee::{RpcModule, server::Server, types::ErrorObjectOwned};
+    use std::net::SocketAddr;
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    fn certificate(head_epoch: ChainEpoch) -> FinalityCertificate {
+        let tipset = |epoch: ChainEpoch| {
+            let cid = Cid::new_v1(
+                fvm_ipld_encoding::DAG_CBOR,
+                MultihashCode::Blake2b256.digest(&epoch.to_be_bytes()),
+            );
+            ECTipSet {
+                key: TipsetKey::from(nunny::vec![cid]),
+                epoch,
+                power_table: cid,
+                commitments: vec![0; 32],
+            }
+        };
+        FinalityCertificate {
+            instance: 42,
+            ec_chain: nunny::vec![tipset(head_epoch - 1), tipset(head_epoch)],
+            supplemental_data: SupplementalData {
+                commitments: vec![0; 32],
+                power_table: tipset(head_epoch).power_table,
+            },
+            signers: Default::default(),
+            signature: vec![],
+            power_table_delta: vec![],
+        }
+    }
+
+    /// Serves `certificate` as the latest one, like the sidecar RPC server.
+    async fn mock_sidecar(certificate: FinalityCertificate) -> SocketAddr {
+        let server = Server::builder().build("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        let certificate = serde_json::to_value(certificate).unwrap();
+        let mut module = RpcModule::new(());
+        module
+            .register_method(F3GetLatestCertificate::NAME, move |_, _, _| {
+                Ok::<_, ErrorObjectOwned>(certificate.clone())
+            })
+            .unwrap();
+        // Runs until the end of the test
+        std::mem::forget(server.start(module));
+        addr
+    }
+
+    /// Accepts connections, and never answers.
+    async fn wedged_sidecar() -> SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connections = vec![];
+            while let Ok((connection, _)) = listener.accept().await {
+                connections.push(connection);
+            }
+        });
+        addr
+    }
+
+    fn sidecar(state: F3SidecarRunState) -> F3SidecarStatus {
+        F3SidecarStatus {
+            state,
+            ..Default::default()
+        }
+    }
+
+    async fn check(sidecar: &F3SidecarStatus, rpc_endpoint: Option<SocketAddr>) -> F3ReadyResult {
+        F3Ready::check(
+            true,
+            true,
+            sidecar,
+            rpc_endpoint.map(|addr| addr.to_string()).as_deref(),
+            1000,
+            TIMEOUT,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_f3_ready() {
+        let running = sidecar(F3SidecarRunState::Running);
+
+        let up_to_date = mock_sidecar(certificate(1000 - F3_READY_MAX_LAG)).await;
+        assert_eq!(
+            check(&running, Some(up_to_date)).await,
+            F3ReadyResult::ready()
+        );
+
+        let lagging = mock_sidecar(certificate(900)).await;
+        assert_eq!(
+            check(&running, Some(lagging)).await,
+            F3ReadyResult::not_ready(F3NotReadyReason::Lagging { epochs: 100 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_f3_not_ready() {
+        let running = sidecar(F3SidecarRunState::Running);
+        let endpoint = Some(mock_sidecar(certificate(1000)).await);
+
+        assert_eq!(
+            F3Ready::check(false, true, &running, None, 1000, TIMEOUT).await,
+            F3ReadyResult::not_ready(F3NotReadyReason::NotCompiled)
+        );
+        assert_eq!(
+            F3Ready::check(true, false, &running, None, 1000, TIMEOUT).await,
+            F3ReadyResult::not_ready(F3NotReadyReason::Disabled)
+        );
+        for state in [
+            F3SidecarRunState::NotStarted,
+            F3SidecarRunState::WaitingForSync,
+            F3SidecarRunState::Restarting,
+        ] {
+            assert_eq!(
+                check(&sidecar(state), endpoint).await,
+                F3ReadyResult::not_ready(F3NotReadyReason::Starting { state })
+            );
+        }
+        let gave_up = F3SidecarStatus {
+            state: F3SidecarRunState::GaveUp,
+            restarts: 5,
+            last_error: Some("boom".into()),
+            launch_epoch: None,
+        };
+        assert_eq!(
+            check(&gave_up, endpoint).await,
+            F3ReadyResult::not_ready(F3NotReadyReason::Unreachable {
+                error: "boom".into()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_f3_ready_unreachable() {
+        let running = sidecar(F3SidecarRunState::Running);
+
+        // Nothing listens
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let result = check(&running, Some(closed)).await;
+        assert!(
+            matches!(result.reason, Some(F3NotReadyReason::Unreachable { .. })),
+            "{result:?}"
+        );
+
+        // Wedged
+        let start = std::time::Instant::now();
+        let result = check(&running, Some(wedged_sidecar().await)).await;
+        assert!(start.elapsed() < TIMEOUT * 4, "{:?}", start.elapsed());
+        assert!(
+            matches!(
+                &result.reason,
+                Some(F3NotReadyReason::Unreachable { error }) if error.contains("did not answer")
+            ),
+            "{result:?}"
+        );
+    }
+}
@@ -4,10 +4,14 @@
 use super::*;
 use crate::{
     blocks::{Tipset, TipsetKey},
+    f3::{
+        F3Options, F3OptionsReport,
+        supervisor::{F3SidecarRunState, F3SidecarStatus},
+    },
     lotus_json::{HasLotusJson, LotusJson, base64_standard, lotus_json_with_self},
     networks::NetworkChain,
     shim::executor::Receipt,
-    utils::multihash::prelude::*,
+    utils::{cid::CidCborExt as _, multihash::prelude::*},
 };
 use byteorder::ByteOrder as _;
 use cid::Cid;
@@ -22,6 +26,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, serde_as};
 use std::io::Read as _;
+use std::path::PathBuf;
 use std::sync::LazyLock;
 use std::{cmp::Ordering, time::Duration};
 
@@ -166,6 +171,29 @@ impl PartialOrd for F3PowerEntry {
     }
 }
 
+/// Computes the CID of a power table the way go-f3 does, i.e. over the DAG-CBOR
+/// encoding of its entries, in the given order.
+pub fn make_power_table_cid(power_table: &[F3PowerEntry]) -> anyhow::Result<Cid> {
+    #[derive(Serialize_tuple)]
+    struct PowerEntryCbor {
+        id: ActorID,
+        #[serde(with = "crate::shim::fvm_shared_latest::bigint::bigint_ser")]
+        power: num::BigInt,
+        #[serde(with = "fvm_ipld_encoding::strict_bytes")]
+        pub_key: Vec<u8>,
+    }
+
+    let entries = power_table
+        .iter()
+        .map(|F3PowerEntry { id, power, pub_key }| PowerEntryCbor {
+            id: *id,
+            power: power.clone(),
+            pub_key: pub_key.clone(),
+        })
+        .collect_vec();
+    Ok(Cid::from_cbor_blake2b256(&entries)?)
+}
+
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 pub struct F3InstanceProgress {
@@ -453,9 +481,9 @@ lotus_json_with_self!(PowerTableDelta);
 pub struct FinalityCertificate {
     #[serde(rename = "GPBFTInstance")]
     pub instance: u64,
-    #[schemars(with = "LotusJson<Vec<ECTipSet>>")]
+    #[schemars(with = "LotusJson<nunny::Vec<ECTipSet>>")]
     #[serde(rename = "ECChain", with = "crate::lotus_json")]
-    pub ec_chain: Vec<ECTipSet>,
+    pub ec_chain: nunny::Vec<ECTipSet>,
     #[schemars(with = "LotusJson<SupplementalData>")]
     #[serde(with = "crate::lotus_json")]
     pub supplemental_data: SupplementalData,
@@ -493,13 +521,11 @@ impl FinalityCertificate {
     }
 
     pub fn chain_base(&self) -> &ECTipSet {
-        // Switch to NonEmpty and drop `.expect`
-        self.ec_chain.first().expect("ec_chain is empty")
+        self.ec_chain.first()
     }
 
     pub fn chain_head(&self) -> &ECTipSet {
-        // Switch to NonEmpty and drop `.expect`
-        self.ec_chain.last().expect("ec_chain is empty")
+        self.ec_chain.last()
     }
 }
 
@@ -752,6 +778,102 @@ impl F3LeaseManager {
     }
 }
 
+/// Response of `Forest.F3Status`.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct F3StatusResult {
+    pub finality: i64,
+    pub bootstrap_epoch: i64,
+    #[schemars(with = "String")]
+    #[serde(with = "crate::lotus_json")]
+    pub initial_power_table: Option<Cid>,
+    /// Whether the Forest binary is compiled with the f3-sidecar Go lib.
+    pub ffi_compiled: bool,
+    /// Whether the sidecar is enabled by `f3.enabled` or `FOREST_F3_SIDECAR_FFI_ENABLED`.
+    /// It only runs when also compiled in.
+    pub enabled: bool,
+    pub sidecar: F3SidecarStatus,
+    pub rpc_endpoint: String,
+    /// Data directory of the sidecar, absent when F3 has not been set up.
+    pub root: Option<PathBuf>,
+    /// Where each option comes from.
+    pub sources: Option<F3OptionsReport>,
+}
+lotus_json_with_self!(F3StatusResult);
+
+impl F3StatusResult {
+    pub fn new(
+        options: &F3Options,
+        ffi_compiled: bool,
+        enabled: bool,
+        sidecar: F3SidecarStatus,
+        rpc_endpoint: String,
+        root: Option<PathBuf>,
+        sources: Option<F3OptionsReport>,
+    ) -> Self {
+        let F3Options {
+            chain_finality,
+            bootstrap_epoch,
+            initial_power_table,
+        } = options;
+        Self {
+            finality: *chain_finality,
+            bootstrap_epoch: *bootstrap_epoch,
+            initial_power_table: *initial_power_table,
+            ffi_compiled,
+            enabled,
+            sidecar,
+            rpc_endpoint,
+            root,
+            sources,
+        }
+    }
+}
+
+/// Response of `Forest.F3Ready`.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct F3ReadyResult {
+    pub ready: bool,
+    /// Why F3 is not ready, absent when it is.
+    pub reason: Option<F3NotReadyReason>,
+}
+lotus_json_with_self!(F3ReadyResult);
+
+impl F3ReadyResult {
+    pub fn ready() -> Self {
+        Self {
+            ready: true,
+            reason: None,
+        }
+    }
+
+    pub fn not_ready(reason: F3NotReadyReason) -> Self {
+        Self {
+            ready: false,
+            reason: Some(reason),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "Kind")]
+pub enum F3NotReadyReason {
+    /// The Forest binary is not compiled with the f3-sidecar Go lib.
+    NotCompiled,
+    /// The sidecar is disabled.
+    Disabled,
+    /// The sidecar hasn't come up yet, or is being restarted.
+    #[serde(rename_all = "PascalCase")]
+    Starting { state: F3SidecarRunState },
+    /// The sidecar is down, or didn't answer in time.
+    #[serde(rename_all = "PascalCase")]
+    Unreachable { error: String },
+    /// The latest finality certificate is this many epochs behind the chain head.
+    #[serde(rename_all = "PascalCase")]
+    Lagging { epochs: ChainEpoch },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -985,4 +1107,143 @@ mod tests {
                 .unwrap(),
         );
     }
+
+    #[test]
+    fn test_make_power_table_cid() {
+        let entry = |id, power: u64, key: u8| F3PowerEntry {
+            id,
+            power: power.into(),
+            pub_key: vec![key; 48],
+        };
+        let mut power_table = vec![entry(3, 10, 3), entry(1, 100, 1), entry(2, 10, 2)];
+        power_table.sort();
+        assert_eq!(
+            power_table.iter().map(|e| e.id).collect_vec(),
+            vec![1, 2, 3]
+        );
+
+        // go-f3 hashes the CBOR tuple encoding of `gpbft.PowerEntries`
+        let mut expected_bytes = vec![0x83];
+        for (id, power, key) in [(1, 100, 1), (2, 10, 2), (3, 10, 3)] {
+            expected_bytes.extend([0x83, id, 0x42, 0x00, power, 0x58, 48]);
+            expected_bytes.extend([key; 48]);
+        }
+        let cid = make_power_table_cid(&power_table).unwrap();
+        assert_eq!(
+            cid,
+            Cid::new_v1(
+                fvm_ipld_encoding::DAG_CBOR,
+                MultihashCode::Blake2b256.digest(&expected_bytes)
+            )
+        );
+
+        // Deterministic, but sensitive to order and content
+        assert_eq!(make_power_table_cid(&power_table.clone()).unwrap(), cid);
+        power_table.swap(1, 2);
+        assert_ne!(make_power_table_cid(&power_table).unwrap(), cid);
+        power_table.swap(1, 2);
+        power_table[0].power += 1;
+        assert_ne!(make_power_table_cid(&power_table).unwrap(), cid);
+    }
+
+    #[test]
+    fn test_f3_status_result_enabled() {
+        use crate::f3::F3OptionSource;
+
+        let power_table =
+            Cid::from_str("bafy2bzaceazjn2promafvtkaquebfgc3xvhoavdbxwns4i54ilgnzch7pkgua")
+                .unwrap();
+        let options = F3Options {
+            chain_finality: 900,
+            bootstrap_epoch: 2_081_674,
+            initial_power_table: Some(power_table),
+        };
+        let sidecar = F3SidecarStatus {
+            state: F3SidecarRunState::Restarting,
+            restarts: 2,
+            last_error: Some("F3 sidecar exited with an error".into()),
+            launch_epoch: None,
+        };
+        let status = F3StatusResult::new(
+            &options,
+            true,
+            true,
+            sidecar.clone(),
+            "127.0.0.1:23456".into(),
+            Some("/data/f3/calibnet".into()),
+            Some(F3OptionsReport {
+                finality: F3OptionSource::ChainDefault,
+                bootstrap_epoch: F3OptionSource::Config,
+                initial_power_table: F3OptionSource::EnvVar(
+                    crate::f3::FOREST_F3_INITIAL_POWER_TABLE.into(),
+                ),
+                ignored_env: [(crate::f3::FOREST_F3_FINALITY.into(), "0".into())].into(),
+            }),
+        );
+        assert_eq!(status.finality, options.chain_finality);
+        assert_eq!(status.bootstrap_epoch, options.bootstrap_epoch);
+        assert_eq!(status.initial_power_table, Some(power_table));
+        assert_eq!(status.sidecar, sidecar);
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "Finality": 900,
+                "BootstrapEpoch": 2_081_674,
+                "InitialPowerTable": { "/": power_table.to_string() },
+                "FfiCompiled": true,
+                "Enabled": true,
+                "Sidecar": {
+                    "state": "Restarting",
+                    "restarts": 2,
+                    "last_error": "F3 sidecar exited with an error",
+                },
+                "RpcEndpoint": "127.0.0.1:23456",
+                "Root": "/data/f3/calibnet",
+                "Sources": {
+                    "Finality": "ChainDefault",
+                    "BootstrapEpoch": "Config",
+                    "InitialPowerTable": { "EnvVar": "FOREST_F3_INITIAL_POWER_TABLE" },
+                    "IgnoredEnv": { "FOREST_F3_FINALITY": "0" },
+                },
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<F3StatusResult>(json).unwrap(),
+            status
+        );
+    }
+
+    #[test]
+    fn test_f3_status_result_compiled_out() {
+        let options = F3Options {
+            chain_finality: 5,
+            bootstrap_epoch: crate::f3::F3_BOOTSTRAP_EPOCH_DISABLED,
+            initial_power_table: None,
+        };
+        let status = F3StatusResult::new(
+            &options,
+            false,
+            true,
+            F3SidecarStatus::default(),
+            "127.0.0.1:23456".into(),
+            None,
+            None,
+        );
+        assert!(!status.ffi_compiled);
+        assert!(status.enabled);
+        assert_eq!(status.finality, 5);
+        assert_eq!(status.bootstrap_epoch, -1);
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["InitialPowerTable"], serde_json::Value::Null);
+        assert_eq!(json["Sidecar"]["state"], "NotStarted");
+        assert_eq!(json["Root"], serde_json::Value::Null);
+        assert_eq!(json["Sources"], serde_json::Value::Null);
+        assert_eq!(
+            serde_json::from_value::<F3StatusResult>(json).unwrap(),
+            status
+        );
+    }
 }
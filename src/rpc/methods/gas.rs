@@ -147,14 +147,14 @@ pub async fn estimate_gas_premium<DB: Blockstore>(
             prev = price.price;
             continue;
         }
-        if prev == TokenAmount::zero() {
+        if prev.is_zero() {
             let ret: TokenAmount = price.price + TokenAmount::from_atto(1);
             return Ok(ret);
         }
         premium = (&price.price + &prev).div_floor(2) + TokenAmount::from_atto(1)
     }
 
-    if premium == TokenAmount::zero() {
+    if premium.is_zero() {
         premium = TokenAmount::from_atto(match nblocksincl {
             1 => (MIN_GAS_PREMIUM * 2.0) as u64,
             2 => (MIN_GAS_PREMIUM * 1.5) as u64,
@@ -0,0 +1,503 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+use crate::{
+    blocks::Tipset,
+    chain::{ChainStore, index::ResolveNullTipset},
+    daemon::db_util::{index_tipset_eth_mappings, index_tipset_events},
+    interpreter::VMTrace,
+    lotus_json::lotus_json_with_self,
+    rpc::{ApiPaths, Ctx, Permission, RpcMethod, ServerError},
+    shim::clock::ChainEpoch,
+    state_manager::{NO_CALLBACK, StateManager, StateOutput},
+};
+use anyhow::{bail, ensure};
+use enumflags2::BitFlags;
+use fvm_ipld_blockstore::Blockstore;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+/// The backfill job of the node, see [`BackfillIndexes`].
+static BACKFILL_JOBS: LazyLock<BackfillJobs> = LazyLock::new(BackfillJobs::default);
+
+pub enum BackfillIndexes {}
+impl RpcMethod<1> for BackfillIndexes {
+    const NAME: &'static str = "Forest.BackfillIndexes";
+    const PARAM_NAMES: [&'static str; 1] = ["params"];
+    const API_PATHS: BitFlags<ApiPaths> = ApiPaths::all();
+    const PERMISSION: Permission = Permission::Admin;
+    const DESCRIPTION: Option<&'static str> = Some(
+        "Starts backfilling indexes from an epoch down to another in the background. Fails if a backfill job is already running.",
+    );
+
+    type Params = (BackfillIndexesParams,);
+    type Ok = BackfillStatusReport;
+
+    async fn handle(
+        ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
+        (params,): Self::Params,
+    ) -> Result<Self::Ok, ServerError> {
+        Ok(BACKFILL_JOBS.start(ctx.state_manager.clone(), params)?)
+    }
+}
+
+pub enum BackfillStatus {}
+impl RpcMethod<0> for BackfillStatus {
+    const NAME: &'static str = "Forest.BackfillStatus";
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_PATHS: BitFlags<ApiPaths> = ApiPaths::all();
+    const PERMISSION: Permission = Permission::Admin;
+    const DESCRIPTION: Option<&'static str> =
+        Some("Returns the progress of the latest backfill job.");
+
+    type Params = ();
+    type Ok = BackfillStatusReport;
+
+    async fn handle(_ctx: Ctx<impl Blockstore>, (): Self::Params) -> Result<Self::Ok, ServerError> {
+        Ok(BACKFILL_JOBS.status())
+    }
+}
+
+pub enum BackfillCancel {}
+impl RpcMethod<0> for BackfillCancel {
+    const NAME: &'static str = "Forest.BackfillCancel";
+    const PARAM_NAMES: [&'static str; 0] = [];
+    const API_PATHS: BitFlags<ApiPaths> = ApiPaths::all();
+    const PERMISSION: Permission = Permission::Admin;
+    const DESCRIPTION: Option<&'static str> =
+        Some("Cancels the running backfill job. It stops after the tipset it is processing.");
+
+    type Params = ();
+    type Ok = BackfillStatusReport;
+
+    async fn handle(_ctx: Ctx<impl Blockstore>, (): Self::Params) -> Result<Self::Ok, ServerError> {
+        Ok(BACKFILL_JOBS.cancel()?)
+    }
+}
+
+/// An index that can be backfilled with [`BackfillIndexes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexKind {
+    /// The Ethereum mappings, see [`index_tipset_eth_mappings`].
+    EthMappings,
+    /// The events roots and the secondary index of events, see [`index_tipset_events`].
+    /// This requires computing the state of every tipset.
+    Events,
+}
+
+/// The parameters of [`BackfillIndexes`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BackfillIndexesParams {
+    /// The epoch to start from, going backwards.
+    pub from_epoch: ChainEpoch,
+    /// The last epoch to backfill, inclusive.
+    pub to_epoch: ChainEpoch,
+    /// The indexes to backfill.
+    pub kinds: Vec<IndexKind>,
+}
+lotus_json_with_self!(BackfillIndexesParams);
+
+/// The state of the latest backfill job.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum BackfillJobState {
+    /// No job has been started.
+    #[default]
+    Idle,
+    /// The job is running, or stopping after it was cancelled.
+    Running,
+    /// The job processed every tipset in its range.
+    Completed,
+    /// The job was cancelled with [`BackfillCancel`].
+    Cancelled,
+    /// The job stopped on an error.
+    Failed { error: String },
+}
+
+/// The progress of the latest backfill job.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BackfillStatusReport {
+    /// The state of the job.
+    pub state: BackfillJobState,
+    /// The parameters of the job, if any.
+    pub params: Option<BackfillIndexesParams>,
+    /// The epoch of the last processed tipset.
+    pub current_epoch: Option<ChainEpoch>,
+    /// Number of epochs processed per second.
+    pub rate: f64,
+    /// Estimated number of seconds until the job completes, if it is running.
+    pub eta_secs: Option<u64>,
+}
+lotus_json_with_self!(BackfillStatusReport);
+
+struct BackfillJob {
+    params: BackfillIndexesParams,
+    state: BackfillJobState,
+    started: Instant,
+    /// How long the job ran for, once it's no longer running.
+    elapsed: Option<Duration>,
+    /// The epoch of the first tipset, which is before `params.from_epoch` if that's a
+    /// null round.
+    start_epoch: Option<ChainEpoch>,
+    current_epoch: Option<ChainEpoch>,
+    cancel: CancellationToken,
+}
+
+impl BackfillJob {
+    fn status(&self) -> BackfillStatusReport {
+        let elapsed = self.elapsed.unwrap_or_else(|| self.started.elapsed());
+        let (rate, eta_secs) = match self.start_epoch.zip(self.current_epoch) {
+            Some((start_epoch, current_epoch)) => {
+                let processed = start_epoch - current_epoch + 1;
+                let rate = processed as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+                let remaining = current_epoch - self.params.to_epoch;
+                let eta_secs = (self.state == BackfillJobState::Running)
+                    .then(|| (remaining as f64 / rate).ceil() as u64);
+                (rate, eta_secs)
+            }
+            None => (0.0, None),
+        };
+        BackfillStatusReport {
+            state: self.state.clone(),
+            params: Some(self.params.clone()),
+            current_epoch: self.current_epoch,
+            rate,
+            eta_secs,
+        }
+    }
+}
+
+/// Runs at most one backfill job at a time, in the background.
+#[derive(Default, Clone)]
+struct BackfillJobs(Arc<Mutex<Option<BackfillJob>>>);
+
+impl BackfillJobs {
+    /// Starts a job, unless one is already running.
+    fn start<DB>(
+        &self,
+        state_manager: Arc<StateManager<DB>>,
+        params: BackfillIndexesParams,
+    ) -> anyhow::Result<BackfillStatusReport>
+    where
+        DB: Blockstore + Send + Sync + 'static,
+    {
+        ensure!(!params.kinds.is_empty(), "no index to backfill");
+        ensure!(
+            params.to_epoch >= 0 && params.to_epoch <= params.from_epoch,
+            "invalid epoch range: from {} down to {}",
+            params.from_epoch,
+            params.to_epoch
+        );
+        let head_epoch = state_manager.chain_store().heaviest_tipset().epoch();
+        ensure!(
+            params.from_epoch <= head_epoch,
+            "epoch {} is after the chain head {head_epoch}",
+            params.from_epoch
+        );
+
+        let mut job = self.0.lock();
+        if let Some(running) = job
+            .as_ref()
+            .filter(|job| job.state == BackfillJobState::Running)
+        {
+            bail!(
+                "a backfill job is already running, from epoch {} down to {}",
+                running.params.from_epoch,
+                running.params.to_epoch
+            );
+        }
+        let cancel = CancellationToken::new();
+        let new_job = job.insert(BackfillJob {
+            params: params.clone(),
+            state: BackfillJobState::Running,
+            started: Instant::now(),
+            elapsed: None,
+            start_epoch: None,
+            current_epoch: None,
+            cancel: cancel.clone(),
+        });
+        let status = new_job.status();
+
+        let jobs = self.clone();
+        tokio::spawn(async move {
+            let state_manager = &state_manager;
+            let result = run_backfill(
+                state_manager.chain_store(),
+                &params,
+                &cancel,
+                |ts| async move {
+                    Ok(state_manager
+                        .compute_tipset_state(ts, NO_CALLBACK, VMTrace::NotTraced)
+                        .await?)
+                },
+                |start_epoch, epoch| {
+                    if let Some(job) = jobs.0.lock().as_mut() {
+                        job.start_epoch = Some(start_epoch);
+                        job.current_epoch = Some(epoch);
+                    }
+                },
+            )
+            .await;
+            if let Some(job) = jobs.0.lock().as_mut() {
+                job.elapsed = Some(job.started.elapsed());
+                job.state = match result {
+                    Ok(true) => BackfillJobState::Completed,
+                    Ok(false) => BackfillJobState::Cancelled,
+                    Err(e) => {
+                        tracing::warn!("Backfill job failed: {e:#}");
+                        BackfillJobState::Failed {
+                            error: format!("{e:#}"),
+                        }
+                    }
+                };
+            }
+        });
+        Ok(status)
+    }
+
+    fn status(&self) -> BackfillStatusReport {
+        self.0
+            .lock()
+            .as_ref()
+            .map(BackfillJob::status)
+            .unwrap_or_default()
+    }
+
+    /// Requests the running job to stop. It is [cancelled](BackfillJobState::Cancelled)
+    /// once it's done with the tipset it is processing.
+    fn cancel(&self) -> anyhow::Result<BackfillStatusReport> {
+        match self.0.lock().as_ref() {
+            Some(job) if job.state == BackfillJobState::Running => {
+                job.cancel.cancel();
+                Ok(job.status())
+            }
+            _ => bail!("no backfill job is running"),
+        }
+    }
+}
+
+/// Backfills the `params.kinds` indexes of the tipsets from `params.from_epoch` down to
+/// `params.to_epoch`, calling `on_tipset` with the epochs of the first tipset and of each
+/// processed tipset. Returns `false` if it was cancelled.
+async fn run_backfill<DB, F, Fut>(
+    chain_store: &ChainStore<DB>,
+    params: &BackfillIndexesParams,
+    cancel: &CancellationToken,
+    compute_tipset_state: F,
+    mut on_tipset: impl FnMut(ChainEpoch, ChainEpoch),
+) -> anyhow::Result<bool>
+where
+    DB: Blockstore,
+    F: Fn(Arc<Tipset>) -> Fut,
+    Fut: Future<Output = anyhow::Result<StateOutput>>,
+{
+    let from_ts = chain_store.chain_index.tipset_by_height(
+        params.from_epoch,
+        chain_store.heaviest_tipset(),
+        ResolveNullTipset::TakeOlder,
+    )?;
+    let start_epoch = from_ts.epoch();
+    tracing::info!(
+        "Backfilling indexes {:?} from epoch {start_epoch} down to {}",
+        params.kinds,
+        params.to_epoch
+    );
+    for ts in Arc::unwrap_or_clone(from_ts)
+        .chain(chain_store.blockstore())
+        .take_while(|ts| ts.epoch() >= params.to_epoch)
+    {
+        if cancel.is_cancelled() {
+            tracing::info!("Backfill job cancelled at epoch {}", ts.epoch());
+            return Ok(false);
+        }
+        if params.kinds.contains(&IndexKind::Events) {
+            let state_output = compute_tipset_state(Arc::new(ts.clone())).await?;
+            index_tipset_events(chain_store, &ts, &state_output)?;
+        }
+        if params.kinds.contains(&IndexKind::EthMappings) {
+            index_tipset_eth_mappings(chain_store, &ts)?;
+        }
+        on_tipset(start_epoch, ts.epoch());
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::networks::ChainConfig;
+    use crate::test_utils::chain4_store;
+
+    async fn wait_until_done(jobs: &BackfillJobs) -> BackfillStatusReport {
+        loop {
+            let status = jobs.status();
+            if status.state != BackfillJobState::Running {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn backfill_job_lifecycle() {
+        let (db, chain_store, head_ts) = chain4_store(ChainConfig::devnet()).await;
+        let chain_store = Arc::new(chain_store);
+        let chain_config = chain_store.chain_config.clone();
+        chain_store
+            .set_heaviest_tipset(Arc::new(head_ts.clone()))
+            .unwrap();
+        let state_manager = Arc::new(StateManager::new(chain_store.clone(), chain_config).unwrap());
+        let epochs = head_ts
+            .clone()
+            .chain(&db)
+            .map(|ts| ts.epoch())
+            .collect::<Vec<_>>();
+        let params = BackfillIndexesParams {
+            from_epoch: head_ts.epoch(),
+            to_epoch: epochs[3],
+            kinds: vec![IndexKind::EthMappings],
+        };
+        let is_indexed = |ts: &Tipset| {
+            chain_store
+                .get_required_tipset_key(&ts.key().cid().unwrap().into())
+                .is_ok()
+        };
+
+        let jobs = BackfillJobs::default();
+        assert_eq!(jobs.status().state, BackfillJobState::Idle);
+        jobs.cancel().unwrap_err();
+        // Invalid ranges
+        for (from_epoch, to_epoch) in [(0, 1), (head_ts.epoch() + 1, 0), (0, -1)] {
+            let params = BackfillIndexesParams {
+                from_epoch,
+                to_epoch,
+                ..params.clone()
+            };
+            jobs.start(state_manager.clone(), params).unwrap_err();
+        }
+
+        // Cancelled before it processes any tipset, as the test runtime is single-threaded
+        let status = jobs.start(state_manager.clone(), params.clone()).unwrap();
+        assert_eq!(status.state, BackfillJobState::Running);
+        assert_eq!(status.params.as_ref(), Some(&params));
+        jobs.start(state_manager.clone(), params.clone())
+            .unwrap_err();
+        jobs.cancel().unwrap();
+        let status = wait_until_done(&jobs).await;
+        assert_eq!(status.state, BackfillJobState::Cancelled);
+        assert_eq!(status.current_epoch, None);
+        assert!(!is_indexed(&head_ts));
+
+        // Restarted
+        jobs.start(state_manager.clone(), params.clone()).unwrap();
+        let status = wait_until_done(&jobs).await;
+        assert_eq!(status.state, BackfillJobState::Completed);
+        assert_eq!(status.current_epoch, Some(epochs[3]));
+        assert!(status.rate > 0.0);
+        assert_eq!(status.eta_secs, None);
+        assert_eq!(
+            head_ts
+                .clone()
+                .chain(&db)
+                .filter(is_indexed)
+                .map(|ts| ts.epoch())
+                .collect::<Vec<_>>(),
+            epochs[..4]
+        );
+    }
+
+    #[test]
+    fn backfill_rate_from_first_tipset() {
+        let job = BackfillJob {
+            params: BackfillIndexesParams {
+                from_epoch: 100,
+                to_epoch: 0,
+                kinds: vec![IndexKind::EthMappings],
+            },
+            state: BackfillJobState::Running,
+            started: Instant::now(),
+            elapsed: Some(Duration::from_secs(10)),
+            // Epochs 91 to 100 are null rounds
+            start_epoch: Some(90),
+            current_epoch: Some(81),
+            cancel: CancellationToken::new(),
+        };
+        let status = job.status();
+        assert_eq!(status.rate, 1.0);
+        assert_eq!(status.eta_secs, Some(81));
+    }
+
+    #[tokio::test]
+    async fn backfill_events() {
+        let (db, chain_store, head_ts) = chain4_store(ChainConfig::devnet()).await;
+        chain_store
+            .set_heaviest_tipset(Arc::new(head_ts.clone()))
+            .unwrap();
+        let tipsets = head_ts.clone().chain(&db).collect::<Vec<_>>();
+        let params = BackfillIndexesParams {
+            from_epoch: head_ts.epoch(),
+            to_epoch: tipsets[3].epoch(),
+            kinds: vec![IndexKind::Events],
+        };
+        // A tipset without events has an events root too
+        let events_root = |ts: &Tipset| ts.key().cid().unwrap();
+
+        let mut processed = vec![];
+        let completed = run_backfill(
+            &chain_store,
+            &params,
+            &CancellationToken::new(),
+            |ts| async move {
+                Ok(StateOutput {
+                    state_root: *ts.parent_state(),
+                    receipt_root: *ts.parent_state(),
+                    events: vec![],
+                    events_roots: vec![Some(events_root(&ts))],
+                })
+            },
+            |start_epoch, epoch| processed.push((start_epoch, epoch)),
+        )
+        .await
+        .unwrap();
+        assert!(completed);
+        assert_eq!(
+            processed,
+            tipsets[..4]
+                .iter()
+                .map(|ts| (head_ts.epoch(), ts.epoch()))
+                .collect::<Vec<_>>()
+        );
+        let any_emitter = crate::chain::store::events_index::EventsIndexFilter {
+            emitters: Some(vec![0]),
+            topic0s: None,
+        };
+        for (i, ts) in tipsets.iter().enumerate() {
+            let indexed = i < 4;
+            assert_eq!(
+                chain_store
+                    .get_tipset_key(&events_root(ts))
+                    .unwrap()
+                    .as_ref(),
+                indexed.then_some(ts.key())
+            );
+            // The events of the indexed tipsets are known to not match
+            assert_eq!(
+                chain_store
+                    .may_have_matching_events(ts, &any_emitter)
+                    .unwrap(),
+                !indexed
+            );
+            // Only the events were backfilled
+            assert!(
+                chain_store
+                    .get_required_tipset_key(&ts.key().cid().unwrap().into())
+                    .is_err()
+            );
+        }
+    }
+}
@@ -4,6 +4,8 @@
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::{
+    daemon::db_util::EthIndexStatus,
+    f3::supervisor::F3SidecarStatus,
     lotus_json::lotus_json_with_self,
     rpc::{ApiPaths, Ctx, Permission, RpcMethod, ServerError},
 };
@@ -43,6 +45,8 @@ impl RpcMethod<0> for NodeStatus {
 
         node_status.sync_status.epoch = head.epoch() as u64;
         node_status.sync_status.behind = behind;
+        node_status.f3_sidecar = Some(crate::f3::supervisor::sidecar_status());
+        node_status.eth_index = Some(crate::daemon::db_util::eth_index_status());
 
         if head.epoch() > chain_finality {
             let mut block_count = 0;
@@ -96,5 +100,11 @@ pub struct NodeStatusResult {
     pub sync_status: NodeSyncStatus,
     pub peer_status: NodePeerStatus,
     pub chain_status: NodeChainStatus,
+    /// Forest-specific, absent on Lotus.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub f3_sidecar: Option<F3SidecarStatus>,
+    /// Forest-specific, absent on Lotus.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eth_index: Option<EthIndexStatus>,
 }
 lotus_json_with_self!(NodeStatusResult);
@@ -36,8 +36,13 @@ use crate::shim::piece::PaddedPieceSize;
 use crate::shim::sector::{SectorNumber, SectorSize};
 use crate::shim::state_tree::{ActorID, StateTree};
 use crate::shim::{
-    address::Address, clock::ChainEpoch, deal::DealID, econ::TokenAmount, executor::Receipt,
-    state_tree::ActorState, version::NetworkVersion,
+    address::Address,
+    clock::ChainEpoch,
+    deal::DealID,
+    econ::{NonNegativeTokenAmount, Rounding, TokenAmount},
+    executor::Receipt,
+    state_tree::ActorState,
+    version::NetworkVersion,
 };
 use crate::state_manager::{
     MarketBalance, StateManager, StateOutput, circulating_supply::GenesisInfo, utils::structured,
@@ -68,13 +73,11 @@ use nunny::vec as nonempty;
 use parking_lot::Mutex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::ops::Mul;
 use std::path::PathBuf;
 use std::{sync::Arc, time::Duration};
 use tokio::task::JoinSet;
 
-const INITIAL_PLEDGE_NUM: u64 = 110;
-const INITIAL_PLEDGE_DEN: u64 = 100;
+const INITIAL_PLEDGE_PERCENT: u64 = 110;
 
 pub enum StateCall {}
 
@@ -1000,8 +1003,7 @@ impl RpcMethod<3> for StateMinerInitialPledgeCollateral {
             )?
             .into();
 
-        let (q, _) = (initial_pledge * INITIAL_PLEDGE_NUM).div_rem(INITIAL_PLEDGE_DEN);
-        Ok(q)
+        Ok(initial_pledge.percent(INITIAL_PLEDGE_PERCENT, Rounding::Floor))
     }
 }
 
@@ -1053,8 +1055,7 @@ impl RpcMethod<3> for StateMinerPreCommitDepositForPower {
         let deposit: TokenAmount = reward_state
             .pre_commit_deposit_for_power(power_smoothed, sector_weight)?
             .into();
-        let (value, _) = (deposit * INITIAL_PLEDGE_NUM).div_rem(INITIAL_PLEDGE_DEN);
-        Ok(value)
+        Ok(deposit.percent(INITIAL_PLEDGE_PERCENT, Rounding::Floor))
     }
 }
 
@@ -1514,7 +1515,7 @@ impl RpcMethod<4> for StateGetRandomnessFromTickets {
     const DESCRIPTION: Option<&'static str> = Some("Samples the chain for randomness.");
 
     type Params = (i64, ChainEpoch, Vec<u8>, ApiTipsetKey);
-    type Ok = Vec<u8>;
+    type Ok = [u8; 32];
 
     async fn handle(
         ctx: Ctx<impl Blockstore>,
@@ -1529,7 +1530,7 @@ impl RpcMethod<4> for StateGetRandomnessFromTickets {
             rand_epoch,
             &entropy,
         )?;
-        Ok(value.to_vec())
+        Ok(value)
     }
 }
 
@@ -1543,7 +1544,7 @@ impl RpcMethod<2> for StateGetRandomnessDigestFromTickets {
     const DESCRIPTION: Option<&'static str> = Some("Samples the chain for randomness.");
 
     type Params = (ChainEpoch, ApiTipsetKey);
-    type Ok = Vec<u8>;
+    type Ok = [u8; 32];
 
     async fn handle(
         ctx: Ctx<impl Blockstore>,
@@ -1551,8 +1552,7 @@ impl RpcMethod<2> for StateGetRandomnessDigestFromTickets {
     ) -> Result<Self::Ok, ServerError> {
         let tipset = ctx.chain_store().load_required_tipset_or_heaviest(&tsk)?;
         let chain_rand = ctx.state_manager.chain_rand(tipset);
-        let digest = chain_rand.get_chain_randomness(rand_epoch, false)?;
-        Ok(digest.to_vec())
+        Ok(chain_rand.get_chain_randomness(rand_epoch, false)?)
     }
 }
 
@@ -1569,7 +1569,7 @@ impl RpcMethod<4> for StateGetRandomnessFromBeacon {
     );
 
     type Params = (i64, ChainEpoch, Vec<u8>, ApiTipsetKey);
-    type Ok = Vec<u8>;
+    type Ok = [u8; 32];
 
     async fn handle(
         ctx: Ctx<impl Blockstore>,
@@ -1584,7 +1584,7 @@ impl RpcMethod<4> for StateGetRandomnessFromBeacon {
             rand_epoch,
             &entropy,
         )?;
-        Ok(value.to_vec())
+        Ok(value)
     }
 }
 
@@ -1598,7 +1598,7 @@ impl RpcMethod<2> for StateGetRandomnessDigestFromBeacon {
     const DESCRIPTION: Option<&'static str> = Some("Samples the beacon for randomness.");
 
     type Params = (ChainEpoch, ApiTipsetKey);
-    type Ok = Vec<u8>;
+    type Ok = [u8; 32];
 
     async fn handle(
         ctx: Ctx<impl Blockstore>,
@@ -1606,8 +1606,7 @@ impl RpcMethod<2> for StateGetRandomnessDigestFromBeacon {
     ) -> Result<Self::Ok, ServerError> {
         let tipset = ctx.chain_store().load_required_tipset_or_heaviest(&tsk)?;
         let chain_rand = ctx.state_manager.chain_rand(tipset);
-        let digest = chain_rand.get_beacon_randomness_v3(rand_epoch)?;
-        Ok(digest.to_vec())
+        Ok(chain_rand.get_beacon_randomness_v3(rand_epoch)?)
     }
 }
 
@@ -1682,7 +1681,7 @@ impl RpcMethod<1> for StateCirculatingSupply {
         Some("Returns the exact circulating supply of Filecoin at the given tipset.");
 
     type Params = (ApiTipsetKey,);
-    type Ok = TokenAmount;
+    type Ok = NonNegativeTokenAmount;
 
     async fn handle(
         ctx: Ctx<impl Blockstore>,
@@ -1694,7 +1693,7 @@ impl RpcMethod<1> for StateCirculatingSupply {
         let genesis_info = GenesisInfo::from_chain_config(ctx.chain_config().clone());
         let supply =
             genesis_info.get_state_circulating_supply(height - 1, &ctx.store_owned(), root)?;
-        Ok(supply)
+        Ok(supply.try_into()?)
     }
 }
 
@@ -1889,8 +1888,7 @@ impl RpcMethod<3> for StateDealProviderCollateralBounds {
         ctx: Ctx<impl Blockstore + Send + Sync + 'static>,
         (size, verified, ApiTipsetKey(tsk)): Self::Params,
     ) -> Result<Self::Ok, ServerError> {
-        let deal_provider_collateral_num = BigInt::from(110);
-        let deal_provider_collateral_denom = BigInt::from(100);
+        let deal_provider_collateral_percent = 110;
 
         // This is more eloquent than giving the whole match pattern a type.
         let _: bool = verified;
@@ -1922,14 +1920,11 @@ impl RpcMethod<3> for StateDealProviderCollateralBounds {
             &supply.into(),
         );
 
-        let min = min
-            .atto()
-            .mul(deal_provider_collateral_num)
-            .div_euclid(&deal_provider_collateral_denom);
+        let min = TokenAmount::from(min).percent(deal_provider_collateral_percent, Rounding::Floor);
 
         Ok(DealCollateralBounds {
             max: max.into(),
-            min: TokenAmount::from_atto(min),
+            min,
         })
     }
 }
@@ -3037,8 +3032,7 @@ impl RpcMethod<4> for StateMinerInitialPledgeForSector {
             )?
             .into();
 
-        let (value, _) = (initial_pledge * INITIAL_PLEDGE_NUM).div_rem(INITIAL_PLEDGE_DEN);
-        Ok(value)
+        Ok(initial_pledge.percent(INITIAL_PLEDGE_PERCENT, Rounding::Floor))
     }
 }
 
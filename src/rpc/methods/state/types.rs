@@ -26,7 +26,7 @@ pub struct ComputeStateOutput {
     #[serde(with = "crate::lotus_json")]
     pub root: Cid,
     #[schemars(with = "LotusJson<ApiInvocResult>")]
-    #[serde(with = "crate::lotus_json")]
+    #[serde(with = "crate::lotus_json::by_ref")]
     pub trace: Vec<ApiInvocResult>,
 }
 
@@ -38,10 +38,10 @@ pub struct ApiInvocResult {
     #[serde(with = "crate::lotus_json")]
     #[schemars(with = "LotusJson<Cid>")]
     pub msg_cid: Cid,
-    #[serde(with = "crate::lotus_json")]
+    #[serde(with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<Message>")]
     pub msg: Message,
-    #[serde(with = "crate::lotus_json")]
+    #[serde(with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<Option<Receipt>>")]
     pub msg_rct: Option<Receipt>,
     pub error: String,
@@ -131,7 +131,7 @@ pub struct ExecutionTrace {
     pub msg_rct: ReturnTrace,
     pub invoked_actor: Option<ActorTrace>,
     pub gas_charges: Vec<GasTrace>,
-    #[serde(with = "crate::lotus_json")]
+    #[serde(with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<Vec<ExecutionTrace>>")]
     pub subcalls: Vec<ExecutionTrace>,
 }
@@ -163,7 +163,7 @@ pub struct MessageTrace {
     #[schemars(with = "LotusJson<TokenAmount>")]
     pub value: TokenAmount,
     pub method: u64,
-    #[serde(with = "crate::lotus_json")]
+    #[serde(with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<RawBytes>")]
     pub params: RawBytes,
     pub params_codec: u64,
@@ -188,7 +188,7 @@ lotus_json_with_self!(ActorTrace);
 #[serde(rename_all = "PascalCase")]
 pub struct ReturnTrace {
     pub exit_code: ExitCode,
-    #[serde(with = "crate::lotus_json")]
+    #[serde(with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<RawBytes>")]
     pub r#return: RawBytes,
     pub return_codec: u64,
@@ -225,10 +225,10 @@ impl PartialEq for GasTrace {
 #[derive(PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 pub struct InvocResult {
-    #[serde(with = "crate::lotus_json")]
+    #[serde(with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<Message>")]
     pub msg: Message,
-    #[serde(with = "crate::lotus_json")]
+    #[serde(with = "crate::lotus_json::by_ref")]
     #[schemars(with = "LotusJson<Option<Receipt>>")]
     pub msg_rct: Option<Receipt>,
     pub error: Option<String>,
@@ -9,7 +9,7 @@ use crate::rpc::{ApiPaths, Ctx, Permission, RpcMethod, ServerError};
 use crate::shim::{
     address::Address,
     crypto::{Signature, SignatureType},
-    econ::TokenAmount,
+    econ::{NonNegativeTokenAmount, TokenAmount},
     message::Message,
     state_tree::StateTree,
 };
@@ -25,7 +25,7 @@ impl RpcMethod<1> for WalletBalance {
     const DESCRIPTION: Option<&'static str> = Some("Returns the balance of a wallet.");
 
     type Params = (Address,);
-    type Ok = TokenAmount;
+    type Ok = NonNegativeTokenAmount;
 
     async fn handle(
         ctx: Ctx<impl Blockstore>,
@@ -34,10 +34,11 @@ impl RpcMethod<1> for WalletBalance {
         let heaviest_ts = ctx.chain_store().heaviest_tipset();
         let cid = heaviest_ts.parent_state();
 
-        Ok(StateTree::new_from_root(ctx.store_owned(), cid)?
+        let balance: TokenAmount = StateTree::new_from_root(ctx.store_owned(), cid)?
             .get_actor(&address)?
             .map(|it| it.balance.clone().into())
-            .unwrap_or_default())
+            .unwrap_or_default();
+        Ok(balance.try_into()?)
     }
 }
 
@@ -22,7 +22,7 @@ use futures::FutureExt as _;
 use jsonrpsee::server::ServerConfig;
 use log_layer::LogLayer;
 use reflect::Ctx;
-pub use reflect::{ApiPaths, Permission, RpcMethod, RpcMethodExt};
+pub use reflect::{ApiPaths, Permission, RpcMethod, RpcMethodExt, UnknownFields};
 pub use request::Request;
 use schemars::Schema;
 use segregation_layer::SegregationLayer;
@@ -135,6 +135,11 @@ macro_rules! for_each_rpc_method {
         $callback!($crate::rpc::gas::GasEstimateGasPremium);
         $callback!($crate::rpc::gas::GasEstimateMessageGas);
 
+        // index vertical
+        $callback!($crate::rpc::index::BackfillCancel);
+        $callback!($crate::rpc::index::BackfillIndexes);
+        $callback!($crate::rpc::index::BackfillStatus);
+
         // market vertical
         $callback!($crate::rpc::market::MarketAddBalance);
 
@@ -273,6 +278,8 @@ macro_rules! for_each_rpc_method {
         $callback!($crate::rpc::f3::F3GetF3PowerTable);
         $callback!($crate::rpc::f3::F3GetF3PowerTableByInstance);
         $callback!($crate::rpc::f3::F3IsRunning);
+        $callback!($crate::rpc::f3::F3Ready);
+        $callback!($crate::rpc::f3::F3Status);
         $callback!($crate::rpc::f3::F3GetProgress);
         $callback!($crate::rpc::f3::F3GetManifest);
         $callback!($crate::rpc::f3::F3ListParticipants);
@@ -385,6 +392,7 @@ mod methods {
     pub mod eth;
     pub mod f3;
     pub mod gas;
+    pub mod index;
     pub mod market;
     pub mod miner;
     pub mod misc;
@@ -497,6 +505,7 @@ pub async fn start_rpc<DB>(
     state: RPCState<DB>,
     rpc_endpoint: SocketAddr,
     filter_list: Option<FilterList>,
+    unknown_fields: UnknownFields,
 ) -> anyhow::Result<()>
 where
     DB: Blockstore + Send + Sync + 'static,
@@ -505,7 +514,7 @@ where
     // `Arc` is needed because we will share the state between two modules
     let state = Arc::new(state);
     let keystore = state.keystore.clone();
-    let mut module = create_module(state.clone());
+    let mut module = create_module(state.clone(), unknown_fields);
 
     let mut pubsub_module = FilRpcModule::default();
 
@@ -635,14 +644,22 @@ where
     Ok(())
 }
 
-fn create_module<DB>(state: Arc<RPCState<DB>>) -> RpcModule<RPCState<DB>>
+fn create_module<DB>(
+    state: Arc<RPCState<DB>>,
+    unknown_fields: UnknownFields,
+) -> RpcModule<RPCState<DB>>
 where
     DB: Blockstore + Send + Sync + 'static,
 {
     let mut module = RpcModule::from_arc(state);
     macro_rules! register {
         ($ty:ty) => {
-            <$ty>::register(&mut module, ParamStructure::ByPosition).unwrap();
+            <$ty>::register(
+                &mut module,
+                ParamStructure::ByPosition,
+                <$ty>::UNKNOWN_FIELDS.unwrap_or(unknown_fields),
+            )
+            .unwrap();
             // Optionally register an alias for the method.
             <$ty>::register_alias(&mut module).unwrap();
         };
@@ -750,4 +767,50 @@ mod tests {
             insta::assert_yaml_snapshot!(_spec);
         }
     }
+
+    #[test]
+    fn openrpc_schema_annotations() {
+        let spec = serde_json::to_value(super::openrpc(ApiPaths::V1, None)).unwrap();
+        let schemas = &spec["components"]["schemas"];
+        let methods = spec["methods"].as_array().unwrap();
+        let resolve = |schema: &serde_json::Value| {
+            let name = schema["$ref"]
+                .as_str()
+                .and_then(|it| it.strip_prefix("#/components/schemas/"))
+                .unwrap_or_else(|| panic!("not a reference: {schema}"));
+            schemas[name].clone()
+        };
+        let method = |name: &str| {
+            methods
+                .iter()
+                .find(|it| it["name"] == name)
+                .unwrap_or_else(|| panic!("no method {name}"))
+        };
+
+        for (schema, format) in [
+            (
+                resolve(&method("Filecoin.ChainReadObj")["params"][0]["schema"]),
+                "cid",
+            ),
+            (
+                resolve(&method("Filecoin.ChainReadObj")["result"]["schema"]),
+                "byte",
+            ),
+            (
+                resolve(&method("Filecoin.WalletBalance")["params"][0]["schema"]),
+                "filecoin-address",
+            ),
+            (
+                resolve(&method("Filecoin.WalletBalance")["result"]["schema"]),
+                "bigint",
+            ),
+        ] {
+            assert_eq!(schema["format"], format, "{schema}");
+            assert!(schema["description"].is_string(), "{schema}");
+            assert!(
+                !schema["examples"].as_array().unwrap().is_empty(),
+                "{schema}"
+            );
+        }
+    }
 }
@@ -71,6 +71,8 @@ pub trait RpcMethod<const ARITY: usize> {
     const SUMMARY: Option<&'static str> = None;
     /// Becomes [`openrpc_types::Method::description`].
     const DESCRIPTION: Option<&'static str> = None;
+    /// Overrides the server-wide [`UnknownFields`] setting for this method.
+    const UNKNOWN_FIELDS: Option<UnknownFields> = None;
     /// Types of each argument. [`Option`]-al arguments MUST follow mandatory ones.
     type Params: Params<ARITY>;
     /// Return value of this method.
@@ -139,6 +141,34 @@ impl ApiPaths {
     }
 }
 
+/// What to do with unknown fields in the parameters of an RPC request.
+///
+/// Responses from other implementations are always parsed leniently.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    strum::Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(test, derive(derive_quickcheck_arbitrary::Arbitrary))]
+pub enum UnknownFields {
+    /// Silently ignore them, like Lotus does.
+    #[default]
+    Ignore,
+    /// Fail the request, naming the unknown field and the accepted ones.
+    /// Useful for catching typos in clients.
+    Reject,
+}
+
 /// Utility methods, defined as an extension trait to avoid having to specify
 /// `ARITY` in user code.
 pub trait RpcMethodExt<const ARITY: usize>: RpcMethod<ARITY> {
@@ -163,6 +193,7 @@ pub trait RpcMethodExt<const ARITY: usize>: RpcMethod<ARITY> {
     fn parse_params(
         params_raw: Option<impl AsRef<str>>,
         calling_convention: ParamStructure,
+        unknown_fields: UnknownFields,
     ) -> anyhow::Result<Self::Params> {
         Ok(Self::Params::parse(
             params_raw
@@ -171,6 +202,7 @@ pub trait RpcMethodExt<const ARITY: usize>: RpcMethod<ARITY> {
             Self::PARAM_NAMES,
             calling_convention,
             Self::N_REQUIRED_PARAMS,
+            unknown_fields,
         )?)
     }
 
@@ -226,6 +258,7 @@ pub trait RpcMethodExt<const ARITY: usize>: RpcMethod<ARITY> {
     fn register(
         module: &mut RpcModule<crate::rpc::RPCState<impl Blockstore + Send + Sync + 'static>>,
         calling_convention: ParamStructure,
+        unknown_fields: UnknownFields,
     ) -> Result<&mut jsonrpsee::MethodCallback, jsonrpsee::core::RegisterMethodError>
     where
         <Self::Ok as HasLotusJson>::LotusJson: Clone + 'static,
@@ -238,7 +271,7 @@ pub trait RpcMethodExt<const ARITY: usize>: RpcMethod<ARITY> {
         );
 
         module.register_async_method(Self::NAME, move |params, ctx, _extensions| async move {
-            let params = Self::parse_params(params.as_str(), calling_convention)
+            let params = Self::parse_params(params.as_str(), calling_convention, unknown_fields)
                 .map_err(|e| Error::invalid_params(e, None))?;
             let ok = Self::handle(ctx, params).await?;
             Result::<_, jsonrpsee::types::ErrorObjectOwned>::Ok(ok.into_lotus_json())
@@ -338,6 +371,7 @@ pub trait Params<const ARITY: usize>: HasLotusJson {
         names: [&str; ARITY],
         calling_convention: ParamStructure,
         n_required: usize,
+        unknown_fields: UnknownFields,
     ) -> Result<Self, Error>
     where
         Self: Sized;
@@ -393,8 +427,10 @@ macro_rules! do_impls {
                 arg_names: [&str; $arity],
                 calling_convention: ParamStructure,
                 n_required: usize,
+                unknown_fields: UnknownFields,
             ) -> Result<Self, Error> {
-                let mut _parser = Parser::new(raw, &arg_names, calling_convention, n_required)?;
+                let mut _parser = Parser::new(raw, &arg_names, calling_convention, n_required)?
+                    .unknown_fields(unknown_fields);
                 Ok(($(_parser.parse::<crate::lotus_json::LotusJson<$arg>>()?.into_inner(),)*))
             }
             fn schemas(_gen: &mut SchemaGenerator) -> [(Schema, bool); $arity] {
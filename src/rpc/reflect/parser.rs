@@ -6,7 +6,7 @@ use openrpc_types::ParamStructure;
 use serde::Deserialize;
 use serde_json::{Value, json};
 
-use super::{jsonrpc_types::RequestParameters, util::Optional as _};
+use super::{UnknownFields, jsonrpc_types::RequestParameters, util::Optional as _};
 use crate::rpc::error::ServerError;
 
 /// Parser for JSON-RPC parameters.
@@ -23,6 +23,8 @@ pub struct Parser<'a> {
     n_required: usize,
     /// Has any error occurred?
     has_errored: bool,
+    /// What to do with unknown fields in the parameters.
+    unknown_fields: UnknownFields,
 }
 
 #[derive(Debug)]
@@ -97,8 +99,14 @@ impl<'a> Parser<'a> {
             call_count: 0,
             n_required,
             has_errored: false,
+            unknown_fields: UnknownFields::default(),
         })
     }
+    /// See [`UnknownFields`].
+    pub fn unknown_fields(mut self, unknown_fields: UnknownFields) -> Self {
+        self.unknown_fields = unknown_fields;
+        self
+    }
     fn error<T>(&mut self, e: ParseError<'a>) -> Result<T, ParseError<'a>> {
         self.has_errored = true;
         Err(e)
@@ -136,13 +144,17 @@ impl<'a> Parser<'a> {
             ty,
             error,
         };
+        let from_value = match self.unknown_fields {
+            UnknownFields::Ignore => serde_json::from_value::<T>,
+            UnknownFields::Reject => crate::lotus_json::from_value_strict::<T>,
+        };
         let t = match &mut self.params {
             None => match T::optional() {
                 true => T::unwrap_none(),
                 false => self.error(missing_parameter)?,
             },
             Some(ParserInner::ByName(it)) => match it.remove(name) {
-                Some(it) => match serde_json::from_value::<T>(it) {
+                Some(it) => match from_value(it) {
                     Ok(it) => it,
                     Err(e) => self.error(deserialize_error(e))?,
                 },
@@ -152,7 +164,7 @@ impl<'a> Parser<'a> {
                 },
             },
             Some(ParserInner::ByPosition(it)) => match it.pop_front() {
-                Some(it) => match serde_json::from_value::<T>(it) {
+                Some(it) => match from_value(it) {
                     Ok(it) => it,
                     Err(e) => self.error(deserialize_error(e))?,
                 },
@@ -382,6 +394,33 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn unknown_fields() {
+        use crate::{lotus_json::LotusJson, shim::message::Message};
+
+        let typo = || from_value!([{ "To": "f01", "From": "f02", "GasLimt": 1 }]);
+
+        // lenient by default
+        let mut parser = Parser::_new(typo(), &["p0"], ParamStructure::Either, 1).unwrap();
+        let message = parser._parse::<LotusJson<Message>>().unwrap().into_inner();
+        assert_eq!(message.gas_limit, 0);
+
+        let mut parser = Parser::_new(typo(), &["p0"], ParamStructure::Either, 1)
+            .unwrap()
+            .unknown_fields(UnknownFields::Reject);
+        match parser._parse::<LotusJson<Message>>().unwrap_err() {
+            ParseError::Deser {
+                name: "p0", error, ..
+            } => assert!(
+                error
+                    .to_string()
+                    .starts_with("unknown field `GasLimt`, expected one of `Version`, `To`"),
+                "{error}"
+            ),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
     #[test]
     #[should_panic = "`Parser` was initialized with 0 arguments, but `parse` was called 1 times"]
     fn called_too_much() {
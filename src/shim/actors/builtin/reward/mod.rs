@@ -19,7 +19,6 @@ use fil_actor_miner_state::v14::initial_pledge_for_power as initial_pledge_for_p
 use fil_actor_miner_state::v15::initial_pledge_for_power as initial_pledge_for_power_v15;
 use fil_actor_miner_state::v16::initial_pledge_for_power as initial_pledge_for_power_v16;
 use fvm_shared2::address::Address;
-use fvm_shared2::bigint::Integer;
 use fvm_shared2::sector::StoragePower;
 use fvm_shared2::smooth::FilterEstimate;
 use fvm_shared2::{TOTAL_FILECOIN, econ::TokenAmount, piece::PaddedPieceSize};
@@ -174,12 +173,13 @@ impl State {
         let power_share_denom =
             max(max(network_raw_power, baseline_power), &power_share_num).clone();
 
-        let num: BigInt = power_share_num * lock_target_num.atto();
-        let denom: BigInt = power_share_denom * policy.prov_collateral_percent_supply_denom;
-        (
-            TokenAmount::from_atto(num.div_floor(&denom)),
-            TOTAL_FILECOIN.clone(),
-        )
+        let min = crate::shim::econ::TokenAmount::from(lock_target_num)
+            .mul_div_floor(
+                power_share_num,
+                power_share_denom * policy.prov_collateral_percent_supply_denom,
+            )
+            .expect("denominator must be non-zero");
+        (min.into(), TOTAL_FILECOIN.clone())
     }
 
     pub fn deal_provider_collateral_bounds(
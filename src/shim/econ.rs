@@ -2,18 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use std::{
+    borrow::Borrow,
     fmt,
-    ops::{Add, AddAssign, Deref, DerefMut, Mul, MulAssign, Sub, SubAssign},
+    iter::Sum,
+    ops::{Add, AddAssign, Deref, DerefMut, Div, Mul, MulAssign, Rem, Sub, SubAssign},
+    str::FromStr,
     sync::LazyLock,
 };
 
 use super::fvm_shared_latest::econ::TokenAmount as TokenAmount_latest;
+use bigdecimal::BigDecimal;
 use fvm_shared2::econ::TokenAmount as TokenAmount_v2;
 use fvm_shared3::econ::TokenAmount as TokenAmount_v3;
 pub use fvm_shared3::{BLOCK_GAS_LIMIT, TOTAL_FILECOIN_BASE};
 use fvm_shared4::econ::TokenAmount as TokenAmount_v4;
-use num_bigint::BigInt;
-use num_traits::Zero;
+use num::Integer as _;
+use num_bigint::{BigInt, Sign};
+use num_rational::BigRational;
+use num_traits::{ToPrimitive as _, Zero};
 use serde::{Deserialize, Serialize};
 use static_assertions::const_assert_eq;
 
@@ -24,6 +30,29 @@ const_assert_eq!(TOTAL_FILECOIN_BASE, fvm_shared2::TOTAL_FILECOIN_BASE);
 pub static TOTAL_FILECOIN: LazyLock<TokenAmount> =
     LazyLock::new(|| TokenAmount::from_whole(TOTAL_FILECOIN_BASE));
 
+/// Length of the big-endian encoding of an Ethereum `uint256`, see
+/// [`TokenAmount::to_eth_bytes`].
+pub const ETH_UINT256_LENGTH: usize = 32;
+
+/// The default multiple of [`TOTAL_FILECOIN`] that
+/// [`TokenAmount::try_from_atto_checked`] accepts.
+pub const DEFAULT_MAX_SUPPLY_MULTIPLE: u64 = 10;
+
+// The amounts below can't be `const`, as [`TokenAmount_latest`] has no `const`
+// constructor, so borrow them instead of constructing amounts in hot paths.
+
+/// Zero attoFIL, see [`TokenAmount::zero`].
+#[allow(dead_code)]
+pub static ZERO: LazyLock<TokenAmount> = LazyLock::new(TokenAmount::zero);
+
+/// One FIL.
+#[allow(dead_code)]
+pub static ONE_FIL: LazyLock<TokenAmount> = LazyLock::new(|| TokenAmount::from_whole(1));
+
+/// One nanoFIL.
+#[allow(dead_code)]
+pub static ONE_NANO_FIL: LazyLock<TokenAmount> = LazyLock::new(|| TokenAmount::from_nano(1));
+
 #[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Serialize, Deserialize, Default)]
 #[serde(transparent)]
 pub struct TokenAmount(TokenAmount_latest);
@@ -49,16 +78,24 @@ impl quickcheck::Arbitrary for TokenAmount {
         // to log-scaled.
         let significant_bits = usize::arbitrary(g) % ((MAX_BIGINT_SIZE - 1) * 8);
         let bigint_upper_limit = BigUint::from(1u8) << significant_bits;
-        TokenAmount::from_atto(BigUint::arbitrary(g) % bigint_upper_limit)
+        let amount = TokenAmount::from_atto(BigUint::arbitrary(g) % bigint_upper_limit);
+        // Negative amounts are valid, e.g. penalty deltas. Their sign takes the
+        // same leading byte as that of positive amounts, so they fit too.
+        match bool::arbitrary(g) {
+            true => TokenAmount::from_atto(-amount.atto()),
+            false => amount,
+        }
     }
 }
 
 impl Zero for TokenAmount {
+    /// Doesn't allocate, as zero has no digits.
     fn zero() -> Self {
-        TokenAmount(TokenAmount_latest::zero())
+        TokenAmount::from_atto(BigInt::ZERO)
     }
+    /// Checks the sign, without comparing digits, so prefer it to `== ZERO`.
     fn is_zero(&self) -> bool {
-        self.0.is_zero()
+        self.atto().sign() == Sign::NoSign
     }
 }
 
@@ -76,10 +113,46 @@ impl DerefMut for TokenAmount {
     }
 }
 
+impl Sum for TokenAmount {
+    fn sum<I: Iterator<Item = TokenAmount>>(iter: I) -> Self {
+        iter.fold(TokenAmount::zero(), |acc, it| acc + it)
+    }
+}
+
+impl<'a> Sum<&'a TokenAmount> for TokenAmount {
+    fn sum<I: Iterator<Item = &'a TokenAmount>>(iter: I) -> Self {
+        iter.fold(TokenAmount::zero(), |acc, it| acc + it)
+    }
+}
+
+/// `{}` formats the amount like the underlying FVM type, while the alternate
+/// form, `{:#}`, formats it for humans with a unit, see [`TokenAmount::pretty`].
+/// The precision of the alternate form is the number of significant digits, see
+/// [`TokenAmount::format_units`].
 impl std::fmt::Display for TokenAmount {
     // This trait requires `fmt` with this exact signature.
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        self.0.fmt(f)
+        if f.alternate() {
+            let formatted = self
+                .format_units(f.precision().unwrap_or(Self::PRETTY_SIGNIFICANT_DIGITS))
+                .to_string();
+            // Not `f.pad`, which would truncate the unit to the precision
+            let padding = f
+                .width()
+                .unwrap_or(0)
+                .saturating_sub(formatted.chars().count());
+            let (before, after) = match f.align() {
+                None | Some(fmt::Alignment::Left) => (0, padding),
+                Some(fmt::Alignment::Right) => (padding, 0),
+                Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+            };
+            let fill = f.fill().to_string();
+            f.write_str(&fill.repeat(before))?;
+            f.write_str(&formatted)?;
+            f.write_str(&fill.repeat(after))
+        } else {
+            self.0.fmt(f)
+        }
     }
 }
 
@@ -95,10 +168,61 @@ impl TokenAmount {
         self.0.atto()
     }
 
+    /// Whether the amount is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.atto().sign() == Sign::Minus
+    }
+
+    /// Whether the amount is greater than zero.
+    pub fn is_positive(&self) -> bool {
+        self.atto().sign() == Sign::Plus
+    }
+
+    /// The amount without its sign.
+    pub fn abs(&self) -> TokenAmount {
+        TokenAmount::from_atto(self.atto().magnitude().clone())
+    }
+
+    /// `-1`, `0` or `1`, depending on the sign of the amount.
+    pub fn signum(&self) -> i8 {
+        match self.atto().sign() {
+            Sign::Minus => -1,
+            Sign::NoSign => 0,
+            Sign::Plus => 1,
+        }
+    }
+
     pub fn from_atto(atto: impl Into<BigInt>) -> Self {
         TokenAmount_v3::from_atto(atto).into()
     }
 
+    /// Like [`Self::from_atto`], but rejects amounts whose magnitude exceeds
+    /// [`DEFAULT_MAX_SUPPLY_MULTIPLE`] times [`TOTAL_FILECOIN`]. Use it for
+    /// untrusted inputs, e.g. RPC parameters, so that absurdly large amounts
+    /// aren't carried into (expensive) arithmetic.
+    pub fn try_from_atto_checked(
+        atto: impl Into<BigInt>,
+    ) -> Result<Self, TokenAmountTooLargeError> {
+        Self::try_from_atto_bounded(atto, DEFAULT_MAX_SUPPLY_MULTIPLE)
+    }
+
+    /// Like [`Self::try_from_atto_checked`], with a custom multiple of
+    /// [`TOTAL_FILECOIN`].
+    pub fn try_from_atto_bounded(
+        atto: impl Into<BigInt>,
+        max_supply_multiple: u64,
+    ) -> Result<Self, TokenAmountTooLargeError> {
+        let amount = Self::from_atto(atto);
+        match amount.atto().magnitude() > &(TOTAL_FILECOIN.atto().magnitude() * max_supply_multiple)
+        {
+            true => Err(TokenAmountTooLargeError {
+                amount,
+                max_supply_multiple,
+            }),
+            false => Ok(amount),
+        }
+    }
+
     pub fn from_nano(nano: impl Into<BigInt>) -> Self {
         TokenAmount_v3::from_nano(nano).into()
     }
@@ -107,6 +231,271 @@ impl TokenAmount {
         TokenAmount_v3::from_whole(fil).into()
     }
 
+    /// The amount in attoFIL as a [`u64`], or an error if it's negative or
+    /// too large, e.g. from adversarial RPC inputs.
+    #[allow(dead_code)]
+    pub fn try_to_atto_u64(&self) -> Result<u64, TokenAmountOutOfRangeError> {
+        self.atto()
+            .to_u64()
+            .ok_or_else(|| TokenAmountOutOfRangeError::new(self, "u64", "attoFIL"))
+    }
+
+    /// The amount in attoFIL as a [`u128`], or an error if it's negative or
+    /// too large.
+    #[allow(dead_code)]
+    pub fn try_to_atto_u128(&self) -> Result<u128, TokenAmountOutOfRangeError> {
+        self.atto()
+            .to_u128()
+            .ok_or_else(|| TokenAmountOutOfRangeError::new(self, "u128", "attoFIL"))
+    }
+
+    /// The amount in whole nanoFIL, rounded down, as a [`u64`], or an error if
+    /// it's negative or too large.
+    #[allow(dead_code)]
+    pub fn try_to_nano_u64(&self) -> Result<u64, TokenAmountOutOfRangeError> {
+        const ATTO_PER_NANO: u64 = 10_u64.pow(9);
+        match self.is_negative() {
+            true => None,
+            false => (self.atto() / ATTO_PER_NANO).to_u64(),
+        }
+        .ok_or_else(|| TokenAmountOutOfRangeError::new(self, "u64", "nanoFIL"))
+    }
+
+    /// Converts an amount of FIL to attoFIL exactly, without going through
+    /// floats.
+    pub fn try_from_decimal(fil: BigDecimal) -> Result<Self, TokenAmountFromDecimalError> {
+        if fil.sign() == Sign::Minus {
+            return Err(TokenAmountFromDecimalError::Negative(fil));
+        }
+        // `1.500` has a scale of 3, but only needs 1 decimal place
+        let (digits, scale) = fil.normalized().into_bigint_and_exponent();
+        let Ok(exponent) = u32::try_from(Self::DECIMALS as i64 - scale) else {
+            return Err(TokenAmountFromDecimalError::TooPrecise(fil));
+        };
+        Ok(Self::from_atto(digits * BigInt::from(10).pow(exponent)))
+    }
+
+    /// The whole FIL in the amount, and the rest, which has the same sign as
+    /// the amount, so that `whole * PRECISION + remainder == self` exactly.
+    #[allow(dead_code)]
+    pub fn whole_fil_and_remainder(&self) -> (BigInt, TokenAmount) {
+        let (whole, remainder) = self.atto().div_rem(&BigInt::from(Self::PRECISION));
+        (whole, TokenAmount::from_atto(remainder))
+    }
+
+    /// `whole` FIL plus `atto` attoFIL, e.g. `1 FIL and 500 attoFIL`, exactly.
+    /// `atto` must be less than a whole FIL, see [`Self::PRECISION`].
+    #[allow(dead_code)]
+    pub fn from_whole_and_atto(whole: u64, atto: u64) -> Result<Self, AttoFractionTooLargeError> {
+        if atto >= Self::PRECISION {
+            return Err(AttoFractionTooLargeError(atto));
+        }
+        Ok(Self::from_atto(
+            BigInt::from(whole) * Self::PRECISION + BigInt::from(atto),
+        ))
+    }
+
+    /// The amount in FIL as a float, rounded to the nearest, for metrics and
+    /// progress displays. This is lossy: a float has 53 bits of precision, so
+    /// e.g. `1000000000 FIL` and `1000000000 FIL + 1 attoFIL` are the same
+    /// float. Never panics, but amounts beyond [`f64::MAX`] FIL are infinite.
+    #[allow(dead_code)]
+    pub fn to_fil_f64_lossy(&self) -> f64 {
+        BigRational::new(self.atto().clone(), BigInt::from(Self::PRECISION))
+            .to_f64()
+            .unwrap_or(f64::NAN)
+    }
+
+    /// The inverse of [`Self::to_fil_f64_lossy`], rounded to the nearest
+    /// attoFIL. Floats can't represent most decimal amounts exactly, so this is
+    /// for tests and metrics only: parse amounts with [`TokenAmount::from_str`]
+    /// or [`Self::try_from_decimal`] instead.
+    #[allow(dead_code)]
+    pub fn from_fil_f64_lossy(fil: f64) -> Result<Self, TokenAmountFromFloatError> {
+        if fil < 0.0 {
+            return Err(TokenAmountFromFloatError::Negative(fil));
+        }
+        let Some(fil) = BigRational::from_float(fil) else {
+            return Err(TokenAmountFromFloatError::NotFinite(fil));
+        };
+        let atto = fil * BigRational::from_integer(BigInt::from(Self::PRECISION));
+        Ok(Self::from_atto(atto.round().to_integer()))
+    }
+
+    /// Formats the amount in FIL with exactly [`Self::DECIMALS`] decimal places
+    /// and no unit, e.g. `1.500000000000000000`, for CSV exports to accounting
+    /// tools. Unlike [`fmt::Display`], trailing zeros are kept and scientific
+    /// notation is never used.
+    #[allow(dead_code)]
+    pub fn to_accounting_string(&self) -> String {
+        let magnitude = self.atto().magnitude();
+        let precision = num_bigint::BigUint::from(Self::PRECISION);
+        let sign = if self.atto().sign() == Sign::Minus {
+            "-"
+        } else {
+            ""
+        };
+        format!(
+            "{sign}{}.{:0>width$}",
+            magnitude / &precision,
+            magnitude % &precision,
+            width = Self::DECIMALS
+        )
+    }
+
+    /// Returns the amount in wei, the unit of values in FEVM. One attoFIL is
+    /// exactly one wei, so this is the same as [`Self::atto`].
+    pub fn to_wei(&self) -> BigInt {
+        self.atto().clone()
+    }
+
+    /// The inverse of [`Self::to_wei`].
+    pub fn from_wei(wei: impl Into<BigInt>) -> Self {
+        Self::from_atto(wei)
+    }
+
+    /// The amount in wei of a big-endian, unsigned 256-bit integer, e.g. an EVM
+    /// word.
+    pub fn from_eth_big_int(bytes: &[u8; ETH_UINT256_LENGTH]) -> Self {
+        Self::from_wei(BigInt::from_bytes_be(Sign::Plus, bytes))
+    }
+
+    /// The amount in wei as a big-endian, unsigned 256-bit integer, left-padded
+    /// with zeros like Lotus does, or an error if it's negative or exceeds
+    /// `2^256 - 1`.
+    pub fn to_eth_bytes(&self) -> Result<[u8; ETH_UINT256_LENGTH], TokenAmountOutOfRangeError> {
+        let (sign, digits) = self.to_wei().to_bytes_be();
+        if sign == Sign::Minus || digits.len() > ETH_UINT256_LENGTH {
+            return Err(TokenAmountOutOfRangeError::new(self, "uint256", "wei"));
+        }
+        let mut bytes = [0; ETH_UINT256_LENGTH];
+        bytes[ETH_UINT256_LENGTH - digits.len()..].copy_from_slice(&digits);
+        Ok(bytes)
+    }
+
+    /// Formats the amount in gwei (`10^9` wei, i.e. nanoFIL) without unit or
+    /// trailing zeros, e.g. `1.5` for 1500000000 wei.
+    #[allow(dead_code)]
+    pub fn to_gwei_string(&self) -> String {
+        const GWEI_DECIMALS: usize = 9;
+        let magnitude = self.atto().magnitude();
+        let wei_per_gwei = num_bigint::BigUint::from(10_u64.pow(GWEI_DECIMALS as u32));
+        let sign = if self.atto().sign() == Sign::Minus {
+            "-"
+        } else {
+            ""
+        };
+        let whole = magnitude / &wei_per_gwei;
+        let fraction = format!(
+            "{:0>width$}",
+            magnitude % &wei_per_gwei,
+            width = GWEI_DECIMALS
+        );
+        match fraction.trim_end_matches('0') {
+            "" => format!("{sign}{whole}"),
+            fraction => format!("{sign}{whole}.{fraction}"),
+        }
+    }
+
+    /// Default number of significant digits of [`Self::pretty`].
+    pub const PRETTY_SIGNIFICANT_DIGITS: usize = 6;
+
+    /// Formats the amount for humans, e.g. `5 FIL` or `-1.23457 nanoFIL`, see
+    /// [`Self::format_units`].
+    pub fn pretty(&self) -> impl fmt::Display + '_ {
+        self.format_units(Self::PRETTY_SIGNIFICANT_DIGITS)
+    }
+
+    /// Formats the amount in the largest of FIL, nanoFIL and attoFIL that it is at
+    /// least one of, rounded half up to `significant_digits`. The integer part is
+    /// never rounded, e.g. `2000000000 FIL` with 4 significant digits. Nothing is
+    /// formatted until the returned value is displayed.
+    pub fn format_units(&self, significant_digits: usize) -> impl fmt::Display + '_ {
+        FormatUnits {
+            amount: self,
+            significant_digits,
+        }
+    }
+
+    /// `self + other`, or [`None`] if the sum is not a valid token amount, see
+    /// [`Self::checked`].
+    pub fn checked_add(&self, other: &TokenAmount) -> Option<TokenAmount> {
+        (self + other).checked()
+    }
+
+    /// `self + other`, or [`None`] if the sum is negative or exceeds the total
+    /// supply, [`TOTAL_FILECOIN`], which no balance can.
+    pub fn checked_add_supply_bounded(&self, other: &TokenAmount) -> Option<TokenAmount> {
+        self.checked_add(other)
+            .filter(|sum| sum <= &*TOTAL_FILECOIN)
+    }
+
+    /// The sum of `amounts`, or an error if it exceeds the total supply,
+    /// [`TOTAL_FILECOIN`], which means that something was counted twice or the
+    /// state is inconsistent.
+    #[allow(dead_code)]
+    pub fn total_of<T: Borrow<TokenAmount>>(
+        amounts: impl IntoIterator<Item = T>,
+    ) -> Result<TokenAmount, TotalSupplyExceededError> {
+        let total = amounts.into_iter().fold(TokenAmount::zero(), |acc, it| {
+            acc + Borrow::<TokenAmount>::borrow(&it)
+        });
+        match total > *TOTAL_FILECOIN {
+            true => Err(TotalSupplyExceededError(total)),
+            false => Ok(total),
+        }
+    }
+
+    /// `self - other`, or [`None`] if `other` is larger than `self` (or the
+    /// difference is otherwise not a valid token amount, see [`Self::checked`]).
+    pub fn checked_sub(&self, other: &TokenAmount) -> Option<TokenAmount> {
+        (self - other.clone()).checked()
+    }
+
+    /// `self * other`, or [`None`] if the product is not a valid token amount, see
+    /// [`Self::checked`].
+    #[allow(dead_code)]
+    pub fn checked_mul(&self, other: impl Into<BigInt>) -> Option<TokenAmount> {
+        (self * other.into()).checked()
+    }
+
+    /// `self - other`, clamped at zero.
+    pub fn saturating_sub(&self, other: &TokenAmount) -> TokenAmount {
+        (self - other.clone()).max(TokenAmount::zero())
+    }
+
+    /// `Some(self)` if the amount is neither negative nor too large to be serialized.
+    fn checked(self) -> Option<TokenAmount> {
+        (self.atto().sign() != Sign::Minus && self.is_serializable()).then_some(self)
+    }
+
+    fn is_serializable(&self) -> bool {
+        // Serialized big integers are a sign byte followed by the magnitude.
+        const MAX_BITS: u64 = (fvm_shared4::bigint::MAX_BIGINT_SIZE as u64 - 1) * 8;
+        self.atto().bits() <= MAX_BITS
+    }
+
+    /// Like [`TokenAmount::from_str`], but also accepts negative amounts, e.g. `-1.5 FIL`.
+    pub fn from_str_signed(s: &str) -> Result<Self, ParseTokenAmountError> {
+        parse_token_amount(s, true)
+    }
+
+    /// Parses a bare, non-negative integer of attoFIL, e.g. `1000`, as the FVM
+    /// serializes token amounts. Unlike [`TokenAmount::from_str`], units,
+    /// decimals, signs and surrounding whitespace are rejected.
+    pub fn from_atto_str(s: &str) -> Result<Self, ParseTokenAmountError> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseTokenAmountError::NotAttoInteger(s.to_owned()));
+        }
+        let amount = TokenAmount::from_atto(
+            BigInt::from_str(s).expect("a non-empty string of digits is a valid integer"),
+        );
+        if !amount.is_serializable() {
+            return Err(ParseTokenAmountError::TooLarge(s.to_owned()));
+        }
+        Ok(amount)
+    }
+
     #[inline]
     pub fn div_rem(&self, other: impl Into<BigInt>) -> (TokenAmount, TokenAmount) {
         let (q, r) = self.0.div_rem(other);
@@ -122,6 +511,396 @@ impl TokenAmount {
     pub fn div_floor(&self, other: impl Into<BigInt>) -> TokenAmount {
         self.0.div_floor(other).into()
     }
+
+    /// `self * num / denom`, rounded towards negative infinity. The product is
+    /// computed exactly before dividing.
+    pub fn mul_div_floor(
+        &self,
+        num: impl Into<BigInt>,
+        denom: impl Into<BigInt>,
+    ) -> Result<TokenAmount, DivisionByZero> {
+        let denom = nonzero(denom.into())?;
+        Ok(TokenAmount::from_atto(
+            (self.atto() * num.into()).div_floor(&denom),
+        ))
+    }
+
+    /// `self * num / denom`, rounded towards positive infinity. The product is
+    /// computed exactly before dividing.
+    pub fn mul_div_ceil(
+        &self,
+        num: impl Into<BigInt>,
+        denom: impl Into<BigInt>,
+    ) -> Result<TokenAmount, DivisionByZero> {
+        let denom = nonzero(denom.into())?;
+        Ok(TokenAmount::from_atto(
+            (self.atto() * num.into()).div_ceil(&denom),
+        ))
+    }
+
+    /// Splits the amount into `n` equal shares, returning the share and the
+    /// leftover, such that `share * n + remainder == self`. The share is rounded
+    /// towards negative infinity, so the remainder is in `0..n`.
+    #[allow(dead_code)]
+    pub fn split_evenly(&self, n: u64) -> Result<(TokenAmount, TokenAmount), DivisionByZero> {
+        let n = nonzero(n.into())?;
+        let (share, remainder) = self.atto().div_mod_floor(&n);
+        Ok((share.into(), remainder.into()))
+    }
+
+    /// The exact ratio `self / other`, in lowest terms.
+    #[allow(dead_code)]
+    pub fn ratio(&self, other: &TokenAmount) -> Result<BigRational, DivisionByZero> {
+        let denom = nonzero(other.atto().clone())?;
+        Ok(BigRational::new(self.atto().clone(), denom))
+    }
+
+    /// `self * num / denom`, rounded as specified, see [`Self::mul_div_floor`] and
+    /// [`Self::mul_div_ceil`].
+    pub fn mul_div(
+        &self,
+        num: impl Into<BigInt>,
+        denom: impl Into<BigInt>,
+        rounding: Rounding,
+    ) -> Result<TokenAmount, DivisionByZero> {
+        match rounding {
+            Rounding::Floor => self.mul_div_floor(num, denom),
+            Rounding::Ceil => self.mul_div_ceil(num, denom),
+            Rounding::Nearest => {
+                let denom = nonzero(denom.into())?;
+                let exact = BigRational::new(self.atto() * num.into(), denom);
+                Ok(TokenAmount::from_atto(exact.round().to_integer()))
+            }
+        }
+    }
+
+    /// `basis_points` hundredths of a percent of the amount, e.g. `25` for
+    /// `0.25%`, rounded as specified.
+    pub fn bps(&self, basis_points: u64, rounding: Rounding) -> TokenAmount {
+        self.mul_div(basis_points, 10_000, rounding)
+            .expect("denominator is not zero")
+    }
+
+    /// `pct` percent of the amount, rounded as specified.
+    pub fn percent(&self, pct: u64, rounding: Rounding) -> TokenAmount {
+        self.mul_div(pct, 100, rounding)
+            .expect("denominator is not zero")
+    }
+}
+
+fn nonzero(denom: BigInt) -> Result<BigInt, DivisionByZero> {
+    match denom.is_zero() {
+        true => Err(DivisionByZero),
+        false => Ok(denom),
+    }
+}
+
+/// How to round the result of e.g. [`TokenAmount::percent`] to a whole attoFIL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards negative infinity.
+    Floor,
+    /// Towards positive infinity.
+    Ceil,
+    /// To the nearest attoFIL, with halves rounded away from zero.
+    Nearest,
+}
+
+/// See [`TokenAmount::mul_div_floor`], [`TokenAmount::mul_div_ceil`] and
+/// [`TokenAmount::ratio`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("division by zero")]
+pub struct DivisionByZero;
+
+/// See [`TokenAmount::format_units`].
+struct FormatUnits<'a> {
+    amount: &'a TokenAmount,
+    significant_digits: usize,
+}
+
+impl fmt::Display for FormatUnits<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NANO: u128 = 10_u128.pow(9);
+        const WHOLE: u128 = 10_u128.pow(TokenAmount::DECIMALS as u32);
+
+        let atto = self.amount.atto();
+        let sign = if atto.sign() == Sign::Minus { "-" } else { "" };
+        let Some(magnitude) = atto.magnitude().to_u128() else {
+            // Far more than the total supply, round to whole FIL
+            let whole = num_bigint::BigUint::from(WHOLE);
+            let fil = (atto.magnitude() + &whole / 2_u32) / &whole;
+            return write!(f, "{sign}{fil} FIL");
+        };
+        if magnitude == 0 {
+            return f.write_str("0 FIL");
+        }
+        let unit_of = |magnitude: u128| match magnitude {
+            WHOLE.. => ("FIL", TokenAmount::DECIMALS as u32),
+            NANO.. => ("nanoFIL", 9),
+            _ => ("attoFIL", 0),
+        };
+        // The magnitude rounded to `significant_digits` in the unit with `decimals`,
+        // as a number of the smallest digit kept, and how many fraction digits it has
+        let round = |magnitude: u128, decimals: u32| {
+            let integer_digits = (magnitude / 10_u128.pow(decimals)).ilog10() as usize + 1;
+            let fraction_digits =
+                u32::try_from(self.significant_digits.saturating_sub(integer_digits))
+                    .unwrap_or(u32::MAX)
+                    .min(decimals);
+            let dropped = 10_u128.pow(decimals - fraction_digits);
+            let rounded =
+                magnitude / dropped + u128::from(dropped > 1 && magnitude % dropped >= dropped / 2);
+            (rounded, fraction_digits, dropped)
+        };
+        let (mut unit, decimals) = unit_of(magnitude);
+        let (mut rounded, mut fraction_digits, dropped) = round(magnitude, decimals);
+        // Rounding up may reach the next unit, e.g. 999999999.9 nanoFIL is 1 FIL
+        let rounded_magnitude = rounded * dropped;
+        let (next_unit, next_decimals) = unit_of(rounded_magnitude);
+        if next_unit != unit {
+            unit = next_unit;
+            (rounded, fraction_digits, _) = round(rounded_magnitude, next_decimals);
+        }
+        let scale = 10_u128.pow(fraction_digits);
+        let integer = rounded / scale;
+        let mut fraction = rounded % scale;
+        while fraction_digits > 0 && fraction % 10 == 0 {
+            fraction /= 10;
+            fraction_digits -= 1;
+        }
+        if fraction_digits == 0 {
+            write!(f, "{sign}{integer} {unit}")
+        } else {
+            write!(
+                f,
+                "{sign}{integer}.{fraction:0width$} {unit}",
+                width = fraction_digits as usize
+            )
+        }
+    }
+}
+
+/// Why a [`BigDecimal`] isn't a valid [`TokenAmount`], see
+/// [`TokenAmount::try_from_decimal`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TokenAmountFromDecimalError {
+    #[error("token amounts cannot be negative, got {0}")]
+    Negative(BigDecimal),
+    #[error(
+        "token amounts have at most {} decimal places, got {0}",
+        TokenAmount::DECIMALS
+    )]
+    TooPrecise(BigDecimal),
+}
+
+/// Why an [`f64`] isn't a valid [`TokenAmount`], see
+/// [`TokenAmount::from_fil_f64_lossy`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TokenAmountFromFloatError {
+    #[error("token amounts cannot be negative, got {0}")]
+    Negative(f64),
+    #[error("token amounts must be finite, got {0}")]
+    NotFinite(f64),
+}
+
+impl TryFrom<BigDecimal> for TokenAmount {
+    type Error = TokenAmountFromDecimalError;
+    fn try_from(fil: BigDecimal) -> Result<Self, Self::Error> {
+        Self::try_from_decimal(fil)
+    }
+}
+
+/// A [`TokenAmount`] that is zero or more, for APIs where a negative amount is
+/// a logic error, e.g. balances.
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Default)]
+pub struct NonNegativeTokenAmount(TokenAmount);
+
+#[cfg(test)]
+impl TokenAmount {
+    /// An arbitrary amount that [`TokenAmount::try_from_atto_checked`] accepts.
+    pub fn arbitrary_checked(g: &mut quickcheck::Gen) -> Self {
+        let max = TOTAL_FILECOIN.atto() * DEFAULT_MAX_SUPPLY_MULTIPLE;
+        // The remainder takes the sign of the dividend
+        TokenAmount::from_atto(<TokenAmount as quickcheck::Arbitrary>::arbitrary(g).atto() % max)
+    }
+}
+
+#[cfg(test)]
+impl quickcheck::Arbitrary for NonNegativeTokenAmount {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Self(<TokenAmount as quickcheck::Arbitrary>::arbitrary(g).abs())
+    }
+}
+
+impl NonNegativeTokenAmount {
+    pub fn into_inner(self) -> TokenAmount {
+        self.0
+    }
+}
+
+impl Deref for NonNegativeTokenAmount {
+    type Target = TokenAmount;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for NonNegativeTokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl TryFrom<TokenAmount> for NonNegativeTokenAmount {
+    type Error = NegativeTokenAmountError;
+    fn try_from(amount: TokenAmount) -> Result<Self, Self::Error> {
+        match amount.is_negative() {
+            true => Err(NegativeTokenAmountError(amount)),
+            false => Ok(Self(amount)),
+        }
+    }
+}
+
+impl From<NonNegativeTokenAmount> for TokenAmount {
+    fn from(amount: NonNegativeTokenAmount) -> Self {
+        amount.0
+    }
+}
+
+/// See [`TokenAmount::from_whole_and_atto`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "the attoFIL part of a token amount must be less than {}, got {0}",
+    TokenAmount::PRECISION
+)]
+pub struct AttoFractionTooLargeError(pub u64);
+
+/// See [`TokenAmount::try_to_atto_u64`] and friends.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("token amount {amount} is out of range for a {primitive} of {unit}")]
+pub struct TokenAmountOutOfRangeError {
+    pub amount: TokenAmount,
+    pub primitive: &'static str,
+    pub unit: &'static str,
+}
+
+impl TokenAmountOutOfRangeError {
+    fn new(amount: &TokenAmount, primitive: &'static str, unit: &'static str) -> Self {
+        Self {
+            amount: amount.clone(),
+            primitive,
+            unit,
+        }
+    }
+}
+
+/// See [`TokenAmount::total_of`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("total of {0} exceeds the total supply of {supply}", supply = *TOTAL_FILECOIN)]
+pub struct TotalSupplyExceededError(pub TokenAmount);
+
+/// See [`TokenAmount::try_from_atto_checked`]. The amount isn't part of the
+/// message, as it may have thousands of digits.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "token amount exceeds {max_supply_multiple} times the total supply of {supply}",
+    supply = *TOTAL_FILECOIN
+)]
+pub struct TokenAmountTooLargeError {
+    pub amount: TokenAmount,
+    pub max_supply_multiple: u64,
+}
+
+/// See [`NonNegativeTokenAmount`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("token amount {0} is negative")]
+pub struct NegativeTokenAmountError(pub TokenAmount);
+
+/// Units accepted by [`TokenAmount::from_str`], with their number of attoFIL decimal places.
+const UNITS: &[(&str, usize)] = &[
+    ("FIL", 18),
+    ("milliFIL", 15),
+    ("microFIL", 12),
+    ("nanoFIL", 9),
+    ("picoFIL", 6),
+    ("femtoFIL", 3),
+    ("attoFIL", 0),
+];
+
+/// Why a string isn't a valid [`TokenAmount`], see [`TokenAmount::from_str`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseTokenAmountError {
+    #[error("invalid token amount {0:?}, expected e.g. `1.5 FIL` or `1000 attoFIL`")]
+    Invalid(String),
+    #[error(
+        "unknown unit {0:?}, expected one of FIL, milliFIL, microFIL, nanoFIL, picoFIL, femtoFIL or attoFIL"
+    )]
+    UnknownUnit(String),
+    #[error("token amounts cannot be negative, got {0:?}")]
+    Negative(String),
+    #[error("token amounts cannot be smaller than one attoFIL, got {0:?}")]
+    TooPrecise(String),
+    #[error("token amount {0:?} is too large")]
+    TooLarge(String),
+    #[error("invalid attoFIL amount {0:?}, expected an integer without a unit, sign or whitespace")]
+    NotAttoInteger(String),
+}
+
+impl FromStr for TokenAmount {
+    type Err = ParseTokenAmountError;
+
+    /// Parses a decimal amount with an optional, case-insensitive unit suffix, e.g.
+    /// `1.5 FIL`, `20nanoFIL` or `1000`. Bare amounts are in attoFIL. Negative amounts
+    /// are rejected, see [`TokenAmount::from_str_signed`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_token_amount(s, false)
+    }
+}
+
+fn parse_token_amount(s: &str, signed: bool) -> Result<TokenAmount, ParseTokenAmountError> {
+    let input = s.trim();
+    let (number, unit) = input.split_at(
+        input
+            .find(|c: char| c.is_alphabetic())
+            .unwrap_or(input.len()),
+    );
+    let decimals = match unit {
+        "" => 0,
+        unit => UNITS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+            .map(|(_, decimals)| *decimals)
+            .ok_or_else(|| ParseTokenAmountError::UnknownUnit(unit.to_owned()))?,
+    };
+    let number = number.trim_end();
+    let (negative, number) = match number.strip_prefix('-') {
+        Some(number) => (true, number),
+        None => (false, number.strip_prefix('+').unwrap_or(number)),
+    };
+    let (whole, fraction) = match number.split_once('.') {
+        Some((whole, fraction)) if !fraction.is_empty() => (whole, fraction),
+        Some(_) => return Err(ParseTokenAmountError::Invalid(s.to_owned())),
+        None => (number, ""),
+    };
+    let is_digits = |digits: &str| digits.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+        return Err(ParseTokenAmountError::Invalid(s.to_owned()));
+    }
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals {
+        return Err(ParseTokenAmountError::TooPrecise(s.to_owned()));
+    }
+    let atto = BigInt::from_str(&format!("{whole}{fraction:0<decimals$}"))
+        .expect("a non-empty string of digits is a valid integer");
+    let amount = TokenAmount::from_atto(if negative { -atto } else { atto });
+    if !signed && amount.atto().sign() == Sign::Minus {
+        return Err(ParseTokenAmountError::Negative(s.to_owned()));
+    }
+    if !amount.is_serializable() {
+        return Err(ParseTokenAmountError::TooLarge(s.to_owned()));
+    }
+    Ok(amount)
 }
 
 impl From<TokenAmount> for BigInt {
@@ -136,6 +915,10 @@ impl From<BigInt> for TokenAmount {
     }
 }
 
+// Every version of `TokenAmount` is a signed `BigInt` with the same CBOR and
+// JSON encoding, so conversions between them are lossless, including for
+// negative amounts.
+
 impl From<TokenAmount_v2> for TokenAmount {
     fn from(other: TokenAmount_v2) -> Self {
         (&other).into()
@@ -256,6 +1039,58 @@ impl Mul<u64> for TokenAmount {
     }
 }
 
+/// Multiplies by the fraction `numerator / denominator`, rounding towards
+/// negative infinity, see [`TokenAmount::mul_div_floor`].
+///
+/// # Panics
+/// - If the denominator is zero.
+impl Mul<(u64, u64)> for &TokenAmount {
+    type Output = TokenAmount;
+    fn mul(self, (numerator, denominator): (u64, u64)) -> Self::Output {
+        self.mul_div_floor(numerator, denominator)
+            .expect("attempt to multiply by a fraction with a zero denominator")
+    }
+}
+
+/// See the `Mul<(u64, u64)>` implementation for `&TokenAmount`.
+impl Mul<(u64, u64)> for TokenAmount {
+    type Output = TokenAmount;
+    fn mul(self, fraction: (u64, u64)) -> Self::Output {
+        &self * fraction
+    }
+}
+
+/// See the `Mul<(u64, u64)>` implementation for `&TokenAmount`.
+impl MulAssign<(u64, u64)> for TokenAmount {
+    fn mul_assign(&mut self, fraction: (u64, u64)) {
+        *self = &*self * fraction
+    }
+}
+
+/// The integer quotient of two amounts, rounded towards zero like [`BigInt`]
+/// division, so that `(a / b) * b + a % b == a`.
+///
+/// # Panics
+/// - If the divisor is zero.
+impl Div<&TokenAmount> for &TokenAmount {
+    type Output = BigInt;
+    fn div(self, rhs: &TokenAmount) -> Self::Output {
+        self.atto() / rhs.atto()
+    }
+}
+
+/// The remainder of the `Div<&TokenAmount>` implementation for `&TokenAmount`,
+/// which has the sign of the dividend.
+///
+/// # Panics
+/// - If the divisor is zero.
+impl Rem<&TokenAmount> for &TokenAmount {
+    type Output = TokenAmount;
+    fn rem(self, rhs: &TokenAmount) -> Self::Output {
+        TokenAmount::from_atto(self.atto() % rhs.atto())
+    }
+}
+
 impl Add<TokenAmount> for &TokenAmount {
     type Output = TokenAmount;
     fn add(self, rhs: TokenAmount) -> Self::Output {
@@ -309,3 +1144,1308 @@ impl Sub<TokenAmount> for &TokenAmount {
         (&self.0).sub(&rhs.0).into()
     }
 }
+
+/// Serializes a [`TokenAmount`] as a string of attoFIL, e.g. `"1500000000000000000"`, like
+/// Lotus does. Both strings and bare integers are deserialized.
+///
+/// Usage: `#[serde(with = "crate::shim::econ::tokenamount_as_atto_string")]`
+#[allow(dead_code)]
+pub mod tokenamount_as_atto_string {
+    use super::*;
+    use serde::{Deserializer, Serializer, de::Error as _};
+
+    pub fn serialize<S: Serializer>(
+        amount: &TokenAmount,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(amount.atto())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<TokenAmount, D::Error> {
+        let atto = deserializer.deserialize_any(StringOrNumber)?;
+        let atto = BigInt::from_str(&atto)
+            .map_err(|e| D::Error::custom(format!("invalid attoFIL amount {atto:?}: {e}")))?;
+        Ok(TokenAmount::from_atto(atto))
+    }
+}
+
+/// Serializes a [`TokenAmount`] as a string of FIL without trailing zeros, e.g. `"1.5"`.
+/// Both strings and bare numbers are deserialized.
+///
+/// Usage: `#[serde(with = "crate::shim::econ::tokenamount_as_fil_string")]`
+#[allow(dead_code)]
+pub mod tokenamount_as_fil_string {
+    use super::*;
+    use serde::{Deserializer, Serializer, de::Error as _};
+
+    pub fn serialize<S: Serializer>(
+        amount: &TokenAmount,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let fil = amount.to_accounting_string();
+        serializer.serialize_str(fil.trim_end_matches('0').trim_end_matches('.'))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<TokenAmount, D::Error> {
+        let fil = deserializer.deserialize_any(StringOrNumber)?;
+        let invalid =
+            |e: &dyn fmt::Display| D::Error::custom(format!("invalid FIL amount {fil:?}: {e}"));
+        let fil = BigDecimal::from_str(&fil).map_err(|e| invalid(&e))?;
+        let amount = TokenAmount::try_from_decimal(fil.abs()).map_err(|e| invalid(&e))?;
+        Ok(match fil.sign() {
+            Sign::Minus => TokenAmount::from_atto(-amount.atto()),
+            _ => amount,
+        })
+    }
+}
+
+/// Deserializes a string or a bare number into a string.
+struct StringOrNumber;
+
+impl serde::de::Visitor<'_> for StringOrNumber {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a string")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(v.to_owned())
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_u128<E: serde::de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_i128<E: serde::de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        // The shortest representation that round-trips, e.g. `0.1` rather than its
+        // binary expansion.
+        if v.is_finite() {
+            Ok(v.to_string())
+        } else {
+            Err(E::invalid_value(serde::de::Unexpected::Float(v), &self))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    fn try_from_decimal(fil: &str) -> Result<TokenAmount, TokenAmountFromDecimalError> {
+        TokenAmount::try_from_decimal(BigDecimal::from_str(fil).unwrap())
+    }
+
+    #[test]
+    fn test_try_from_decimal_exact() {
+        for (fil, atto) in [
+            ("0", 0_u128),
+            ("0.000", 0),
+            ("1", 1_000_000_000_000_000_000),
+            ("1.5", 1_500_000_000_000_000_000),
+            ("1.500000000000000000000", 1_500_000_000_000_000_000),
+            ("0.000000000000000001", 1),
+            (
+                "123456789.123456789123456789",
+                123_456_789_123_456_789_123_456_789,
+            ),
+            ("2e3", 2_000_000_000_000_000_000_000),
+            ("1e-18", 1),
+        ] {
+            assert_eq!(
+                try_from_decimal(fil).unwrap(),
+                TokenAmount::from_atto(atto),
+                "{fil}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_accounting_string() {
+        for (atto, expected) in [
+            (0_i128, "0.000000000000000000"),
+            (1, "0.000000000000000001"),
+            (1_000_000_000, "0.000000001000000000"),
+            (1_500_000_000_000_000_000, "1.500000000000000000"),
+            (10_000_000_000_000_000_000, "10.000000000000000000"),
+            (
+                123_456_789_123_456_789_123_456_789,
+                "123456789.123456789123456789",
+            ),
+            (-1_500_000_000_000_000_000, "-1.500000000000000000"),
+            (-1, "-0.000000000000000001"),
+        ] {
+            assert_eq!(
+                TokenAmount::from_atto(atto).to_accounting_string(),
+                expected,
+                "{atto}"
+            );
+        }
+        assert_eq!(
+            TOTAL_FILECOIN.to_accounting_string(),
+            "2000000000.000000000000000000"
+        );
+    }
+
+    #[test]
+    fn test_wei() {
+        for (fil, wei) in [
+            ("0", 0_u128),
+            ("0.000000000000000001", 1),
+            ("0.000000001", 1_000_000_000),
+            ("1", 1_000_000_000_000_000_000),
+            ("2000000000", 2_000_000_000_000_000_000_000_000_000),
+        ] {
+            let amount = try_from_decimal(fil).unwrap();
+            assert_eq!(amount.to_wei(), BigInt::from(wei), "{fil}");
+            assert_eq!(TokenAmount::from_wei(wei), amount, "{fil}");
+        }
+        assert_eq!(TOTAL_FILECOIN.to_wei(), TOTAL_FILECOIN.atto().clone());
+    }
+
+    #[test]
+    fn test_to_gwei_string() {
+        for (wei, expected) in [
+            (0_i128, "0"),
+            (1, "0.000000001"),
+            (1_000_000_000, "1"),
+            (1_500_000_000, "1.5"),
+            (123_456_789_012, "123.456789012"),
+            (1_000_000_000_000_000_000, "1000000000"),
+            (-2_500_000_000, "-2.5"),
+        ] {
+            assert_eq!(
+                TokenAmount::from_wei(wei).to_gwei_string(),
+                expected,
+                "{wei}"
+            );
+        }
+        assert_eq!(TokenAmount::from_nano(42).to_gwei_string(), "42");
+    }
+
+    #[quickcheck]
+    fn checked_sub_is_none_iff_underflow(a: TokenAmount, b: TokenAmount) {
+        assert_eq!(a.checked_sub(&b).is_none(), a < b);
+        assert_eq!(a.saturating_sub(&b), a.checked_sub(&b).unwrap_or_default());
+    }
+
+    #[quickcheck]
+    fn checked_add_sub_roundtrip(a: TokenAmount, b: TokenAmount) {
+        let (a, b) = (a.abs(), b.abs());
+        let sum = a.checked_add(&b).unwrap();
+        assert_eq!(sum, &a + &b);
+        assert_eq!(sum.checked_sub(&b), Some(a.clone()));
+        if let Some(difference) = a.checked_sub(&b) {
+            assert_eq!(difference + &b, a);
+        }
+    }
+
+    #[quickcheck]
+    fn sign(amount: TokenAmount) {
+        assert_eq!(amount.is_negative(), amount < TokenAmount::zero());
+        assert_eq!(amount.is_positive(), amount > TokenAmount::zero());
+        assert_eq!(
+            amount.signum(),
+            match amount.cmp(&TokenAmount::zero()) {
+                std::cmp::Ordering::Less => -1,
+                std::cmp::Ordering::Equal => 0,
+                std::cmp::Ordering::Greater => 1,
+            }
+        );
+        assert!(!amount.abs().is_negative());
+        assert_eq!(&amount.abs() * i64::from(amount.signum()), amount);
+    }
+
+    #[quickcheck]
+    fn non_negative(amount: TokenAmount) {
+        match NonNegativeTokenAmount::try_from(amount.clone()) {
+            Ok(non_negative) => assert_eq!(non_negative.into_inner(), amount),
+            Err(NegativeTokenAmountError(negative)) => {
+                assert!(amount.is_negative());
+                assert_eq!(negative, amount);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sign() {
+        let one = TokenAmount::from_atto(1);
+        let minus_one = TokenAmount::from_atto(-1);
+        assert!(minus_one.is_negative() && !minus_one.is_positive());
+        assert!(one.is_positive() && !one.is_negative());
+        assert!(!TokenAmount::zero().is_positive() && !TokenAmount::zero().is_negative());
+        assert_eq!(minus_one.abs(), one);
+        assert_eq!(minus_one.signum(), -1);
+        assert_eq!(TokenAmount::zero().signum(), 0);
+        assert_eq!(one.signum(), 1);
+
+        assert_eq!(
+            NonNegativeTokenAmount::try_from(TokenAmount::zero()).map(|it| it.into_inner()),
+            Ok(TokenAmount::zero())
+        );
+        assert_eq!(
+            NonNegativeTokenAmount::try_from(minus_one.clone()),
+            Err(NegativeTokenAmountError(minus_one))
+        );
+    }
+
+    #[test]
+    fn test_sum() {
+        assert_eq!(
+            std::iter::empty::<TokenAmount>().sum::<TokenAmount>(),
+            TokenAmount::zero()
+        );
+        assert_eq!(
+            [TokenAmount::from_atto(42)].iter().sum::<TokenAmount>(),
+            TokenAmount::from_atto(42)
+        );
+        assert_eq!(
+            (1..=1000).map(TokenAmount::from_whole).sum::<TokenAmount>(),
+            TokenAmount::from_whole(500_500)
+        );
+        assert_eq!(
+            [TokenAmount::from_atto(1), TokenAmount::from_atto(-3)]
+                .into_iter()
+                .sum::<TokenAmount>(),
+            TokenAmount::from_atto(-2)
+        );
+        assert_eq!(
+            std::iter::repeat_n(&*TOTAL_FILECOIN, 1000).sum::<TokenAmount>(),
+            &*TOTAL_FILECOIN * 1000_u64
+        );
+    }
+
+    #[quickcheck]
+    fn cbor_roundtrip(amount: TokenAmount) {
+        use fvm_ipld_encoding::{from_slice, to_vec};
+        let bytes = to_vec(&amount).unwrap();
+        assert_eq!(from_slice::<TokenAmount>(&bytes).unwrap(), amount);
+        // Every version encodes and decodes alike
+        assert_eq!(to_vec(&TokenAmount_v2::from(&amount)).unwrap(), bytes);
+        assert_eq!(to_vec(&TokenAmount_v3::from(&amount)).unwrap(), bytes);
+        assert_eq!(to_vec(&TokenAmount_v4::from(&amount)).unwrap(), bytes);
+        assert_eq!(
+            TokenAmount::from(from_slice::<TokenAmount_v2>(&bytes).unwrap()),
+            amount
+        );
+        assert_eq!(
+            TokenAmount::from(from_slice::<TokenAmount_v3>(&bytes).unwrap()),
+            amount
+        );
+        assert_eq!(
+            TokenAmount::from(from_slice::<TokenAmount_v4>(&bytes).unwrap()),
+            amount
+        );
+    }
+
+    #[quickcheck]
+    fn json_roundtrip(amount: TokenAmount) {
+        use crate::lotus_json::HasLotusJson as _;
+        let json = serde_json::to_string(&amount).unwrap();
+        assert_eq!(serde_json::from_str::<TokenAmount>(&json).unwrap(), amount);
+        assert_eq!(
+            serde_json::to_string(&TokenAmount_v2::from(&amount)).unwrap(),
+            json
+        );
+        assert_eq!(
+            serde_json::to_string(&TokenAmount_v3::from(&amount)).unwrap(),
+            json
+        );
+
+        let lotus_json = serde_json::to_value(amount.clone().into_lotus_json()).unwrap();
+        assert_eq!(lotus_json, serde_json::json!(amount.atto().to_string()));
+        assert_eq!(
+            TokenAmount::from_lotus_json(serde_json::from_value(lotus_json).unwrap()),
+            amount
+        );
+    }
+
+    #[quickcheck]
+    fn version_conversion_roundtrip(amount: TokenAmount) {
+        assert_eq!(TokenAmount::from(TokenAmount_v2::from(&amount)), amount);
+        assert_eq!(TokenAmount::from(TokenAmount_v3::from(&amount)), amount);
+        assert_eq!(TokenAmount::from(TokenAmount_v4::from(&amount)), amount);
+        assert_eq!(TokenAmount_v2::from(&amount).atto(), amount.atto());
+        assert_eq!(TokenAmount_v3::from(&amount).atto(), amount.atto());
+    }
+
+    #[quickcheck]
+    fn sum_is_fold(amounts: Vec<TokenAmount>) {
+        let folded = amounts.iter().fold(TokenAmount::zero(), |acc, it| acc + it);
+        assert_eq!(amounts.iter().sum::<TokenAmount>(), folded);
+        assert_eq!(amounts.into_iter().sum::<TokenAmount>(), folded);
+    }
+
+    #[test]
+    fn test_total_of() {
+        assert_eq!(
+            TokenAmount::total_of(Vec::<TokenAmount>::new()),
+            Ok(TokenAmount::zero())
+        );
+        assert_eq!(
+            TokenAmount::total_of([TokenAmount::from_atto(1)]),
+            Ok(TokenAmount::from_atto(1))
+        );
+        let half = TOTAL_FILECOIN.div_floor(2);
+        assert_eq!(
+            TokenAmount::total_of([&half, &half]).as_ref(),
+            Ok(&*TOTAL_FILECOIN)
+        );
+        let over = TOTAL_FILECOIN.clone() + TokenAmount::from_atto(1);
+        assert_eq!(
+            TokenAmount::total_of([&half, &half, &TokenAmount::from_atto(1)]),
+            Err(TotalSupplyExceededError(over))
+        );
+        // Large iterators
+        let amounts = vec![TokenAmount::from_whole(1); 1_000_000];
+        assert_eq!(
+            TokenAmount::total_of(&amounts),
+            Ok(TokenAmount::from_whole(1_000_000))
+        );
+        assert!(TokenAmount::total_of(std::iter::repeat_n(&*TOTAL_FILECOIN, 2)).is_err());
+    }
+
+    #[test]
+    fn test_try_from_atto_checked() {
+        let max = TOTAL_FILECOIN.atto() * DEFAULT_MAX_SUPPLY_MULTIPLE;
+        for atto in [BigInt::ZERO, max.clone(), -max.clone()] {
+            assert_eq!(
+                TokenAmount::try_from_atto_checked(atto.clone()),
+                Ok(TokenAmount::from_atto(atto))
+            );
+        }
+        for atto in [&max + 1, -(&max + 1), BigInt::from(10).pow(10_000)] {
+            assert_eq!(
+                TokenAmount::try_from_atto_checked(atto.clone()),
+                Err(TokenAmountTooLargeError {
+                    amount: TokenAmount::from_atto(atto),
+                    max_supply_multiple: DEFAULT_MAX_SUPPLY_MULTIPLE
+                })
+            );
+        }
+        // Custom bounds
+        assert!(TokenAmount::try_from_atto_bounded(TOTAL_FILECOIN.atto().clone(), 1).is_ok());
+        assert!(TokenAmount::try_from_atto_bounded(TOTAL_FILECOIN.atto() + 1, 1).is_err());
+        assert!(TokenAmount::try_from_atto_bounded(1, 0).is_err());
+        // Internal arithmetic is unrestricted
+        let huge = TokenAmount::from_atto(&max + 1) * 1_000_u64;
+        assert_eq!(huge.div_floor(1_000), TokenAmount::from_atto(&max + 1));
+    }
+
+    #[quickcheck]
+    fn whole_fil_and_remainder(amount: TokenAmount) {
+        let (whole, remainder) = amount.whole_fil_and_remainder();
+        assert!(remainder.abs() < TokenAmount::from_whole(1));
+        assert!(remainder.is_zero() || remainder.signum() == amount.signum());
+        assert_eq!(TokenAmount::from_whole(whole) + remainder, amount);
+    }
+
+    #[quickcheck]
+    fn fil_f64_roundtrip(atto: u64) {
+        // Exact below 2^52 attoFIL
+        let atto = atto >> 12;
+        let amount = TokenAmount::from_atto(atto);
+        let fil = amount.to_fil_f64_lossy();
+        assert_eq!(fil, atto as f64 / 1e18);
+        assert_eq!(TokenAmount::from_fil_f64_lossy(fil), Ok(amount));
+    }
+
+    #[test]
+    fn test_fil_f64() {
+        assert_eq!(
+            TokenAmount::from_whole(-3).whole_fil_and_remainder(),
+            (BigInt::from(-3), TokenAmount::zero())
+        );
+        assert_eq!(
+            TokenAmount::from_atto(-1_500_000_000_000_000_000_i64).whole_fil_and_remainder(),
+            (
+                BigInt::from(-1),
+                TokenAmount::from_atto(-500_000_000_000_000_000_i64)
+            )
+        );
+
+        for (fil, atto) in [
+            (0.0, 0),
+            (-0.0, 0),
+            (1.5, 1_500_000_000_000_000_000),
+            (0.1, 100_000_000_000_000_000),
+            (1e-18, 1),
+            (2e9, 2_000_000_000_000_000_000_000_000),
+        ] {
+            let amount = TokenAmount::from_atto(atto);
+            assert_eq!(TokenAmount::from_fil_f64_lossy(fil), Ok(amount.clone()));
+            assert_eq!(amount.to_fil_f64_lossy(), fil);
+        }
+        assert_eq!(TokenAmount::from_atto(-1).to_fil_f64_lossy(), -1e-18);
+        assert_eq!(TOTAL_FILECOIN.to_fil_f64_lossy(), 2e9);
+
+        // Lossy beyond the precision of a float
+        let fil = TokenAmount::from_whole(1_000_000_000);
+        let fil_and_one_atto = &fil + TokenAmount::from_atto(1);
+        assert_eq!(fil_and_one_atto.to_fil_f64_lossy(), fil.to_fil_f64_lossy());
+        assert_eq!(
+            TokenAmount::from_fil_f64_lossy(fil_and_one_atto.to_fil_f64_lossy()),
+            Ok(fil)
+        );
+        assert_eq!(
+            TokenAmount::from_whole((1_u64 << 53) + 1).to_fil_f64_lossy(),
+            (1_u64 << 53) as f64
+        );
+        // Less than half an attoFIL
+        assert_eq!(
+            TokenAmount::from_fil_f64_lossy(4e-19),
+            Ok(TokenAmount::zero())
+        );
+        // Too large for a float, but doesn't panic
+        let huge = TokenAmount::from_atto(BigInt::from(1) << 1100);
+        assert_eq!(huge.to_fil_f64_lossy(), f64::INFINITY);
+        assert_eq!(
+            TokenAmount::from_atto(1_u128 << 100).to_fil_f64_lossy(),
+            2_f64.powi(100) / 1e18
+        );
+
+        assert_eq!(
+            TokenAmount::from_fil_f64_lossy(-1.0),
+            Err(TokenAmountFromFloatError::Negative(-1.0))
+        );
+        assert!(matches!(
+            TokenAmount::from_fil_f64_lossy(f64::NAN),
+            Err(TokenAmountFromFloatError::NotFinite(_))
+        ));
+        assert_eq!(
+            TokenAmount::from_fil_f64_lossy(f64::INFINITY),
+            Err(TokenAmountFromFloatError::NotFinite(f64::INFINITY))
+        );
+        assert_eq!(
+            TokenAmount::from_fil_f64_lossy(f64::NEG_INFINITY),
+            Err(TokenAmountFromFloatError::Negative(f64::NEG_INFINITY))
+        );
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(*ZERO, TokenAmount::zero());
+        assert_eq!(*ZERO, TokenAmount::from_whole(0));
+        assert_eq!(*ONE_FIL, TokenAmount::from_whole(1));
+        assert_eq!(*ONE_FIL, TokenAmount::from_nano(1_000_000_000));
+        assert_eq!(*ONE_NANO_FIL, TokenAmount::from_nano(1));
+        assert_eq!(*ONE_NANO_FIL, TokenAmount::from_atto(1_000_000_000));
+        assert!(*ZERO < *ONE_NANO_FIL && *ONE_NANO_FIL < *ONE_FIL);
+        assert!(ZERO.is_zero() && !ONE_NANO_FIL.is_zero());
+    }
+
+    #[quickcheck]
+    fn constants_compare_like_constructed(amount: TokenAmount) {
+        assert_eq!(amount.is_zero(), amount == TokenAmount::from_atto(0));
+        assert_eq!(amount.cmp(&ZERO), amount.cmp(&TokenAmount::from_whole(0)));
+        assert_eq!(
+            amount.cmp(&ONE_FIL),
+            amount.cmp(&TokenAmount::from_whole(1))
+        );
+        assert_eq!(
+            amount.cmp(&ONE_NANO_FIL),
+            amount.cmp(&TokenAmount::from_nano(1))
+        );
+    }
+
+    #[test]
+    fn test_percent() {
+        use Rounding::*;
+        let atto = TokenAmount::from_atto;
+        for (amount, pct, floor, ceil, nearest) in [
+            (3, 50, 1, 2, 2),
+            (-3, 50, -2, -1, -2),
+            (5, 10, 0, 1, 1),
+            (7, 110, 7, 8, 8),
+            (10, 110, 11, 11, 11),
+            (1, 0, 0, 0, 0),
+        ] {
+            assert_eq!(atto(amount).percent(pct, Floor), atto(floor));
+            assert_eq!(atto(amount).percent(pct, Ceil), atto(ceil));
+            assert_eq!(atto(amount).percent(pct, Nearest), atto(nearest));
+        }
+        // 1 bps of 1 attoFIL
+        assert_eq!(atto(1).bps(1, Floor), atto(0));
+        assert_eq!(atto(1).bps(1, Ceil), atto(1));
+        assert_eq!(atto(1).bps(1, Nearest), atto(0));
+        assert_eq!(atto(1).bps(5_000, Nearest), atto(1));
+        assert_eq!(atto(-1).bps(5_000, Nearest), atto(-1));
+        assert_eq!(atto(-1).bps(4_999, Nearest), atto(0));
+        assert_eq!(
+            TokenAmount::from_whole(1).bps(25, Floor),
+            TokenAmount::from_nano(2_500_000)
+        );
+        assert_eq!(
+            TOTAL_FILECOIN.bps(u64::MAX, Ceil),
+            TOTAL_FILECOIN.mul_div_ceil(u64::MAX, 10_000).unwrap()
+        );
+        assert_eq!(atto(1).mul_div(1, 0, Nearest), Err(DivisionByZero));
+    }
+
+    #[quickcheck]
+    fn percent_identity(amount: TokenAmount, pct: u32) {
+        let pct = u64::from(pct);
+        for rounding in [Rounding::Floor, Rounding::Ceil, Rounding::Nearest] {
+            assert_eq!(amount.percent(100, rounding), amount);
+            assert_eq!(amount.bps(10_000, rounding), amount);
+            assert_eq!(
+                amount.percent(pct, rounding),
+                amount.bps(pct * 100, rounding)
+            );
+        }
+        let floor = amount.bps(pct, Rounding::Floor);
+        let ceil = amount.bps(pct, Rounding::Ceil);
+        let nearest = amount.bps(pct, Rounding::Nearest);
+        assert!(floor <= nearest && nearest <= ceil);
+        assert!(ceil - &floor <= TokenAmount::from_atto(1));
+    }
+
+    #[test]
+    fn test_from_whole_and_atto() {
+        assert_eq!(
+            TokenAmount::from_whole_and_atto(0, 0),
+            Ok(TokenAmount::zero())
+        );
+        assert_eq!(
+            TokenAmount::from_whole_and_atto(1, 500),
+            Ok(TokenAmount::from_atto(1_000_000_000_000_000_500_u64))
+        );
+        let max_atto = TokenAmount::PRECISION - 1;
+        assert_eq!(
+            TokenAmount::from_whole_and_atto(1, max_atto),
+            Ok(TokenAmount::from_atto(1_999_999_999_999_999_999_u64))
+        );
+        assert_eq!(
+            TokenAmount::from_whole_and_atto(u64::MAX, max_atto),
+            Ok(TokenAmount::from_whole(u128::from(u64::MAX) + 1) - &TokenAmount::from_atto(1))
+        );
+        assert_eq!(
+            TokenAmount::from_whole_and_atto(1, TokenAmount::PRECISION),
+            Err(AttoFractionTooLargeError(TokenAmount::PRECISION))
+        );
+        assert!(TokenAmount::from_whole_and_atto(0, u64::MAX).is_err());
+    }
+
+    #[quickcheck]
+    fn from_whole_and_atto_roundtrip(whole: u64, atto: u64) {
+        let atto = atto % TokenAmount::PRECISION;
+        let amount = TokenAmount::from_whole_and_atto(whole, atto).unwrap();
+        assert_eq!(
+            amount.whole_fil_and_remainder(),
+            (BigInt::from(whole), TokenAmount::from_atto(atto))
+        );
+    }
+
+    #[quickcheck]
+    fn try_to_primitive_roundtrip(a: u64, b: u128) {
+        assert_eq!(TokenAmount::from_atto(a).try_to_atto_u64(), Ok(a));
+        assert_eq!(TokenAmount::from_atto(a).try_to_atto_u128(), Ok(a.into()));
+        assert_eq!(TokenAmount::from_atto(b).try_to_atto_u128(), Ok(b));
+        assert_eq!(TokenAmount::from_nano(a).try_to_nano_u64(), Ok(a));
+    }
+
+    #[test]
+    fn test_try_to_primitive() {
+        let max = TokenAmount::from_atto(u64::MAX);
+        let one = TokenAmount::from_atto(1);
+        assert_eq!(max.try_to_atto_u64(), Ok(u64::MAX));
+        assert_eq!((max.clone() - &one).try_to_atto_u64(), Ok(u64::MAX - 1));
+        let error = (&max + &one).try_to_atto_u64().unwrap_err();
+        assert_eq!(error.amount, &max + &one);
+        assert_eq!(
+            error.to_string(),
+            "token amount 18.446744073709551616 FIL is out of range for a u64 of attoFIL"
+        );
+        assert_eq!(
+            (&max + &one).try_to_atto_u128(),
+            Ok(u128::from(u64::MAX) + 1)
+        );
+        assert!(TokenAmount::from_atto(-1).try_to_atto_u64().is_err());
+        assert!(TokenAmount::from_atto(-1).try_to_atto_u128().is_err());
+
+        let max = TokenAmount::from_atto(u128::MAX);
+        assert_eq!(max.try_to_atto_u128(), Ok(u128::MAX));
+        assert!((&max + &one).try_to_atto_u128().is_err());
+
+        let max = TokenAmount::from_nano(u64::MAX);
+        assert_eq!(max.try_to_nano_u64(), Ok(u64::MAX));
+        assert_eq!(
+            (&max + TokenAmount::from_atto(999_999_999)).try_to_nano_u64(),
+            Ok(u64::MAX)
+        );
+        assert!(
+            (&max + TokenAmount::from_nano(1))
+                .try_to_nano_u64()
+                .is_err()
+        );
+        assert_eq!(TokenAmount::from_atto(999_999_999).try_to_nano_u64(), Ok(0));
+        assert!(TokenAmount::from_atto(-1).try_to_nano_u64().is_err());
+    }
+
+    #[test]
+    fn test_eth_bytes() {
+        let zero = TokenAmount::zero();
+        assert_eq!(zero.to_eth_bytes(), Ok([0; 32]));
+        assert_eq!(TokenAmount::from_eth_big_int(&[0; 32]), zero);
+
+        let max = TokenAmount::from_atto((BigInt::from(1) << 256) - 1);
+        assert_eq!(max.to_eth_bytes(), Ok([0xff; 32]));
+        assert_eq!(TokenAmount::from_eth_big_int(&[0xff; 32]), max);
+        let error = (&max + TokenAmount::from_atto(1))
+            .to_eth_bytes()
+            .unwrap_err();
+        assert_eq!((error.primitive, error.unit), ("uint256", "wei"));
+
+        let error = TokenAmount::from_atto(-1).to_eth_bytes().unwrap_err();
+        assert_eq!(error.amount, TokenAmount::from_atto(-1));
+    }
+
+    #[test]
+    fn eth_bytes_snapshot() {
+        // Big-endian and left-padded, like Lotus' `abi` encoding of a `uint256`
+        assert_eq!(
+            hex::encode(TokenAmount::from_whole(1).to_eth_bytes().unwrap()),
+            "0000000000000000000000000000000000000000000000000de0b6b3a7640000"
+        );
+        assert_eq!(
+            hex::encode(TOTAL_FILECOIN.to_eth_bytes().unwrap()),
+            "000000000000000000000000000000000000000006765c793fa10079d0000000"
+        );
+    }
+
+    #[quickcheck]
+    fn eth_bytes_roundtrip(amount: TokenAmount) {
+        match amount.to_eth_bytes() {
+            Ok(bytes) => assert_eq!(TokenAmount::from_eth_big_int(&bytes), amount),
+            Err(_) => assert!(amount.is_negative() || amount.atto().bits() > 256),
+        }
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let one = TokenAmount::from_atto(1);
+        let two = TokenAmount::from_atto(2);
+        assert_eq!(two.checked_sub(&one), Some(one.clone()));
+        assert_eq!(two.checked_sub(&two), Some(TokenAmount::zero()));
+        assert_eq!(one.checked_sub(&two), None);
+        assert_eq!(one.saturating_sub(&two), TokenAmount::zero());
+        assert_eq!(one.checked_add(&TokenAmount::from_atto(-2)), None);
+        assert_eq!(two.checked_mul(3), Some(TokenAmount::from_atto(6)));
+        assert_eq!(two.checked_mul(-1), None);
+
+        // Too large to be serialized
+        let max = TokenAmount::from_atto((BigInt::from(1) << 1016) - 1);
+        assert_eq!(max.checked_add(&TokenAmount::zero()), Some(max.clone()));
+        assert_eq!(max.checked_add(&one), None);
+        assert_eq!(max.checked_mul(2), None);
+        assert!(fvm_ipld_encoding::to_vec(&max).is_ok());
+        assert!(fvm_ipld_encoding::to_vec(&(max + &one)).is_err());
+    }
+
+    #[test]
+    fn test_checked_add_supply_bounded() {
+        let one = TokenAmount::from_atto(1);
+        let below = TOTAL_FILECOIN.clone() - &one;
+        assert_eq!(
+            below.checked_add_supply_bounded(&one).as_ref(),
+            Some(&*TOTAL_FILECOIN)
+        );
+        assert_eq!(
+            TOTAL_FILECOIN
+                .checked_add_supply_bounded(&TokenAmount::zero())
+                .as_ref(),
+            Some(&*TOTAL_FILECOIN)
+        );
+        assert_eq!(TOTAL_FILECOIN.checked_add_supply_bounded(&one), None);
+        assert_eq!(
+            below.checked_add_supply_bounded(&TokenAmount::from_atto(2)),
+            None
+        );
+        assert_eq!(
+            TOTAL_FILECOIN.checked_add_supply_bounded(&TokenAmount::from_atto(-1)),
+            Some(below.clone())
+        );
+        assert_eq!(
+            one.checked_add_supply_bounded(&TokenAmount::from_atto(-2)),
+            None
+        );
+        // Unbounded addition is unaffected
+        assert_eq!(&*TOTAL_FILECOIN + &one, below + TokenAmount::from_atto(2));
+    }
+
+    #[quickcheck]
+    fn mul_div_bounds(amount: TokenAmount, num: i64, denom: i64) -> bool {
+        if denom == 0 {
+            return true;
+        }
+        let floor = amount.mul_div_floor(num, denom).unwrap();
+        let ceil = amount.mul_div_ceil(num, denom).unwrap();
+        let exact = BigRational::new(amount.atto() * num, BigInt::from(denom));
+        BigRational::from(floor.atto().clone()) <= exact
+            && exact <= BigRational::from(ceil.atto().clone())
+            && ceil.atto() - floor.atto() <= BigInt::from(1)
+    }
+
+    #[quickcheck]
+    fn mul_div_unit_numerator(amount: TokenAmount, denom: i64) -> bool {
+        if denom == 0 {
+            return true;
+        }
+        amount.mul_div_floor(1, denom).unwrap() == amount.div_floor(denom)
+            && amount.mul_div_ceil(1, denom).unwrap() == amount.div_ceil(denom)
+    }
+
+    #[quickcheck]
+    fn mul_div_unit_denominator(amount: TokenAmount, num: i64) -> bool {
+        let product = &amount * num;
+        amount.mul_div_floor(num, 1).unwrap() == product
+            && amount.mul_div_ceil(num, 1).unwrap() == product
+    }
+
+    #[test]
+    fn test_fraction_and_quotient_operators() {
+        let atto = TokenAmount::from_atto;
+        assert_eq!(&atto(7) * (1, 2), atto(3));
+        assert_eq!(&atto(-7) * (1, 2), atto(-4));
+        assert_eq!(atto(7) * (3, 1), atto(21));
+        let mut amount = atto(10);
+        amount *= (2, 3);
+        assert_eq!(amount, atto(6));
+
+        assert_eq!(&atto(7) / &atto(2), BigInt::from(3));
+        assert_eq!(&atto(-7) / &atto(2), BigInt::from(-3));
+        assert_eq!(&atto(7) % &atto(2), atto(1));
+        assert_eq!(&atto(-7) % &atto(2), atto(-1));
+        assert_eq!(&atto(7) % &atto(-2), atto(1));
+        assert_eq!(
+            &*TOTAL_FILECOIN / &*ONE_FIL,
+            BigInt::from(TOTAL_FILECOIN_BASE)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_by_fraction_with_zero_denominator() {
+        let _ = &*ONE_FIL * (1, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn div_by_zero_amount() {
+        let _ = &*ONE_FIL / &TokenAmount::zero();
+    }
+
+    #[quickcheck]
+    fn mul_fraction_matches_bigint(amount: TokenAmount, numerator: u64, denominator: u64) {
+        if denominator == 0 {
+            return;
+        }
+        let expected = (amount.atto() * numerator).div_floor(&BigInt::from(denominator));
+        assert_eq!(
+            &amount * (numerator, denominator),
+            TokenAmount::from_atto(expected)
+        );
+        let mut assigned = amount.clone();
+        assigned *= (numerator, denominator);
+        assert_eq!(assigned, amount * (numerator, denominator));
+    }
+
+    #[quickcheck]
+    fn div_rem_matches_bigint(a: TokenAmount, b: TokenAmount) {
+        if b.is_zero() {
+            return;
+        }
+        let (quotient, remainder) = (&a / &b, &a % &b);
+        assert_eq!(quotient, a.atto() / b.atto());
+        assert_eq!(remainder.atto(), &(a.atto() % b.atto()));
+        assert_eq!(&b * quotient + &remainder, a);
+        assert!(remainder.abs() < b.abs());
+    }
+
+    #[test]
+    fn test_mul_div() {
+        let amount = TokenAmount::from_atto(10);
+        assert_eq!(amount.mul_div_floor(2, 3), Ok(TokenAmount::from_atto(6)));
+        assert_eq!(amount.mul_div_ceil(2, 3), Ok(TokenAmount::from_atto(7)));
+        assert_eq!(amount.mul_div_floor(-2, 3), Ok(TokenAmount::from_atto(-7)));
+        assert_eq!(amount.mul_div_ceil(-2, 3), Ok(TokenAmount::from_atto(-6)));
+        assert_eq!(amount.mul_div_floor(3, 3), Ok(amount.clone()));
+        assert_eq!(amount.mul_div_floor(1, 0), Err(DivisionByZero));
+        assert_eq!(amount.mul_div_ceil(1, 0), Err(DivisionByZero));
+        // The product doesn't overflow
+        assert_eq!(
+            TOTAL_FILECOIN.mul_div_floor(u128::MAX, u128::MAX).as_ref(),
+            Ok(&*TOTAL_FILECOIN)
+        );
+
+        assert_eq!(
+            amount.ratio(&TokenAmount::from_atto(4)),
+            Ok(BigRational::new(5.into(), 2.into()))
+        );
+        assert_eq!(
+            TokenAmount::zero().ratio(&amount),
+            Ok(BigRational::from_integer(0.into()))
+        );
+        assert_eq!(amount.ratio(&TokenAmount::zero()), Err(DivisionByZero));
+    }
+
+    #[test]
+    fn test_split_evenly() {
+        for (atto, n, share, remainder) in [
+            (10, 1, 10, 0),
+            (10, 5, 2, 0),
+            (10, 3, 3, 1),
+            (2, 3, 0, 2),
+            (0, 3, 0, 0),
+            (-10, 3, -4, 2),
+        ] {
+            assert_eq!(
+                TokenAmount::from_atto(atto).split_evenly(n),
+                Ok((
+                    TokenAmount::from_atto(share),
+                    TokenAmount::from_atto(remainder)
+                )),
+                "{atto} / {n}"
+            );
+        }
+        assert_eq!(
+            TokenAmount::from_atto(1).split_evenly(0),
+            Err(DivisionByZero)
+        );
+    }
+
+    #[quickcheck]
+    fn split_evenly_is_exact(amount: TokenAmount, n: u64) {
+        let Ok((share, remainder)) = amount.split_evenly(n) else {
+            assert_eq!(n, 0);
+            return;
+        };
+        assert_eq!(&share * n + &remainder, amount);
+        assert!(!remainder.is_negative());
+        assert!(remainder.atto() < &BigInt::from(n));
+    }
+
+    #[test]
+    fn test_from_str() {
+        for (input, atto) in [
+            ("0", 0_i128),
+            ("1", 1),
+            ("1000", 1_000),
+            ("1000 attoFIL", 1_000),
+            ("1 FIL", 1_000_000_000_000_000_000),
+            ("1.5 FIL", 1_500_000_000_000_000_000),
+            ("1.5FIL", 1_500_000_000_000_000_000),
+            ("1.5 fil", 1_500_000_000_000_000_000),
+            ("1.500 Fil", 1_500_000_000_000_000_000),
+            ("0.000000000000000001 FIL", 1),
+            ("2 milliFIL", 2_000_000_000_000_000),
+            ("2 microFIL", 2_000_000_000_000),
+            ("20 nanoFIL", 20_000_000_000),
+            ("20 NANOFIL", 20_000_000_000),
+            ("2 picoFIL", 2_000_000),
+            ("2 femtoFIL", 2_000),
+            ("2.5 femtofil", 2_500),
+            ("1.0 attoFIL", 1),
+            ("+1 FIL", 1_000_000_000_000_000_000),
+            ("007 attoFIL", 7),
+            ("  1.5   FIL  ", 1_500_000_000_000_000_000),
+            ("\t20\nnanoFIL\n", 20_000_000_000),
+            ("-0", 0),
+        ] {
+            assert_eq!(
+                TokenAmount::from_str(input),
+                Ok(TokenAmount::from_atto(atto)),
+                "{input:?}"
+            );
+            assert_eq!(
+                TokenAmount::from_str_signed(input),
+                Ok(TokenAmount::from_atto(atto)),
+                "{input:?}"
+            );
+        }
+        assert_eq!(
+            TokenAmount::from_str("2000000000 FIL").as_ref(),
+            Ok(&*TOTAL_FILECOIN)
+        );
+    }
+
+    #[test]
+    fn test_from_str_signed() {
+        for (input, atto) in [
+            ("-1", -1_i128),
+            ("-1.5 FIL", -1_500_000_000_000_000_000),
+            (" -20 nanoFIL ", -20_000_000_000),
+        ] {
+            assert_eq!(
+                TokenAmount::from_str_signed(input),
+                Ok(TokenAmount::from_atto(atto)),
+                "{input:?}"
+            );
+            assert_eq!(
+                TokenAmount::from_str(input),
+                Err(ParseTokenAmountError::Negative(input.to_owned())),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_atto_str() {
+        assert_eq!(TokenAmount::from_atto_str("0"), Ok(TokenAmount::zero()));
+        assert_eq!(
+            TokenAmount::from_atto_str("007"),
+            Ok(TokenAmount::from_atto(7))
+        );
+        assert_eq!(
+            TokenAmount::from_atto_str("2000000000000000000000000000").as_ref(),
+            Ok(&*TOTAL_FILECOIN)
+        );
+        let huge = "9".repeat(1000);
+        assert_eq!(
+            TokenAmount::from_atto_str(&huge),
+            Err(ParseTokenAmountError::TooLarge(huge.clone()))
+        );
+        for input in [
+            "", " ", " 1", "1 ", "1\n", "+1", "-1", "1.0", "1.5", "1e3", "1_000", "0x10", "1 FIL",
+            "1attoFIL", "١",
+        ] {
+            assert_eq!(
+                TokenAmount::from_atto_str(input),
+                Err(ParseTokenAmountError::NotAttoInteger(input.to_owned())),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        for input in [
+            "",
+            " ",
+            "FIL",
+            "1.",
+            ".5 FIL",
+            "1.2.3 FIL",
+            "1,5 FIL",
+            "1 000",
+            "- 1",
+            "--1",
+            "+-1",
+            "1e3",
+            "1E3 FIL",
+            "0x10",
+            "1_000",
+            "one FIL",
+            "1 FIL 2",
+        ] {
+            let error = TokenAmount::from_str(input).unwrap_err();
+            assert!(
+                matches!(
+                    error,
+                    ParseTokenAmountError::Invalid(_) | ParseTokenAmountError::UnknownUnit(_)
+                ),
+                "{input:?}: {error}"
+            );
+        }
+        for (input, unit) in [
+            ("1 kFIL", "kFIL"),
+            ("1 aFIL", "aFIL"),
+            ("1 atto", "atto"),
+            ("1 FILs", "FILs"),
+            ("1 µFIL", "µFIL"),
+            ("1 nano FIL", "nano FIL"),
+        ] {
+            assert_eq!(
+                TokenAmount::from_str(input),
+                Err(ParseTokenAmountError::UnknownUnit(unit.to_owned())),
+                "{input:?}"
+            );
+        }
+        for input in [
+            "0.0000000000000000001 FIL",
+            "1.5",
+            "0.1 attoFIL",
+            "1.0001 femtoFIL",
+            "-1.5",
+        ] {
+            assert_eq!(
+                TokenAmount::from_str_signed(input),
+                Err(ParseTokenAmountError::TooPrecise(input.to_owned())),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_str_too_large() {
+        // The largest serializable amount has 1016 bits, i.e. 306 decimal digits.
+        let max = (BigInt::from(1) << 1016) - 1;
+        assert_eq!(
+            TokenAmount::from_str(&max.to_string()),
+            Ok(TokenAmount::from_atto(max.clone()))
+        );
+        for input in [
+            (&max + 1).to_string(),
+            format!("-{}", &max + 1),
+            format!("{max} FIL"),
+            "9".repeat(10_000),
+        ] {
+            assert_eq!(
+                TokenAmount::from_str_signed(&input),
+                Err(ParseTokenAmountError::TooLarge(input.clone())),
+            );
+        }
+    }
+
+    #[quickcheck]
+    fn from_str_roundtrip(amount: TokenAmount) {
+        assert_eq!(
+            TokenAmount::from_str_signed(&amount.atto().to_string()),
+            Ok(amount.clone())
+        );
+        assert_eq!(
+            TokenAmount::from_str_signed(&format!("{} FIL", amount.to_accounting_string())),
+            Ok(amount.clone())
+        );
+        assert_eq!(
+            TokenAmount::from_str(&amount.atto().to_string()).is_ok(),
+            !amount.is_negative()
+        );
+    }
+
+    #[test]
+    fn test_pretty() {
+        for (atto, expected) in [
+            (0_i128, "0 FIL"),
+            (1, "1 attoFIL"),
+            (-1, "-1 attoFIL"),
+            (999_999_999, "999999999 attoFIL"),
+            (1_000_000_000, "1 nanoFIL"),
+            (-1_500_000_000, "-1.5 nanoFIL"),
+            (123_456_789_012, "123.457 nanoFIL"),
+            (1_000_000_000_000_000_000, "1 FIL"),
+            (5_000_000_000_000_000_000, "5 FIL"),
+            (1_234_567_890_123_456_789, "1.23457 FIL"),
+            (-2_500_000_000_000_000_000, "-2.5 FIL"),
+            (1_000_000_000_000_000_001, "1 FIL"),
+            // Rounded up into the next unit
+            (999_999_999_999_999_999, "1 FIL"),
+            (-999_999_999_999_999_999, "-1 FIL"),
+            (999_999_999_999_500, "1000000 nanoFIL"),
+            (1_234_567_890_500_000_000_000_000_000, "1234567891 FIL"),
+        ] {
+            assert_eq!(
+                TokenAmount::from_atto(atto).pretty().to_string(),
+                expected,
+                "{atto}"
+            );
+        }
+        assert_eq!(TOTAL_FILECOIN.pretty().to_string(), "2000000000 FIL");
+        assert_eq!(
+            (TOTAL_FILECOIN.clone() - &TokenAmount::from_atto(1))
+                .pretty()
+                .to_string(),
+            "2000000000 FIL"
+        );
+        assert_eq!(
+            TokenAmount::from_atto(-TOTAL_FILECOIN.atto())
+                .pretty()
+                .to_string(),
+            "-2000000000 FIL"
+        );
+        // More than `u128::MAX` attoFIL
+        assert_eq!(
+            TokenAmount::from_atto(BigInt::from(10).pow(40))
+                .pretty()
+                .to_string(),
+            "10000000000000000000000 FIL"
+        );
+        assert_eq!(
+            TokenAmount::from_atto(-BigInt::from(10).pow(40) - 1)
+                .pretty()
+                .to_string(),
+            "-10000000000000000000000 FIL"
+        );
+    }
+
+    #[test]
+    fn test_display_alternate() {
+        for atto in [
+            0_i128,
+            1,
+            -1_500_000_000,
+            1_234_567_890_123_456_789,
+            -2_500_000_000_000_000_000,
+        ] {
+            let amount = TokenAmount::from_atto(atto);
+            assert_eq!(format!("{amount}"), format!("{}", *amount), "{atto}");
+            assert_eq!(format!("{amount:#}"), amount.pretty().to_string(), "{atto}");
+        }
+        assert_eq!(format!("{:#}", TokenAmount::from_whole(5)), "5 FIL");
+        assert_eq!(format!("{:#}", TokenAmount::from_nano(1)), "1 nanoFIL");
+        // Width, fill, alignment and precision are honored
+        let amount = TokenAmount::from_whole(5);
+        assert_eq!(format!("{amount:>#8}"), "   5 FIL");
+        assert_eq!(format!("{amount:*<#8}"), "5 FIL***");
+        assert_eq!(format!("{amount:#.1}"), "5 FIL");
+        // The precision is the number of significant digits, and keeps the unit
+        let amount = TokenAmount::from_nano(1_450_000_000);
+        assert_eq!(format!("{amount:#}"), "1.45 FIL");
+        assert_eq!(format!("{amount:#.2}"), "1.5 FIL");
+        assert_eq!(format!("{amount:^#11.1}"), "   1 FIL   ");
+        assert_eq!(format!("{:#.1}", TokenAmount::from_nano(5)), "5 nanoFIL");
+    }
+
+    #[test]
+    fn test_format_units() {
+        for (atto, significant_digits, expected) in [
+            (1_500_000_000_000_000_000_i128, 0, "2 FIL"),
+            (1_500_000_000_000_000_000, 1, "2 FIL"),
+            (1_450_000_000_000_000_000, 2, "1.5 FIL"),
+            (1_234_567_890_123_456_789, 20, "1.234567890123456789 FIL"),
+            (
+                1_234_567_890_123_456_789,
+                usize::MAX,
+                "1.234567890123456789 FIL",
+            ),
+            (123_456_789_012, 0, "123 nanoFIL"),
+            (123_456_789_012, 4, "123.5 nanoFIL"),
+            (1_000_000_001, 10, "1.000000001 nanoFIL"),
+            (-1_000_000_001, 9, "-1 nanoFIL"),
+            (42, 1, "42 attoFIL"),
+        ] {
+            assert_eq!(
+                TokenAmount::from_atto(atto)
+                    .format_units(significant_digits)
+                    .to_string(),
+                expected,
+                "{atto}, {significant_digits}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_serde_as_string() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Amounts {
+            #[serde(with = "tokenamount_as_atto_string")]
+            atto: TokenAmount,
+            #[serde(with = "tokenamount_as_fil_string")]
+            fil: TokenAmount,
+        }
+
+        for (atto, json) in [
+            (0_i128, r#"{"atto":"0","fil":"0"}"#),
+            (1, r#"{"atto":"1","fil":"0.000000000000000001"}"#),
+            (
+                1_500_000_000_000_000_000,
+                r#"{"atto":"1500000000000000000","fil":"1.5"}"#,
+            ),
+            (
+                10_000_000_000_000_000_000,
+                r#"{"atto":"10000000000000000000","fil":"10"}"#,
+            ),
+            (
+                -2_500_000_000_000_000_000,
+                r#"{"atto":"-2500000000000000000","fil":"-2.5"}"#,
+            ),
+            (
+                2_000_000_000_000_000_000_000_000_000,
+                r#"{"atto":"2000000000000000000000000000","fil":"2000000000"}"#,
+            ),
+        ] {
+            let amounts = Amounts {
+                atto: TokenAmount::from_atto(atto),
+                fil: TokenAmount::from_atto(atto),
+            };
+            assert_eq!(serde_json::to_string(&amounts).unwrap(), json);
+            assert_eq!(serde_json::from_str::<Amounts>(json).unwrap(), amounts);
+        }
+
+        // Bare numbers
+        for (json, atto) in [
+            (
+                r#"{"atto":15,"fil":1}"#,
+                (15, 1_000_000_000_000_000_000_i128),
+            ),
+            (
+                r#"{"atto":-15,"fil":1.5}"#,
+                (-15, 1_500_000_000_000_000_000),
+            ),
+            (
+                r#"{"atto":18446744073709551615,"fil":0.1}"#,
+                (18_446_744_073_709_551_615, 100_000_000_000_000_000),
+            ),
+            (
+                r#"{"atto":1e21,"fil":-0.000000000000000001}"#,
+                (1_000_000_000_000_000_000_000, -1),
+            ),
+            (
+                r#"{"atto":"15","fil":"1e3"}"#,
+                (15, 1_000_000_000_000_000_000_000),
+            ),
+        ] {
+            assert_eq!(
+                serde_json::from_str::<Amounts>(json).unwrap(),
+                Amounts {
+                    atto: TokenAmount::from_atto(atto.0),
+                    fil: TokenAmount::from_atto(atto.1),
+                },
+                "{json}"
+            );
+        }
+
+        for json in [
+            r#"{"atto":"1.5","fil":"1"}"#,
+            r#"{"atto":1.5,"fil":"1"}"#,
+            r#"{"atto":"1 FIL","fil":"1"}"#,
+            r#"{"atto":"","fil":"1"}"#,
+            r#"{"atto":null,"fil":"1"}"#,
+            r#"{"atto":true,"fil":"1"}"#,
+            r#"{"atto":"1","fil":"0.0000000000000000001"}"#,
+            r#"{"atto":"1","fil":"1 FIL"}"#,
+            r#"{"atto":"1","fil":[1]}"#,
+        ] {
+            serde_json::from_str::<Amounts>(json).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_try_from_decimal_invalid() {
+        for fil in ["0.0000000000000000001", "1.0000000000000000001", "1e-19"] {
+            assert_eq!(
+                try_from_decimal(fil),
+                Err(TokenAmountFromDecimalError::TooPrecise(
+                    BigDecimal::from_str(fil).unwrap()
+                )),
+                "{fil}"
+            );
+        }
+        for fil in ["-1", "-0.5", "-1e-19"] {
+            assert_eq!(
+                try_from_decimal(fil),
+                Err(TokenAmountFromDecimalError::Negative(
+                    BigDecimal::from_str(fil).unwrap()
+                )),
+                "{fil}"
+            );
+        }
+    }
+}
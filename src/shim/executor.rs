@@ -346,6 +346,27 @@ impl From<StampedEvent_v4> for StampedEvent {
 }
 
 impl StampedEvent {
+    #[cfg(test)]
+    pub fn new(emitter: ActorID, entries: Vec<Entry>) -> Self {
+        StampedEvent::V4(StampedEvent_v4 {
+            emitter,
+            event: ActorEvent_v4 {
+                entries: entries
+                    .into_iter()
+                    .map(|entry| {
+                        let (flags, key, codec, value) = entry.into_parts();
+                        Entry_v4 {
+                            flags: fvm_shared4::event::Flags::from_bits_truncate(flags),
+                            key,
+                            codec,
+                            value,
+                        }
+                    })
+                    .collect(),
+            },
+        })
+    }
+
     /// Returns the ID of the actor that emitted this event.
     pub fn emitter(&self) -> ActorID {
         match self {
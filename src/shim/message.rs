@@ -23,6 +23,8 @@ pub struct Message {
     pub from: Address,
     pub to: Address,
     pub sequence: u64,
+    // Lotus JSON rejects absurdly large amounts, see `TokenAmount::try_from_atto_checked`
+    #[cfg_attr(test, arbitrary(gen(|g| TokenAmount::arbitrary_checked(g))))]
     pub value: TokenAmount,
     pub method_num: MethodNum,
     #[cfg_attr(test, arbitrary(gen(
@@ -30,7 +32,9 @@ pub struct Message {
     )))]
     pub params: RawBytes,
     pub gas_limit: u64,
+    #[cfg_attr(test, arbitrary(gen(|g| TokenAmount::arbitrary_checked(g))))]
     pub gas_fee_cap: TokenAmount,
+    #[cfg_attr(test, arbitrary(gen(|g| TokenAmount::arbitrary_checked(g))))]
     pub gas_premium: TokenAmount,
 }
 
@@ -100,10 +100,8 @@ impl GenesisInfo {
         } else {
             TokenAmount::default()
         };
-        let fil_circulating = TokenAmount::max(
-            &fil_vested + &fil_mined + &fil_reserve_disbursed - &fil_burnt - &fil_locked,
-            TokenAmount::default(),
-        );
+        let fil_circulating = (&fil_vested + &fil_mined + &fil_reserve_disbursed)
+            .saturating_sub(&(&fil_burnt + &fil_locked));
         Ok(CirculatingSupply {
             fil_vested,
             fil_mined,
@@ -182,8 +180,7 @@ impl GenesisInfo {
                         let ms = multisig::State::load(&db, actor.code, actor.state)?;
 
                         let locked_balance: TokenAmount = ms.locked_balance(height)?.into();
-                        let avail_balance = actor_balance.clone() - &locked_balance;
-                        circ += avail_balance.max(TokenAmount::zero());
+                        circ += actor_balance.saturating_sub(&locked_balance);
                         un_circ += actor_balance.min(locked_balance);
                     }
                     _ => bail!("unexpected actor: {:?}", actor),
@@ -194,7 +191,12 @@ impl GenesisInfo {
             Ok(())
         })?;
 
-        let total = circ.clone() + un_circ;
+        let total = circ.checked_add_supply_bounded(&un_circ).with_context(|| {
+            format!(
+                "total filecoin exceeds the total supply: {circ} + {un_circ} > {}",
+                *TOTAL_FILECOIN
+            )
+        })?;
         if total != *TOTAL_FILECOIN {
             bail!(
                 "total filecoin didn't add to expected amount: {} != {}",
@@ -425,6 +425,8 @@ where
                     trace!("Indexing events root @{}: {}", tipset.epoch(), events_root);
                     self.chain_store().put_index(events_root, key)?;
                 }
+                self.chain_store()
+                    .put_events_index(tipset, &state_output.events)?;
 
                 self.update_cache_with_state_output(key, &state_output);
 
@@ -1,17 +1,21 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
 use crate::{
-    blocks::{Ticket, VRFProof},
+    blocks::{CachingBlockHeader, RawBlockHeader, Ticket, Tipset, TipsetKey, VRFProof},
+    chain::ChainStore,
+    db::MemoryDB,
     eth::EVMMethod,
     message::SignedMessage,
+    networks::ChainConfig,
     shim::{
         address::Address,
         crypto::{SECP_SIG_LEN, Signature, SignatureType},
         message::{Message, Message_v3},
     },
+    utils::db::car_util::load_car,
 };
 use base64::{Engine, prelude::BASE64_STANDARD};
 
@@ -88,3 +92,44 @@ macro_rules! from_str_with {
         $deserializer(&mut serde_json::de::Deserializer::from_str($str)).unwrap()
     };
 }
+
+/// Loads `test-snapshots/chain4.car` into a new [`MemoryDB`], and returns it along with a
+/// [`ChainStore`] that uses it for every column, and the head tipset of the snapshot.
+pub async fn chain4_store(
+    chain_config: ChainConfig,
+) -> (Arc<MemoryDB>, ChainStore<MemoryDB>, Tipset) {
+    let db = Arc::new(MemoryDB::default());
+    let car = tokio::fs::read("test-snapshots/chain4.car").await.unwrap();
+    let header = load_car(&db, std::io::Cursor::new(car)).await.unwrap();
+    let head_ts = Tipset::load_required(&db, &TipsetKey::from(header.roots)).unwrap();
+    let chain_store =
+        memory_chain_store_with_genesis(&db, chain_config, head_ts.genesis(&db).unwrap());
+    (db, chain_store, head_ts)
+}
+
+/// A [`ChainStore`] that uses `db` for every column, with the default [`ChainConfig`] and a
+/// dummy genesis block header.
+pub fn memory_chain_store(db: &Arc<MemoryDB>) -> ChainStore<MemoryDB> {
+    let genesis = CachingBlockHeader::new(RawBlockHeader {
+        miner_address: Address::new_id(0),
+        ..Default::default()
+    });
+    memory_chain_store_with_genesis(db, ChainConfig::default(), genesis)
+}
+
+/// A [`ChainStore`] that uses `db` for every column.
+pub fn memory_chain_store_with_genesis(
+    db: &Arc<MemoryDB>,
+    chain_config: ChainConfig,
+    genesis: CachingBlockHeader,
+) -> ChainStore<MemoryDB> {
+    ChainStore::new(
+        db.clone(),
+        db.clone(),
+        db.clone(),
+        db.clone(),
+        Arc::new(chain_config),
+        genesis,
+    )
+    .unwrap()
+}
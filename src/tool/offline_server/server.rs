@@ -7,7 +7,7 @@ use crate::chain_sync::SyncStatusReport;
 use crate::chain_sync::network_context::SyncNetworkContext;
 use crate::cli_shared::cli::EventsConfig;
 use crate::cli_shared::snapshot::TrustedVendor;
-use crate::daemon::db_util::{backfill_db, populate_eth_mappings};
+use crate::daemon::db_util::{backfill_db, default_backfill_concurrency, populate_eth_mappings};
 use crate::db::{MemoryDB, car::ManyCar};
 use crate::genesis::read_genesis_header;
 use crate::key_management::{KeyStore, KeyStoreConfig};
@@ -15,7 +15,7 @@ use crate::libp2p::PeerManager;
 use crate::message_pool::{MessagePool, MpoolRpcProvider};
 use crate::networks::{ChainConfig, NetworkChain};
 use crate::rpc::eth::filter::EthEventHandler;
-use crate::rpc::{RPCState, start_rpc};
+use crate::rpc::{RPCState, UnknownFields, start_rpc};
 use crate::shim::address::{CurrentNetwork, Network};
 use crate::state_manager::StateManager;
 use crate::utils::net::{DownloadFileOption, download_to};
@@ -85,7 +85,13 @@ pub async fn start_offline_server(
     proofs_api::maybe_set_proofs_parameter_cache_dir_env(&Config::default().client.data_dir);
     ensure_proof_params_downloaded().await?;
 
-    backfill_db(&state_manager, &head_ts, head_ts.epoch() - 300).await?;
+    backfill_db(
+        &state_manager,
+        &head_ts,
+        head_ts.epoch() - 300,
+        default_backfill_concurrency(),
+    )
+    .await?;
     populate_eth_mappings(&state_manager, &head_ts)?;
 
     let (network_send, _) = flume::bounded(5);
@@ -164,7 +170,7 @@ where
     let mut terminate = signal(SignalKind::terminate())?;
 
     let result = tokio::select! {
-        ret = start_rpc(state, rpc_address, None) => ret,
+        ret = start_rpc(state, rpc_address, None, UnknownFields::default()) => ret,
         _ = ctrl_c() => {
             info!("Keyboard interrupt.");
             Ok(())
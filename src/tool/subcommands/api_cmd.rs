@@ -3,6 +3,7 @@
 
 mod api_compare_tests;
 mod generate_test_snapshot;
+mod lotus_json_fixtures;
 mod report;
 mod test_snapshot;
 
@@ -177,6 +178,16 @@ pub enum ApiCommands {
         #[arg(num_args = 1.., required = true)]
         files: Vec<PathBuf>,
     },
+    /// Capture payloads from a live Lotus node as fixtures for the lotus JSON
+    /// round-trip tests.
+    DumpLotusJsonFixtures {
+        /// Lotus address
+        #[clap(long, default_value = "/ip4/127.0.0.1/tcp/1234/http")]
+        lotus: UrlFromMultiAddr,
+        /// Folder into which fixtures are dumped, one sub-folder per type
+        #[arg(long, default_value = "src/lotus_json/fixtures")]
+        out_dir: PathBuf,
+    },
 }
 
 impl ApiCommands {
@@ -366,6 +377,13 @@ impl ApiCommands {
                     println!();
                 }
             }
+            Self::DumpLotusJsonFixtures {
+                lotus: UrlFromMultiAddr(lotus),
+                out_dir,
+            } => {
+                let lotus = rpc::Client::from_url(lotus);
+                lotus_json_fixtures::dump(&lotus, &out_dir).await?;
+            }
         }
         Ok(())
     }
@@ -10,14 +10,15 @@ use crate::{
     chain_sync::network_context::SyncNetworkContext,
     daemon::db_util::load_all_forest_cars,
     db::{
-        CAR_DB_DIR_NAME, EthMappingsStore, HeaviestTipsetKeyProvider, IndicesStore, MemoryDB,
-        SettingsStore, SettingsStoreExt, db_engine::open_db, parity_db::ParityDb,
+        CAR_DB_DIR_NAME, ColumnStats, EthMappingsStore, HeaviestTipsetKeyProvider, IndicesStore,
+        MemoryDB, SettingsStore, SettingsStoreExt, db_engine::open_db, parity_db::ParityDb,
     },
     genesis::read_genesis_header,
     libp2p::{NetworkMessage, PeerManager},
     libp2p_bitswap::{BitswapStoreRead, BitswapStoreReadWrite, Block64},
     message_pool::{MessagePool, MpoolRpcProvider},
     networks::ChainConfig,
+    rpc::UnknownFields,
     shim::address::CurrentNetwork,
     state_manager::StateManager,
 };
@@ -44,7 +45,11 @@ pub async fn run_test_with_dump(
     macro_rules! run_test {
         ($ty:ty) => {
             if test_dump.request.method_name.as_ref() == <$ty>::NAME {
-                let params = <$ty>::parse_params(params_raw.clone(), ParamStructure::Either)?;
+                let params = <$ty>::parse_params(
+                    params_raw.clone(),
+                    ParamStructure::Either,
+                    UnknownFields::Ignore,
+                )?;
                 let result = <$ty>::handle(ctx.clone(), params).await?;
                 anyhow::ensure!(
                     allow_response_mismatch
@@ -66,7 +71,7 @@ pub(super) fn load_db(
     let db_writer = open_db(db_root.into(), &Default::default())?;
     let db = ManyCar::new(db_writer);
     let forest_car_db_dir = db_root.join(CAR_DB_DIR_NAME);
-    load_all_forest_cars(&db, &forest_car_db_dir)?;
+    load_all_forest_cars(&db, &forest_car_db_dir, false)?;
     Ok(Arc::new(ReadOpsTrackingStore::new(db)))
 }
 
@@ -291,9 +296,17 @@ impl<T: EthMappingsStore> EthMappingsStore for ReadOpsTrackingStore<T> {
         self.inner.get_message_cids()
     }
 
+    fn get_tipset_keys(&self) -> anyhow::Result<Vec<TipsetKey>> {
+        self.inner.get_tipset_keys()
+    }
+
     fn delete(&self, keys: Vec<EthHash>) -> anyhow::Result<()> {
         self.inner.delete(keys)
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        EthMappingsStore::stats(&self.inner)
+    }
 }
 
 impl<T: IndicesStore> IndicesStore for ReadOpsTrackingStore<T> {
@@ -312,4 +325,8 @@ impl<T: IndicesStore> IndicesStore for ReadOpsTrackingStore<T> {
     fn exists(&self, key: &Cid) -> anyhow::Result<bool> {
         self.inner.exists(key)
     }
+
+    fn stats(&self) -> anyhow::Result<ColumnStats> {
+        IndicesStore::stats(&self.inner)
+    }
 }
@@ -0,0 +1,71 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Captures payloads from a Lotus node as fixtures for the lotus JSON round-trip
+//! tests in `src/lotus_json/fixtures.rs`.
+//!
+//! Responses are saved as-is, without going through Forest's types.
+
+use crate::blocks::Tipset;
+use crate::lotus_json::LotusJson;
+use crate::rpc::{self, prelude::*, types::ApiTipsetKey};
+use anyhow::Context as _;
+use serde_json::Value;
+use std::path::Path;
+
+/// Saves the chain head, the first of its parent messages and receipts, and the
+/// actor state of its first miner, under `out_dir/<kind>/`.
+pub async fn dump(client: &rpc::Client, out_dir: &Path) -> anyhow::Result<()> {
+    let head = client
+        .call(ChainHead::request(())?.map_ty::<Value>())
+        .await?;
+    let tipset = serde_json::from_value::<LotusJson<Tipset>>(head.clone())
+        .context("couldn't parse the chain head")?
+        .into_inner();
+    let block = tipset.block_headers().first();
+    let height = tipset.epoch();
+    save(out_dir, "tipset", &height.to_string(), &head)?;
+
+    let messages = client
+        .call(ChainGetParentMessages::request((*block.cid(),))?.map_ty::<Value>())
+        .await?;
+    match messages.get(0) {
+        Some(message) => save(
+            out_dir,
+            "message",
+            &format!("{height}-0"),
+            message.get("Message").context("no message")?,
+        )?,
+        None => println!("No parent messages at height {height}, skipping messages"),
+    }
+
+    let receipts = client
+        .call(ChainGetParentReceipts::request((*block.cid(),))?.map_ty::<Value>())
+        .await?;
+    match receipts.get(0) {
+        Some(receipt) => save(out_dir, "receipt", &format!("{height}-0"), receipt)?,
+        None => println!("No parent receipts at height {height}, skipping receipts"),
+    }
+
+    let miner = block.miner_address;
+    let actor = client
+        .call(
+            StateGetActor::request((miner, ApiTipsetKey(Some(tipset.key().clone()))))?
+                .map_ty::<Value>(),
+        )
+        .await?;
+    match actor {
+        Value::Null => println!("No actor for {miner}, skipping actor states"),
+        actor => save(out_dir, "actor_state", &miner.to_string(), &actor)?,
+    }
+    Ok(())
+}
+
+fn save(out_dir: &Path, kind: &str, name: &str, payload: &Value) -> anyhow::Result<()> {
+    let dir = out_dir.join(kind);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(name).with_extension("json");
+    std::fs::write(&path, serde_json::to_string_pretty(payload)? + "\n")?;
+    println!("Saved {}", path.display());
+    Ok(())
+}
@@ -16,7 +16,7 @@ use crate::{
     message_pool::{MessagePool, MpoolRpcProvider},
     networks::{ChainConfig, NetworkChain},
     rpc::{
-        RPCState, RpcMethod, RpcMethodExt as _,
+        RPCState, RpcMethod, RpcMethodExt as _, UnknownFields,
         eth::{filter::EthEventHandler, types::EthHash},
     },
     shim::address::{CurrentNetwork, Network},
@@ -102,7 +102,11 @@ pub async fn run_test_from_snapshot(path: &Path) -> anyhow::Result<()> {
     macro_rules! run_test {
         ($ty:ty) => {
             if method_name.as_str() == <$ty>::NAME {
-                let params = <$ty>::parse_params(params_raw.clone(), ParamStructure::Either)?;
+                let params = <$ty>::parse_params(
+                    params_raw.clone(),
+                    ParamStructure::Either,
+                    UnknownFields::Ignore,
+                )?;
                 let result = <$ty>::handle(ctx.clone(), params)
                     .await
                     .map(|r| r.into_lotus_json())
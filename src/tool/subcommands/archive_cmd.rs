@@ -33,6 +33,7 @@ use crate::chain::{
 };
 use crate::cid_collections::CidHashSet;
 use crate::cli_shared::{snapshot, snapshot::TrustedVendor};
+use crate::db::CachingStore;
 use crate::db::car::{AnyCar, ManyCar};
 use crate::interpreter::VMTrace;
 use crate::ipld::{stream_graph, unordered_stream_graph};
@@ -51,7 +52,9 @@ use futures::TryStreamExt;
 use fvm_ipld_blockstore::Blockstore;
 use indicatif::ProgressIterator;
 use itertools::Itertools;
+use nonzero_ext::nonzero;
 use sha2::Sha256;
+use std::num::NonZeroUsize;
 use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -131,6 +134,16 @@ pub enum ArchiveCommands {
         /// Overwrite output file without prompting.
         #[arg(long, default_value_t = false)]
         force: bool,
+        /// Fail if the snapshot archives have different CAR versions.
+        #[arg(long, default_value_t = false)]
+        strict_versions: bool,
+    },
+    /// Truncate an uncompressed archive (CAR) after its last complete block.
+    /// Useful for recovering snapshots whose download or export was
+    /// interrupted.
+    Repair {
+        /// Path to an uncompressed archive (CAR). It is modified in place.
+        snapshot: PathBuf,
     },
     /// Show the difference between the canonical and computed state of a
     /// tipset.
@@ -207,7 +220,13 @@ impl ArchiveCommands {
                 snapshot_files,
                 output_path,
                 force,
-            } => merge_snapshots(snapshot_files, output_path, force).await,
+                strict_versions,
+            } => merge_snapshots(snapshot_files, output_path, force, strict_versions).await,
+            Self::Repair { snapshot } => {
+                let len = crate::db::car::repair_truncated(&snapshot)?;
+                println!("{} is {len} bytes long", snapshot.display());
+                Ok(())
+            }
             Self::Diff {
                 snapshot_files,
                 epoch,
@@ -523,10 +542,18 @@ async fn merge_snapshots(
     snapshot_files: Vec<PathBuf>,
     output_path: PathBuf,
     force: bool,
+    strict_versions: bool,
 ) -> anyhow::Result<()> {
     use crate::db::car::forest;
 
-    let store = ManyCar::try_from(snapshot_files)?;
+    let store = match strict_versions {
+        true => ManyCar::default().with_strict_versions(),
+        false => ManyCar::default(),
+    }
+    .with_read_only_files(snapshot_files.into_iter())?;
+    for (path, version) in store.versions() {
+        info!("{} is a CARv{version}", path.display());
+    }
     let heaviest_tipset = store.heaviest_tipset()?;
     let roots = heaviest_tipset.key().to_cids();
 
@@ -567,6 +594,10 @@ async fn merge_snapshots(
     Ok(())
 }
 
+/// The number of blocks cached while computing the state of a tipset, see
+/// [`show_tipset_diff`].
+const STATE_DIFF_BLOCK_CACHE_CAPACITY: NonZeroUsize = nonzero!(100_000usize);
+
 /// Compute the tree of actor states for a given epoch and compare it to the
 /// expected result (as encoded in the blockchain). Differences are printed
 /// using the diff format (red for the blockchain state, green for the computed
@@ -578,9 +609,13 @@ async fn show_tipset_diff(
 ) -> anyhow::Result<()> {
     use colored::*;
 
-    let store = Arc::new(ManyCar::try_from(snapshot_files)?);
-
+    let store = ManyCar::try_from(snapshot_files)?;
     let heaviest_tipset = Arc::new(store.heaviest_tipset()?);
+    // Blocks of plain CARs are otherwise read from disk on every access
+    let store = Arc::new(CachingStore::with_capacity(
+        store,
+        STATE_DIFF_BLOCK_CACHE_CAPACITY,
+    ));
     if heaviest_tipset.epoch() <= epoch {
         anyhow::bail!(
             "Highest epoch must be at least 1 greater than the target epoch. \
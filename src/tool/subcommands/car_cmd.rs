@@ -3,6 +3,7 @@
 
 use std::path::{Path, PathBuf};
 
+use anyhow::Context as _;
 use clap::Subcommand;
 use futures::{StreamExt, TryStreamExt};
 use fvm_ipld_blockstore::Blockstore;
@@ -14,11 +15,13 @@ use tokio::{
     io::{AsyncWriteExt, BufReader},
 };
 
-use crate::db::car::ForestCar;
+use crate::db::car::plain::{HashMismatch, external_index_path};
+use crate::db::car::{ForestCar, PlainCar};
 use crate::utils::db::{
     car_stream::CarStream,
     car_util::{dedup_block_stream, merge_car_streams},
 };
+use crate::utils::io::EitherMmapOrRandomAccessFile;
 
 #[derive(Debug, Subcommand)]
 pub enum CarCommands {
@@ -30,6 +33,83 @@ pub enum CarCommands {
         /// The output `.forest.car.zst` file path
         #[arg(short, long)]
         output: PathBuf,
+        /// Copy the compressed blocks of two `.forest.car.zst` files as they are, instead of
+        /// recompressing them. The second file must descend from the first, e.g. a diff
+        /// snapshot and the snapshot it is based on, and its roots are kept.
+        #[arg(long)]
+        raw: bool,
+    },
+    /// Compare the blocks of two uncompressed CAR archives, e.g. to check that an incremental
+    /// snapshot is a superset of its base snapshot
+    Diff {
+        /// Uncompressed CAR archive
+        a: PathBuf,
+        /// Uncompressed CAR archive
+        b: PathBuf,
+        /// Fail unless every block of `B` is also in `A`, with the same data
+        #[arg(long)]
+        expect_superset: bool,
+    },
+    /// Write the blocks of an uncompressed CAR archive that have a given IPLD codec to a
+    /// `.forest.car.zst`, e.g. to process only the `DAG-CBOR` blocks of a snapshot. The
+    /// roots are kept.
+    Filter {
+        /// Uncompressed CAR archive
+        car_file: PathBuf,
+        /// The IPLD codec of the blocks to keep, e.g. `113` (`0x71`) for `DAG-CBOR` or `85`
+        /// (`0x55`) for raw blocks
+        #[arg(long)]
+        codec: u64,
+        /// The output `.forest.car.zst` file path
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Show how many blocks of an uncompressed CAR archive fall in each power-of-two size
+    /// range, in bytes. No block data is read.
+    Histogram {
+        /// Uncompressed CAR archive
+        car_file: PathBuf,
+    },
+    /// Write the block index of an uncompressed CAR archive to a sidecar file, so that
+    /// other `forest-tool car` subcommands don't have to scan the archive.
+    Index {
+        /// Uncompressed CAR archive
+        car_file: PathBuf,
+        /// The output index file path. Defaults to `<CAR_FILE>.caridx`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Recompress a CAR archive in place at another zstd level, e.g. for archival storage
+    /// of a snapshot that was imported at a fast level. The archive is rewritten as a
+    /// `.forest.car.zst`, with the same roots and block order.
+    Recompress {
+        /// CAR archive. Supported extensions: `.car`, `.car.zst`, `.forest.car.zst`
+        car_file: PathBuf,
+        /// The zstd compression level
+        #[arg(long)]
+        level: u16,
+    },
+    /// Split a CAR archive into `.forest.car.zst` shards of bounded size. Each shard has the
+    /// roots of the archive and can be loaded on its own.
+    Shard {
+        /// CAR archive. Supported extensions: `.car`, `.car.zst`, `.forest.car.zst`
+        car_file: PathBuf,
+        /// The directory to write `shard-NNNN.forest.car.zst` files to
+        #[arg(short, long)]
+        output_dir: PathBuf,
+        /// The maximum size of a shard, in bytes
+        #[arg(long)]
+        max_bytes: u64,
+    },
+    /// Check, in parallel, that every block of an uncompressed CAR archive hashes to its
+    /// CID. Faster than `forest-tool car validate` on multi-core machines.
+    Verify {
+        /// Uncompressed CAR archive
+        car_file: PathBuf,
+        /// Also check that the archive is deterministic, i.e., holds exactly the blocks
+        /// reachable from its single root, in depth-first order, as Filecoin exports do
+        #[arg(long)]
+        deterministic: bool,
     },
     /// Check the validity of a CAR archive. For Filecoin-specific checks, see
     /// `forest-tool snapshot validate`.
@@ -48,7 +128,20 @@ pub enum CarCommands {
 impl CarCommands {
     pub async fn run(self) -> anyhow::Result<()> {
         match self {
-            Self::Concat { car_files, output } => {
+            Self::Concat {
+                car_files,
+                output,
+                raw: true,
+            } => {
+                let [base, addition] = <[PathBuf; 2]>::try_from(car_files)
+                    .map_err(|_| anyhow::anyhow!("--raw takes exactly two CAR files"))?;
+                crate::db::car::concat_raw(base, addition, output).await?;
+            }
+            Self::Concat {
+                car_files,
+                output,
+                raw: false,
+            } => {
                 let car_streams: Vec<_> = futures::stream::iter(car_files)
                     .then(tokio::fs::File::open)
                     .map_ok(tokio::io::BufReader::new)
@@ -73,6 +166,108 @@ impl CarCommands {
                 crate::db::car::forest::Encoder::write(&mut writer, all_roots, frames).await?;
                 writer.flush().await?;
             }
+            Self::Diff {
+                a,
+                b,
+                expect_superset,
+            } => {
+                let diff = crate::db::car::diff(&open_plain_car(&a)?, &open_plain_car(&b)?)?;
+                if diff.is_empty() {
+                    println!("The archives have the same blocks");
+                }
+                for (label, cids) in [
+                    ("Only in A", &diff.only_in_a),
+                    ("Only in B", &diff.only_in_b),
+                    ("Conflicting", &diff.conflicting),
+                ] {
+                    if !cids.is_empty() {
+                        println!("{label}: {}", cids.len());
+                        for cid in cids {
+                            println!("  {cid}");
+                        }
+                    }
+                }
+                anyhow::ensure!(
+                    !expect_superset || diff.a_is_superset(),
+                    "{} is not a superset of {}",
+                    a.display(),
+                    b.display()
+                );
+            }
+            Self::Filter {
+                car_file,
+                codec,
+                output,
+            } => {
+                let car = open_plain_car(&car_file)?;
+                let frames = crate::db::car::forest::Encoder::compress_stream_default(
+                    futures::stream::iter(car.blocks_by_codec(codec)).map_err(anyhow::Error::from),
+                );
+                let mut writer = tokio::io::BufWriter::new(tokio::fs::File::create(&output).await?);
+                crate::db::car::forest::Encoder::write(&mut writer, car.roots().clone(), frames)
+                    .await?;
+                writer.flush().await?;
+            }
+            Self::Histogram { car_file } => {
+                for (range, count) in open_plain_car(&car_file)?.block_size_histogram() {
+                    println!("{range}: {count}");
+                }
+            }
+            Self::Index { car_file, output } => {
+                let output = output.unwrap_or_else(|| external_index_path(&car_file));
+                index_plain_car(&car_file)?.export_index(&output)?;
+                println!(
+                    "Wrote the index of {} to {}",
+                    car_file.display(),
+                    output.display()
+                );
+            }
+            Self::Recompress { car_file, level } => {
+                crate::db::car::recompress_in_place(car_file, level).await?
+            }
+            Self::Shard {
+                car_file,
+                output_dir,
+                max_bytes,
+            } => {
+                for shard in crate::db::car::shard(car_file, output_dir, max_bytes).await? {
+                    println!(
+                        "{}: {} blocks, {}",
+                        shard.path.display(),
+                        shard.num_blocks,
+                        human_bytes::human_bytes(shard.len as f64)
+                    );
+                }
+            }
+            Self::Verify {
+                car_file,
+                deterministic,
+            } => {
+                let car = open_plain_car(&car_file)?;
+                let report = car.verify_all_hashes()?;
+                for HashMismatch {
+                    cid,
+                    offset,
+                    actual,
+                } in &report.mismatches
+                {
+                    match actual {
+                        Some(actual) => println!("{cid} at offset {offset} hashes to {actual}"),
+                        None => println!("{cid} at offset {offset} uses an unsupported hash"),
+                    }
+                }
+                println!("{} blocks verified", report.verified);
+                anyhow::ensure!(
+                    report.mismatches.is_empty(),
+                    "{} blocks don't match their CID",
+                    report.mismatches.len()
+                );
+                anyhow::ensure!(
+                    !deterministic || car.is_deterministic()?,
+                    "{} is not deterministic",
+                    car_file.display()
+                );
+            }
             Self::Validate {
                 car_file,
                 ignore_block_validity,
@@ -83,6 +278,40 @@ impl CarCommands {
     }
 }
 
+/// Open the uncompressed CAR archive at `path`, with the index written by
+/// `forest-tool car index` if there is one. Otherwise, index the archive, showing
+/// the progress.
+fn open_plain_car(path: &Path) -> anyhow::Result<PlainCar<EitherMmapOrRandomAccessFile>> {
+    let index_path = external_index_path(path);
+    if index_path.exists() {
+        match PlainCar::new_with_external_index(
+            EitherMmapOrRandomAccessFile::open(path)?,
+            &index_path,
+        ) {
+            Ok(car) => return Ok(car),
+            Err(e) => tracing::warn!("ignoring {}: {e}", index_path.display()),
+        }
+    }
+    index_plain_car(path)
+}
+
+/// Index the uncompressed CAR archive at `path`, showing the progress.
+fn index_plain_car(path: &Path) -> anyhow::Result<PlainCar<EitherMmapOrRandomAccessFile>> {
+    let pb = ProgressBar::new(0).with_style(
+        ProgressStyle::with_template("Indexing {bar} {percent}%, eta: {eta}").expect("infallible"),
+    );
+    let car = PlainCar::new_with_progress(
+        EitherMmapOrRandomAccessFile::open(path)?,
+        |processed, total| {
+            pb.set_length(total);
+            pb.set_position(processed);
+        },
+    )
+    .with_context(|| format!("{} is not a valid CAR", path.display()))?;
+    pb.finish_and_clear();
+    Ok(car)
+}
+
 /// At present, three properties are checked:
 /// - The CAR file is syntactically valid and all blocks can be streamed.
 /// - Each block CID is checked against the hash of the block.
@@ -124,7 +353,7 @@ async fn validate(
 
 #[cfg(test)]
 mod tests {
-    use super::validate;
+    use super::{external_index_path, index_plain_car, open_plain_car, validate};
     use crate::db::car::forest;
     use crate::networks::{calibnet, mainnet};
     use crate::utils::db::car_stream::CarBlock;
@@ -259,4 +488,31 @@ mod tests {
         // Ignoring index validity should make the test pass.
         assert!(validate(&temp_path, false, true).await.is_ok());
     }
+
+    #[test]
+    fn open_plain_car_with_external_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let car_path = dir.path().join("chain4.car");
+        std::fs::write(
+            &car_path,
+            include_bytes!("../../../test-snapshots/chain4.car"),
+        )
+        .unwrap();
+        let mut cids = index_plain_car(&car_path).unwrap().cids();
+        cids.sort();
+
+        // A stale index is ignored
+        std::fs::write(external_index_path(&car_path), b"stale").unwrap();
+        let mut opened = open_plain_car(&car_path).unwrap().cids();
+        opened.sort();
+        assert_eq!(opened, cids);
+
+        index_plain_car(&car_path)
+            .unwrap()
+            .export_index(external_index_path(&car_path))
+            .unwrap();
+        let mut opened = open_plain_car(&car_path).unwrap().cids();
+        opened.sort();
+        assert_eq!(opened, cids);
+    }
 }
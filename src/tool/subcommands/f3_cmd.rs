@@ -0,0 +1,81 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+};
+
+use anyhow::Context as _;
+use clap::Subcommand;
+
+use crate::cli_shared::{cli::Config, read_config};
+use crate::networks::NetworkChain;
+
+#[derive(Debug, Subcommand)]
+pub enum F3Commands {
+    /// Export the F3 finality certificates of a stopped node to a portable archive
+    ExportCertificates {
+        /// Path to the archive to write
+        output: PathBuf,
+        /// Optional TOML file containing forest daemon configuration
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+        /// Optional chain, will override the chain section of configuration file if used
+        #[arg(long)]
+        chain: Option<NetworkChain>,
+    },
+    /// Import the F3 finality certificates of an archive into a stopped node, skipping the ones it already has
+    ImportCertificates {
+        /// Path to the archive to read
+        input: PathBuf,
+        /// Optional TOML file containing forest daemon configuration
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+        /// Optional chain, will override the chain section of configuration file if used
+        #[arg(long)]
+        chain: Option<NetworkChain>,
+    },
+}
+
+impl F3Commands {
+    pub fn run(self) -> anyhow::Result<()> {
+        match self {
+            Self::ExportCertificates {
+                output,
+                config,
+                chain,
+            } => {
+                let (_, config) = read_config(config.as_ref(), chain)?;
+                let file = File::create(&output)
+                    .with_context(|| format!("failed to create {}", output.display()))?;
+                crate::f3::export_certificates(
+                    &f3_root(&config),
+                    config.chain().genesis_name().as_ref(),
+                    BufWriter::new(file),
+                )?;
+                Ok(())
+            }
+            Self::ImportCertificates {
+                input,
+                config,
+                chain,
+            } => {
+                let (_, config) = read_config(config.as_ref(), chain)?;
+                let file = File::open(&input)
+                    .with_context(|| format!("failed to open {}", input.display()))?;
+                crate::f3::import_certificates(
+                    &f3_root(&config),
+                    config.chain().genesis_name().as_ref(),
+                    BufReader::new(file),
+                )?;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn f3_root(config: &Config) -> PathBuf {
+    crate::f3::get_f3_root(&config.f3, &config.client.data_dir, config.chain())
+}
@@ -1,18 +1,21 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::{path::PathBuf, sync::Arc};
+use std::{num::NonZeroUsize, path::PathBuf, sync::Arc};
 
+use anyhow::Context as _;
 use clap::Subcommand;
 
+use crate::blocks::Tipset;
 use crate::chain::ChainStore;
 use crate::chain::index::ResolveNullTipset;
 use crate::cli_shared::{chain_path, read_config};
-use crate::daemon::db_util::backfill_db;
 use crate::daemon::db_util::load_all_forest_cars;
-use crate::db::CAR_DB_DIR_NAME;
+use crate::daemon::db_util::{backfill_db, default_backfill_concurrency};
 use crate::db::car::ManyCar;
 use crate::db::db_engine::{db_root, open_db};
+use crate::db::ttl::{DEFAULT_PRUNE_BATCH_SIZE, PruneReport, prune_tipset_mappings};
+use crate::db::{CAR_DB_DIR_NAME, HeaviestTipsetKeyProvider as _};
 use crate::genesis::read_genesis_header;
 use crate::networks::NetworkChain;
 use crate::shim::clock::ChainEpoch;
@@ -35,6 +38,25 @@ pub enum IndexCommands {
         /// The ending tipset epoch for back-filling (inclusive)
         #[arg(long)]
         to: ChainEpoch,
+        /// Maximum number of tipset states computed concurrently, defaults to the number of CPUs
+        #[arg(long)]
+        max_concurrency: Option<NonZeroUsize>,
+    },
+    /// Prune the Ethereum tipset mappings older than the retention window
+    PruneEthMappings {
+        /// Optional TOML file containing forest daemon configuration
+        #[arg(short, long)]
+        config: Option<PathBuf>,
+        /// Optional chain, will override the chain section of configuration file if used
+        #[arg(long)]
+        chain: Option<NetworkChain>,
+        /// Number of epochs of tipset mappings to keep before the chain head, defaults to
+        /// `chain_indexer.eth_mappings_retention_epochs`
+        #[arg(long)]
+        retention_epochs: Option<ChainEpoch>,
+        /// Number of mappings deleted at once
+        #[arg(long, default_value_t = DEFAULT_PRUNE_BATCH_SIZE)]
+        batch_size: NonZeroUsize,
     },
 }
 
@@ -46,6 +68,7 @@ impl IndexCommands {
                 chain,
                 from,
                 to,
+                max_concurrency,
             } => {
                 let (_, config) = read_config(config.as_ref(), chain.clone())?;
 
@@ -56,7 +79,7 @@ impl IndexCommands {
                 let db = Arc::new(ManyCar::new(db_writer.clone()));
                 let forest_car_db_dir = db_root_dir.join(CAR_DB_DIR_NAME);
 
-                load_all_forest_cars(&db, &forest_car_db_dir)?;
+                load_all_forest_cars(&db, &forest_car_db_dir, false)?;
 
                 let chain_config = Arc::new(handle_chain_config(&config.chain)?);
                 let genesis_header = read_genesis_header(
@@ -94,7 +117,51 @@ impl IndexCommands {
                     head_ts
                 };
 
-                backfill_db(&state_manager, &from_ts, *to).await?;
+                backfill_db(
+                    &state_manager,
+                    &from_ts,
+                    *to,
+                    max_concurrency.unwrap_or_else(default_backfill_concurrency),
+                )
+                .await?;
+
+                Ok(())
+            }
+            Self::PruneEthMappings {
+                config,
+                chain,
+                retention_epochs,
+                batch_size,
+            } => {
+                let (_, config) = read_config(config.as_ref(), chain.clone())?;
+                let retention_epochs = retention_epochs
+                    .or(config
+                        .chain_indexer
+                        .eth_mappings_retention_epochs
+                        .map(ChainEpoch::from))
+                    .context(
+                        "no retention window, set --retention-epochs or chain_indexer.eth_mappings_retention_epochs",
+                    )?;
+
+                let db_root_dir = db_root(&chain_path(&config))?;
+                let db_writer = Arc::new(open_db(db_root_dir.clone(), config.db_config())?);
+                let db = ManyCar::new(db_writer.clone());
+                load_all_forest_cars(&db, &db_root_dir.join(CAR_DB_DIR_NAME), false)?;
+                let head_ts = Tipset::load_required(&db, &db_writer.heaviest_tipset_key()?)?;
+
+                println!("Database path:    {}", db_root_dir.display());
+                println!("Head epoch:       {}", head_ts.epoch());
+                println!("Retention epochs: {retention_epochs}");
+
+                let PruneReport {
+                    kept,
+                    pruned,
+                    unresolved,
+                } = prune_tipset_mappings(&db, head_ts.epoch(), retention_epochs, *batch_size)?;
+                println!("Kept {kept} tipset mappings, pruned {pruned}");
+                if unresolved > 0 {
+                    println!("{unresolved} of the pruned mappings had no tipset in the database");
+                }
 
                 Ok(())
             }
@@ -7,6 +7,7 @@ mod backup_cmd;
 mod benchmark_cmd;
 mod car_cmd;
 mod db_cmd;
+mod f3_cmd;
 mod fetch_params_cmd;
 mod index_cmd;
 mod net_cmd;
@@ -61,6 +62,10 @@ pub enum Subcommand {
     #[command(subcommand)]
     DB(db_cmd::DBCommands),
 
+    /// F3 finality certificate management
+    #[command(subcommand)]
+    F3(f3_cmd::F3Commands),
+
     /// Index database management
     #[command(subcommand)]
     Index(index_cmd::IndexCommands),
@@ -1,6 +1,7 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use crate::cid_collections::CidHashSet;
 use crate::db::car::plain::read_v2_header;
 use crate::utils::multihash::prelude::*;
 use async_compression::tokio::bufread::ZstdDecoder;
@@ -25,7 +26,7 @@ use tokio_util::codec::FramedRead;
 use tokio_util::either::Either;
 use unsigned_varint::codec::UviBytes;
 
-use crate::utils::encoding::from_slice_with_fallback;
+use crate::utils::encoding::{extract_cids, from_slice_with_fallback};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CarV1Header {
@@ -45,6 +46,22 @@ pub struct CarV2Header {
     pub index_offset: i64,
 }
 
+impl CarV2Header {
+    /// Where the index starts, or [`None`] if there's no index, which the spec
+    /// signals with an `index_offset` of zero.
+    pub fn index_position(&self) -> Option<u64> {
+        u64::try_from(self.index_offset)
+            .ok()
+            .filter(|offset| *offset != 0)
+    }
+
+    /// Whether the index lists every block, which the spec signals with the
+    /// leftmost bit of the characteristics.
+    pub fn is_fully_indexed(&self) -> bool {
+        self.characteristics[0] & 0x80 != 0
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct CarBlock {
     pub cid: Cid,
@@ -103,6 +120,65 @@ pin_project! {
         pub header_v1: CarV1Header,
         pub header_v2: Option<CarV2Header>,
         first_block: Option<CarBlock>,
+        order: Option<DfsOrder>,
+    }
+}
+
+/// Checks that blocks arrive in first-seen order of a depth-first traversal from the
+/// roots, following the links of each `DAG_CBOR` block in encoding order, see
+/// [`CarStream::with_strict_order`].
+struct DfsOrder {
+    /// Links that are still to be visited, the next one last.
+    pending: Vec<Cid>,
+    seen: CidHashSet,
+    /// Links that were passed over because the block did not come next, i.e. that must
+    /// not be in the CAR.
+    skipped: CidHashSet,
+}
+
+impl DfsOrder {
+    fn new(roots: &NonEmpty<Cid>) -> Self {
+        Self {
+            pending: roots.iter().rev().copied().collect(),
+            seen: CidHashSet::new(),
+            skipped: CidHashSet::new(),
+        }
+    }
+
+    fn check(&mut self, block: &CarBlock) -> io::Result<()> {
+        let out_of_order = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("block {} is out of first-seen depth-first order", block.cid),
+            )
+        };
+        if !self.seen.insert(block.cid) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("duplicate block {}", block.cid),
+            ));
+        }
+        if self.skipped.contains(&block.cid) {
+            return Err(out_of_order());
+        }
+        // Links to blocks that are not in the CAR are skipped over.
+        loop {
+            match self.pending.pop() {
+                Some(cid) if cid == block.cid => break,
+                Some(cid) => {
+                    if !self.seen.contains(&cid) {
+                        self.skipped.insert(cid);
+                    }
+                }
+                None => return Err(out_of_order()),
+            }
+        }
+        if block.cid.codec() == fvm_ipld_encoding::DAG_CBOR {
+            let links = extract_cids(&block.data)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.pending.extend(links.into_iter().rev());
+        }
+        Ok(())
     }
 }
 
@@ -174,6 +250,7 @@ impl<ReaderT: AsyncBufRead + Unpin> CarStream<ReaderT> {
                 header_v1,
                 header_v2,
                 first_block: Some(block),
+                order: None,
             })
         } else {
             Ok(CarStream {
@@ -181,6 +258,7 @@ impl<ReaderT: AsyncBufRead + Unpin> CarStream<ReaderT> {
                 header_v1,
                 header_v2,
                 first_block: None,
+                order: None,
             })
         }
     }
@@ -239,16 +317,36 @@ impl<ReaderT: AsyncBufRead + AsyncSeek + Unpin> CarStream<ReaderT> {
     }
 }
 
+impl<ReaderT> CarStream<ReaderT> {
+    /// Fails the stream on the first block that is not in first-seen order of a
+    /// depth-first traversal from the roots, as in a
+    /// [filecoin-deterministic](https://ipld.io/specs/transport/car/carv1/#determinism)
+    /// CAR, instead of accepting any order. Duplicate blocks are rejected too.
+    ///
+    /// Note that this remembers the [`Cid`] of every block.
+    pub fn with_strict_order(mut self) -> Self {
+        self.order = Some(DfsOrder::new(&self.header_v1.roots));
+        self
+    }
+}
+
 impl<ReaderT: AsyncBufRead> Stream for CarStream<ReaderT> {
     type Item = io::Result<CarBlock>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
-        if let Some(block) = this.first_block.take() {
-            return Poll::Ready(Some(Ok(block)));
-        }
-        let item = futures::ready!(this.reader.poll_next(cx));
-        Poll::Ready(item.map(|ret| ret.and_then(CarBlock::from_bytes)))
+        let item = match this.first_block.take() {
+            Some(block) => Some(Ok(block)),
+            None => futures::ready!(this.reader.poll_next(cx))
+                .map(|ret| ret.and_then(CarBlock::from_bytes)),
+        };
+        Poll::Ready(item.map(|ret| {
+            let block = ret?;
+            if let Some(order) = this.order {
+                order.check(&block)?;
+            }
+            Ok(block)
+        }))
     }
 }
 
@@ -338,6 +436,94 @@ mod tests {
         }
     }
 
+    /// A CARv1 with `roots`, and `blocks` in the given order.
+    fn car(roots: &[Cid], blocks: &[&CarBlock]) -> Vec<u8> {
+        let header = to_vec(&CarV1Header {
+            roots: NonEmpty::new(roots.to_vec()).unwrap(),
+            version: 1,
+        })
+        .unwrap();
+        let mut car = header.len().encode_var_vec();
+        car.extend(header);
+        for block in blocks {
+            block.write(&mut car).unwrap();
+        }
+        car
+    }
+
+    #[tokio::test]
+    async fn stream_strict_order() {
+        let block = |codec, data: Vec<u8>| CarBlock {
+            cid: Cid::new_v1(codec, MultihashCode::Blake2b256.digest(&data)),
+            data,
+        };
+        let node = |links: &[Cid]| block(fvm_ipld_encoding::DAG_CBOR, to_vec(links).unwrap());
+        let raw = |data: &[u8]| block(fvm_ipld_encoding::IPLD_RAW, data.to_vec());
+        let missing = raw(b"not in the CAR");
+        let leaf = raw(b"leaf");
+        let c = node(&[missing.cid]);
+        let a = node(&[c.cid, leaf.cid]);
+        let b = node(&[c.cid, missing.cid]);
+        let root = node(&[a.cid, b.cid]);
+
+        async fn stream(car: Vec<u8>) -> io::Result<Vec<CarBlock>> {
+            CarStream::new(Cursor::new(car))
+                .await?
+                .with_strict_order()
+                .try_collect()
+                .await
+        }
+
+        let canonical = [&root, &a, &c, &leaf, &b];
+        assert_eq!(
+            stream(car(&[root.cid], &canonical)).await.unwrap().len(),
+            canonical.len()
+        );
+
+        for (blocks, out_of_order) in [
+            // Breadth-first
+            (vec![&root, &a, &b, &c, &leaf], &c),
+            // Links visited in reverse
+            (vec![&root, &b, &c, &a, &leaf], &a),
+            // Unreachable
+            (vec![&root, &a, &c, &leaf, &b, &missing], &missing),
+            // Skipped over, then appears
+            (vec![&root, &a, &leaf, &c, &b], &c),
+        ] {
+            let error = stream(car(&[root.cid], &blocks)).await.unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "block {} is out of first-seen depth-first order",
+                    out_of_order.cid
+                )
+            );
+
+            // Any order is accepted otherwise
+            let accepted: Vec<CarBlock> = CarStream::new(Cursor::new(car(&[root.cid], &blocks)))
+                .await
+                .unwrap()
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(accepted.len(), blocks.len());
+        }
+
+        let error = stream(car(&[root.cid], &[&root, &a, &c, &c, &leaf, &b]))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), format!("duplicate block {}", c.cid));
+
+        // Roots are visited in order
+        stream(car(&[a.cid, b.cid], &[&a, &c, &leaf, &b]))
+            .await
+            .unwrap();
+        stream(car(&[b.cid, a.cid], &[&a, &c, &leaf, &b]))
+            .await
+            .unwrap_err();
+    }
+
     #[tokio::test]
     async fn stream_calibnet_genesis_unsafe() {
         let stream = CarStream::new_unsafe(calibnet::DEFAULT_GENESIS)
@@ -95,6 +95,13 @@ impl<S> WithProgress<S> {
         self.progress.item_type = ItemType::Bytes;
         self
     }
+
+    /// Starts the progress at `completed` items, e.g., when resuming a transfer.
+    pub fn with_completed(mut self, completed: u64) -> Self {
+        self.progress.completed_items = completed;
+        self.progress.last_logged_items = completed;
+        self
+    }
 }
 
 #[derive(Clone, Educe)]
@@ -278,4 +285,16 @@ mod tests {
             "test 102 / 1024, 9%, 10 items/s, elapsed time: 10s"
         );
     }
+
+    #[test]
+    fn test_progress_with_completed() {
+        let progress = WithProgress::wrap_sync_read_with_callback("test", (), 1024, None)
+            .bytes()
+            .with_completed(512)
+            .progress;
+        assert_eq!(
+            progress.msg(progress.start + Duration::from_secs(1)),
+            "test 512 B / 1 KiB, 50%, 0 B/s, elapsed time: 1s"
+        );
+    }
 }
@@ -1,7 +1,7 @@
 // Copyright 2019-2025 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 /// Get the value of an environment variable, or a default value if it is not set or cannot be
 /// parsed.
@@ -12,6 +12,130 @@ pub fn env_or_default<T: FromStr>(key: &str, default: T) -> T {
         .unwrap_or(default)
 }
 
+/// Get the value of an environment variable, or `default` if it is unset or empty. Unlike
+/// [`env_or_default`], an unparsable value is logged before falling back to `default`.
+pub fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    env_opt(name).unwrap_or(default)
+}
+
+/// Get the value of an environment variable, or [`None`] if it is unset or empty. An
+/// unparsable value is logged and ignored.
+pub fn env_opt<T: FromStr>(name: &str) -> Option<T> {
+    parse_env_value(name, std::env::var(name).ok(), |_| true, "a valid value")
+}
+
+/// Like [`env_opt`], but also ignores values rejected by `is_valid`, logging that `expected`
+/// (e.g. `"a positive integer"`) is expected.
+pub fn env_opt_if<T: FromStr>(
+    name: &str,
+    is_valid: impl FnOnce(&T) -> bool,
+    expected: &str,
+) -> Option<T> {
+    parse_env_value(name, std::env::var(name).ok(), is_valid, expected)
+}
+
+/// Parses `value`, the raw value of the environment variable `name`, the way [`env_opt_if`]
+/// does. This is for callers that read the environment themselves, e.g. to be testable.
+pub fn parse_env_value<T: FromStr>(
+    name: &str,
+    value: Option<String>,
+    is_valid: impl FnOnce(&T) -> bool,
+    expected: &str,
+) -> Option<T> {
+    try_parse_env_value(value, is_valid).unwrap_or_else(|value| {
+        tracing::warn!("Ignoring invalid {name} value {value:?}, {expected} is expected");
+        None
+    })
+}
+
+/// Parses `value` like [`parse_env_value`], without logging. An unset or empty value is
+/// `Ok(None)`, and an invalid one is returned as the error.
+pub fn try_parse_env_value<T: FromStr>(
+    value: Option<String>,
+    is_valid: impl FnOnce(&T) -> bool,
+) -> Result<Option<T>, String> {
+    let Some(value) = value.filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    match value.parse() {
+        Ok(parsed) if is_valid(&parsed) => Ok(Some(parsed)),
+        _ => Err(value),
+    }
+}
+
+/// Resolves a setting from `value`, the raw value of the environment variable `name`, then
+/// `config`, then `default`, logging when the environment variable takes effect. See
+/// [`parse_env_value`] and [`Provenanced::resolve`].
+pub fn env_or_config<T: FromStr + Display>(
+    name: &str,
+    value: Option<String>,
+    config: Option<T>,
+    default: T,
+) -> Provenanced<T> {
+    Provenanced::resolve(
+        parse_env_value(name, value, |_| true, "a valid value"),
+        config,
+        default,
+    )
+    .logged(name)
+}
+
+/// Where the value of a setting comes from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Provenance {
+    /// An environment variable.
+    Env,
+    /// The configuration file.
+    Config,
+    /// The built-in default, e.g. of the chain.
+    #[default]
+    Default,
+    /// Computed at runtime, e.g. from the chain state.
+    Derived,
+}
+
+/// The value of a setting, along with its [`Provenance`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenanced<T> {
+    pub value: T,
+    pub provenance: Provenance,
+}
+
+impl<T> Provenanced<T> {
+    pub fn new(value: T, provenance: Provenance) -> Self {
+        Self { value, provenance }
+    }
+
+    /// Picks `env`, then `config`, then `default`, in that order of precedence.
+    pub fn resolve(env: Option<T>, config: Option<T>, default: T) -> Self {
+        match (env, config) {
+            (Some(value), _) => Self::new(value, Provenance::Env),
+            (None, Some(value)) => Self::new(value, Provenance::Config),
+            (None, None) => Self::new(default, Provenance::Default),
+        }
+    }
+
+    /// Logs the value if it comes from the environment variable `name`.
+    pub fn logged(self, name: &str) -> Self
+    where
+        T: Display,
+    {
+        if self.provenance == Provenance::Env {
+            tracing::info!("Using {} set by {name}", self.value);
+        }
+        self
+    }
+}
+
+/// Whether `value` is one of `1`, `true`, `yes` or `_yes_`, case-insensitively.
+pub fn is_truthy(value: &str) -> bool {
+    matches!(
+        value.to_lowercase().as_str(),
+        "1" | "true" | "yes" | "_yes_"
+    )
+}
+
 /// Check if the given environment variable is set to truthy value.
 /// Returns false if not set.
 pub fn is_env_truthy(env: &str) -> bool {
@@ -21,9 +145,7 @@ pub fn is_env_truthy(env: &str) -> bool {
 /// Check if the given environment variable is set to truthy value.
 /// Returns None if not set.
 pub fn is_env_set_and_truthy(env: &str) -> Option<bool> {
-    std::env::var(env)
-        .ok()
-        .map(|var| matches!(var.to_lowercase().as_str(), "1" | "true" | "yes" | "_yes_"))
+    std::env::var(env).ok().map(|var| is_truthy(&var))
 }
 
 #[cfg(test)]
@@ -63,4 +185,76 @@ mod tests {
             assert_eq!(is_env_truthy("TEST_ENV"), *expected);
         }
     }
+
+    #[test]
+    fn test_env_or() {
+        unsafe {
+            std::env::set_var("TEST_ENV_OR", "42");
+            assert_eq!(env_or("TEST_ENV_OR", 0), 42);
+            assert_eq!(env_opt::<u64>("TEST_ENV_OR"), Some(42));
+
+            // invalid and empty values fall back to the default
+            std::env::set_var("TEST_ENV_OR", "forty-two");
+            assert_eq!(env_or("TEST_ENV_OR", 7), 7);
+            assert_eq!(env_opt::<u64>("TEST_ENV_OR"), None);
+            std::env::set_var("TEST_ENV_OR", "");
+            assert_eq!(env_or("TEST_ENV_OR", 7), 7);
+            assert_eq!(env_opt::<String>("TEST_ENV_OR"), None);
+
+            std::env::remove_var("TEST_ENV_OR");
+            assert_eq!(env_or("TEST_ENV_OR", 7), 7);
+        }
+    }
+
+    #[test]
+    fn test_parse_env_value() {
+        let positive = |value: &str| {
+            parse_env_value::<i64>("TEST", Some(value.into()), |&v| v > 0, "a positive integer")
+        };
+        assert_eq!(positive("5"), Some(5));
+        assert_eq!(positive("0"), None);
+        assert_eq!(positive("-5"), None);
+        assert_eq!(positive("1.5"), None);
+        assert_eq!(positive(""), None);
+        assert_eq!(
+            parse_env_value::<i64>("TEST", None, |_| true, "an integer"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_provenance() {
+        assert_eq!(
+            Provenanced::resolve(Some(1), Some(2), 3),
+            Provenanced::new(1, Provenance::Env)
+        );
+        assert_eq!(
+            Provenanced::resolve(None, Some(2), 3),
+            Provenanced::new(2, Provenance::Config)
+        );
+        assert_eq!(
+            Provenanced::resolve(None, None, 3),
+            Provenanced::new(3, Provenance::Default)
+        );
+
+        let env_or_config = |value: Option<&str>, config| {
+            env_or_config("TEST_ENV_OR_CONFIG", value.map(Into::into), config, 3)
+        };
+        assert_eq!(
+            env_or_config(Some("1"), Some(2)),
+            Provenanced::new(1, Provenance::Env)
+        );
+        assert_eq!(
+            env_or_config(Some("invalid"), Some(2)),
+            Provenanced::new(2, Provenance::Config)
+        );
+        assert_eq!(
+            env_or_config(Some(""), Some(2)),
+            Provenanced::new(2, Provenance::Config)
+        );
+        assert_eq!(
+            env_or_config(None, None),
+            Provenanced::new(3, Provenance::Default)
+        );
+    }
 }
@@ -12,6 +12,7 @@ pub mod prelude {
     pub use multihash_codetable::MultihashDigest as _;
 }
 
+use cid::Cid;
 use multihash_derive::{Hasher, MultihashDigest};
 
 /// Extends [`multihash_codetable::Code`] with `Identity`
@@ -176,6 +177,57 @@ impl MultihashCode {
     }
 }
 
+/// The multihash codes Forest expects in block CIDs. Defaults to
+/// [`MultihashCode::Sha2_256`], [`MultihashCode::Blake2b256`] and
+/// [`MultihashCode::Identity`], see [`MultihashAllowlist::with`] for accepting more.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultihashAllowlist(Vec<MultihashCode>);
+
+impl Default for MultihashAllowlist {
+    fn default() -> Self {
+        Self(vec![
+            MultihashCode::Sha2_256,
+            MultihashCode::Blake2b256,
+            MultihashCode::Identity,
+        ])
+    }
+}
+
+impl MultihashAllowlist {
+    /// Also accepts `code`, e.g. [`MultihashCode::Blake3_256`].
+    pub fn with(mut self, code: MultihashCode) -> Self {
+        if !self.0.contains(&code) {
+            self.0.push(code);
+        }
+        self
+    }
+
+    pub fn contains(&self, code: u64) -> bool {
+        self.0.iter().any(|allowed| u64::from(*allowed) == code)
+    }
+
+    pub fn check(&self, cid: &Cid) -> Result<(), UnexpectedMultihashError> {
+        let code = cid.hash().code();
+        if self.contains(code) {
+            Ok(())
+        } else {
+            Err(UnexpectedMultihashError {
+                cid: *cid,
+                code,
+                allowed: self.0.clone(),
+            })
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unexpected multihash code {code:#x} in {cid}, expected one of {allowed:?}")]
+pub struct UnexpectedMultihashError {
+    pub cid: Cid,
+    pub code: u64,
+    pub allowed: Vec<MultihashCode>,
+}
+
 /// Identity hasher with a maximum size.
 ///
 /// # Panics
@@ -3,6 +3,8 @@
 
 mod download_file;
 pub use download_file::*;
+mod upload_file;
+pub use upload_file::*;
 
 use crate::utils::io::WithProgress;
 use crate::utils::reqwest_resume;
@@ -0,0 +1,272 @@
+// Copyright 2019-2025 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Resumable, chunked uploads of snapshots, the counterpart of
+//! [`download_file`](super::download_file).
+//!
+//! The remote endpoint at `url` must support:
+//! - `HEAD url`, with the number of bytes received so far in an `Upload-Offset`
+//!   header, or `404 Not Found` if nothing was received.
+//! - `PUT url` with a chunk of the file as the body and e.g. a
+//!   `Content-Range: bytes 0-1023/4096` header. Chunks are sent in order.
+//! - `PUT url` with an empty body, a `Content-Range: bytes */4096` header and a
+//!   `Repr-Digest: sha-256=:<base64>:` header (see RFC 9530), to complete the
+//!   upload. The endpoint should reject the upload if the length or checksum
+//!   don't match what it received.
+
+use crate::db::car::ForestCar;
+use crate::utils::io::WithProgress;
+use crate::utils::{RetryArgs, net::global_http_client, retry};
+use anyhow::Context as _;
+use base64::{Engine, prelude::BASE64_STANDARD};
+use reqwest::{StatusCode, header::CONTENT_RANGE};
+use sha2::{Digest as _, Sha256};
+use std::io::{self, BufRead as _};
+use std::path::Path;
+use tokio::io::{AsyncReadExt as _, AsyncSeekExt as _};
+use url::Url;
+
+/// Size of the chunks of [`upload_forest_car`].
+const UPLOAD_CHUNK_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Name of the header with the number of bytes received so far.
+const UPLOAD_OFFSET: &str = "Upload-Offset";
+
+/// Name of the header with the checksum of the uploaded file.
+const REPR_DIGEST: &str = "Repr-Digest";
+
+/// Upload the `.forest.car.zst` file at `path` to `url`, see the
+/// [module documentation](self) for the protocol. Failed uploads are resumed
+/// from the number of bytes the remote endpoint has received.
+pub async fn upload_forest_car(path: &Path, url: &Url) -> anyhow::Result<()> {
+    upload_forest_car_in_chunks(path, url, UPLOAD_CHUNK_SIZE).await
+}
+
+async fn upload_forest_car_in_chunks(
+    path: &Path,
+    url: &Url,
+    chunk_size: u64,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        ForestCar::is_valid(&std::fs::File::open(path)?),
+        "{} is not a forest CAR",
+        path.display()
+    );
+    let len = tokio::fs::metadata(path).await?.len();
+    let digest = {
+        let path = path.to_owned();
+        tokio::task::spawn_blocking(move || sha256(&path)).await??
+    };
+    let digest = format!("sha-256=:{}:", BASE64_STANDARD.encode(digest));
+    let source = path.display();
+    tracing::info!(%url, %source, "uploading snapshot");
+    Ok(retry(
+        RetryArgs {
+            timeout: None,
+            ..Default::default()
+        },
+        || upload_remaining_chunks(path, url, len, chunk_size, &digest),
+    )
+    .await?)
+}
+
+/// Upload the part of the file at `path` that the remote endpoint hasn't
+/// received yet, and complete the upload.
+async fn upload_remaining_chunks(
+    path: &Path,
+    url: &Url,
+    len: u64,
+    chunk_size: u64,
+    digest: &str,
+) -> anyhow::Result<()> {
+    let client = global_http_client();
+    let response = client.head(url.clone()).send().await?;
+    let mut offset = match response.status() {
+        StatusCode::NOT_FOUND => 0,
+        _ => response
+            .error_for_status()?
+            .headers()
+            .get(UPLOAD_OFFSET)
+            .with_context(|| format!("missing {UPLOAD_OFFSET} header"))?
+            .to_str()?
+            .parse()?,
+    };
+    anyhow::ensure!(
+        offset <= len,
+        "{url} has received {offset} bytes, but the file is only {len} bytes"
+    );
+    if offset > 0 {
+        tracing::info!(%url, %offset, "resuming upload");
+    }
+
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(io::SeekFrom::Start(offset)).await?;
+    let mut reader = WithProgress::wrap_sync_read_with_callback("Uploading", file, len, None)
+        .bytes()
+        .with_completed(offset);
+    while offset < len {
+        let mut chunk = vec![];
+        (&mut reader)
+            .take(chunk_size)
+            .read_to_end(&mut chunk)
+            .await?;
+        anyhow::ensure!(!chunk.is_empty(), "{} was truncated", path.display());
+        let end = offset + chunk.len() as u64;
+        client
+            .put(url.clone())
+            .header(CONTENT_RANGE, format!("bytes {offset}-{}/{len}", end - 1))
+            .body(chunk)
+            .send()
+            .await?
+            .error_for_status()?;
+        offset = end;
+    }
+
+    client
+        .put(url.clone())
+        .header(CONTENT_RANGE, format!("bytes */{len}"))
+        .header(REPR_DIGEST, digest)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+fn sha256(path: &Path) -> io::Result<[u8; 32]> {
+    let mut reader = io::BufReader::new(std::fs::File::open(path)?);
+    let mut hasher = Sha256::new();
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(hasher.finalize().into());
+        }
+        hasher.update(buf);
+        let consumed = buf.len();
+        reader.consume(consumed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use axum::{
+        Router,
+        body::Bytes,
+        extract::State,
+        http::HeaderMap,
+        response::{IntoResponse, Response},
+        routing::head,
+    };
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    /// What a mock remote endpoint received.
+    #[derive(Default)]
+    struct Upload {
+        data: Vec<u8>,
+        puts: usize,
+        /// Fail the request for the chunk at this offset, after storing it, as if
+        /// the response was lost.
+        fail_at: Option<u64>,
+        completed: bool,
+    }
+
+    async fn head_upload(State(upload): State<Arc<Mutex<Upload>>>) -> Response {
+        let upload = upload.lock();
+        match upload.data.is_empty() {
+            true => StatusCode::NOT_FOUND.into_response(),
+            false => [(UPLOAD_OFFSET, upload.data.len().to_string())].into_response(),
+        }
+    }
+
+    async fn put_upload(
+        State(upload): State<Arc<Mutex<Upload>>>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        let mut upload = upload.lock();
+        upload.puts += 1;
+        let range = headers[CONTENT_RANGE].to_str().unwrap();
+        let range = range.strip_prefix("bytes ").unwrap();
+        if let Some(len) = range.strip_prefix("*/") {
+            let digest = format!(
+                "sha-256=:{}:",
+                BASE64_STANDARD.encode(Sha256::digest(&upload.data))
+            );
+            if len.parse::<usize>().unwrap() != upload.data.len() || headers[REPR_DIGEST] != digest
+            {
+                return StatusCode::BAD_REQUEST;
+            }
+            upload.completed = true;
+            return StatusCode::CREATED;
+        }
+        let (start, _) = range.split_once('-').unwrap();
+        let start = start.parse::<u64>().unwrap();
+        if start != upload.data.len() as u64 {
+            return StatusCode::CONFLICT;
+        }
+        upload.data.extend_from_slice(&body);
+        match upload.fail_at == Some(start) {
+            true => {
+                upload.fail_at = None;
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            false => StatusCode::OK,
+        }
+    }
+
+    async fn serve(upload: Arc<Mutex<Upload>>) -> Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/snapshot", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route("/snapshot", head(head_upload).put(put_upload))
+            .with_state(upload);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        url.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_upload_forest_car() {
+        const CHUNK_SIZE: u64 = 64 * 1024;
+        let path = Path::new("test-snapshots/chain4.forest.car.zst");
+        let expected = std::fs::read(path).unwrap();
+        let upload = Arc::new(Mutex::new(Upload {
+            fail_at: Some(2 * CHUNK_SIZE),
+            ..Default::default()
+        }));
+        let url = serve(upload.clone()).await;
+
+        upload_forest_car_in_chunks(path, &url, CHUNK_SIZE)
+            .await
+            .unwrap();
+
+        let upload = upload.lock();
+        assert!(upload.completed);
+        assert!(upload.data == expected);
+        // Every chunk, and the completing request, once
+        let num_chunks = (expected.len() as u64).div_ceil(CHUNK_SIZE) as usize;
+        assert_eq!(upload.puts, num_chunks + 1);
+    }
+
+    #[tokio::test]
+    async fn test_upload_checksum_mismatch() {
+        let upload = Arc::new(Mutex::new(Upload::default()));
+        let url = serve(upload.clone()).await;
+        let path = Path::new("test-snapshots/chain4.forest.car.zst");
+        // Received before the upload started
+        upload.lock().data = b"unrelated".to_vec();
+
+        upload_forest_car_in_chunks(path, &url, 64 * 1024)
+            .await
+            .unwrap_err();
+        assert!(!upload.lock().completed);
+    }
+
+    #[tokio::test]
+    async fn test_upload_not_forest_car() {
+        let url = "http://127.0.0.1:1/snapshot".parse().unwrap();
+        upload_forest_car(Path::new("test-snapshots/chain4.car"), &url)
+            .await
+            .unwrap_err();
+    }
+}
@@ -7,7 +7,7 @@ use std::{
     str::{self, FromStr},
 };
 
-use crate::cli::humantoken::TokenAmountPretty as _;
+use crate::cli::humantoken::TokenAmountPretty;
 use crate::key_management::{Key, KeyInfo};
 use crate::{
     ENCRYPTED_KEYSTORE_NAME,
@@ -585,12 +585,12 @@ fn input_password_to_load_encrypted_keystore(data_dir: PathBuf) -> dialoguer::Re
 fn format_balance(balance: &TokenAmount, no_round: bool, no_abbrev: bool) -> String {
     match (no_round, no_abbrev) {
         // no_round, absolute
-        (true, true) => format!("{:#}", balance.pretty()),
+        (true, true) => format!("{:#}", TokenAmountPretty::pretty(balance)),
         // no_round, relative
-        (true, false) => format!("{}", balance.pretty()),
+        (true, false) => format!("{}", TokenAmountPretty::pretty(balance)),
         // round, absolute
-        (false, true) => format!("{:#.4}", balance.pretty()),
+        (false, true) => format!("{:#.4}", TokenAmountPretty::pretty(balance)),
         // round, relative
-        (false, false) => format!("{:.4}", balance.pretty()),
+        (false, false) => format!("{:.4}", TokenAmountPretty::pretty(balance)),
     }
 }